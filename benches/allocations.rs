@@ -0,0 +1,93 @@
+use cinema::ActorSystem;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+mod common;
+use common::{Add, Calculator, Count, CounterActor};
+
+/// counts allocations, to report how many each message costs
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const BURST: usize = 1000;
+
+fn bench_hot_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("allocations");
+    // single threaded, so the counts only include the sender and the actor
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let (_sys, counter, calculator) = rt.block_on(async {
+        let sys = ActorSystem::new();
+        let counter = sys.spawn(CounterActor {
+            count: Arc::new(AtomicUsize::new(0)),
+        });
+        let calculator = sys.spawn(Calculator);
+        (sys, counter, calculator)
+    });
+
+    // fire and forget burst, the mailbox fills up and the actor drains it
+    let do_send_burst = || async {
+        for _ in 0..BURST {
+            counter.do_send(Count).await.unwrap();
+        }
+    };
+    // request round trips, the actor waits for each one
+    let send_burst = || async {
+        for _ in 0..BURST {
+            black_box(calculator.send(Add(1, 2)).await.unwrap());
+        }
+    };
+
+    for (name, allocations) in [
+        ("do_send", count(&rt, do_send_burst)),
+        ("send", count(&rt, send_burst)),
+    ] {
+        println!("allocations/{}: {:.2} per message", name, allocations);
+    }
+
+    group.bench_function("do_send_burst_1000", |b| {
+        b.to_async(&rt).iter(do_send_burst);
+    });
+    group.bench_function("send_round_trip_1000", |b| {
+        b.to_async(&rt).iter(send_burst);
+    });
+    group.finish();
+}
+
+/// allocations per message of one burst, after a warm-up one
+fn count<F, Fut>(rt: &tokio::runtime::Runtime, burst: F) -> f64
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    rt.block_on(burst());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    rt.block_on(burst());
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / BURST as f64
+}
+
+criterion_group!(benches, bench_hot_path);
+criterion_main!(benches);
@@ -98,13 +98,13 @@ fn bench_gossip_operations(c: &mut Criterion) {
 
                 // chain gossip
                 let start = std::time::Instant::now();
-                for i in 0..6 {
+                for (i, node) in nodes.iter().enumerate().take(6) {
                     let peer = Node {
                         id: format!("node-{}", i + 2),
                         addr: format!("127.0.0.1:{}", get_bench_port(201 + (i + 1) as u16)),
                         status: NodeStatus::Up,
                     };
-                    nodes[i].send_gossip_to(&peer).await.unwrap();
+                    node.send_gossip_to(&peer).await.unwrap();
                     tokio::time::sleep(std::time::Duration::from_millis(2)).await;
                 }
 
@@ -30,6 +30,8 @@ pub struct CounterActor {
 impl Actor for CounterActor {}
 
 impl Handler<Count> for CounterActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Count, _ctx: &mut Context<Self>) {
         self.count.fetch_add(1, Ordering::Relaxed);
     }
@@ -45,6 +47,8 @@ pub struct Calculator;
 impl Actor for Calculator {}
 
 impl Handler<Add> for Calculator {
+    type Result = u32;
+
     fn handle(&mut self, msg: Add, _ctx: &mut Context<Self>) -> u32 {
         msg.0.wrapping_add(msg.1)
     }
@@ -97,7 +101,7 @@ impl Message for BenchRequest {
 
 impl cinema::remote::RemoteMessage for BenchRequest {}
 
-#[derive(Clone, ProstMessage)]
+#[derive(Clone, ProstMessage, cinema::MessageResponse)]
 pub struct BenchResponse {
     #[prost(uint64, tag = "1")]
     pub request_id: u64,
@@ -0,0 +1,150 @@
+//! Derive macros for cinema, re-exported by the main crate
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, ImplItem, ItemImpl, LitStr, ReturnType, Type};
+
+/// Implement `cinema::Message`
+/// The result type comes from `#[rtype(result = "T")]` (or `#[rtype(T)]`)
+/// and defaults to `()`
+#[proc_macro_derive(Message, attributes(rtype))]
+pub fn derive_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_message(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_message(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut result: Option<Type> = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("rtype"))
+    {
+        if result.is_some() {
+            return Err(syn::Error::new_spanned(attr, "duplicate rtype attribute"));
+        }
+        //#[rtype(T)]
+        if let Ok(ty) = attr.parse_args::<Type>() {
+            result = Some(ty);
+            continue;
+        }
+        //#[rtype(result = "T")]
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("result") {
+                return Err(meta.error("expected `result = \"Type\"`"));
+            }
+            let ty: LitStr = meta.value()?.parse()?;
+            result = Some(ty.parse()?);
+            Ok(())
+        })?;
+    }
+    let result = result.unwrap_or_else(|| syn::parse_quote!(()));
+
+    Ok(quote! {
+        impl #impl_generics ::cinema::Message for #name #ty_generics #where_clause {
+            type Result = #result;
+        }
+    })
+}
+
+/// Implement `cinema::MessageResponse` for a result type, so handlers can
+/// return it directly
+#[proc_macro_derive(MessageResponse)]
+pub fn derive_message_response(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    //the actor and message become extra impl parameters
+    let mut generics = input.generics.clone();
+    generics
+        .params
+        .push(syn::parse_quote!(__A: ::cinema::Actor));
+    generics
+        .params
+        .push(syn::parse_quote!(__M: ::cinema::Message<Result = Self>));
+    let (impl_generics, _, _) = generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics ::cinema::response::MessageResponse<__A, __M> for #name #ty_generics
+        #where_clause
+        {
+            fn handle(
+                self,
+                _ctx: &mut ::cinema::Context<__A>,
+                tx: ::std::option::Option<::cinema::response::Sender<Self>>,
+            ) {
+                ::cinema::response::reply(tx, self)
+            }
+        }
+    )
+    .into()
+}
+
+/// Implement `cinema::remote::RemoteMessage` and register the type for
+/// deserialization at link time, so no `register_message` call is needed.
+/// Generic types only get the impl; register each instantiation by hand
+#[proc_macro_derive(RemoteMessage)]
+pub fn derive_remote_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let registration = input.generics.params.is_empty().then(|| {
+        quote! {
+            ::cinema::remote::__private::inventory::submit! {
+                ::cinema::remote::AutoRegistration {
+                    register: ::cinema::remote::register_message::<#name>,
+                }
+            }
+        }
+    });
+
+    quote!(
+        impl #impl_generics ::cinema::remote::RemoteMessage for #name #ty_generics #where_clause {}
+
+        #registration
+    )
+    .into()
+}
+
+/// Write `AsyncHandler` impls with `async fn handle`
+/// Put it on the impl block; each `async fn` becomes a method returning
+/// `BoxFuture<'_, T>`. As with a hand-written impl the future borrows only
+/// `self`, so `ctx` can't be used inside the body
+#[proc_macro_attribute]
+pub fn async_handler(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        let args = proc_macro2::TokenStream::from(args);
+        return syn::Error::new_spanned(args, "async_handler takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+    let mut item = parse_macro_input!(input as ItemImpl);
+
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(method) = impl_item else {
+            continue;
+        };
+        if method.sig.asyncness.take().is_none() {
+            continue;
+        }
+        let output = match &method.sig.output {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(_, ty) => quote!(#ty),
+        };
+        method.sig.output = syn::parse_quote!(-> ::cinema::actor::BoxFuture<'_, #output>);
+        let body = &method.block;
+        method.block = syn::parse_quote!({
+            ::std::boxed::Box::pin(async move #body)
+        });
+    }
+
+    quote!(#item).into()
+}
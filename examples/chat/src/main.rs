@@ -76,6 +76,8 @@ impl Actor for ChatServer {
 }
 
 impl Handler<Connect> for ChatServer {
+    type Result = ();
+
     fn handle(&mut self, msg: Connect, _ctx: &mut Context<Self>) {
         println!("[server] {} joined", msg.username);
         self.broadcast(&format!("* {} joined the chat\n", msg.username));
@@ -84,6 +86,8 @@ impl Handler<Connect> for ChatServer {
 }
 
 impl Handler<Disconnect> for ChatServer {
+    type Result = ();
+
     fn handle(&mut self, msg: Disconnect, _ctx: &mut Context<Self>) {
         println!("[server] {} left", msg.username);
         self.clients.remove(&msg.username);
@@ -92,6 +96,8 @@ impl Handler<Disconnect> for ChatServer {
 }
 
 impl Handler<Broadcast> for ChatServer {
+    type Result = ();
+
     fn handle(&mut self, msg: Broadcast, _ctx: &mut Context<Self>) {
         let line = format!("[{}]: {}\n", msg.from, msg.text);
         for (username, client) in &self.clients {
@@ -158,6 +164,8 @@ impl StreamHandler<Result<String, io::Error>> for ClientSession {
 }
 
 impl Handler<SendLine> for ClientSession {
+    type Result = ();
+
     fn handle(&mut self, msg: SendLine, _ctx: &mut Context<Self>) {
         let _ = self.writer_tx.send(msg.0);
     }
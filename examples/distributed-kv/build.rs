@@ -1,3 +1,9 @@
 fn main() {
-    prost_build::compile_protos(&["proto/kv.proto"], &["proto/"]).unwrap();
+    prost_build::Config::new()
+        //responses are returned straight from handlers
+        .type_attribute(".kv.GetResponse", "#[derive(cinema::MessageResponse)]")
+        .type_attribute(".kv.SetResponse", "#[derive(cinema::MessageResponse)]")
+        .type_attribute(".kv.DeleteResponse", "#[derive(cinema::MessageResponse)]")
+        .compile_protos(&["proto/kv.proto"], &["proto/"])
+        .unwrap();
 }
@@ -51,6 +51,8 @@ impl cinema::remote::RemoteMessage for DeleteResponse {}
 
 //handler implementations
 impl Handler<GetRequest> for KVStore {
+    type Result = GetResponse;
+
     fn handle(&mut self, msg: GetRequest, _ctx: &mut Context<Self>) -> GetResponse {
         let value = self.data.get(&msg.key).cloned();
         println!("[KVStore] GET {} -> {:?}", msg.key, value);
@@ -59,6 +61,8 @@ impl Handler<GetRequest> for KVStore {
 }
 
 impl Handler<SetRequest> for KVStore {
+    type Result = SetResponse;
+
     fn handle(&mut self, msg: SetRequest, _ctx: &mut Context<Self>) -> SetResponse {
         self.data.insert(msg.key.clone(), msg.value.clone());
         println!("[KVStore] SET {} = {}", msg.key, msg.value);
@@ -67,6 +71,8 @@ impl Handler<SetRequest> for KVStore {
 }
 
 impl Handler<DeleteRequest> for KVStore {
+    type Result = DeleteResponse;
+
     fn handle(&mut self, msg: DeleteRequest, _ctx: &mut Context<Self>) -> DeleteResponse {
         let deleted = self.data.remove(&msg.key).is_some();
         println!("[KVStore] DELETE {} -> {}", msg.key, deleted);
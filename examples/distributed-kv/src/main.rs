@@ -63,12 +63,10 @@ async fn main() {
     //join existing cluster if seed provided
     if let Some(seed) = seed_addr {
         println!("joining cluster via seed: {}", seed);
-        let seed_node = cinema::remote::cluster::Node {
-            id: "temp-seed".to_string(),
-            addr: seed.to_string(),
-            status: cinema::remote::cluster::NodeStatus::Up,
-        };
-        let _ = cluster.send_gossip_to(&seed_node).await;
+        if let Err(e) = cluster.join(&[seed.as_str()]).await {
+            eprintln!("failed to join cluster: {:?}", e);
+            return;
+        }
     }
 
     //start periodic gossip
@@ -93,7 +91,7 @@ async fn main() {
 
         let mut input = String::new();
         io::stdin().read_line(&mut input).unwrap();
-        let parts: Vec<&str> = input.trim().split_whitespace().collect();
+        let parts: Vec<&str> = input.split_whitespace().collect();
 
         if parts.is_empty() {
             continue;
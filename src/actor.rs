@@ -1,27 +1,207 @@
 use std::{
+    collections::BTreeSet,
     future::Future,
     pin::Pin,
-    sync::atomic::{AtomicU64, Ordering},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime},
 };
 
-use crate::{Context, Message};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use crate::{
+    directory,
+    message::{ChildFailed, UnhandledMessage},
+    remote::proto,
+    response::{MessageResponse, MessageResult},
+    Context, Message,
+};
 
 //it is an entity which has own state, also
 //it's size is to be known during compile time
 pub trait Actor: Send + Sized + 'static {
     fn started(&mut self, _ctx: &mut Context<Self>) {}
     fn stopped(&mut self, _ctx: &mut Context<Self>) {}
+
+    /// Awaited right after `started`, before the first message is handled
+    /// (open connections, load state, ...)
+    fn started_async<'a>(&'a mut self, _ctx: &'a mut Context<Self>) -> BoxFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
+    /// Awaited right after `stopped` (flush buffers, close connections, ...)
+    fn stopped_async<'a>(&'a mut self, _ctx: &'a mut Context<Self>) -> BoxFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
+    /// Called when a message addressed to this actor had no handler
+    /// (remote routing miss, dynamic dispatch layers, ...)
+    fn unhandled(&mut self, _msg: UnhandledMessage, _ctx: &mut Context<Self>) {}
+
+    /// Called when a child supervised with `Escalate` panicked
+    /// Return true if handled; otherwise the failure becomes this actor's own
+    /// and its supervisor decides (up to the guardian, which stops the subtree)
+    fn child_failed(&mut self, _failure: &ChildFailed, _ctx: &mut Context<Self>) -> bool {
+        false
+    }
 }
 
 /// Unique identifier for an actor
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct ActorId(u64);
+/// Ids are handed out in creation order and remember their parent,
+/// equality, ordering and hashing only look at the node and sequence number
+/// The name and node are interned for the life of the process, so ids stay
+/// `Copy`; keep the set of distinct names bounded
+#[derive(Debug, Clone, Copy)]
+pub struct ActorId {
+    seq: u64,
+    parent: Option<u64>,
+    created_at: SystemTime,
+    name: Option<&'static str>,
+    node: Option<&'static str>,
+}
 
 static NEXT_ID: AtomicU64 = AtomicU64::new(1);
 
+///names and node ids of every id created so far, see `ActorId`
+static INTERNED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+fn intern(s: &str) -> &'static str {
+    let mut interned = INTERNED.lock().unwrap_or_else(|p| p.into_inner());
+    if let Some(s) = interned.get(s) {
+        return s;
+    }
+    let s: &'static str = Box::leak(s.into());
+    interned.insert(s);
+    s
+}
+
+tokio::task_local! {
+    ///id of the actor whose loop is running on this task
+    pub(crate) static CURRENT_ACTOR: ActorId;
+}
+
+///actor running on the current task, None outside actor loops
+pub(crate) fn current() -> Option<ActorId> {
+    CURRENT_ACTOR.try_with(|id| *id).ok()
+}
+
 impl ActorId {
     pub fn new() -> Self {
-        Self(NEXT_ID.fetch_add(1, Ordering::SeqCst))
+        Self {
+            seq: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            parent: None,
+            created_at: SystemTime::now(),
+            name: None,
+            node: None,
+        }
+    }
+
+    ///id for a child spawned by `parent`
+    pub(crate) fn child_of(parent: ActorId) -> Self {
+        Self {
+            parent: Some(parent.seq),
+            ..Self::new()
+        }
+    }
+
+    ///the same id carrying the name the actor was spawned with
+    pub(crate) fn named(self, name: &str) -> Self {
+        Self {
+            name: Some(intern(name)),
+            ..self
+        }
+    }
+
+    /// The same id marked as living on `node`, e.g. before it is sent to
+    /// another node. Ids with a node don't resolve their parent or ancestors
+    pub fn with_node(self, node: &str) -> Self {
+        Self {
+            node: Some(intern(node)),
+            ..self
+        }
+    }
+
+    /// Position in creation order (process-wide, starts at 1)
+    pub fn sequence(&self) -> u64 {
+        self.seq
+    }
+
+    /// Name given with `SpawnOptions::name`, if any
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Node the actor lives on, None for local ids
+    pub fn node(&self) -> Option<&'static str> {
+        self.node
+    }
+
+    /// Id of the actor that spawned this one, None for top-level actors
+    /// Resolves while the parent is alive
+    pub fn parent(&self) -> Option<ActorId> {
+        if self.node.is_some() {
+            return None;
+        }
+        directory::lookup_id(self.parent?)
+    }
+
+    /// When the actor was spawned
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    /// Path from the top-level ancestor down to this actor, e.g. `/3/7/12`
+    /// Stops at the first ancestor that is no longer alive; ids with a node
+    /// only know their parent
+    pub fn path(&self) -> String {
+        let mut chain = vec![self.seq];
+        let mut next = self.parent;
+        while let Some(seq) = next {
+            chain.push(seq);
+            next = match self.node {
+                Some(_) => None,
+                None => directory::lookup_id(seq).and_then(|id| id.parent),
+            };
+        }
+        chain.iter().rev().map(|seq| format!("/{}", seq)).collect()
+    }
+
+    /// Read an id back from its `Display` form, `[node@]path[#name]`, e.g.
+    /// `node-a@/3/7#worker`. The last two path segments are the sequence
+    /// number and the parent's; `created_at` is not part of the text, so
+    /// it is the epoch
+    pub fn parse(s: &str) -> Result<Self, ParseActorIdError> {
+        let error = |reason: &str| ParseActorIdError {
+            input: s.to_string(),
+            reason: reason.to_string(),
+        };
+        let (node, rest) = match s.split_once('@') {
+            Some((node, rest)) if !node.is_empty() => (Some(intern(node)), rest),
+            Some(_) => return Err(error("empty node")),
+            None => (None, s),
+        };
+        let (path, name) = match rest.split_once('#') {
+            Some((path, name)) if !name.is_empty() => (path, Some(intern(name))),
+            Some(_) => return Err(error("empty name")),
+            None => (rest, None),
+        };
+        let segments = path
+            .strip_prefix('/')
+            .ok_or_else(|| error("path must start with '/'"))?
+            .split('/')
+            .map(|segment| segment.parse::<u64>().map_err(|_| error("segments must be numbers")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (&seq, ancestors) = segments.split_last().ok_or_else(|| error("empty path"))?;
+        Ok(Self {
+            seq,
+            parent: ancestors.last().copied(),
+            created_at: SystemTime::UNIX_EPOCH,
+            name,
+            node,
+        })
     }
 }
 
@@ -31,10 +211,151 @@ impl Default for ActorId {
     }
 }
 
+impl PartialEq for ActorId {
+    fn eq(&self, other: &Self) -> bool {
+        self.seq == other.seq && self.node == other.node
+    }
+}
+
+impl Eq for ActorId {}
+
+impl std::hash::Hash for ActorId {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.seq.hash(state);
+        self.node.hash(state);
+    }
+}
+
+impl PartialOrd for ActorId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Local ids first, then by node; creation order within a node
+impl Ord for ActorId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.node, self.seq).cmp(&(other.node, other.seq))
+    }
+}
+
+/// `[node@]path[#name]`, e.g. `/3/7` or `node-a@/3/7#worker`, see `ActorId::parse`
+impl std::fmt::Display for ActorId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(node) = self.node {
+            write!(f, "{}@", node)?;
+        }
+        write!(f, "{}", self.path())?;
+        if let Some(name) = self.name {
+            write!(f, "#{}", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Text that is not an `ActorId`, see `ActorId::parse`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseActorIdError {
+    pub input: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ParseActorIdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid actor id '{}': {}", self.input, self.reason)
+    }
+}
+
+impl std::error::Error for ParseActorIdError {}
+
+///serde form of an `ActorId`, with owned strings
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ActorIdRepr {
+    sequence: u64,
+    parent: Option<u64>,
+    created_at: SystemTime,
+    name: Option<String>,
+    node: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl From<ActorId> for ActorIdRepr {
+    fn from(id: ActorId) -> Self {
+        Self {
+            sequence: id.seq,
+            parent: id.parent,
+            created_at: id.created_at,
+            name: id.name.map(str::to_string),
+            node: id.node.map(str::to_string),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ActorIdRepr> for ActorId {
+    fn from(repr: ActorIdRepr) -> Self {
+        Self {
+            seq: repr.sequence,
+            parent: repr.parent,
+            created_at: repr.created_at,
+            name: repr.name.as_deref().map(intern),
+            node: repr.node.as_deref().map(intern),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ActorId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ActorIdRepr::from(*self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ActorId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ActorIdRepr::deserialize(deserializer).map(Self::from)
+    }
+}
+
+///protobuf form of an `ActorId`, for remote envelopes
+impl From<ActorId> for proto::ActorId {
+    fn from(id: ActorId) -> Self {
+        Self {
+            sequence: id.seq,
+            parent: id.parent.unwrap_or_default(),
+            created_at_ms: id
+                .created_at
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            name: id.name.unwrap_or_default().to_string(),
+            node: id.node.unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<proto::ActorId> for ActorId {
+    fn from(id: proto::ActorId) -> Self {
+        let text = |s: &str| (!s.is_empty()).then(|| intern(s));
+        Self {
+            seq: id.sequence,
+            parent: (id.parent != 0).then_some(id.parent),
+            created_at: SystemTime::UNIX_EPOCH + Duration::from_millis(id.created_at_ms),
+            name: text(&id.name),
+            node: text(&id.node),
+        }
+    }
+}
+
 /// Defines how an actor handles a specific message type.
 /// One actor can handle multiple message types
+/// `Result` is usually `M::Result` itself; return a `ResponseFuture` or
+/// `ResponseActFuture` to reply once some async work is done
 pub trait Handler<M: Message>: Actor {
-    fn handle(&mut self, msg: M, ctx: &mut Context<Self>) -> M::Result;
+    type Result: MessageResponse<Self, M>;
+
+    fn handle(&mut self, msg: M, ctx: &mut Context<Self>) -> Self::Result;
 }
 
 ///return type for async functions in actors
@@ -53,3 +374,62 @@ where
     fn handle(&mut self, item: I, ctx: &mut Context<Self>);
     fn finished(&mut self, _ctx: &mut Context<Self>) {}
 }
+
+///signature of the closure driving an FnActor
+type FnHandler<S, M> =
+    Box<dyn FnMut(&mut S, M, &mut Context<FnActor<S, M>>) -> <M as Message>::Result + Send>;
+
+///Anonymous actor built from a state value and a closure handling one message type
+/// Created with `ActorSystem::spawn_fn`
+pub struct FnActor<S: Send + 'static, M: Message> {
+    state: S,
+    handler: FnHandler<S, M>,
+}
+
+impl<S: Send + 'static, M: Message> FnActor<S, M> {
+    pub fn new<F>(state: S, handler: F) -> Self
+    where
+        F: FnMut(&mut S, M, &mut Context<Self>) -> M::Result + Send + 'static,
+    {
+        Self {
+            state,
+            handler: Box::new(handler),
+        }
+    }
+}
+
+impl<S: Send + 'static, M: Message> Actor for FnActor<S, M> {}
+
+impl<S: Send + 'static, M: Message> Handler<M> for FnActor<S, M> {
+    type Result = MessageResult<M>;
+
+    fn handle(&mut self, msg: M, ctx: &mut Context<Self>) -> MessageResult<M> {
+        MessageResult((self.handler)(&mut self.state, msg, ctx))
+    }
+}
+
+///Anonymous actor forwarding every message it receives into a stream
+/// Created with `ActorSystem::spawn_receiver`; stops once the stream is dropped
+pub struct ReceiverActor<M> {
+    tx: mpsc::UnboundedSender<M>,
+}
+
+impl<M> ReceiverActor<M> {
+    pub fn new() -> (Self, UnboundedReceiverStream<M>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, UnboundedReceiverStream::new(rx))
+    }
+}
+
+impl<M: Message<Result = ()>> Actor for ReceiverActor<M> {}
+
+impl<M: Message<Result = ()>> Handler<M> for ReceiverActor<M> {
+    type Result = ();
+
+    fn handle(&mut self, msg: M, ctx: &mut Context<Self>) {
+        if self.tx.send(msg).is_err() {
+            //nobody is listening anymore
+            ctx.stop();
+        }
+    }
+}
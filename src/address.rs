@@ -1,13 +1,25 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use tokio::sync::{mpsc, oneshot, Notify};
+use futures::future::BoxFuture;
+use tokio::sync::{oneshot, Notify};
 
 use crate::{
     actor::{ActorId, AsyncHandler},
-    envelope::{ActorMessage, AsyncMessageEnvelope, MessageEnvelope},
+    dead_letter::{self, DeadLetter},
+    envelope::{ActorMessage, AsyncMessageEnvelope, MessageEnvelope, UnhandledEnvelope},
     error::MailboxError,
-    message::Terminated,
-    watcher::Watcher,
+    mailbox::{Mailbox, Priority, Reserved},
+    message::{MessageOrigin, Terminated, TerminationReason, UnhandledMessage},
+    retry::{RetryError, RetryPolicy},
+    stream::AddrSink,
+    sync::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    throttle::Throttled,
+    watcher::{Watcher, WatcherList},
     Actor, Handler, Message,
 };
 
@@ -15,29 +27,140 @@ use crate::{
 pub trait ChildHandle: Send + Sync {
     fn stop(&self);
     fn is_alive(&self) -> bool;
+    ///approximate number of queued messages
+    fn mailbox_len(&self) -> usize;
+    ///stop accepting messages; the actor stops once it has handled the queued ones
+    fn close(&self);
 }
 
 ///Address of an actor
 /// Allows sending messages to the actor
 /// Also allows registering watchers to be notified when the actor stops
+/// A top-level actor stops once every Addr is dropped and it has no streams left
 pub struct Addr<A: Actor> {
-    sender: mpsc::Sender<ActorMessage<A>>,
+    mailbox: Arc<dyn Mailbox<A>>,
     id: ActorId,
-    watchers: Arc<Mutex<Vec<Arc<dyn Watcher>>>>,
+    watchers: Arc<WatcherList>,
     stop_signal: Arc<Notify>,
+    unhandled: Arc<AtomicU64>,
+    activity: Arc<Activity>,
+    name: Option<Arc<str>>,
+    path: Arc<str>,
+    refs: Arc<StrongRefs>,
+    //set by `kill`, the actor loop then skips what a stop would wait for
+    killed: Arc<AtomicBool>,
+    //false for the actor's own handle and other internal references
+    counted: bool,
+    //key of the owning system, for its dead letter subscribers; 0 outside one
+    system: u64,
+}
+
+///Messages handled so far and when the last one was, for `ActorSystem::snapshot`
+pub(crate) struct Activity {
+    processed: AtomicU64,
+    //milliseconds since the unix epoch, 0 until the first message
+    last_active: AtomicU64,
+    //actor loops (sync arbiter threads) holding a message, for `ActorSystem::wait_idle`
+    handling: AtomicUsize,
+    //`do_send_now` messages waiting in spawned tasks for room in the mailbox
+    deferred: AtomicUsize,
+}
+
+/// How many `do_send_now` messages per actor may wait for room at once
+const MAX_DEFERRED: usize = 1024;
+
+impl Activity {
+    fn new() -> Self {
+        Self {
+            processed: AtomicU64::new(0),
+            last_active: AtomicU64::new(0),
+            handling: AtomicUsize::new(0),
+            deferred: AtomicUsize::new(0),
+        }
+    }
+
+    fn is_handling(&self) -> bool {
+        self.handling.load(Ordering::SeqCst) > 0
+    }
+
+    fn record(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        self.processed.fetch_add(1, Ordering::Relaxed);
+        self.last_active.store(now.max(1), Ordering::Relaxed);
+    }
+
+    fn processed(&self) -> u64 {
+        self.processed.load(Ordering::Relaxed)
+    }
+
+    fn last_active(&self) -> Option<SystemTime> {
+        match self.last_active.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(UNIX_EPOCH + Duration::from_millis(millis)),
+        }
+    }
+}
+
+/// Room held in an actor's mailbox by `Addr::reserve` or `Addr::reserve_many`
+pub struct Permit<'a, A: Actor> {
+    addr: &'a Addr<A>,
+    slots: Box<dyn Reserved<A> + 'a>,
+    remaining: usize,
+}
+
+impl<A: Actor> Permit<'_, A> {
+    /// Fire and forget into a held slot, without waiting
+    /// MailboxFull once every slot is used, MailboxClosed if the actor stopped
+    pub fn send<M>(&mut self, msg: M) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        if self.remaining == 0 {
+            return Err(MailboxError::MailboxFull);
+        }
+        let envelope = ActorMessage::Sync(Box::new(MessageEnvelope::new(msg)));
+        let message_type = envelope.message_type();
+        let result = self.slots.enqueue(envelope);
+        if result.is_ok() {
+            self.remaining -= 1;
+        }
+        self.addr.report_undelivered(&result, message_type);
+        result
+    }
+
+    /// Slots not used yet
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+///Number of live Addr handles, the actor loop waits on `released`
+pub(crate) struct StrongRefs {
+    count: AtomicUsize,
+    released: Notify,
 }
 
 impl<A: Actor> Addr<A> {
-    pub fn new(
-        sender: mpsc::Sender<ActorMessage<A>>,
-        id: ActorId,
-        stop_signal: Arc<Notify>,
-    ) -> Self {
+    pub fn new(mailbox: Arc<dyn Mailbox<A>>, id: ActorId, stop_signal: Arc<Notify>) -> Self {
         Self {
-            sender,
+            mailbox,
             id,
-            watchers: Arc::new(Mutex::new(Vec::new())),
+            watchers: Arc::new(WatcherList::new()),
             stop_signal,
+            unhandled: Arc::new(AtomicU64::new(0)),
+            activity: Arc::new(Activity::new()),
+            name: None,
+            path: format!("/user/{}", id.sequence()).into(),
+            refs: Arc::new(StrongRefs {
+                count: AtomicUsize::new(1),
+                released: Notify::new(),
+            }),
+            killed: Arc::new(AtomicBool::new(false)),
+            counted: true,
+            system: 0,
         }
     }
 
@@ -45,6 +168,41 @@ impl<A: Actor> Addr<A> {
         self.id
     }
 
+    /// Name given with `SpawnOptions::name`, if any
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub(crate) fn with_name(mut self, name: Option<Arc<str>>) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Place in the supervision tree, e.g. `/user/ingest/worker-3`
+    /// Built from the names (or sequence numbers) of the actor and its ancestors
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub(crate) fn path_arc(&self) -> Arc<str> {
+        self.path.clone()
+    }
+
+    pub(crate) fn with_path(mut self, path: Arc<str>) -> Self {
+        self.path = path;
+        self
+    }
+
+    pub(crate) fn with_system(mut self, system: u64) -> Self {
+        self.system = system;
+        self
+    }
+
+    ///key of the system the actor belongs to, see `directory::system_key`
+    pub(crate) fn system(&self) -> u64 {
+        self.system
+    }
+
     ///Send message and wait for response
     pub async fn send<M>(&self, msg: M) -> Result<M::Result, MailboxError>
     where
@@ -53,10 +211,7 @@ impl<A: Actor> Addr<A> {
     {
         let (tx, rx) = oneshot::channel();
         let envelope = MessageEnvelope::with_response(msg, tx);
-        self.sender
-            .send(ActorMessage::Sync(Box::new(envelope)))
-            .await
-            .map_err(|_| MailboxError::MailboxClosed)?;
+        self.deliver(ActorMessage::Sync(Box::new(envelope)), Priority::Normal).await?;
 
         rx.await.map_err(|_| MailboxError::MailboxClosed)
     }
@@ -72,10 +227,7 @@ impl<A: Actor> Addr<A> {
     {
         let (tx, rx) = oneshot::channel();
         let envelope = MessageEnvelope::with_response(msg, tx);
-        self.sender
-            .send(ActorMessage::Sync(Box::new(envelope)))
-            .await
-            .map_err(|_| MailboxError::MailboxClosed)?;
+        self.deliver(ActorMessage::Sync(Box::new(envelope)), Priority::Normal).await?;
 
         match tokio::time::timeout(timeout, rx).await {
             Ok(res) => res.map_err(|_| MailboxError::MailboxClosed),
@@ -83,6 +235,60 @@ impl<A: Actor> Addr<A> {
         }
     }
 
+    /// Send and wait for the response, retrying while the mailbox is full
+    /// Waits between attempts as `policy` says; any other error ends the
+    /// retries. The error lists what each attempt ran into. Custom mailboxes
+    /// that can't hand a message back (see `Mailbox::enqueue_now`) get one try
+    pub async fn send_retry<M>(&self, msg: M, policy: RetryPolicy) -> Result<M::Result, RetryError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        let started = tokio::time::Instant::now();
+        let mut attempts = Vec::new();
+        let (tx, rx) = oneshot::channel();
+        let mut envelope = ActorMessage::Sync(Box::new(MessageEnvelope::with_response(msg, tx)));
+        loop {
+            let error = match self.try_deliver_or_return(envelope) {
+                Ok(Ok(())) => break,
+                Ok(Err(err)) => err,
+                Err(rejected) if attempts.len() + 1 < policy.attempts as usize => {
+                    attempts.push(MailboxError::MailboxFull);
+                    tokio::time::sleep(policy.delay(attempts.len() as u32)).await;
+                    envelope = rejected;
+                    continue;
+                }
+                Err(_) => MailboxError::MailboxFull,
+            };
+            attempts.push(error);
+            return Err(RetryError {
+                attempts,
+                elapsed: started.elapsed(),
+            });
+        }
+        rx.await.map_err(|_| {
+            attempts.push(MailboxError::MailboxClosed);
+            RetryError {
+                attempts,
+                elapsed: started.elapsed(),
+            }
+        })
+    }
+
+    /// `try_send` that waits up to `timeout` for room in a full mailbox,
+    /// failing with MailboxFull after that
+    pub async fn try_send_timeout<M>(&self, msg: M, timeout: Duration) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        match tokio::time::timeout(timeout, self.reserve()).await {
+            Ok(Ok(mut permit)) => permit.send(msg),
+            Ok(Err(err)) => Err(err),
+            Err(_) => Err(MailboxError::MailboxFull),
+        }
+    }
+
     ///Fire and forget message sending
     pub async fn do_send<M>(&self, msg: M) -> Result<(), MailboxError>
     where
@@ -90,10 +296,60 @@ impl<A: Actor> Addr<A> {
         M: Message,
     {
         let envelope = MessageEnvelope::new(msg);
-        self.sender
-            .send(ActorMessage::Sync(Box::new(envelope)))
-            .await
-            .map_err(|_| MailboxError::MailboxClosed)
+        self.deliver(ActorMessage::Sync(Box::new(envelope)), Priority::Normal).await
+    }
+
+    /// Fire and forget without awaiting, usable in sync code and `Drop` impls
+    /// If the mailbox is full the message is delivered by a spawned task once
+    /// there is room, so it may arrive after messages sent later. At most 1024
+    /// messages per actor wait like that; past it, and outside a tokio
+    /// runtime, a full mailbox fails with MailboxFull instead
+    pub fn do_send_now<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        self.deliver_now(ActorMessage::Sync(Box::new(MessageEnvelope::new(msg))))
+    }
+
+    ///hand a request on with the original caller's reply channel, see
+    /// `Context::forward`; without one it is a plain `do_send_now`
+    pub(crate) fn forward<M>(
+        &self,
+        msg: M,
+        tx: Option<oneshot::Sender<M::Result>>,
+    ) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        let envelope = match tx {
+            Some(tx) => MessageEnvelope::with_response(msg, tx),
+            None => MessageEnvelope::new(msg),
+        };
+        self.deliver_now(ActorMessage::Sync(Box::new(envelope)))
+    }
+
+    ///enqueue from sync code, through a spawned task if the mailbox is full
+    fn deliver_now(&self, envelope: ActorMessage<A>) -> Result<(), MailboxError> {
+        let envelope = match self.try_deliver_or_return(envelope) {
+            Ok(result) => return result,
+            Err(envelope) => envelope,
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return Err(MailboxError::MailboxFull);
+        };
+        let deferred = &self.activity.deferred;
+        if deferred.fetch_add(1, Ordering::SeqCst) >= MAX_DEFERRED {
+            deferred.fetch_sub(1, Ordering::SeqCst);
+            return Err(MailboxError::MailboxFull);
+        }
+        let addr = self.clone();
+        runtime.spawn(async move {
+            let _ = addr.deliver(envelope, Priority::Normal).await;
+            addr.activity.deferred.fetch_sub(1, Ordering::SeqCst);
+        });
+        Ok(())
     }
 
     /// Fire and forget for async handlers
@@ -103,10 +359,7 @@ impl<A: Actor> Addr<A> {
         M: Message,
     {
         let envelope = AsyncMessageEnvelope::new(msg);
-        self.sender
-            .send(ActorMessage::Async(Box::new(envelope)))
-            .await
-            .map_err(|_| MailboxError::MailboxClosed)
+        self.deliver(ActorMessage::Async(Box::new(envelope)), Priority::Normal).await
     }
 
     /// Try to send a message without blocking
@@ -117,12 +370,7 @@ impl<A: Actor> Addr<A> {
         M: Message,
     {
         let envelope = MessageEnvelope::new(msg);
-        self.sender
-            .try_send(ActorMessage::Sync(Box::new(envelope)))
-            .map_err(|e| match e {
-                mpsc::error::TrySendError::Full(_) => MailboxError::MailboxFull,
-                mpsc::error::TrySendError::Closed(_) => MailboxError::MailboxClosed,
-            })
+        self.try_deliver(ActorMessage::Sync(Box::new(envelope)), Priority::Normal)
     }
 
     /// Try to send a message to async handler without blocking
@@ -133,12 +381,7 @@ impl<A: Actor> Addr<A> {
         M: Message,
     {
         let envelope = AsyncMessageEnvelope::new(msg);
-        self.sender
-            .try_send(ActorMessage::Async(Box::new(envelope)))
-            .map_err(|e| match e {
-                mpsc::error::TrySendError::Full(_) => MailboxError::MailboxFull,
-                mpsc::error::TrySendError::Closed(_) => MailboxError::MailboxClosed,
-            })
+        self.try_deliver(ActorMessage::Async(Box::new(envelope)), Priority::Normal)
     }
 
     /// Send and wait for response from async handler
@@ -149,16 +392,208 @@ impl<A: Actor> Addr<A> {
     {
         let (tx, rx) = oneshot::channel();
         let envelope = AsyncMessageEnvelope::with_response(msg, tx);
-        self.sender
-            .send(ActorMessage::Async(Box::new(envelope)))
-            .await
-            .map_err(|_| MailboxError::MailboxClosed)?;
+        self.deliver(ActorMessage::Async(Box::new(envelope)), Priority::Normal).await?;
+        rx.await.map_err(|_| MailboxError::MailboxClosed)
+    }
+
+    /// Send and wait for the response, queued in the given priority lane
+    /// Only mailboxes with lanes (`PriorityMailbox`) reorder; others keep FIFO
+    pub async fn send_priority<M>(
+        &self,
+        msg: M,
+        priority: Priority,
+    ) -> Result<M::Result, MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        let (tx, rx) = oneshot::channel();
+        let envelope = MessageEnvelope::with_response(msg, tx);
+        self.deliver(ActorMessage::Sync(Box::new(envelope)), priority).await?;
         rx.await.map_err(|_| MailboxError::MailboxClosed)
     }
 
+    /// Fire and forget in the given priority lane
+    pub async fn do_send_priority<M>(&self, msg: M, priority: Priority) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        let envelope = MessageEnvelope::new(msg);
+        self.deliver(ActorMessage::Sync(Box::new(envelope)), priority).await
+    }
+
+    /// Non-blocking send in the given priority lane
+    pub fn try_send_priority<M>(&self, msg: M, priority: Priority) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        let envelope = MessageEnvelope::new(msg);
+        self.try_deliver(ActorMessage::Sync(Box::new(envelope)), priority)
+    }
+
+    /// Feed the actor from a stream, e.g. `stream.map(Ok).forward(addr.into_sink())`
+    /// Items are sent with `do_send`, so a full mailbox holds the stream back
+    pub fn into_sink<M>(self) -> AddrSink<A, M>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        AddrSink::new(self)
+    }
+
+    /// Wait for room for one message, held until the permit sends it:
+    /// `addr.reserve().await?.send(msg)` applies backpressure before the
+    /// message is built, and the send itself never waits
+    pub async fn reserve(&self) -> Result<Permit<'_, A>, MailboxError> {
+        self.reserve_many(1).await
+    }
+
+    /// Wait for room for `n` messages at once, so a batch can't find the
+    /// mailbox full halfway through; MailboxFull if `n` exceeds the capacity
+    /// Room the permit doesn't use is given back when it drops
+    /// Mailboxes that can't hold room resolve right away, see `Mailbox::reserve`
+    pub async fn reserve_many(&self, n: usize) -> Result<Permit<'_, A>, MailboxError> {
+        let slots = self.mailbox.reserve(n).await?;
+        Ok(Permit {
+            addr: self,
+            slots,
+            remaining: n,
+        })
+    }
+
+    ///Enqueue a prebuilt envelope, waiting for capacity
+    /// Extension point for custom envelope kinds (see `envelope::DispatchHook`)
+    pub async fn send_envelope(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        self.deliver(msg, Priority::Normal).await
+    }
+
+    ///Enqueue a prebuilt envelope without waiting
+    pub fn try_send_envelope(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        self.try_deliver(msg, Priority::Normal)
+    }
+
+    ///enqueue, reporting a dead letter if the actor has stopped
+    async fn deliver(&self, msg: ActorMessage<A>, priority: Priority) -> Result<(), MailboxError> {
+        let message_type = msg.message_type();
+        //only wait, through the boxed enqueue future, when there is no room
+        let result = match self.mailbox.enqueue_now(msg, priority) {
+            Ok(result) => result,
+            Err(msg) => self.mailbox.enqueue_priority(msg, priority).await,
+        };
+        self.report_undelivered(&result, message_type);
+        result
+    }
+
+    fn try_deliver(&self, msg: ActorMessage<A>, priority: Priority) -> Result<(), MailboxError> {
+        let message_type = msg.message_type();
+        let result = self.mailbox.try_enqueue_priority(msg, priority);
+        self.report_undelivered(&result, message_type);
+        result
+    }
+
+    ///enqueue without waiting, handing the message back while the mailbox is full
+    fn try_deliver_or_return(
+        &self,
+        msg: ActorMessage<A>,
+    ) -> Result<Result<(), MailboxError>, ActorMessage<A>> {
+        let message_type = msg.message_type();
+        let result = match self.mailbox.enqueue_now(msg, Priority::Normal) {
+            Ok(result) => result,
+            Err(msg) if self.is_full() => return Err(msg),
+            //the mailbox doesn't hand messages back, it has one try
+            Err(msg) => self.mailbox.try_enqueue(msg),
+        };
+        self.report_undelivered(&result, message_type);
+        Ok(result)
+    }
+
+    fn report_undelivered(&self, result: &Result<(), MailboxError>, message_type: &'static str) {
+        if let Err(MailboxError::MailboxClosed) = result {
+            dead_letter::publish(
+                self.system,
+                DeadLetter::new(self.id, self.path.clone(), message_type),
+            );
+        }
+    }
+
+    ///try_send that never reports a dead letter, for the dead letter subscribers themselves
+    pub(crate) fn try_send_quiet<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        let envelope = MessageEnvelope::new(msg);
+        self.mailbox.try_enqueue(ActorMessage::Sync(Box::new(envelope)))
+    }
+
+    /// Report a message no handler was found for
+    /// Increments the unhandled counter and runs the actor's `unhandled` hook
+    pub fn notify_unhandled(&self, message_type: &str, origin: MessageOrigin) {
+        self.unhandled.fetch_add(1, Ordering::Relaxed);
+        let info = UnhandledMessage {
+            message_type: message_type.to_string(),
+            origin,
+        };
+        let _ = self
+            .mailbox
+            .try_enqueue(ActorMessage::Sync(Box::new(UnhandledEnvelope { info })));
+    }
+
+    /// Number of messages reported as unhandled for this actor
+    pub fn unhandled_messages(&self) -> u64 {
+        self.unhandled.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages this actor has handled, across restarts
+    pub fn messages_processed(&self) -> u64 {
+        self.activity.processed()
+    }
+
+    /// When the actor last finished handling a message, None if it never has
+    pub fn last_active(&self) -> Option<SystemTime> {
+        self.activity.last_active()
+    }
+
+    ///count a handled message
+    pub(crate) fn record_activity(&self) {
+        self.activity.record();
+    }
+
+    ///an actor loop took a message out of the mailbox (or from `ctx.notify`)
+    pub(crate) fn begin_handling(&self) {
+        self.activity.handling.fetch_add(1, Ordering::SeqCst);
+    }
+
+    ///the actor loop is about to wait for mail again
+    pub(crate) fn end_handling(&self) {
+        self.activity.handling.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Mailbox capacity, None if the mailbox is unbounded
+    pub fn capacity(&self) -> Option<usize> {
+        self.mailbox.capacity()
+    }
+
+    /// Approximate number of queued messages
+    pub fn len(&self) -> usize {
+        self.mailbox.len()
+    }
+
+    /// True if no messages are queued
+    pub fn is_empty(&self) -> bool {
+        self.mailbox.is_empty()
+    }
+
+    /// True if a bounded mailbox is at capacity (sends would wait or fail)
+    pub fn is_full(&self) -> bool {
+        self.capacity().is_some_and(|cap| self.len() >= cap)
+    }
+
     ///Check if the actor is still alive
     pub fn is_alive(&self) -> bool {
-        !self.sender.is_closed()
+        !self.mailbox.is_closed()
     }
 
     /// Add a watcher to be notified when this actor stops
@@ -168,25 +603,350 @@ impl<A: Actor> Addr<A> {
     where
         W: Actor + Handler<Terminated>,
     {
-        let watcher_arc = Arc::new(watcher);
-        self.watchers.lock().unwrap().push(watcher_arc);
+        self.add_watcher_erased(Arc::new(watcher));
+    }
+
+    pub(crate) fn add_watcher_erased(&self, watcher: Arc<dyn Watcher>) {
+        self.watchers.add(watcher);
+    }
+
+    /// Handle accepting only `M`, hiding the actor type
+    pub fn recipient<M>(&self) -> Recipient<M>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        Recipient {
+            id: self.id,
+            path: self.path.clone(),
+            sender: Arc::new(self.clone()),
+        }
+    }
+
+    /// Handle letting at most `rate` messages per second through,
+    /// with bursts of up to `burst` messages
+    pub fn throttled(&self, rate: u32, burst: u32) -> Throttled<A> {
+        Throttled::new(self.clone(), rate, burst)
+    }
+
+    /// Weak handle that doesn't keep the actor alive
+    pub fn downgrade(&self) -> WeakAddr<A> {
+        WeakAddr {
+            addr: self.uncounted(),
+        }
+    }
+
+    ///handle that doesn't count towards keeping the actor alive
+    pub(crate) fn uncounted(&self) -> Self {
+        self.with_counted(false)
+    }
+
+    ///handle that keeps the actor alive, even when made from an uncounted one
+    pub(crate) fn counted(&self) -> Self {
+        self.with_counted(true)
+    }
+
+    fn with_counted(&self, counted: bool) -> Self {
+        if counted {
+            self.refs.count.fetch_add(1, Ordering::SeqCst);
+        }
+        Self {
+            mailbox: self.mailbox.clone(),
+            id: self.id,
+            watchers: self.watchers.clone(),
+            stop_signal: self.stop_signal.clone(),
+            unhandled: self.unhandled.clone(),
+            activity: self.activity.clone(),
+            name: self.name.clone(),
+            path: self.path.clone(),
+            refs: self.refs.clone(),
+            killed: self.killed.clone(),
+            counted,
+            system: self.system,
+        }
+    }
+
+    ///resolves once no counted handle is left
+    pub(crate) async fn released(&self) {
+        loop {
+            if self.refs.count.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            self.refs.released.notified().await;
+        }
+    }
+
+    /// Stop the actor once it handled the mail already queued, resolving after
+    /// `stopped` ran with the reason it ended for; new mail is refused
+    /// Resolves right away for an actor that already stopped
+    pub async fn stop_graceful(&self) -> TerminationReason {
+        let terminated = self.terminated();
+        self.mailbox.close();
+        terminated.await
+    }
+
+    /// `stop_graceful`, giving up on the queued mail after `timeout`: the
+    /// actor is then killed like with `kill`, so a running async handler is
+    /// dropped where it awaits and the reason is `TerminationReason::Killed`
+    pub async fn stop_graceful_timeout(&self, timeout: Duration) -> TerminationReason {
+        let terminated = self.terminated();
+        self.mailbox.close();
+        tokio::pin!(terminated);
+        match tokio::time::timeout(timeout, &mut terminated).await {
+            Ok(reason) => reason,
+            Err(_) => {
+                self.kill();
+                terminated.await
+            }
+        }
+    }
+
+    /// Stop the actor right away: a running handler is dropped where it
+    /// awaits, without the stop grace, and `stopped` hooks don't run
+    /// Queued mail becomes dead letters; watchers see `TerminationReason::Killed`
+    /// For actors stuck in an async handler, where `ChildHandle::stop` would wait.
+    /// A sync handler can't be interrupted, the actor is killed once it returns
+    pub fn kill(&self) {
+        self.killed.store(true, Ordering::SeqCst);
+        self.mailbox.close();
+        self.stop_signal.notify_one();
+    }
+
+    ///whether `kill` was called, for the actor loop
+    pub(crate) fn is_killed(&self) -> bool {
+        self.killed.load(Ordering::SeqCst)
+    }
+
+    ///resolves with the reason the actor stopped for
+    fn terminated(&self) -> impl std::future::Future<Output = TerminationReason> {
+        let (tx, rx) = oneshot::channel();
+        self.watchers.add(Arc::new(TerminationWaiter(Mutex::new(Some(tx)))));
+        async move { rx.await.unwrap_or(TerminationReason::Normal) }
+    }
+
+    ///Close the mailbox once the actor loop has exited
+    pub(crate) fn close_mailbox(&self) {
+        self.mailbox.close();
+    }
+
+    pub(crate) fn notify_watchers(
+        &self,
+        reason: TerminationReason,
+        backtrace: Option<Arc<Backtrace>>,
+    ) {
+        self.watchers.notify_all(Terminated {
+            id: self.id,
+            reason,
+            name: self.name.clone(),
+            path: self.path.clone(),
+            backtrace,
+        });
+    }
+}
+
+///Type erased address of any actor, as returned by `ActorSystem::resolve`
+/// Can stop the actor, report metadata, or be downcast back to a typed Addr
+#[derive(Clone)]
+pub struct ErasedAddr {
+    id: ActorId,
+    name: Option<Arc<str>>,
+    path: Arc<str>,
+    actor_type: &'static str,
+    activity: Arc<Activity>,
+    addr: Arc<dyn Any + Send + Sync>,
+    control: Arc<dyn ChildHandle>,
+    watchers: Arc<WatcherList>,
+}
+
+impl ErasedAddr {
+    pub fn id(&self) -> ActorId {
+        self.id
+    }
+
+    /// Name given with `SpawnOptions::name`, if any
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Place in the supervision tree, see `Addr::path`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Type name of the actor behind this address
+    pub fn actor_type(&self) -> &'static str {
+        self.actor_type
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.control.is_alive()
+    }
+
+    /// Approximate number of queued messages
+    pub fn mailbox_len(&self) -> usize {
+        self.control.mailbox_len()
+    }
+
+    /// See `Addr::messages_processed`
+    pub fn messages_processed(&self) -> u64 {
+        self.activity.processed()
+    }
+
+    /// See `Addr::last_active`
+    pub fn last_active(&self) -> Option<SystemTime> {
+        self.activity.last_active()
+    }
+
+    ///nothing queued and no handler running, see `ActorSystem::wait_idle`
+    pub(crate) fn is_idle(&self) -> bool {
+        !self.activity.is_handling() && self.control.mailbox_len() == 0
+    }
+
+    pub fn stop(&self) {
+        self.control.stop();
     }
 
-    pub(crate) fn notify_watchers(&self) {
-        let watchers = self.watchers.lock().unwrap();
-        for watcher in watchers.iter() {
-            watcher.notify(self.id);
+    pub(crate) fn close(&self) {
+        self.control.close();
+    }
+
+    pub(crate) fn add_watcher(&self, watcher: Arc<dyn Watcher>) {
+        self.watchers.add(watcher);
+    }
+
+    /// Typed address, None if the actor is not an A
+    pub fn downcast<A: Actor>(&self) -> Option<Addr<A>> {
+        self.addr.downcast_ref::<Addr<A>>().map(Addr::counted)
+    }
+}
+
+impl<A: Actor> From<Addr<A>> for ErasedAddr {
+    fn from(addr: Addr<A>) -> Self {
+        Self {
+            id: addr.id,
+            name: addr.name.clone(),
+            path: addr.path.clone(),
+            actor_type: std::any::type_name::<A>(),
+            activity: addr.activity.clone(),
+            control: Arc::new(addr.clone()),
+            watchers: addr.watchers.clone(),
+            addr: Arc::new(addr),
         }
     }
 }
 
 impl<A: Actor> Clone for Addr<A> {
+    fn clone(&self) -> Self {
+        self.with_counted(self.counted)
+    }
+}
+
+impl<A: Actor> Drop for Addr<A> {
+    fn drop(&mut self) {
+        if self.counted && self.refs.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.refs.released.notify_one();
+        }
+    }
+}
+
+///Non-owning reference to an actor, created with `Addr::downgrade`
+/// Caches and registries can hold it without keeping the actor running
+pub struct WeakAddr<A: Actor> {
+    addr: Addr<A>,
+}
+
+impl<A: Actor> WeakAddr<A> {
+    pub fn id(&self) -> ActorId {
+        self.addr.id
+    }
+
+    /// Strong address, None once the actor has stopped
+    pub fn upgrade(&self) -> Option<Addr<A>> {
+        self.addr.is_alive().then(|| self.addr.counted())
+    }
+
+    ///Check if the actor is still alive
+    pub fn is_alive(&self) -> bool {
+        self.addr.is_alive()
+    }
+}
+
+impl<A: Actor> Clone for WeakAddr<A> {
+    fn clone(&self) -> Self {
+        Self {
+            addr: self.addr.clone(),
+        }
+    }
+}
+
+///Type erased sending half of a Recipient
+trait MessageSender<M: Message>: Send + Sync {
+    fn send(&self, msg: M) -> BoxFuture<'static, Result<M::Result, MailboxError>>;
+    fn do_send(&self, msg: M) -> BoxFuture<'static, Result<(), MailboxError>>;
+    fn try_send(&self, msg: M) -> Result<(), MailboxError>;
+}
+
+impl<A, M> MessageSender<M> for Addr<A>
+where
+    A: Actor + Handler<M>,
+    M: Message,
+{
+    fn send(&self, msg: M) -> BoxFuture<'static, Result<M::Result, MailboxError>> {
+        let addr = self.clone();
+        Box::pin(async move { addr.send(msg).await })
+    }
+
+    fn do_send(&self, msg: M) -> BoxFuture<'static, Result<(), MailboxError>> {
+        let addr = self.clone();
+        Box::pin(async move { addr.do_send(msg).await })
+    }
+
+    fn try_send(&self, msg: M) -> Result<(), MailboxError> {
+        Addr::try_send(self, msg)
+    }
+}
+
+///Address of any actor handling `M`, created with `Addr::recipient`
+/// Keeps the actor alive like the Addr it came from
+pub struct Recipient<M: Message> {
+    id: ActorId,
+    path: Arc<str>,
+    sender: Arc<dyn MessageSender<M>>,
+}
+
+impl<M: Message> Recipient<M> {
+    pub fn id(&self) -> ActorId {
+        self.id
+    }
+
+    /// Place in the supervision tree, see `Addr::path`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    ///Send message and wait for response
+    pub async fn send(&self, msg: M) -> Result<M::Result, MailboxError> {
+        self.sender.send(msg).await
+    }
+
+    ///Fire and forget message sending
+    pub async fn do_send(&self, msg: M) -> Result<(), MailboxError> {
+        self.sender.do_send(msg).await
+    }
+
+    /// Try to send a message without blocking
+    /// Returns MailboxFull if the mailbox is at capacity
+    pub fn try_send(&self, msg: M) -> Result<(), MailboxError> {
+        self.sender.try_send(msg)
+    }
+}
+
+impl<M: Message> Clone for Recipient<M> {
     fn clone(&self) -> Self {
         Self {
-            sender: self.sender.clone(),
             id: self.id,
-            watchers: self.watchers.clone(),
-            stop_signal: self.stop_signal.clone(),
+            path: self.path.clone(),
+            sender: self.sender.clone(),
         }
     }
 }
@@ -195,8 +955,20 @@ impl<A> Watcher for Addr<A>
 where
     A: Actor + Handler<Terminated>,
 {
-    fn notify(&self, id: ActorId) {
-        let _ = self.try_send(Terminated { id });
+    fn notify(&self, msg: Terminated) {
+        let _ = self.try_send(msg);
+    }
+}
+
+///hands the termination reason to `Addr::stop_graceful`
+struct TerminationWaiter(Mutex<Option<oneshot::Sender<TerminationReason>>>);
+
+impl Watcher for TerminationWaiter {
+    fn notify(&self, msg: Terminated) {
+        let tx = self.0.lock().unwrap_or_else(|p| p.into_inner()).take();
+        if let Some(tx) = tx {
+            let _ = tx.send(msg.reason);
+        }
     }
 }
 
@@ -206,6 +978,14 @@ impl<A: Actor> ChildHandle for Addr<A> {
     }
 
     fn is_alive(&self) -> bool {
-        !self.sender.is_closed()
+        !self.mailbox.is_closed()
+    }
+
+    fn mailbox_len(&self) -> usize {
+        self.mailbox.len()
+    }
+
+    fn close(&self) {
+        self.mailbox.close();
     }
 }
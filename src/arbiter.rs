@@ -0,0 +1,81 @@
+use std::future::Future;
+
+use tokio::{
+    runtime::{Builder, Handle},
+    sync::oneshot,
+    task::JoinHandle,
+};
+
+/// A dedicated single-threaded runtime on its own OS thread
+/// Actors spawned with `SpawnOptions::arbiter` run there instead of on the
+/// shared runtime, isolating their latency from everything else; their
+/// children and timers stay on the arbiter too.
+/// Stopping or dropping the arbiter shuts its runtime down, dropping every
+/// actor still running on it: their watchers see them stop as `Killed`,
+/// without `stopped` having run
+pub struct Arbiter {
+    handle: Handle,
+    stop: Option<oneshot::Sender<()>>,
+}
+
+impl Arbiter {
+    pub fn new() -> Self {
+        Self::with_name("cinema-arbiter")
+    }
+
+    /// Arbiter whose thread carries `name`, as shown by debuggers and profilers
+    pub fn with_name(name: impl Into<String>) -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build arbiter runtime");
+        let handle = runtime.handle().clone();
+        let (stop, stopped) = oneshot::channel::<()>();
+
+        std::thread::Builder::new()
+            .name(name.into())
+            .spawn(move || {
+                //runs spawned tasks until stopped; dropping the runtime cancels the rest
+                let _ = runtime.block_on(stopped);
+            })
+            .expect("failed to spawn arbiter thread");
+
+        Self {
+            handle,
+            stop: Some(stop),
+        }
+    }
+
+    /// Handle to the arbiter's runtime
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// Run a future on the arbiter
+    pub fn spawn<F>(&self, fut: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.handle.spawn(fut)
+    }
+
+    /// Shut the runtime down, same as dropping the arbiter
+    pub fn stop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+    }
+}
+
+impl Default for Arbiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Arbiter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::{
+    remote::{proto::Envelope, RemoteAddr, RemoteMessage, TransportError},
+    Actor, Addr, Handler, MailboxError, Message,
+};
+
+/// Where a CircuitBreaker stands, see `CircuitBreaker::state`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    ///calls go through
+    Closed,
+    ///calls fail fast until the cooldown is over
+    Open,
+    ///the cooldown is over, the next call decides whether to close again
+    HalfOpen,
+}
+
+/// Errors a CircuitBreaker can judge and return
+pub trait BreakerError {
+    ///true if the error counts towards tripping the breaker
+    fn is_failure(&self) -> bool;
+    ///the error returned without calling while the breaker is open
+    fn circuit_open() -> Self;
+}
+
+impl BreakerError for MailboxError {
+    fn is_failure(&self) -> bool {
+        matches!(self, MailboxError::Timeout | MailboxError::MailboxClosed)
+    }
+
+    fn circuit_open() -> Self {
+        MailboxError::CircuitOpen
+    }
+}
+
+impl BreakerError for TransportError {
+    fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            TransportError::Timeout | TransportError::Disconnected | TransportError::Io(_)
+        )
+    }
+
+    fn circuit_open() -> Self {
+        TransportError::CircuitOpen
+    }
+}
+
+struct Circuit {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<CircuitState>,
+}
+
+#[derive(Default)]
+struct CircuitState {
+    ///consecutive failures since the last success
+    failures: u32,
+    opened_at: Option<Instant>,
+    ///a half-open trial call is in flight
+    probing: bool,
+}
+
+impl Circuit {
+    fn lock(&self) -> MutexGuard<'_, CircuitState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn state(&self) -> BreakerState {
+        let state = self.lock();
+        match state.opened_at {
+            None => BreakerState::Closed,
+            Some(at) if at.elapsed() >= self.cooldown => BreakerState::HalfOpen,
+            Some(_) => BreakerState::Open,
+        }
+    }
+
+    ///whether a call may go through; once cooled down only one trial call at a time
+    fn admit(&self) -> bool {
+        let mut state = self.lock();
+        match state.opened_at {
+            None => true,
+            Some(at) if at.elapsed() >= self.cooldown && !state.probing => {
+                state.probing = true;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    fn record(&self, failed: bool) {
+        let mut state = self.lock();
+        if failed {
+            state.failures += 1;
+            if state.probing || state.failures >= self.threshold {
+                state.opened_at = Some(Instant::now());
+            }
+        } else {
+            state.failures = 0;
+            state.opened_at = None;
+        }
+        state.probing = false;
+    }
+}
+
+///an admitted call, releases the trial slot if the caller drops it midway
+struct Attempt<'a> {
+    circuit: &'a Circuit,
+    finished: bool,
+}
+
+impl Attempt<'_> {
+    fn finish(mut self, failed: bool) {
+        self.finished = true;
+        self.circuit.record(failed);
+    }
+}
+
+impl Drop for Attempt<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.circuit.lock().probing = false;
+        }
+    }
+}
+
+/// Wraps an `Addr` or `RemoteAddr` so that after `failure_threshold`
+/// consecutive timeouts or lost connections calls fail fast with a
+/// `CircuitOpen` error, instead of each waiting for its own timeout.
+/// After `cooldown` one trial call is let through: success closes the
+/// breaker, failure opens it for another cooldown. Clones share the state
+pub struct CircuitBreaker<T> {
+    target: T,
+    circuit: Arc<Circuit>,
+}
+
+impl<T: Clone> Clone for CircuitBreaker<T> {
+    fn clone(&self) -> Self {
+        Self {
+            target: self.target.clone(),
+            circuit: self.circuit.clone(),
+        }
+    }
+}
+
+impl<T> CircuitBreaker<T> {
+    pub fn new(target: T, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            target,
+            circuit: Arc::new(Circuit {
+                threshold: failure_threshold.max(1),
+                cooldown,
+                state: Mutex::new(CircuitState::default()),
+            }),
+        }
+    }
+
+    /// The wrapped address, calls made on it directly bypass the breaker
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.circuit.state()
+    }
+
+    /// Run any call on the target through the breaker,
+    /// e.g. `breaker.call(breaker.target().send_async(msg))`
+    pub async fn call<F, R, E>(&self, call: F) -> Result<R, E>
+    where
+        F: Future<Output = Result<R, E>>,
+        E: BreakerError,
+    {
+        if !self.circuit.admit() {
+            return Err(E::circuit_open());
+        }
+        let attempt = Attempt {
+            circuit: &self.circuit,
+            finished: false,
+        };
+        let result = call.await;
+        attempt.finish(result.as_ref().is_err_and(BreakerError::is_failure));
+        result
+    }
+}
+
+impl<A: Actor> CircuitBreaker<Addr<A>> {
+    ///Send message and wait for response
+    pub async fn send<M>(&self, msg: M) -> Result<M::Result, MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        self.call(self.target.send(msg)).await
+    }
+
+    pub async fn send_timeout<M>(
+        &self,
+        msg: M,
+        timeout: Duration,
+    ) -> Result<M::Result, MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        self.call(self.target.send_timeout(msg, timeout)).await
+    }
+
+    ///Fire and forget message sending
+    pub async fn do_send<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        self.call(self.target.do_send(msg)).await
+    }
+}
+
+impl<A> CircuitBreaker<RemoteAddr<A>> {
+    pub async fn send<M>(&self, msg: M) -> Result<Envelope, TransportError>
+    where
+        M: RemoteMessage,
+    {
+        self.call(self.target.send(msg)).await
+    }
+
+    pub async fn send_timeout<M>(&self, msg: M, timeout: Duration) -> Result<Envelope, TransportError>
+    where
+        M: RemoteMessage,
+    {
+        self.call(self.target.send_timeout(msg, timeout)).await
+    }
+
+    ///fire and forget send to remote actor
+    pub async fn do_send<M>(&self, msg: M) -> Result<(), TransportError>
+    where
+        M: RemoteMessage,
+    {
+        self.call(self.target.do_send(msg)).await
+    }
+}
@@ -1,80 +1,131 @@
 use std::{
-    panic::{catch_unwind, AssertUnwindSafe},
+    collections::VecDeque,
+    future::Future,
     pin::Pin,
-    sync::Arc,
-    task::Poll,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 
-use futures::{FutureExt, Stream};
-use tokio::sync::{mpsc, Notify};
+use futures::{Stream, StreamExt};
+use tokio::sync::{oneshot, Notify};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    actor::{ActorId, StreamHandler},
+    actor::{ActorId, AsyncHandler, BoxFuture, StreamHandler},
     address::ChildHandle,
-    envelope::ActorMessage,
-    message::Terminated,
-    stream::{poll_streams, ActorStream, StreamWrapper},
-    supervisor::RestartTracker,
-    Actor, Addr, Handler, Message, SupervisorStrategy, TimerHandle,
+    directory::Shutdown,
+    envelope::{
+        ActorMessage, ChildFailedEnvelope, ChildFailureEnvelope, ChildRestartedEnvelope,
+        ClosureEnvelope, ContinuationEnvelope, MessageEnvelope,
+    },
+    mailbox::{ChannelMailbox, Mailbox, Priority, DEFAULT_CAPACITY},
+    message::{ChildFailed, MessageSet, StreamFinished, Terminated, TerminationReason},
+    persistence::Persistence,
+    response::Forwarded,
+    stream::{ActorStream, StreamWrapper},
+    supervisor::{ChildSpec, SupervisionMode, Supervisor},
+    system::{spawn_actor, Decide, Hooks, SpawnOptions, Supervision},
+    watcher::{MappedWatcher, Watcher},
+    timer::Interval,
+    Actor, Addr, Handler, MailboxError, Message, SupervisorStrategy, TimerHandle,
 };
 
+static NEXT_STREAM_ID: AtomicU64 = AtomicU64::new(1);
+
 ///Runtime context for an actor
 pub struct Context<A: Actor> {
     addr: Addr<A>,
     ///signal to stop the actor
     stop_signal: Option<Arc<Notify>>,
-    shutdown: Arc<Notify>,
-    children: Vec<Box<dyn ChildHandle>>,
+    shutdown: Arc<Shutdown>,
+    children: Vec<SupervisedChild>,
+    supervision_mode: SupervisionMode,
     escalate_signal: Arc<Notify>,
     streams: Vec<Pin<Box<dyn ActorStream<A>>>>,
+    cancellation: CancellationToken,
+    stop_grace: Option<Duration>,
+    ///child failure this actor did not handle, fails it after the current message
+    escalation: Option<ChildFailed>,
+    ///messages sent with `notify`, handled before the mailbox
+    notifications: VecDeque<ActorMessage<A>>,
+    ///futures registered with `wait`, in order
+    waits: VecDeque<BoxFuture<'static, ()>>,
+    ///message types handled while set, see `receive_only`
+    receive_only: Option<Vec<&'static str>>,
+    ///set once a persistent actor recovered, see `PersistentActor`
+    persistence: Option<Persistence>,
 }
 
 impl<A: Actor> Context<A> {
-    pub fn new(addr: Addr<A>, shutdown: Arc<Notify>) -> Self {
-        Self {
-            addr,
-            stop_signal: None,
-            shutdown,
-            children: Vec::new(),
-            escalate_signal: Arc::new(Notify::new()),
-            streams: Vec::new(),
-        }
-    }
-
     ///configure the context with a stop signal for graceful shutdown
-    pub fn with_stop_signal(
+    pub(crate) fn with_stop_signal(
         addr: Addr<A>,
         stop_signal: Arc<Notify>,
-        shutdown: Arc<Notify>,
+        shutdown: Arc<Shutdown>,
     ) -> Self {
         Self {
             addr,
             stop_signal: Some(stop_signal),
             shutdown,
             children: Vec::new(),
+            supervision_mode: SupervisionMode::OneForOne,
             escalate_signal: Arc::new(Notify::new()),
             streams: Vec::new(),
+            cancellation: CancellationToken::new(),
+            stop_grace: None,
+            escalation: None,
+            notifications: VecDeque::new(),
+            waits: VecDeque::new(),
+            receive_only: None,
+            persistence: None,
         }
     }
 
-    ///configure the context with custom signals
-    pub fn with_signals(
-        addr: Addr<A>,
-        stop_signal: Arc<Notify>,
-        shutdown: Arc<Notify>,
-        escalate_signal: Arc<Notify>,
-    ) -> Self {
-        Self {
-            addr,
-            stop_signal: Some(stop_signal),
-            shutdown,
-            children: Vec::new(),
-            escalate_signal,
-            streams: Vec::new(),
+    /// Token cancelled when the actor is asked to stop
+    /// Long async handlers should watch it and wind down early
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// How long a running async handler may keep going after a stop request
+    /// before it is dropped; by default the stop waits for it to finish
+    pub fn set_stop_grace(&mut self, grace: Duration) {
+        self.stop_grace = Some(grace);
+    }
+
+    pub(crate) fn stop_grace(&self) -> Option<Duration> {
+        self.stop_grace
+    }
+
+    /// Whether a child's restart restarts its siblings too (OneForOne by default)
+    pub fn set_supervision_mode(&mut self, mode: SupervisionMode) {
+        self.supervision_mode = mode;
+    }
+
+    ///a child was restarted after failing, bring its siblings along under AllForOne
+    pub(crate) fn child_restarted(&mut self, id: ActorId) {
+        if self.supervision_mode != SupervisionMode::AllForOne {
+            return;
+        }
+        for child in &self.children {
+            if child.id != id && child.handle.is_alive() {
+                child.restart.notify_one();
+            }
         }
     }
 
+    ///Fail this actor with a child's failure once the current message is done
+    pub(crate) fn escalate(&mut self, failure: ChildFailed) {
+        self.escalation = Some(failure);
+    }
+
+    pub(crate) fn take_escalation(&mut self) -> Option<ChildFailed> {
+        self.escalation.take()
+    }
+
     ///Get the escalate signal for this actor
     pub fn escalate_signal(&self) -> Arc<Notify> {
         self.escalate_signal.clone()
@@ -83,13 +134,14 @@ impl<A: Actor> Context<A> {
     ///Stop all child actors (when this actor stops)
     pub fn stop_children(&mut self) {
         for child in &self.children {
-            child.stop();
+            child.handle.stop();
         }
     }
 
     ///Get the address of the actor associated with this context
+    /// The returned Addr keeps the actor alive like any other
     pub fn address(&self) -> Addr<A> {
-        self.addr.clone()
+        self.addr.counted()
     }
 
     /// Get this actor's ID
@@ -97,6 +149,16 @@ impl<A: Actor> Context<A> {
         self.addr.id()
     }
 
+    /// This actor's name, if it was spawned with one
+    pub fn name(&self) -> Option<&str> {
+        self.addr.name()
+    }
+
+    /// This actor's place in the supervision tree, e.g. `/user/ingest/worker-3`
+    pub fn path(&self) -> &str {
+        self.addr.path()
+    }
+
     ///stop the actor associated with this context
     pub fn stop(&self) {
         if let Some(signal) = &self.stop_signal {
@@ -115,6 +177,122 @@ impl<A: Actor> Context<A> {
         addr.add_watcher(self.addr.clone());
     }
 
+    /// Watch another actor, mapping its termination into a custom message
+    /// Lets one actor watch many peers without keeping an id -> meaning map
+    pub fn watch_with<B, M, F>(&self, addr: &Addr<B>, map: F)
+    where
+        B: Actor,
+        M: Message,
+        A: Handler<M>,
+        F: Fn(Terminated) -> M + Send + Sync + 'static,
+    {
+        addr.add_watcher_erased(Arc::new(MappedWatcher::new(self.addr.clone(), map)));
+    }
+
+    /// Send a message to self, handled before anything waiting in the mailbox
+    /// Bypasses the mailbox, so it never fails or waits for capacity
+    pub fn notify<M>(&mut self, msg: M)
+    where
+        M: Message,
+        A: Handler<M>,
+    {
+        self.notifications
+            .push_back(ActorMessage::Sync(Box::new(MessageEnvelope::new(msg))));
+    }
+
+    /// Hand the message being handled on to `addr`, to be returned from the
+    /// handler: the caller's reply channel goes along, so `addr` answers the
+    /// caller directly instead of through this actor
+    /// If `addr` has stopped the caller gets MailboxClosed
+    pub fn forward<B, M>(&self, addr: &Addr<B>, msg: M) -> Forwarded<M::Result>
+    where
+        B: Handler<M>,
+        M: Message,
+    {
+        let addr = addr.clone();
+        Forwarded::new(move |tx| {
+            let _ = addr.forward(msg, tx);
+        })
+    }
+
+    pub(crate) fn take_notification(&mut self) -> Option<ActorMessage<A>> {
+        self.notifications.pop_front()
+    }
+
+    ///queue an envelope like `notify` does a message
+    pub(crate) fn notify_envelope(&mut self, msg: ActorMessage<A>) {
+        self.notifications.push_back(msg);
+    }
+
+    pub(crate) fn set_persistence(&mut self, persistence: Persistence) {
+        self.persistence = Some(persistence);
+    }
+
+    pub(crate) fn persistence(&self) -> Option<&Persistence> {
+        self.persistence.as_ref()
+    }
+
+    pub(crate) fn persistence_mut(&mut self) -> Option<&mut Persistence> {
+        self.persistence.as_mut()
+    }
+
+    /// Suspend message processing until `fut` resolves
+    /// Takes effect once the current handler returns; mail keeps queueing
+    /// meanwhile and a stop request still ends the actor
+    pub fn wait<F>(&mut self, fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.waits.push_back(Box::pin(fut));
+    }
+
+    pub(crate) fn take_wait(&mut self) -> Option<BoxFuture<'static, ()>> {
+        self.waits.pop_front()
+    }
+
+    /// Handle only messages of the types in `S` until `receive_all`, e.g.
+    /// `ctx.receive_only::<(CommitVote, Abort)>()` while a commit is pending
+    /// Other mail, `spawn_future` continuations included, is set aside in
+    /// arrival order and handled once it is accepted again, before anything
+    /// still in the mailbox. A restarted instance starts out receiving
+    /// everything; mail set aside when the actor stops becomes dead letters
+    pub fn receive_only<S: MessageSet>(&mut self) {
+        self.receive_only = Some(S::message_types());
+    }
+
+    /// Handle every message type again, set-aside mail first
+    pub fn receive_all(&mut self) {
+        self.receive_only = None;
+    }
+
+    ///whether a message of this type is handled now or set aside
+    pub(crate) fn accepts(&self, message_type: &str) -> bool {
+        self.receive_only
+            .as_ref()
+            .is_none_or(|types| types.contains(&message_type))
+    }
+
+    /// Send a message to self after delay, in the high priority lane
+    /// (ahead of queued mail when the actor uses a `PriorityMailbox`)
+    /// Returns a TimerHandle that can be used to cancel it
+    pub fn notify_later<M>(&self, msg: M, delay: Duration) -> TimerHandle
+    where
+        M: Message,
+        A: Handler<M>,
+    {
+        let addr = self.address();
+        let handle = TimerHandle::after(delay);
+        let handle_clone = handle.clone();
+
+        tokio::spawn(async move {
+            if handle_clone.expired().await {
+                let _ = addr.do_send_priority(msg, Priority::High).await;
+            }
+        });
+
+        handle
+    }
+
     /// Send a message to self after delay
     /// Returns a TimerHandle that can be used to cancel the timer
     pub fn run_later<M>(&self, delay: Duration, msg: M) -> TimerHandle
@@ -122,13 +300,12 @@ impl<A: Actor> Context<A> {
         M: Message,
         A: Handler<M>,
     {
-        let addr = self.addr.clone();
-        let handle = TimerHandle::new();
+        let addr = self.address();
+        let handle = TimerHandle::after(delay);
         let handle_clone = handle.clone();
 
         tokio::spawn(async move {
-            tokio::time::sleep(delay).await;
-            if !handle_clone.is_cancelled() {
+            if handle_clone.expired().await {
                 let _ = addr.do_send(msg).await;
             }
         });
@@ -136,25 +313,141 @@ impl<A: Actor> Context<A> {
         handle
     }
 
+    /// Run a closure in this actor's context after delay
+    /// The closure is queued like a message, so it never overlaps a handler
+    /// Returns a TimerHandle that can be used to cancel it
+    pub fn run_later_with<F>(&self, delay: Duration, f: F) -> TimerHandle
+    where
+        F: for<'a> FnOnce(&'a mut A, &'a mut Context<A>) -> BoxFuture<'a, ()> + Send + 'static,
+    {
+        let addr = self.address();
+        let handle = TimerHandle::after(delay);
+        let handle_clone = handle.clone();
+
+        tokio::spawn(async move {
+            if handle_clone.expired().await {
+                let envelope = ClosureEnvelope { f };
+                let _ = addr.send_envelope(ActorMessage::Async(Box::new(envelope))).await;
+            }
+        });
+
+        handle
+    }
+
+    /// Run a future next to the actor, then hand its output to `then` on the actor
+    /// The continuation is queued like a message and gets `&mut` access to the
+    /// actor; the future is dropped if the actor stops or restarts first
+    pub fn spawn_future<Fut, C>(&self, fut: Fut, then: C)
+    where
+        Fut: Future + Send + 'static,
+        Fut::Output: Send + 'static,
+        C: FnOnce(Fut::Output, &mut A, &mut Context<A>) + Send + 'static,
+    {
+        let addr = self.address();
+        let token = self.cancellation_token();
+
+        tokio::spawn(async move {
+            let output = tokio::select! {
+                _ = token.cancelled() => return,
+                output = fut => output,
+            };
+            let envelope = ContinuationEnvelope {
+                f: move |actor: &mut A, ctx: &mut Context<A>| then(output, actor, ctx),
+            };
+            let _ = addr.send_envelope(ActorMessage::Sync(Box::new(envelope))).await;
+        });
+    }
+
+    /// Ask `addr` without holding up this actor: finish with `then`, which gets
+    /// the reply on the actor once it arrives, queued like a message
+    /// Mail keeps being handled meanwhile, unlike awaiting `addr.send` in a handler
+    pub fn request<B, M>(&self, addr: &Addr<B>, msg: M) -> Request<'_, A, B, M>
+    where
+        B: Handler<M>,
+        M: Message,
+    {
+        Request {
+            ctx: self,
+            addr: addr.clone(),
+            msg,
+            timeout: None,
+        }
+    }
+
     /// Send a message to self repeatedly at fixed intervals
+    /// Takes a Duration, or an `Interval` to add jitter or change missed-tick handling
     /// Returns a TimerHandle that can be used to cancel the interval
-    pub fn run_interval<M>(&self, interval: Duration, msg: M) -> TimerHandle
+    pub fn run_interval<M>(&self, interval: impl Into<Interval>, msg: M) -> TimerHandle
     where
         M: Message + Clone,
         A: Handler<M>,
     {
-        let addr = self.addr.clone();
+        self.run_interval_with(interval, move || msg.clone())
+    }
+
+    /// `run_interval` building a fresh message on every tick
+    /// For messages that can't be cloned (reply channels, owned buffers)
+    pub fn run_interval_with<M, F>(&self, interval: impl Into<Interval>, make: F) -> TimerHandle
+    where
+        M: Message,
+        A: Handler<M>,
+        F: Fn() -> M + Send + 'static,
+    {
+        let addr = self.address();
         let handle = TimerHandle::new();
         let handle_clone = handle.clone();
+        let mut ticker = interval.into().ticker();
 
         tokio::spawn(async move {
-            let mut ticker = tokio::time::interval(interval);
             loop {
                 ticker.tick().await;
                 if !addr.is_alive() || handle_clone.is_cancelled() {
                     break;
                 }
-                let _ = addr.do_send(msg.clone()).await;
+                let _ = addr.do_send(make()).await;
+            }
+        });
+
+        handle
+    }
+
+    /// `run_later` for messages handled by an `AsyncHandler`
+    pub fn run_later_async<M>(&self, delay: Duration, msg: M) -> TimerHandle
+    where
+        M: Message,
+        A: AsyncHandler<M>,
+    {
+        let addr = self.address();
+        let handle = TimerHandle::after(delay);
+        let handle_clone = handle.clone();
+
+        tokio::spawn(async move {
+            if handle_clone.expired().await {
+                let _ = addr.do_send_async(msg).await;
+            }
+        });
+
+        handle
+    }
+
+    /// `run_interval` for messages handled by an `AsyncHandler`
+    pub fn run_interval_async<M>(&self, interval: impl Into<Interval>, msg: M) -> TimerHandle
+    where
+        M: Message + Clone,
+        A: AsyncHandler<M>,
+    {
+        let addr = self.address();
+        let handle = TimerHandle::new();
+        let handle_clone = handle.clone();
+        let mut ticker = interval.into().ticker();
+
+        tokio::spawn(async move {
+            loop {
+                ticker.tick().await;
+                if !addr.is_alive() || handle_clone.is_cancelled() {
+                    break;
+                }
+                let _ = addr.do_send_async(msg.clone()).await;
             }
         });
 
@@ -170,7 +463,26 @@ impl<A: Actor> Context<A> {
         C: Actor,
         A: Handler<Terminated>,
     {
-        self.spawn_child_with_capacity(child, 256)
+        self.spawn_child_with_capacity(child, DEFAULT_CAPACITY)
+    }
+
+    ///Spawn a child actor configured by SpawnOptions
+    pub fn spawn_child_with<C>(&mut self, child: C, mut options: SpawnOptions) -> Addr<C>
+    where
+        C: Actor,
+        A: Handler<Terminated>,
+    {
+        let hooks = options.hooks();
+        let _runtime = options.enter();
+        let mut child_opt = Some(child);
+        self.spawn_supervised_child(
+            move || child_opt.take().expect("Factory called more than once"),
+            SupervisorStrategy::Stop,
+            options.mailbox(),
+            options.name,
+            hooks,
+            None,
+        )
     }
 
     ///Spawn a child actor with custom mailbox capacity
@@ -198,13 +510,13 @@ impl<A: Actor> Context<A> {
         A: Handler<Terminated>,
         F: FnMut() -> C + Send + 'static,
     {
-        self.spawn_child_with_strategy_and_capacity(factory, strategy, 256)
+        self.spawn_child_with_strategy_and_capacity(factory, strategy, DEFAULT_CAPACITY)
     }
 
     /// Spawn a child with custom restart strategy and mailbox capacity
     pub fn spawn_child_with_strategy_and_capacity<C, F>(
         &mut self,
-        mut factory: F,
+        factory: F,
         strategy: SupervisorStrategy,
         capacity: usize,
     ) -> Addr<C>
@@ -213,132 +525,153 @@ impl<A: Actor> Context<A> {
         A: Handler<Terminated>,
         F: FnMut() -> C + Send + 'static,
     {
-        let (tx, mut rx) = mpsc::channel::<ActorMessage<C>>(capacity);
-        let child_id = ActorId::new();
-        let child_stop_signal = Arc::new(Notify::new());
-        let child_addr = Addr::new(tx, child_id, child_stop_signal.clone());
-
-        let shutdown = self.shutdown.clone();
-        let child_addr_for_notify = child_addr.clone();
-
-        let parent_escalate_signal = self.escalate_signal.clone();
-
-        tokio::spawn(async move {
-            let mut tracker = match &strategy {
-                SupervisorStrategy::Restart {
-                    max_restarts,
-                    within,
-                } => Some(RestartTracker::new(*max_restarts, *within)),
-                _ => None,
-            };
+        self.spawn_child_with_mailbox(factory, strategy, ChannelMailbox::new(capacity))
+    }
 
-            'restart: loop {
-                let mut child = factory();
-                let mut child_ctx = Context::with_stop_signal(
-                    child_addr_for_notify.clone(),
-                    child_stop_signal.clone(),
-                    shutdown.clone(),
-                );
-
-                child.started(&mut child_ctx);
-
-                let child_escalate_signal = child_ctx.escalate_signal();
-
-                // Streams for this child instance
-                let mut streams = Vec::new();
-
-                let panic_occurred = loop {
-                    // Grab any new streams added during last iteration
-                    streams.append(&mut child_ctx.take_streams());
-
-                    // Create stream polling future
-                    let stream_poll = std::future::poll_fn(|task_ctx| {
-                        if streams.is_empty() {
-                            Poll::Pending
-                        } else if poll_streams(&mut streams, &mut child, &mut child_ctx, task_ctx)
-                        {
-                            Poll::Ready(())
-                        } else {
-                            Poll::Pending
-                        }
-                    });
+    /// Spawn a child with custom restart strategy backed by a custom mailbox
+    /// The mailbox survives restarts, so queued messages reach the new instance
+    pub fn spawn_child_with_mailbox<C, F, M>(
+        &mut self,
+        factory: F,
+        strategy: SupervisorStrategy,
+        mailbox: M,
+    ) -> Addr<C>
+    where
+        C: Actor,
+        A: Handler<Terminated>,
+        F: FnMut() -> C + Send + 'static,
+        M: Mailbox<C>,
+    {
+        self.spawn_supervised_child(
+            factory,
+            strategy,
+            Arc::new(mailbox),
+            None,
+            Hooks::default(),
+            None,
+        )
+    }
 
-                    tokio::select! {
-                        biased;
-
-                        msg = rx.recv() => {
-                            match msg {
-                                Some(actor_msg) => {
-                                    let result = match actor_msg {
-                                        ActorMessage::Sync(envelope) => {
-                                            catch_unwind(AssertUnwindSafe(|| {
-                                                envelope.handle(&mut child, &mut child_ctx)
-                                            }))
-                                        }
-                                        ActorMessage::Async(envelope) => {
-                                            let fut = envelope.handle(&mut child, &mut child_ctx);
-                                            AssertUnwindSafe(fut).catch_unwind().await
-                                        }
-                                    };
-                                    if result.is_err() {
-                                        break true;
-                                    }
-                                }
-                                None => break false,
-                            }
-                        }
-                        _ = stream_poll => {
-                            // Stream item handled inside poll_streams
-                            continue;
-                        }
-                        _ = shutdown.notified() => break false,
-                        _ = child_stop_signal.notified() => break false,
-                        _ = child_escalate_signal.notified() => {
-                            eprintln!("Child received escalate from grandchild.");
-                            break true;
-                        }
-                    }
+    /// Spawn a child whose failures this actor decides on one by one, with
+    /// `Supervisor::on_child_failure`, instead of a strategy fixed up front
+    pub fn spawn_child_directed<C, F>(&mut self, factory: F) -> Addr<C>
+    where
+        C: Actor,
+        A: Supervisor + Handler<Terminated>,
+        F: FnMut() -> C + Send + 'static,
+    {
+        let parent = self.addr.clone();
+        let decide: Decide = Arc::new(move |child, failure| {
+            let parent = parent.clone();
+            Box::pin(async move {
+                let (reply, directive) = oneshot::channel();
+                let envelope = ChildFailureEnvelope {
+                    child,
+                    failure,
+                    reply,
                 };
+                parent.send_envelope(ActorMessage::Sync(Box::new(envelope))).await.ok()?;
+                directive.await.ok()
+            })
+        });
+        self.spawn_supervised_child(
+            factory,
+            SupervisorStrategy::Stop,
+            Arc::new(ChannelMailbox::new(DEFAULT_CAPACITY)),
+            None,
+            Hooks::default(),
+            Some(decide),
+        )
+    }
 
-                child_ctx.stop_children();
-                child.stopped(&mut child_ctx);
-
-                if panic_occurred {
-                    match &strategy {
-                        SupervisorStrategy::Stop => {
-                            eprintln!("Child panicked. Strategy: Stop.");
-                            break 'restart;
-                        }
-                        SupervisorStrategy::Restart { .. } => {
-                            if let Some(ref mut t) = tracker {
-                                if t.record_restart() {
-                                    eprintln!("Child panicked. Restarting...");
-                                    continue 'restart;
-                                } else {
-                                    eprintln!("Child exceeded restart limit. Stopping.");
-                                    break 'restart;
-                                }
-                            }
-                        }
-                        SupervisorStrategy::Escalate => {
-                            eprintln!("Child panicked. Strategy: Escalate. Notifying parent.");
-                            parent_escalate_signal.notify_one();
-                            break 'restart;
-                        }
-                    }
-                } else {
-                    break 'restart;
-                }
+    /// Spawn a child described by a ChildSpec: its own strategy, restart
+    /// budget, mailbox and whether its permanent failure stops this actor
+    pub fn spawn_child_spec<C>(&mut self, spec: ChildSpec<C>) -> Addr<C>
+    where
+        C: Actor,
+        A: Handler<Terminated>,
+    {
+        let ChildSpec {
+            factory,
+            strategy,
+            significant,
+            mut options,
+        } = spec;
+        let hooks = options.hooks();
+        let _runtime = options.enter();
+        let child = self.spawn_supervised_child(
+            factory,
+            strategy,
+            options.mailbox(),
+            options.name,
+            hooks,
+            None,
+        );
+        if significant {
+            if let Some(stop_signal) = &self.stop_signal {
+                child.add_watcher_erased(Arc::new(SignificantChild {
+                    stop_signal: stop_signal.clone(),
+                }));
             }
+        }
+        child
+    }
 
-            child_addr_for_notify.notify_watchers();
-        });
+    fn spawn_supervised_child<C, F>(
+        &mut self,
+        factory: F,
+        strategy: SupervisorStrategy,
+        mailbox: Arc<dyn Mailbox<C>>,
+        name: Option<Arc<str>>,
+        hooks: Hooks<C>,
+        decide: Option<Decide>,
+    ) -> Addr<C>
+    where
+        C: Actor,
+        A: Handler<Terminated>,
+        F: FnMut() -> C + Send + 'static,
+    {
+        let parent = self.addr.clone();
+        let restarted_parent = self.addr.clone();
+        let restart = Arc::new(Notify::new());
+        let child_addr = spawn_actor(
+            factory,
+            strategy,
+            mailbox,
+            self.shutdown.clone(),
+            Supervision::Parent {
+                id: self.id(),
+                report: Arc::new(move |failure| {
+                    let parent = parent.clone();
+                    tokio::spawn(async move {
+                        let envelope = ChildFailedEnvelope { failure };
+                        let _ = parent.send_envelope(ActorMessage::Sync(Box::new(envelope))).await;
+                    });
+                }),
+                restarted: Arc::new(move |id| {
+                    let parent = restarted_parent.clone();
+                    tokio::spawn(async move {
+                        let envelope = ChildRestartedEnvelope { id };
+                        let _ = parent.send_envelope(ActorMessage::Sync(Box::new(envelope))).await;
+                    });
+                }),
+                restart: restart.clone(),
+                decide,
+                path: self.addr.path_arc(),
+            },
+            name,
+            hooks,
+        );
 
         //auto watch the child
         self.watch(&child_addr);
 
         //keep track of child for stopping later
-        self.children.push(Box::new(child_addr.clone()));
+        self.children.push(SupervisedChild {
+            id: child_addr.id(),
+            handle: Box::new(child_addr.clone()),
+            restart,
+        });
 
         child_addr
     }
@@ -354,6 +687,43 @@ impl<A: Actor> Context<A> {
         self.streams.push(Box::pin(wrapper));
     }
 
+    /// Feed a stream's items to this actor as messages, through its mailbox
+    /// Items queue up behind mail that is already waiting and the stream is
+    /// only polled while the mailbox has room. `StreamFinished` with the
+    /// returned id follows the last item. The stream is dropped when the
+    /// actor stops or restarts
+    pub fn add_message_stream<S, M>(&mut self, stream: S) -> u64
+    where
+        S: Stream<Item = M> + Send + 'static,
+        M: Message,
+        A: Handler<M> + Handler<StreamFinished>,
+    {
+        let id = NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed);
+        let addr = self.address();
+        let token = self.cancellation_token();
+
+        tokio::spawn(async move {
+            let mut stream = std::pin::pin!(stream);
+            loop {
+                let item = tokio::select! {
+                    _ = token.cancelled() => return,
+                    item = stream.next() => item,
+                };
+                match item {
+                    Some(msg) => {
+                        if addr.do_send(msg).await.is_err() {
+                            return;
+                        }
+                    }
+                    None => break,
+                }
+            }
+            let _ = addr.do_send(StreamFinished { id }).await;
+        });
+
+        id
+    }
+
     /// Take the streams out of the context (avoids borrow issues)
     pub fn take_streams(&mut self) -> Vec<Pin<Box<dyn ActorStream<A>>>> {
         std::mem::take(&mut self.streams)
@@ -365,3 +735,73 @@ impl<A: Actor> Context<A> {
         self.streams = streams;
     }
 }
+
+/// Ask started with `Context::request`, sent once `then` is called
+#[must_use = "the request is only sent by `then`"]
+pub struct Request<'a, A: Actor, B: Actor, M: Message> {
+    ctx: &'a Context<A>,
+    addr: Addr<B>,
+    msg: M,
+    timeout: Option<Duration>,
+}
+
+impl<A, B, M> Request<'_, A, B, M>
+where
+    A: Actor,
+    B: Handler<M>,
+    M: Message,
+    M::Result: 'static,
+{
+    /// Give up after `timeout`, `then` gets MailboxError::Timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send the request; `then` runs on the actor with the reply or the error
+    /// It is dropped, unrun, if the actor stops or restarts first
+    pub fn then<C>(self, then: C)
+    where
+        C: FnOnce(Result<M::Result, MailboxError>, &mut A, &mut Context<A>) + Send + 'static,
+    {
+        let Self {
+            ctx,
+            addr,
+            msg,
+            timeout,
+        } = self;
+        ctx.spawn_future(
+            async move {
+                match timeout {
+                    Some(timeout) => addr.send_timeout(msg, timeout).await,
+                    None => addr.send(msg).await,
+                }
+            },
+            then,
+        );
+    }
+}
+
+///a child as its parent tracks it
+struct SupervisedChild {
+    id: ActorId,
+    handle: Box<dyn ChildHandle>,
+    ///makes the child start over without counting against its restart budget
+    restart: Arc<Notify>,
+}
+
+///stops the parent once a significant child fails for good
+struct SignificantChild {
+    stop_signal: Arc<Notify>,
+}
+
+impl Watcher for SignificantChild {
+    fn notify(&self, msg: Terminated) {
+        if matches!(
+            msg.reason,
+            TerminationReason::Panicked(_) | TerminationReason::RestartLimitExceeded
+        ) {
+            self.stop_signal.notify_one();
+        }
+    }
+}
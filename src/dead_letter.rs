@@ -0,0 +1,81 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use crate::{actor::ActorId, Message};
+
+/// A message that could not be delivered because its recipient had stopped
+/// Published for sends to a closed mailbox and for mail still queued when
+/// an actor stops; see `ActorSystem::dead_letters`
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub recipient: ActorId,
+    ///path of the recipient, see `Addr::path`
+    pub recipient_path: Arc<str>,
+    pub message_type: &'static str,
+    ///actor whose handler sent the message, None when sent from outside an actor
+    pub sender: Option<ActorId>,
+    pub timestamp: SystemTime,
+}
+
+impl DeadLetter {
+    pub(crate) fn new(
+        recipient: ActorId,
+        recipient_path: Arc<str>,
+        message_type: &'static str,
+    ) -> Self {
+        Self {
+            recipient,
+            recipient_path,
+            message_type,
+            sender: crate::actor::current(),
+            timestamp: SystemTime::now(),
+        }
+    }
+}
+
+impl Message for DeadLetter {
+    type Result = ();
+}
+
+///returns false once the subscriber is gone and should be dropped
+type Subscriber = Box<dyn Fn(&DeadLetter) -> bool + Send + Sync>;
+
+///Dead letter subscribers of every system, keyed like the directory (see
+/// `directory::system_key`)
+static SUBSCRIBERS: Mutex<Option<HashMap<u64, Vec<Subscriber>>>> = Mutex::new(None);
+
+pub(crate) fn subscribe(system: u64, subscriber: Subscriber) {
+    let mut subscribers = SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    subscribers
+        .get_or_insert_with(HashMap::new)
+        .entry(system)
+        .or_default()
+        .push(subscriber);
+}
+
+///Drop the system's subscribers, ending their streams
+pub(crate) fn remove_subscribers(system: u64) {
+    let mut subscribers = SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(map) = subscribers.as_mut() {
+        map.remove(&system);
+    }
+}
+
+///Hand a dead letter to every subscriber of the recipient's system
+/// Subscribers must not publish themselves, they run under the lock
+pub(crate) fn publish(system: u64, letter: DeadLetter) {
+    let mut subscribers = SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(subscribers) = subscribers.as_mut().and_then(|map| map.get_mut(&system)) else {
+        return;
+    };
+    subscribers.retain(|deliver| deliver(&letter));
+}
@@ -0,0 +1,120 @@
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{actor::ActorId, address::ErasedAddr};
+
+///Every live actor in the process, keyed by id sequence number
+/// Ids are process-wide, so one table serves all systems; each entry
+/// remembers the system it belongs to (see `system_key`)
+static DIRECTORY: RwLock<Option<HashMap<u64, Entry>>> = RwLock::new(None);
+
+struct Entry {
+    addr: ErasedAddr,
+    system: u64,
+}
+
+static NEXT_SYSTEM: AtomicU64 = AtomicU64::new(1);
+
+///A system's shutdown signal, shared by every actor of the system, along
+/// with the id keying the system's entries in process-wide tables
+/// Ids are never reused, so a new system can't see a dropped one's entries
+pub(crate) struct Shutdown {
+    notify: Notify,
+    system: u64,
+}
+
+impl Shutdown {
+    pub(crate) fn new() -> Self {
+        Self {
+            notify: Notify::new(),
+            system: NEXT_SYSTEM.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+impl Deref for Shutdown {
+    type Target = Notify;
+
+    fn deref(&self) -> &Notify {
+        &self.notify
+    }
+}
+
+///Key of the system owning `shutdown`
+pub(crate) fn system_key(shutdown: &Shutdown) -> u64 {
+    shutdown.system
+}
+
+///Keeps an actor listed while its task lives: dropping it, when the task
+/// ends or is dropped with its runtime, removes the entry
+pub(crate) struct Listed(pub(crate) ActorId);
+
+impl Drop for Listed {
+    fn drop(&mut self) {
+        remove(self.0);
+    }
+}
+
+pub(crate) fn insert(addr: ErasedAddr, system: u64) {
+    let mut directory = match DIRECTORY.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    directory
+        .get_or_insert_with(HashMap::new)
+        .insert(addr.id().sequence(), Entry { addr, system });
+}
+
+pub(crate) fn remove(id: ActorId) {
+    let mut directory = match DIRECTORY.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(map) = directory.as_mut() {
+        map.remove(&id.sequence());
+    }
+}
+
+///The live actor `seq` if it belongs to `system`
+pub(crate) fn lookup(seq: u64, system: u64) -> Option<ErasedAddr> {
+    let directory = match DIRECTORY.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    directory
+        .as_ref()?
+        .get(&seq)
+        .filter(|entry| entry.system == system)
+        .map(|entry| entry.addr.clone())
+}
+
+///Live actors of one system, top-level and children alike
+pub(crate) fn members(system: u64) -> Vec<ErasedAddr> {
+    let directory = match DIRECTORY.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    directory
+        .iter()
+        .flat_map(|map| map.values())
+        .filter(|entry| entry.system == system)
+        .map(|entry| entry.addr.clone())
+        .collect()
+}
+
+///Id of the live actor `seq`, whichever system it belongs to
+pub(crate) fn lookup_id(seq: u64) -> Option<ActorId> {
+    let directory = match DIRECTORY.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    directory.as_ref()?.get(&seq).map(|entry| entry.addr.id())
+}
@@ -1,19 +1,72 @@
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::Arc,
+    time::Instant,
+};
+
+use futures::FutureExt;
 use tokio::sync::oneshot;
 
 use crate::{
-    actor::{AsyncHandler, BoxFuture},
+    actor::{ActorId, AsyncHandler, BoxFuture},
+    message::{ChildFailed, UnhandledMessage},
+    panic::{self, PanicInfo},
+    response::MessageResponse,
+    supervisor::{FailureInfo, Supervisor, SupervisorDirective},
     Actor, Context, Handler, Message,
 };
 
 ///Envelope acts as a type erasure for messages sent to actors
-/// it is wrapped in a Box to allow for dynamic dispatch
+/// it is wrapped in a Box to allow for dynamic dispatch.
+/// Implement it to inject custom envelope kinds and enqueue them with `Addr::send_envelope`
 pub trait Envelope<A: Actor>: Send {
     fn handle(self: Box<Self>, actor: &mut A, ctx: &mut Context<A>);
+
+    ///Type name of the carried message (for diagnostics)
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    ///True once nobody is waiting for the result, the mailbox loop then skips it
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
+    ///The carried message, for mailboxes that need to inspect or persist it
+    fn message(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    ///The carried message for in-place changes, see `Middleware`
+    fn message_mut(&mut self) -> Option<&mut dyn Any> {
+        None
+    }
+
+    ///When the message was sent, for queue latency metrics
+    fn sent_at(&self) -> Option<Instant> {
+        None
+    }
 }
 
 ///envelope for async message handling
 pub trait AsyncEnvelope<A: Actor>: Send {
     fn handle<'a>(self: Box<Self>, actor: &'a mut A, ctx: &'a mut Context<A>) -> BoxFuture<'a, ()>;
+
+    ///Type name of the carried message (for diagnostics)
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    ///True once nobody is waiting for the result, the mailbox loop then skips it
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
+    ///When the message was sent, for queue latency metrics
+    fn sent_at(&self) -> Option<Instant> {
+        None
+    }
 }
 
 pub enum ActorMessage<A: Actor> {
@@ -21,6 +74,181 @@ pub enum ActorMessage<A: Actor> {
     Async(Box<dyn AsyncEnvelope<A>>),
 }
 
+///Payload of a panic caught while dispatching an envelope
+pub type PanicPayload = Box<dyn Any + Send>;
+
+impl<A: Actor> ActorMessage<A> {
+    ///Type name of the carried message
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            ActorMessage::Sync(envelope) => envelope.message_type(),
+            ActorMessage::Async(envelope) => envelope.message_type(),
+        }
+    }
+
+    ///True if the sender dropped its request before the actor got to it
+    pub fn is_cancelled(&self) -> bool {
+        match self {
+            ActorMessage::Sync(envelope) => envelope.is_cancelled(),
+            ActorMessage::Async(envelope) => envelope.is_cancelled(),
+        }
+    }
+
+    ///The carried message, None for async and custom envelopes that don't expose it
+    pub fn message(&self) -> Option<&dyn Any> {
+        match self {
+            ActorMessage::Sync(envelope) => envelope.message(),
+            ActorMessage::Async(_) => None,
+        }
+    }
+
+    ///Mutable counterpart of `message`
+    pub fn message_mut(&mut self) -> Option<&mut dyn Any> {
+        match self {
+            ActorMessage::Sync(envelope) => envelope.message_mut(),
+            ActorMessage::Async(_) => None,
+        }
+    }
+
+    ///When the message was sent, None for envelopes that don't record it
+    pub fn sent_at(&self) -> Option<Instant> {
+        match self {
+            ActorMessage::Sync(envelope) => envelope.sent_at(),
+            ActorMessage::Async(envelope) => envelope.sent_at(),
+        }
+    }
+
+    ///Run the envelope against the actor
+    /// Custom envelopes wrapping another ActorMessage delegate to this
+    pub fn handle<'a>(self, actor: &'a mut A, ctx: &'a mut Context<A>) -> BoxFuture<'a, ()> {
+        match self {
+            ActorMessage::Sync(envelope) => Box::pin(async move { envelope.handle(actor, ctx) }),
+            ActorMessage::Async(envelope) => envelope.handle(actor, ctx),
+        }
+    }
+
+    ///Wrap the envelope so the hook runs before and after dispatch
+    /// Sync envelopes stay sync, so hooking does not add a future per message
+    pub fn with_hook<H: DispatchHook<A>>(self, hook: H) -> Self {
+        match self {
+            ActorMessage::Sync(inner) => ActorMessage::Sync(Box::new(Hooked { inner, hook })),
+            ActorMessage::Async(inner) => {
+                ActorMessage::Async(Box::new(AsyncHooked { inner, hook }))
+            }
+        }
+    }
+}
+
+///Best effort text of a panic payload
+pub(crate) fn panic_message(payload: &PanicPayload) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+///Dispatch a message from the mailbox loop, catching panics in the handler
+pub(crate) async fn dispatch<A: Actor>(
+    msg: ActorMessage<A>,
+    actor: &mut A,
+    ctx: &mut Context<A>,
+) -> Result<(), PanicInfo> {
+    //the asker is gone, don't do the work for a dead oneshot
+    if msg.is_cancelled() {
+        return Ok(());
+    }
+
+    let result = match msg {
+        ActorMessage::Sync(envelope) => {
+            catch_unwind(AssertUnwindSafe(|| envelope.handle(actor, ctx)))
+        }
+        ActorMessage::Async(envelope) => {
+            let fut = envelope.handle(actor, ctx);
+            AssertUnwindSafe(fut).catch_unwind().await
+        }
+    };
+    //still on the thread that panicked, so its backtrace is at hand
+    result.map_err(|payload| PanicInfo {
+        id: ctx.id(),
+        name: ctx.name().map(Arc::from),
+        path: Arc::from(ctx.path()),
+        message: panic_message(&payload),
+        backtrace: panic::take_backtrace(),
+    })
+}
+
+///Custom behavior run around the dispatch of a single envelope
+/// (tracing spans, persistence bookkeeping, metrics, ...)
+pub trait DispatchHook<A: Actor>: Send + 'static {
+    fn before(&mut self, _actor: &mut A, _ctx: &mut Context<A>) {}
+    fn after(&mut self, _actor: &mut A, _ctx: &mut Context<A>) {}
+}
+
+struct Hooked<A: Actor, H> {
+    inner: Box<dyn Envelope<A>>,
+    hook: H,
+}
+
+impl<A: Actor, H: DispatchHook<A>> Envelope<A> for Hooked<A, H> {
+    fn handle(self: Box<Self>, actor: &mut A, ctx: &mut Context<A>) {
+        let Hooked { inner, mut hook } = *self;
+        hook.before(actor, ctx);
+        inner.handle(actor, ctx);
+        hook.after(actor, ctx);
+    }
+
+    fn message_type(&self) -> &'static str {
+        self.inner.message_type()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    fn message(&self) -> Option<&dyn Any> {
+        self.inner.message()
+    }
+
+    fn message_mut(&mut self) -> Option<&mut dyn Any> {
+        self.inner.message_mut()
+    }
+
+    fn sent_at(&self) -> Option<Instant> {
+        self.inner.sent_at()
+    }
+}
+
+struct AsyncHooked<A: Actor, H> {
+    inner: Box<dyn AsyncEnvelope<A>>,
+    hook: H,
+}
+
+impl<A: Actor, H: DispatchHook<A>> AsyncEnvelope<A> for AsyncHooked<A, H> {
+    fn handle<'a>(self: Box<Self>, actor: &'a mut A, ctx: &'a mut Context<A>) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let AsyncHooked { inner, mut hook } = *self;
+            hook.before(actor, ctx);
+            inner.handle(actor, ctx).await;
+            hook.after(actor, ctx);
+        })
+    }
+
+    fn message_type(&self) -> &'static str {
+        self.inner.message_type()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    fn sent_at(&self) -> Option<Instant> {
+        self.inner.sent_at()
+    }
+}
+
 pub struct MessageEnvelope<M>
 where
     M: Message,
@@ -28,6 +256,10 @@ where
     //an optional message, once taken it becomes None
     msg: Option<M>,
     response_tx: Option<oneshot::Sender<M::Result>>,
+    sent_at: Instant,
+    ///span the message was sent from, the handler runs in it
+    #[cfg(feature = "otel")]
+    span: tracing::Span,
 }
 
 pub struct AsyncMessageEnvelope<M>
@@ -37,6 +269,10 @@ where
     //an optional message, once taken it becomes None
     msg: Option<M>,
     response_tx: Option<oneshot::Sender<M::Result>>,
+    sent_at: Instant,
+    ///span the message was sent from, the handler runs in it
+    #[cfg(feature = "otel")]
+    span: tracing::Span,
 }
 
 impl<M: Message> MessageEnvelope<M> {
@@ -45,6 +281,9 @@ impl<M: Message> MessageEnvelope<M> {
         Self {
             msg: Some(msg),
             response_tx: None,
+            sent_at: Instant::now(),
+            #[cfg(feature = "otel")]
+            span: tracing::Span::current(),
         }
     }
 
@@ -53,6 +292,9 @@ impl<M: Message> MessageEnvelope<M> {
         Self {
             msg: Some(msg),
             response_tx: Some(tx),
+            sent_at: Instant::now(),
+            #[cfg(feature = "otel")]
+            span: tracing::Span::current(),
         }
     }
 }
@@ -63,6 +305,9 @@ impl<M: Message> AsyncMessageEnvelope<M> {
         Self {
             msg: Some(msg),
             response_tx: None,
+            sent_at: Instant::now(),
+            #[cfg(feature = "otel")]
+            span: tracing::Span::current(),
         }
     }
 
@@ -71,6 +316,9 @@ impl<M: Message> AsyncMessageEnvelope<M> {
         Self {
             msg: Some(msg),
             response_tx: Some(tx),
+            sent_at: Instant::now(),
+            #[cfg(feature = "otel")]
+            span: tracing::Span::current(),
         }
     }
 }
@@ -81,15 +329,33 @@ where
     M: Message,
 {
     fn handle(mut self: Box<Self>, actor: &mut A, ctx: &mut Context<A>) {
+        #[cfg(feature = "otel")]
+        let _entered = self.span.clone().entered();
         if let Some(msg) = self.msg.take() {
-            let result = actor.handle(msg, ctx);
-
-            if let Some(tx) = self.response_tx.take() {
-                //error can be ignored if receiver is dropped
-                let _ = tx.send(result);
-            }
+            let response = actor.handle(msg, ctx);
+            response.handle(ctx, self.response_tx.take());
         }
     }
+
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<M>()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        is_abandoned(&self.response_tx)
+    }
+
+    fn message(&self) -> Option<&dyn Any> {
+        self.msg.as_ref().map(|m| m as &dyn Any)
+    }
+
+    fn message_mut(&mut self) -> Option<&mut dyn Any> {
+        self.msg.as_mut().map(|m| m as &mut dyn Any)
+    }
+
+    fn sent_at(&self) -> Option<Instant> {
+        Some(self.sent_at)
+    }
 }
 
 impl<A, M> AsyncEnvelope<A> for AsyncMessageEnvelope<M>
@@ -102,7 +368,9 @@ where
         actor: &'a mut A,
         ctx: &'a mut Context<A>,
     ) -> BoxFuture<'a, ()> {
-        Box::pin(async move {
+        #[cfg(feature = "otel")]
+        let span = self.span.clone();
+        let handled = async move {
             if let Some(msg) = self.msg.take() {
                 let result = actor.handle(msg, ctx).await;
                 if let Some(tx) = self.response_tx.take() {
@@ -110,6 +378,121 @@ where
                     let _ = tx.send(result);
                 }
             }
-        })
+        };
+        #[cfg(feature = "otel")]
+        let handled = tracing::Instrument::instrument(handled, span);
+        Box::pin(handled)
+    }
+
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<M>()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        is_abandoned(&self.response_tx)
+    }
+
+    fn sent_at(&self) -> Option<Instant> {
+        Some(self.sent_at)
+    }
+}
+
+///System envelope reporting an escalated child failure to the parent
+pub(crate) struct ChildFailedEnvelope {
+    pub(crate) failure: ChildFailed,
+}
+
+impl<A: Actor> Envelope<A> for ChildFailedEnvelope {
+    fn handle(self: Box<Self>, actor: &mut A, ctx: &mut Context<A>) {
+        if !actor.child_failed(&self.failure, ctx) {
+            ctx.escalate(self.failure);
+        }
+    }
+
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<ChildFailed>()
+    }
+}
+
+///System envelope asking a `Supervisor` parent what to do with a failed child
+pub(crate) struct ChildFailureEnvelope {
+    pub(crate) child: ActorId,
+    pub(crate) failure: FailureInfo,
+    pub(crate) reply: oneshot::Sender<SupervisorDirective>,
+}
+
+impl<A: Supervisor> Envelope<A> for ChildFailureEnvelope {
+    fn handle(self: Box<Self>, actor: &mut A, _ctx: &mut Context<A>) {
+        let directive = actor.on_child_failure(self.child, &self.failure);
+        let _ = self.reply.send(directive);
+    }
+
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<FailureInfo>()
+    }
+}
+
+///System envelope telling the parent one of its children was restarted
+pub(crate) struct ChildRestartedEnvelope {
+    pub(crate) id: ActorId,
+}
+
+impl<A: Actor> Envelope<A> for ChildRestartedEnvelope {
+    fn handle(self: Box<Self>, _actor: &mut A, ctx: &mut Context<A>) {
+        ctx.child_restarted(self.id);
+    }
+
+    fn message_type(&self) -> &'static str {
+        "ChildRestarted"
+    }
+}
+
+///A request whose response receiver was dropped (fire and forget is never abandoned)
+fn is_abandoned<T>(response_tx: &Option<oneshot::Sender<T>>) -> bool {
+    response_tx.as_ref().is_some_and(|tx| tx.is_closed())
+}
+
+///System envelope delivering an unhandled message report to the actor's hook
+pub(crate) struct UnhandledEnvelope {
+    pub(crate) info: UnhandledMessage,
+}
+
+impl<A: Actor> Envelope<A> for UnhandledEnvelope {
+    fn handle(self: Box<Self>, actor: &mut A, ctx: &mut Context<A>) {
+        actor.unhandled(self.info, ctx);
+    }
+
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<UnhandledMessage>()
+    }
+}
+
+///Envelope running a closure against the actor, serialized with its mailbox
+pub(crate) struct ClosureEnvelope<F> {
+    pub(crate) f: F,
+}
+
+impl<A, F> AsyncEnvelope<A> for ClosureEnvelope<F>
+where
+    A: Actor,
+    F: for<'a> FnOnce(&'a mut A, &'a mut Context<A>) -> BoxFuture<'a, ()> + Send + 'static,
+{
+    fn handle<'a>(self: Box<Self>, actor: &'a mut A, ctx: &'a mut Context<A>) -> BoxFuture<'a, ()> {
+        (self.f)(actor, ctx)
+    }
+}
+
+///Envelope running a sync closure against the actor, see `Context::spawn_future`
+pub(crate) struct ContinuationEnvelope<F> {
+    pub(crate) f: F,
+}
+
+impl<A, F> Envelope<A> for ContinuationEnvelope<F>
+where
+    A: Actor,
+    F: FnOnce(&mut A, &mut Context<A>) + Send + 'static,
+{
+    fn handle(self: Box<Self>, actor: &mut A, ctx: &mut Context<A>) {
+        (self.f)(actor, ctx)
     }
 }
@@ -1,4 +1,6 @@
-#[derive(Debug, PartialEq, Eq)]
+use crate::remote::{SendError, TransportError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MailboxError {
     ///Actor's mailbox is closed (actor has stopped)
     MailboxClosed,
@@ -6,6 +8,10 @@ pub enum MailboxError {
     Timeout,
     ///Actor's mailbox is full (bounded channel at capacity)
     MailboxFull,
+    ///A throttled address is over its rate, see `Addr::throttled`
+    Throttled,
+    ///A circuit breaker is open and failing calls fast, see `CircuitBreaker`
+    CircuitOpen,
 }
 
 impl std::fmt::Display for MailboxError {
@@ -14,8 +20,101 @@ impl std::fmt::Display for MailboxError {
             MailboxError::MailboxClosed => write!(f, "Actor's mailbox is closed"),
             MailboxError::Timeout => write!(f, "Requested operation timed out"),
             MailboxError::MailboxFull => write!(f, "Actor's mailbox is full"),
+            MailboxError::Throttled => write!(f, "Send rate limit exceeded"),
+            MailboxError::CircuitOpen => write!(f, "Circuit breaker is open"),
         }
     }
 }
 
 impl std::error::Error for MailboxError {}
+
+/// Any failure of a send, local or remote, sorted by what the caller can do
+/// about it. `MailboxError`, `TransportError` and `SendError` convert into
+/// it, so one `?` covers local and remote sends alike
+#[derive(Debug)]
+pub enum Error {
+    ///the local actor's mailbox turned the message away
+    Mailbox(MailboxError),
+    ///the remote node couldn't be reached or the connection failed
+    Transport(TransportError),
+    ///a message or its answer couldn't be encoded or decoded, on either side
+    Serialization(prost::DecodeError),
+    ///the remote node has no handler for the message type, with its description
+    UnknownMessageType(String),
+    ///the request's deadline passed before the remote node answered it
+    DeadlineExceeded,
+    ///the remote actor couldn't take the message, with the reason
+    RemoteHandler(String),
+}
+
+impl Error {
+    ///whether sending the same message again may succeed: the mailbox or
+    /// connection was only busy or briefly gone
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Mailbox(err) => !matches!(err, MailboxError::MailboxClosed),
+            Error::Transport(err) => !matches!(err, TransportError::Handshake(_)),
+            Error::DeadlineExceeded => true,
+            Error::Serialization(_) | Error::UnknownMessageType(_) | Error::RemoteHandler(_) => {
+                false
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Mailbox(err) => write!(f, "{}", err),
+            Error::Transport(err) => write!(f, "{}", err),
+            Error::Serialization(err) => write!(f, "Failed to decode message: {}", err),
+            Error::UnknownMessageType(reason) => write!(f, "No remote handler: {}", reason),
+            Error::DeadlineExceeded => write!(f, "Request deadline exceeded"),
+            Error::RemoteHandler(reason) => write!(f, "Remote handler failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Mailbox(err) => Some(err),
+            Error::Transport(err) => Some(err),
+            Error::Serialization(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<MailboxError> for Error {
+    fn from(err: MailboxError) -> Self {
+        Error::Mailbox(err)
+    }
+}
+
+impl From<TransportError> for Error {
+    fn from(err: TransportError) -> Self {
+        match err {
+            TransportError::Decode(err) => Error::Serialization(err),
+            TransportError::DeadlineExceeded => Error::DeadlineExceeded,
+            TransportError::UnknownMessageType(reason) => Error::UnknownMessageType(reason),
+            TransportError::Remote(reason) => Error::RemoteHandler(reason),
+            err => Error::Transport(err),
+        }
+    }
+}
+
+impl From<prost::DecodeError> for Error {
+    fn from(err: prost::DecodeError) -> Self {
+        Error::Serialization(err)
+    }
+}
+
+impl From<SendError> for Error {
+    fn from(err: SendError) -> Self {
+        match err {
+            SendError::Mailbox(err) => err.into(),
+            SendError::Transport(err) => err.into(),
+        }
+    }
+}
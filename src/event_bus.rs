@@ -0,0 +1,136 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+};
+
+use crate::{actor::ActorId, message::Terminated, watcher::Watcher, Actor, Addr, Handler, Message};
+
+///hands an event (downcast from &dyn Any) to one subscriber
+type Deliver = Arc<dyn Fn(&dyn Any) + Send + Sync>;
+
+struct Subscription {
+    actor: ActorId,
+    deliver: Deliver,
+}
+
+type Topics = Mutex<HashMap<TypeId, Vec<Subscription>>>;
+
+/// Local publish/subscribe keyed by event type
+/// Subscribers are dropped automatically when their actor stops.
+/// Cheap to clone, clones share subscriptions
+#[derive(Clone, Default)]
+pub struct EventBus {
+    topics: Arc<Topics>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn topics(&self) -> MutexGuard<'_, HashMap<TypeId, Vec<Subscription>>> {
+        lock_topics(&self.topics)
+    }
+
+    /// Deliver every published `E` to `addr`
+    /// Subscribing the same actor twice keeps a single subscription.
+    /// The bus doesn't keep the subscriber alive
+    pub fn subscribe<E, A>(&self, addr: &Addr<A>)
+    where
+        E: Message + Clone,
+        A: Actor + Handler<E>,
+    {
+        let target = addr.uncounted();
+        let deliver: Deliver = Arc::new(move |event: &dyn Any| {
+            if let Some(event) = event.downcast_ref::<E>() {
+                let _ = target.try_send(event.clone());
+            }
+        });
+
+        let topic = TypeId::of::<E>();
+        {
+            let mut topics = self.topics();
+            let subscribers = topics.entry(topic).or_default();
+            if subscribers.iter().any(|sub| sub.actor == addr.id()) {
+                return;
+            }
+            subscribers.push(Subscription {
+                actor: addr.id(),
+                deliver,
+            });
+        }
+
+        addr.add_watcher_erased(Arc::new(Unsubscriber {
+            topics: Arc::downgrade(&self.topics),
+            topic,
+            actor: addr.id(),
+        }));
+    }
+
+    /// Stop delivering `E` to `addr`, false if it wasn't subscribed
+    pub fn unsubscribe<E, A>(&self, addr: &Addr<A>) -> bool
+    where
+        E: Message,
+        A: Actor,
+    {
+        remove(&self.topics, TypeId::of::<E>(), addr.id())
+    }
+
+    /// Send a clone of `event` to every subscriber, returns how many there were
+    /// Uses try_send: an event is dropped for subscribers whose mailbox is full
+    pub fn publish<E>(&self, event: E) -> usize
+    where
+        E: Message + Clone,
+    {
+        //deliver outside the lock, a subscriber may be subscribing right now
+        let subscribers: Vec<Deliver> = match self.topics().get(&TypeId::of::<E>()) {
+            Some(subscribers) => subscribers.iter().map(|sub| sub.deliver.clone()).collect(),
+            None => return 0,
+        };
+        for deliver in &subscribers {
+            deliver(&event);
+        }
+        subscribers.len()
+    }
+
+    /// Number of subscribers for `E`
+    pub fn subscribers<E: Message>(&self) -> usize {
+        self.topics().get(&TypeId::of::<E>()).map_or(0, Vec::len)
+    }
+}
+
+fn lock_topics(topics: &Topics) -> MutexGuard<'_, HashMap<TypeId, Vec<Subscription>>> {
+    topics
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn remove(topics: &Topics, topic: TypeId, actor: ActorId) -> bool {
+    let mut topics = lock_topics(topics);
+    let Some(subscribers) = topics.get_mut(&topic) else {
+        return false;
+    };
+    let before = subscribers.len();
+    subscribers.retain(|sub| sub.actor != actor);
+    let removed = subscribers.len() != before;
+    if subscribers.is_empty() {
+        topics.remove(&topic);
+    }
+    removed
+}
+
+///drops a subscription when its actor stops
+struct Unsubscriber {
+    topics: Weak<Topics>,
+    topic: TypeId,
+    actor: ActorId,
+}
+
+impl Watcher for Unsubscriber {
+    fn notify(&self, _msg: Terminated) {
+        if let Some(topics) = self.topics.upgrade() {
+            remove(&topics, self.topic, self.actor);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{actor::ActorId, message::TerminationReason};
+
+/// Actor lifecycle change, as streamed by `ActorSystem::events`
+#[derive(Debug, Clone)]
+pub enum SystemEvent {
+    ActorSpawned {
+        id: ActorId,
+        path: Arc<str>,
+        actor_type: &'static str,
+    },
+    ///the actor is gone for good; restarts are reported as ActorRestarted
+    ActorStopped {
+        id: ActorId,
+        path: Arc<str>,
+        reason: TerminationReason,
+    },
+    ///a handler panicked, whether or not the actor is restarted afterwards
+    ActorPanicked {
+        id: ActorId,
+        path: Arc<str>,
+        message: String,
+    },
+    ///a fresh instance replaced a failed one, or its supervisor restarted it
+    ActorRestarted { id: ActorId, path: Arc<str> },
+}
+
+impl SystemEvent {
+    pub fn id(&self) -> ActorId {
+        match self {
+            Self::ActorSpawned { id, .. }
+            | Self::ActorStopped { id, .. }
+            | Self::ActorPanicked { id, .. }
+            | Self::ActorRestarted { id, .. } => *id,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            Self::ActorSpawned { path, .. }
+            | Self::ActorStopped { path, .. }
+            | Self::ActorPanicked { path, .. }
+            | Self::ActorRestarted { path, .. } => path,
+        }
+    }
+}
+
+///Event streams of every system, keyed like the directory (see `directory::system_key`)
+static SUBSCRIBERS: Mutex<Option<HashMap<u64, Vec<UnboundedSender<SystemEvent>>>>> =
+    Mutex::new(None);
+
+pub(crate) fn subscribe(system: u64, subscriber: UnboundedSender<SystemEvent>) {
+    let mut subscribers = SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    subscribers
+        .get_or_insert_with(HashMap::new)
+        .entry(system)
+        .or_default()
+        .push(subscriber);
+}
+
+///Drop the system's subscribers, ending their streams
+pub(crate) fn remove_subscribers(system: u64) {
+    let mut subscribers = SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(map) = subscribers.as_mut() {
+        map.remove(&system);
+    }
+}
+
+///Hand an event to every stream of the system, forgetting dropped ones
+pub(crate) fn publish(system: u64, event: SystemEvent) {
+    let mut subscribers = SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(streams) = subscribers.as_mut().and_then(|map| map.get_mut(&system)) else {
+        return;
+    };
+    streams.retain(|stream| stream.send(event.clone()).is_ok());
+}
@@ -1,22 +1,63 @@
 pub mod actor;
+pub mod arbiter;
 pub mod address;
+pub mod breaker;
 pub mod context;
+pub mod dead_letter;
+mod directory;
 pub mod envelope;
 pub mod error;
+pub mod event_bus;
+pub mod events;
+pub mod mailbox;
 pub mod message;
+pub mod metrics;
+pub mod middleware;
+pub mod panic;
+pub mod persistence;
 pub mod registry;
 pub mod remote;
+pub mod response;
+pub mod retry;
+pub mod router;
+pub mod scheduler;
+pub mod selection;
 pub mod stream;
 pub mod supervisor;
+mod sync;
+pub mod sync_arbiter;
 pub mod system;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod throttle;
 pub mod timer;
 pub mod watcher;
 
 pub use actor::{Actor, Handler, StreamHandler};
-pub use address::Addr;
+pub use arbiter::Arbiter;
+pub use address::{Addr, Permit, Recipient, WeakAddr};
+pub use breaker::{BreakerState, CircuitBreaker};
 pub use context::Context;
-pub use error::MailboxError;
+pub use error::{Error, MailboxError};
+pub use event_bus::EventBus;
+pub use events::SystemEvent;
+pub use mailbox::Mailbox;
 pub use message::Message;
-pub use supervisor::SupervisorStrategy;
-pub use system::ActorSystem;
-pub use timer::TimerHandle;
+pub use metrics::{MessageMetrics, MetricsSink, RemoteEvent, RemoteMetrics};
+pub use middleware::{Flow, Intercepted, Middleware};
+pub use panic::PanicPolicy;
+pub use persistence::PersistentActor;
+#[cfg(feature = "derive")]
+pub use cinema_derive::{async_handler, Message, MessageResponse};
+pub use supervisor::{
+    ChildSpec, FailureInfo, SupervisionMode, Supervisor, SupervisorDirective, SupervisorStrategy,
+};
+pub use response::{Forwarded, MessageResponse, MessageResult, ResponseActFuture, ResponseFuture};
+pub use retry::{RetryError, RetryPolicy};
+pub use router::Router;
+pub use scheduler::{Schedule, Scheduler};
+pub use selection::ActorSelection;
+pub use sync_arbiter::SyncArbiter;
+pub use system::{ActorSnapshot, ActorSystem, ActorSystemConfig, ShutdownReport, SpawnOptions};
+pub use throttle::Throttled;
+pub use timer::{Interval, MissedTick, TimerHandle};
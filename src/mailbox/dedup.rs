@@ -0,0 +1,201 @@
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{
+    actor::BoxFuture, envelope::ActorMessage, error::MailboxError, Actor, Handler, Message,
+};
+
+use super::Mailbox;
+
+/// Messages that are redundant while another one with the same key is
+/// still queued, e.g. "refresh cache for user X"
+pub trait DedupKey: Message {
+    type Key: Hash + Eq + Send + 'static;
+
+    fn dedup_key(&self) -> Self::Key;
+}
+
+///keys of the queued messages of one type, a HashSet<M::Key> behind Any
+type Keys = Box<dyn Any + Send>;
+
+struct Dedup {
+    keys: Keys,
+    ///records the key of msg, false if it was already queued
+    claim: fn(&mut Keys, &dyn Any) -> bool,
+    release: fn(&mut Keys, &dyn Any),
+}
+
+struct Queue<A: Actor> {
+    messages: VecDeque<ActorMessage<A>>,
+    ///by message type name, like `DurableMailbox`
+    dedup: HashMap<&'static str, Dedup>,
+}
+
+///Bounded mailbox collapsing duplicate messages
+/// A message of a type registered with `dedup` is dropped when one with the
+/// same key is still queued; it counts as delivered for the sender, and a
+/// dropped `send` resolves with MailboxClosed since no reply will come.
+/// A key is free again as soon as its message is taken out for handling
+pub struct DedupMailbox<A: Actor> {
+    queue: Mutex<Queue<A>>,
+    capacity: usize,
+    //a message was queued or the mailbox closed
+    ready: Notify,
+    //a slot was freed, wakes a blocked sender
+    space: Notify,
+    closed: AtomicBool,
+}
+
+impl<A: Actor> DedupMailbox<A> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: Mutex::new(Queue {
+                messages: VecDeque::with_capacity(capacity),
+                dedup: HashMap::new(),
+            }),
+            capacity,
+            ready: Notify::new(),
+            space: Notify::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    ///Collapse queued messages of type M with equal keys
+    pub fn dedup<M>(self) -> Self
+    where
+        M: DedupKey,
+        A: Handler<M>,
+    {
+        let dedup = Dedup {
+            keys: Box::new(HashSet::<M::Key>::new()),
+            claim: |keys, msg| match (
+                keys.downcast_mut::<HashSet<M::Key>>(),
+                msg.downcast_ref::<M>(),
+            ) {
+                (Some(keys), Some(msg)) => keys.insert(msg.dedup_key()),
+                _ => true,
+            },
+            release: |keys, msg| {
+                if let (Some(keys), Some(msg)) = (
+                    keys.downcast_mut::<HashSet<M::Key>>(),
+                    msg.downcast_ref::<M>(),
+                ) {
+                    keys.remove(&msg.dedup_key());
+                }
+            },
+        };
+        self.queue().dedup.insert(std::any::type_name::<M>(), dedup);
+        self
+    }
+
+    fn queue(&self) -> MutexGuard<'_, Queue<A>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    ///queue msg unless it is a duplicate, handing it back only when it must wait
+    fn push(&self, msg: ActorMessage<A>) -> Result<(), (ActorMessage<A>, MailboxError)> {
+        if self.is_closed() {
+            return Err((msg, MailboxError::MailboxClosed));
+        }
+        let mut queue = self.queue();
+        let full = queue.messages.len() >= self.capacity;
+        let Queue { messages, dedup } = &mut *queue;
+        if let (Some(dedup), Some(message)) = (dedup.get_mut(msg.message_type()), msg.message()) {
+            //a duplicate is dropped even when the mailbox is full
+            if !(dedup.claim)(&mut dedup.keys, message) {
+                drop(queue);
+                //dropping an envelope may run arbitrary code (reply channels, hooks), not under the lock
+                drop(msg);
+                return Ok(());
+            }
+            if full {
+                (dedup.release)(&mut dedup.keys, message);
+            }
+        }
+        if full {
+            drop(queue);
+            return Err((msg, MailboxError::MailboxFull));
+        }
+        messages.push_back(msg);
+        drop(queue);
+        self.ready.notify_one();
+        Ok(())
+    }
+
+    fn pop(&self) -> Option<ActorMessage<A>> {
+        let mut queue = self.queue();
+        let msg = queue.messages.pop_front()?;
+        if let (Some(dedup), Some(message)) =
+            (queue.dedup.get_mut(msg.message_type()), msg.message())
+        {
+            (dedup.release)(&mut dedup.keys, message);
+        }
+        Some(msg)
+    }
+}
+
+impl<A: Actor> Mailbox<A> for DedupMailbox<A> {
+    fn enqueue(&self, mut msg: ActorMessage<A>) -> BoxFuture<'_, Result<(), MailboxError>> {
+        Box::pin(async move {
+            loop {
+                let space = self.space.notified();
+                match self.push(msg) {
+                    Ok(()) => return Ok(()),
+                    Err((rejected, MailboxError::MailboxFull)) => {
+                        msg = rejected;
+                        space.await;
+                    }
+                    Err((_, err)) => return Err(err),
+                }
+            }
+        })
+    }
+
+    fn try_enqueue(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        self.push(msg).map_err(|(_, err)| err)
+    }
+
+    fn dequeue(&self) -> BoxFuture<'_, Option<ActorMessage<A>>> {
+        Box::pin(async move {
+            loop {
+                if let Some(msg) = self.pop() {
+                    self.space.notify_one();
+                    return Some(msg);
+                }
+                if self.is_closed() {
+                    return None;
+                }
+                self.ready.notified().await;
+            }
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.queue().messages.len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.capacity)
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.ready.notify_one();
+        self.space.notify_waiters();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
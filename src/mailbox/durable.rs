@@ -0,0 +1,332 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{HashMap, HashSet, VecDeque},
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, Read, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{
+    actor::BoxFuture,
+    envelope::{ActorMessage, DispatchHook, MessageEnvelope},
+    error::MailboxError,
+    remote::{RemoteMessage, Serializer},
+    Actor, Context, Handler,
+};
+
+use super::Mailbox;
+
+const RECORD_ENQUEUE: u8 = 0;
+const RECORD_ACK: u8 = 1;
+
+type EncodeFn = fn(&dyn Any) -> Option<Vec<u8>>;
+type DecodeFn<A> = fn(&[u8]) -> Option<ActorMessage<A>>;
+
+struct Codec<A: Actor> {
+    ///what the log calls the type, `RemoteMessage::type_id`
+    tag: &'static str,
+    encode: EncodeFn,
+    decode: DecodeFn<A>,
+}
+
+///Append-only log of enqueued and acknowledged messages
+struct Journal {
+    file: File,
+    next_seq: u64,
+    ///enqueued but not yet acknowledged
+    outstanding: HashSet<u64>,
+    fsync: bool,
+}
+
+impl Journal {
+    fn append(&mut self, record: &[u8]) -> io::Result<()> {
+        self.file.write_all(record)?;
+        if self.fsync {
+            self.file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    fn enqueue(&mut self, type_id: &str, payload: &[u8]) -> io::Result<u64> {
+        let seq = self.next_seq;
+        let mut record = Vec::with_capacity(17 + type_id.len() + payload.len());
+        record.push(RECORD_ENQUEUE);
+        record.extend_from_slice(&seq.to_be_bytes());
+        record.extend_from_slice(&(type_id.len() as u32).to_be_bytes());
+        record.extend_from_slice(type_id.as_bytes());
+        record.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        record.extend_from_slice(payload);
+        self.append(&record)?;
+
+        self.next_seq += 1;
+        self.outstanding.insert(seq);
+        Ok(seq)
+    }
+
+    fn ack(&mut self, seq: u64) -> io::Result<()> {
+        let mut record = [0u8; 9];
+        record[0] = RECORD_ACK;
+        record[1..].copy_from_slice(&seq.to_be_bytes());
+        self.append(&record)?;
+
+        self.outstanding.remove(&seq);
+        //nothing left to replay, start the log over
+        if self.outstanding.is_empty() {
+            self.file.set_len(0)?;
+        }
+        Ok(())
+    }
+}
+
+///Marks a durable message as done once its handler returned
+struct Ack {
+    journal: Arc<Mutex<Journal>>,
+    seq: u64,
+}
+
+impl<A: Actor> DispatchHook<A> for Ack {
+    fn after(&mut self, _actor: &mut A, _ctx: &mut Context<A>) {
+        let mut journal = self.journal.lock().unwrap_or_else(|p| p.into_inner());
+        if let Err(e) = journal.ack(self.seq) {
+            eprintln!("Durable mailbox failed to ack message {}: {:?}", self.seq, e);
+        }
+    }
+}
+
+///Unbounded mailbox backed by an append-only file
+/// Messages of types registered with `durable` are written to disk before
+/// being accepted and acknowledged after their handler returns, so whatever
+/// was queued or in flight when the process died is redelivered by the next
+/// `open` of the same path. Other messages are queued in memory only.
+/// Only fire-and-forget semantics survive: replayed requests have no asker.
+pub struct DurableMailbox<A: Actor> {
+    journal: Arc<Mutex<Journal>>,
+    codecs: HashMap<TypeId, Codec<A>>,
+    queue: Mutex<VecDeque<(ActorMessage<A>, Option<u64>)>>,
+    notify: Notify,
+    closed: AtomicBool,
+    ///(seq, type, payload) found in the log, decoded once codecs are known
+    replay: Vec<(u64, String, Vec<u8>)>,
+}
+
+impl<A: Actor> DurableMailbox<A> {
+    ///Open (or create) the log at `path`, picking up unacknowledged messages
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let replay = match File::open(path) {
+            Ok(file) => read_log(file)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        //rewrite the log with just the pending records, renumbered from zero,
+        // next to it first so a crash midway leaves the old log intact
+        let mut rewritten = path.as_os_str().to_owned();
+        rewritten.push(".tmp");
+        let mut journal = Journal {
+            file: File::create(&rewritten)?,
+            next_seq: 0,
+            outstanding: HashSet::new(),
+            fsync: false,
+        };
+        let mut renumbered = Vec::with_capacity(replay.len());
+        for (_, type_id, payload) in replay {
+            let seq = journal.enqueue(&type_id, &payload)?;
+            renumbered.push((seq, type_id, payload));
+        }
+        journal.file.sync_all()?;
+        fs::rename(&rewritten, path)?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            //makes the rename itself durable, where directories can be synced
+            let _ = File::open(dir).and_then(|dir| dir.sync_all());
+        }
+        journal.file = OpenOptions::new().append(true).open(path)?;
+
+        Ok(Self {
+            journal: Arc::new(Mutex::new(journal)),
+            codecs: HashMap::new(),
+            queue: Mutex::new(VecDeque::new()),
+            notify: Notify::new(),
+            closed: AtomicBool::new(false),
+            replay: renumbered,
+        })
+    }
+
+    ///Persist messages of type M; pending ones from a previous run are queued again
+    /// Records are tagged with `M::type_id()`, override it to keep logs
+    /// readable across renames of the type
+    pub fn durable<M>(mut self) -> Self
+    where
+        M: RemoteMessage,
+        A: Handler<M>,
+    {
+        let codec = Codec {
+            tag: M::type_id(),
+            encode: |msg| {
+                let msg = msg.downcast_ref::<M>()?;
+                Some(<M::Serializer as Serializer<M>>::encode(msg))
+            },
+            decode: |bytes| {
+                let msg = <M::Serializer as Serializer<M>>::decode(bytes).ok()?;
+                Some(ActorMessage::Sync(Box::new(MessageEnvelope::new(msg))))
+            },
+        };
+
+        //replayed messages keep their original order among their own type
+        let mut queue = self.queue.lock().unwrap_or_else(|p| p.into_inner());
+        for (seq, type_id, payload) in &self.replay {
+            if type_id == codec.tag {
+                if let Some(msg) = (codec.decode)(payload) {
+                    queue.push_back((msg, Some(*seq)));
+                }
+            }
+        }
+        drop(queue);
+
+        self.codecs.insert(TypeId::of::<M>(), codec);
+        self
+    }
+
+    ///Flush every record to stable storage before accepting it (off by default)
+    /// Without it messages survive a process crash but not a power loss
+    pub fn fsync(self, fsync: bool) -> Self {
+        self.journal.lock().unwrap_or_else(|p| p.into_inner()).fsync = fsync;
+        self
+    }
+
+    fn persist(&self, msg: &ActorMessage<A>) -> Result<Option<u64>, MailboxError> {
+        let Some(message) = msg.message() else {
+            return Ok(None);
+        };
+        let Some(codec) = self.codecs.get(&Any::type_id(message)) else {
+            return Ok(None);
+        };
+        let Some(payload) = (codec.encode)(message) else {
+            return Ok(None);
+        };
+
+        let mut journal = self.journal.lock().unwrap_or_else(|p| p.into_inner());
+        match journal.enqueue(codec.tag, &payload) {
+            Ok(seq) => Ok(Some(seq)),
+            Err(e) => {
+                eprintln!("Durable mailbox failed to persist message: {:?}", e);
+                Err(MailboxError::MailboxClosed)
+            }
+        }
+    }
+}
+
+impl<A: Actor> Mailbox<A> for DurableMailbox<A> {
+    fn enqueue(&self, msg: ActorMessage<A>) -> BoxFuture<'_, Result<(), MailboxError>> {
+        let result = self.try_enqueue(msg);
+        Box::pin(async move { result })
+    }
+
+    fn try_enqueue(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        if self.is_closed() {
+            return Err(MailboxError::MailboxClosed);
+        }
+        let seq = self.persist(&msg)?;
+        self.queue
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .push_back((msg, seq));
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    fn dequeue(&self) -> BoxFuture<'_, Option<ActorMessage<A>>> {
+        Box::pin(async move {
+            loop {
+                let next = self
+                    .queue
+                    .lock()
+                    .unwrap_or_else(|p| p.into_inner())
+                    .pop_front();
+                match next {
+                    Some((msg, Some(seq))) => {
+                        return Some(msg.with_hook(Ack {
+                            journal: self.journal.clone(),
+                            seq,
+                        }))
+                    }
+                    Some((msg, None)) => return Some(msg),
+                    None if self.is_closed() => return None,
+                    None => self.notify.notified().await,
+                }
+            }
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.queue.lock().unwrap_or_else(|p| p.into_inner()).len()
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.notify.notify_one();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+///read every enqueue record without a matching ack, in log order
+/// a torn record at the tail (crash mid-write) ends the log
+fn read_log(file: File) -> io::Result<Vec<(u64, String, Vec<u8>)>> {
+    let mut reader = BufReader::new(file);
+    let mut pending = Vec::new();
+    let mut acked = HashSet::new();
+
+    loop {
+        let mut kind = [0u8; 1];
+        if reader.read_exact(&mut kind).is_err() {
+            break;
+        }
+        let Some(seq) = read_u64(&mut reader) else {
+            break;
+        };
+        match kind[0] {
+            RECORD_ENQUEUE => {
+                let Some(type_id) = read_chunk(&mut reader) else {
+                    break;
+                };
+                let Some(payload) = read_chunk(&mut reader) else {
+                    break;
+                };
+                let Ok(type_id) = String::from_utf8(type_id) else {
+                    break;
+                };
+                pending.push((seq, type_id, payload));
+            }
+            RECORD_ACK => {
+                acked.insert(seq);
+            }
+            _ => break,
+        }
+    }
+
+    pending.retain(|(seq, _, _)| !acked.contains(seq));
+    Ok(pending)
+}
+
+fn read_u64(reader: &mut impl Read) -> Option<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf).ok()?;
+    Some(u64::from_be_bytes(buf))
+}
+
+fn read_chunk(reader: &mut impl Read) -> Option<Vec<u8>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len).ok()?;
+    let mut buf = vec![0u8; u32::from_be_bytes(len) as usize];
+    reader.read_exact(&mut buf).ok()?;
+    Some(buf)
+}
@@ -0,0 +1,267 @@
+mod dedup;
+mod durable;
+mod overflow;
+mod priority;
+mod shared;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::{mpsc, Mutex, Notify};
+
+use crate::{actor::BoxFuture, envelope::ActorMessage, error::MailboxError, Actor};
+
+pub use dedup::{DedupKey, DedupMailbox};
+pub use durable::DurableMailbox;
+pub use overflow::{OverflowMailbox, OverflowStrategy};
+pub use priority::{Priority, PriorityMailbox};
+pub(crate) use shared::SharedMailbox;
+
+/// Default mailbox capacity for actors spawned without explicit capacity
+pub const DEFAULT_CAPACITY: usize = 256;
+
+///Queue of envelopes backing an actor.
+/// Implement this to plug in custom mailboxes (persistent, deduplicating,
+/// priority ordered, ...) and select one per actor at spawn time.
+/// The actor loop is the only consumer; any number of `Addr`s may produce.
+pub trait Mailbox<A: Actor>: Send + Sync + 'static {
+    ///Enqueue a message, waiting for capacity if the mailbox is bounded
+    fn enqueue(&self, msg: ActorMessage<A>) -> BoxFuture<'_, Result<(), MailboxError>>;
+
+    ///Enqueue a message without waiting
+    fn try_enqueue(&self, msg: ActorMessage<A>) -> Result<(), MailboxError>;
+
+    ///Enqueue into the lane for `priority`
+    /// Mailboxes without lanes ignore the priority and keep FIFO order
+    fn enqueue_priority(
+        &self,
+        msg: ActorMessage<A>,
+        _priority: Priority,
+    ) -> BoxFuture<'_, Result<(), MailboxError>> {
+        self.enqueue(msg)
+    }
+
+    ///Non-waiting counterpart of `enqueue_priority`
+    fn try_enqueue_priority(
+        &self,
+        msg: ActorMessage<A>,
+        _priority: Priority,
+    ) -> Result<(), MailboxError> {
+        self.try_enqueue(msg)
+    }
+
+    ///Enqueue if it can be done without waiting, handing the message back otherwise
+    /// Lets senders skip the boxed `enqueue` future when there is room; the
+    /// default always hands it back
+    fn enqueue_now(
+        &self,
+        msg: ActorMessage<A>,
+        _priority: Priority,
+    ) -> Result<Result<(), MailboxError>, ActorMessage<A>> {
+        Err(msg)
+    }
+
+    ///Wait for the next message, None once the mailbox is closed and drained
+    fn dequeue(&self) -> BoxFuture<'_, Option<ActorMessage<A>>>;
+
+    ///The next message if one is already queued
+    /// Lets the actor loop skip the boxed `dequeue` future while mail is
+    /// waiting; the default never has one
+    fn try_dequeue(&self) -> Option<ActorMessage<A>> {
+        None
+    }
+
+    ///Hold room for `n` messages, filled later through the returned slots,
+    /// see `Addr::reserve`; room not used is given back when they drop
+    /// The default holds nothing: it resolves right away and each message
+    /// is enqueued without waiting, so it may still find the mailbox full
+    fn reserve(&self, _n: usize) -> BoxFuture<'_, Result<Box<dyn Reserved<A> + '_>, MailboxError>> {
+        Box::pin(async move {
+            if self.is_closed() {
+                return Err(MailboxError::MailboxClosed);
+            }
+            Ok(Box::new(Unreserved(self)) as Box<dyn Reserved<A> + '_>)
+        })
+    }
+
+    ///Number of queued messages (may be approximate)
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Maximum number of queued messages, None if unbounded
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    ///Stop accepting new messages
+    fn close(&self);
+
+    fn is_closed(&self) -> bool;
+}
+
+///Room held in a mailbox by `Mailbox::reserve`
+pub trait Reserved<A: Actor>: Send {
+    ///Enqueue into a held slot, MailboxFull once they are used up
+    fn enqueue(&mut self, msg: ActorMessage<A>) -> Result<(), MailboxError>;
+}
+
+///slots of a mailbox that can't hold room, every message is a `try_enqueue`
+struct Unreserved<'a, M: ?Sized>(&'a M);
+
+impl<A: Actor, M: Mailbox<A> + ?Sized> Reserved<A> for Unreserved<'_, M> {
+    fn enqueue(&mut self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        self.0.try_enqueue(msg)
+    }
+}
+
+///slots held in a channel, released when dropped
+struct ChannelSlots<'a, A: Actor> {
+    permits: mpsc::PermitIterator<'a, ActorMessage<A>>,
+    closing: &'a AtomicBool,
+}
+
+impl<A: Actor> Reserved<A> for ChannelSlots<'_, A> {
+    fn enqueue(&mut self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        if self.closing.load(Ordering::SeqCst) {
+            return Err(MailboxError::MailboxClosed);
+        }
+        let permit = self.permits.next().ok_or(MailboxError::MailboxFull)?;
+        permit.send(msg);
+        Ok(())
+    }
+}
+
+///Default bounded mailbox backed by a tokio mpsc channel
+pub struct ChannelMailbox<A: Actor> {
+    tx: mpsc::Sender<ActorMessage<A>>,
+    //only the actor loop receives, so the lock is uncontended
+    rx: Mutex<mpsc::Receiver<ActorMessage<A>>>,
+    closing: AtomicBool,
+    //wakes a dequeue waiting on an empty channel when the mailbox closes
+    close_notify: Notify,
+}
+
+impl<A: Actor> ChannelMailbox<A> {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, rx) = mpsc::channel(capacity);
+        Self {
+            tx,
+            rx: Mutex::new(rx),
+            closing: AtomicBool::new(false),
+            close_notify: Notify::new(),
+        }
+    }
+}
+
+impl<A: Actor> Default for ChannelMailbox<A> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl<A: Actor> Mailbox<A> for ChannelMailbox<A> {
+    fn enqueue(&self, msg: ActorMessage<A>) -> BoxFuture<'_, Result<(), MailboxError>> {
+        Box::pin(async move {
+            if self.is_closed() {
+                return Err(MailboxError::MailboxClosed);
+            }
+            self.tx
+                .send(msg)
+                .await
+                .map_err(|_| MailboxError::MailboxClosed)
+        })
+    }
+
+    fn try_enqueue(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        if self.is_closed() {
+            return Err(MailboxError::MailboxClosed);
+        }
+        self.tx.try_send(msg).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => MailboxError::MailboxFull,
+            mpsc::error::TrySendError::Closed(_) => MailboxError::MailboxClosed,
+        })
+    }
+
+    fn enqueue_now(
+        &self,
+        msg: ActorMessage<A>,
+        _priority: Priority,
+    ) -> Result<Result<(), MailboxError>, ActorMessage<A>> {
+        if self.is_closed() {
+            return Ok(Err(MailboxError::MailboxClosed));
+        }
+        match self.tx.try_send(msg) {
+            Ok(()) => Ok(Ok(())),
+            Err(mpsc::error::TrySendError::Full(msg)) => Err(msg),
+            Err(mpsc::error::TrySendError::Closed(_)) => Ok(Err(MailboxError::MailboxClosed)),
+        }
+    }
+
+    fn reserve(&self, n: usize) -> BoxFuture<'_, Result<Box<dyn Reserved<A> + '_>, MailboxError>> {
+        Box::pin(async move {
+            if self.is_closed() {
+                return Err(MailboxError::MailboxClosed);
+            }
+            //more than ever fits would wait forever
+            if n > self.tx.max_capacity() {
+                return Err(MailboxError::MailboxFull);
+            }
+            let permits = self
+                .tx
+                .reserve_many(n)
+                .await
+                .map_err(|_| MailboxError::MailboxClosed)?;
+            Ok(Box::new(ChannelSlots {
+                permits,
+                closing: &self.closing,
+            }) as Box<dyn Reserved<A> + '_>)
+        })
+    }
+
+    fn dequeue(&self) -> BoxFuture<'_, Option<ActorMessage<A>>> {
+        Box::pin(async move {
+            let mut rx = self.rx.lock().await;
+            if self.closing.load(Ordering::SeqCst) {
+                //closed channels still hand out what was queued, then None
+                rx.close();
+                return rx.recv().await;
+            }
+            tokio::select! {
+                biased;
+                msg = rx.recv() => msg,
+                _ = self.close_notify.notified() => {
+                    rx.close();
+                    rx.recv().await
+                }
+            }
+        })
+    }
+
+    fn try_dequeue(&self) -> Option<ActorMessage<A>> {
+        //closed channels still hand out what was queued
+        self.rx.try_lock().ok()?.try_recv().ok()
+    }
+
+    fn len(&self) -> usize {
+        self.tx.max_capacity() - self.tx.capacity()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.tx.max_capacity())
+    }
+
+    fn close(&self) {
+        self.closing.store(true, Ordering::SeqCst);
+        match self.rx.try_lock() {
+            Ok(mut rx) => rx.close(),
+            //the actor loop is waiting in dequeue, let it close the channel
+            Err(_) => self.close_notify.notify_one(),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closing.load(Ordering::SeqCst) || self.tx.is_closed()
+    }
+}
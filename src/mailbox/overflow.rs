@@ -0,0 +1,163 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{actor::BoxFuture, envelope::ActorMessage, error::MailboxError, Actor};
+
+use super::{Mailbox, Priority};
+
+/// What a bounded mailbox does with a message arriving while it is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowStrategy {
+    /// Senders wait for space, `try_send` fails with MailboxFull
+    #[default]
+    Block,
+    /// Make room by discarding the oldest queued message
+    DropOldest,
+    /// Discard the incoming message
+    DropNewest,
+}
+
+///Bounded mailbox applying an OverflowStrategy when full
+/// Dropped messages count as delivered for the sender; a dropped `send`
+/// resolves with MailboxClosed since no reply will come
+pub struct OverflowMailbox<A: Actor> {
+    queue: Mutex<VecDeque<ActorMessage<A>>>,
+    capacity: usize,
+    strategy: OverflowStrategy,
+    //a message was queued or the mailbox closed
+    ready: Notify,
+    //a slot was freed, wakes a blocked sender
+    space: Notify,
+    closed: AtomicBool,
+}
+
+impl<A: Actor> OverflowMailbox<A> {
+    pub fn new(capacity: usize, strategy: OverflowStrategy) -> Self {
+        Self {
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            strategy,
+            ready: Notify::new(),
+            space: Notify::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn strategy(&self) -> OverflowStrategy {
+        self.strategy
+    }
+
+    fn queue(&self) -> std::sync::MutexGuard<'_, VecDeque<ActorMessage<A>>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    ///queue msg or apply the strategy, handing it back only when it must wait
+    fn push(&self, msg: ActorMessage<A>) -> Result<(), (ActorMessage<A>, MailboxError)> {
+        if self.is_closed() {
+            return Err((msg, MailboxError::MailboxClosed));
+        }
+        let mut queue = self.queue();
+        let dropped = if queue.len() < self.capacity {
+            None
+        } else {
+            match self.strategy {
+                OverflowStrategy::Block => {
+                    drop(queue);
+                    return Err((msg, MailboxError::MailboxFull));
+                }
+                OverflowStrategy::DropNewest => {
+                    drop(queue);
+                    return Ok(());
+                }
+                OverflowStrategy::DropOldest => queue.pop_front(),
+            }
+        };
+        queue.push_back(msg);
+        drop(queue);
+        //dropping an envelope may run arbitrary code (reply channels, hooks), not under the lock
+        drop(dropped);
+        self.ready.notify_one();
+        Ok(())
+    }
+}
+
+impl<A: Actor> Mailbox<A> for OverflowMailbox<A> {
+    fn enqueue(&self, mut msg: ActorMessage<A>) -> BoxFuture<'_, Result<(), MailboxError>> {
+        Box::pin(async move {
+            loop {
+                let space = self.space.notified();
+                match self.push(msg) {
+                    Ok(()) => return Ok(()),
+                    Err((rejected, MailboxError::MailboxFull)) => {
+                        msg = rejected;
+                        space.await;
+                    }
+                    Err((_, err)) => return Err(err),
+                }
+            }
+        })
+    }
+
+    fn try_enqueue(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        self.push(msg).map_err(|(_, err)| err)
+    }
+
+    fn enqueue_now(
+        &self,
+        msg: ActorMessage<A>,
+        _priority: Priority,
+    ) -> Result<Result<(), MailboxError>, ActorMessage<A>> {
+        match self.push(msg) {
+            Err((rejected, MailboxError::MailboxFull)) => Err(rejected),
+            result => Ok(result.map_err(|(_, err)| err)),
+        }
+    }
+
+    fn dequeue(&self) -> BoxFuture<'_, Option<ActorMessage<A>>> {
+        Box::pin(async move {
+            loop {
+                if let Some(msg) = self.queue().pop_front() {
+                    self.space.notify_one();
+                    return Some(msg);
+                }
+                if self.is_closed() {
+                    return None;
+                }
+                self.ready.notified().await;
+            }
+        })
+    }
+
+    fn try_dequeue(&self) -> Option<ActorMessage<A>> {
+        let msg = self.queue().pop_front()?;
+        self.space.notify_one();
+        Some(msg)
+    }
+
+    fn len(&self) -> usize {
+        self.queue().len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.capacity)
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.ready.notify_one();
+        self.space.notify_waiters();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
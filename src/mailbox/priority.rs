@@ -0,0 +1,169 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{actor::BoxFuture, envelope::ActorMessage, error::MailboxError, Actor};
+
+use super::Mailbox;
+
+/// Lane a message is queued in, see `Addr::send_priority`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    /// Control messages (stop, reconfigure, ...) that should skip the backlog
+    High,
+}
+
+impl Priority {
+    fn lane(self) -> usize {
+        match self {
+            Priority::High => 0,
+            Priority::Normal => 1,
+            Priority::Low => 2,
+        }
+    }
+}
+
+///Mailbox with one lane per Priority, drained highest lane first
+/// `capacity` bounds the normal and low lanes together; high priority
+/// messages are always accepted so control traffic never waits on a backlog
+pub struct PriorityMailbox<A: Actor> {
+    lanes: Mutex<[VecDeque<ActorMessage<A>>; 3]>,
+    capacity: usize,
+    //a message was queued or the mailbox closed
+    ready: Notify,
+    //a bounded slot was freed, wakes a blocked sender
+    space: Notify,
+    closed: AtomicBool,
+}
+
+impl<A: Actor> PriorityMailbox<A> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lanes: Mutex::new(Default::default()),
+            capacity,
+            ready: Notify::new(),
+            space: Notify::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    fn lanes(&self) -> std::sync::MutexGuard<'_, [VecDeque<ActorMessage<A>>; 3]> {
+        self.lanes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn push(
+        &self,
+        msg: ActorMessage<A>,
+        priority: Priority,
+    ) -> Result<(), (ActorMessage<A>, MailboxError)> {
+        if self.is_closed() {
+            return Err((msg, MailboxError::MailboxClosed));
+        }
+        let mut lanes = self.lanes();
+        let bounded = lanes[Priority::Normal.lane()].len() + lanes[Priority::Low.lane()].len();
+        if priority != Priority::High && bounded >= self.capacity {
+            return Err((msg, MailboxError::MailboxFull));
+        }
+        lanes[priority.lane()].push_back(msg);
+        drop(lanes);
+        self.ready.notify_one();
+        Ok(())
+    }
+}
+
+impl<A: Actor> Default for PriorityMailbox<A> {
+    fn default() -> Self {
+        Self::new(super::DEFAULT_CAPACITY)
+    }
+}
+
+impl<A: Actor> Mailbox<A> for PriorityMailbox<A> {
+    fn enqueue(&self, msg: ActorMessage<A>) -> BoxFuture<'_, Result<(), MailboxError>> {
+        self.enqueue_priority(msg, Priority::Normal)
+    }
+
+    fn try_enqueue(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        self.try_enqueue_priority(msg, Priority::Normal)
+    }
+
+    fn enqueue_priority(
+        &self,
+        mut msg: ActorMessage<A>,
+        priority: Priority,
+    ) -> BoxFuture<'_, Result<(), MailboxError>> {
+        Box::pin(async move {
+            loop {
+                let space = self.space.notified();
+                match self.push(msg, priority) {
+                    Ok(()) => return Ok(()),
+                    Err((rejected, MailboxError::MailboxFull)) => {
+                        msg = rejected;
+                        space.await;
+                    }
+                    Err((_, err)) => return Err(err),
+                }
+            }
+        })
+    }
+
+    fn try_enqueue_priority(
+        &self,
+        msg: ActorMessage<A>,
+        priority: Priority,
+    ) -> Result<(), MailboxError> {
+        self.push(msg, priority).map_err(|(_, err)| err)
+    }
+
+    fn dequeue(&self) -> BoxFuture<'_, Option<ActorMessage<A>>> {
+        Box::pin(async move {
+            loop {
+                let next = {
+                    let mut lanes = self.lanes();
+                    lanes
+                        .iter_mut()
+                        .enumerate()
+                        .find_map(|(lane, queue)| queue.pop_front().map(|msg| (lane, msg)))
+                };
+                if let Some((lane, msg)) = next {
+                    if lane != Priority::High.lane() {
+                        self.space.notify_one();
+                    }
+                    return Some(msg);
+                }
+                if self.is_closed() {
+                    return None;
+                }
+                self.ready.notified().await;
+            }
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.lanes().iter().map(VecDeque::len).sum()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.capacity)
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.ready.notify_one();
+        self.space.notify_waiters();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
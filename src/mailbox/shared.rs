@@ -0,0 +1,104 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use tokio::sync::Notify;
+
+use crate::{actor::BoxFuture, envelope::ActorMessage, error::MailboxError, Actor};
+
+use super::{ChannelMailbox, Mailbox, Priority, Reserved};
+
+///One worker's view of a queue shared by a pool, see `Router::work_stealing`
+/// Every view enqueues into and dequeues from the same channel, so whichever
+/// worker is idle takes the next message. Closing a view only stops its own
+/// worker: mail still queued stays for the others instead of becoming dead letters
+pub(crate) struct SharedMailbox<A: Actor> {
+    queue: Arc<ChannelMailbox<A>>,
+    closed: AtomicBool,
+    //wakes this worker's dequeue while it waits on the shared queue
+    close_notify: Notify,
+}
+
+impl<A: Actor> SharedMailbox<A> {
+    pub(crate) fn new(queue: Arc<ChannelMailbox<A>>) -> Self {
+        Self {
+            queue,
+            closed: AtomicBool::new(false),
+            close_notify: Notify::new(),
+        }
+    }
+}
+
+impl<A: Actor> Mailbox<A> for SharedMailbox<A> {
+    fn enqueue(&self, msg: ActorMessage<A>) -> BoxFuture<'_, Result<(), MailboxError>> {
+        if self.is_closed() {
+            return Box::pin(async { Err(MailboxError::MailboxClosed) });
+        }
+        self.queue.enqueue(msg)
+    }
+
+    fn try_enqueue(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        if self.is_closed() {
+            return Err(MailboxError::MailboxClosed);
+        }
+        self.queue.try_enqueue(msg)
+    }
+
+    fn enqueue_now(
+        &self,
+        msg: ActorMessage<A>,
+        priority: Priority,
+    ) -> Result<Result<(), MailboxError>, ActorMessage<A>> {
+        if self.is_closed() {
+            return Ok(Err(MailboxError::MailboxClosed));
+        }
+        self.queue.enqueue_now(msg, priority)
+    }
+
+    fn reserve(&self, n: usize) -> BoxFuture<'_, Result<Box<dyn Reserved<A> + '_>, MailboxError>> {
+        if self.is_closed() {
+            return Box::pin(async { Err(MailboxError::MailboxClosed) });
+        }
+        self.queue.reserve(n)
+    }
+
+    fn dequeue(&self) -> BoxFuture<'_, Option<ActorMessage<A>>> {
+        Box::pin(async move {
+            if self.closed.load(Ordering::SeqCst) {
+                return None;
+            }
+            //waiting for the queue's lock and its next message are both cancel safe
+            tokio::select! {
+                biased;
+                _ = self.close_notify.notified() => None,
+                msg = self.queue.dequeue() => msg,
+            }
+        })
+    }
+
+    fn try_dequeue(&self) -> Option<ActorMessage<A>> {
+        if self.closed.load(Ordering::SeqCst) {
+            return None;
+        }
+        self.queue.try_dequeue()
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.queue.capacity()
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        //stored as a permit if the worker isn't waiting yet
+        self.close_notify.notify_one();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst) || self.queue.is_closed()
+    }
+}
@@ -1,3 +1,5 @@
+use std::{backtrace::Backtrace, sync::Arc};
+
 use crate::actor::ActorId;
 
 ///A message is something that can be sent to an actor
@@ -5,12 +7,105 @@ pub trait Message: Send + 'static {
     type Result: Send;
 }
 
+/// Message types given together to `Context::receive_only`: one type or a
+/// tuple of up to eight, e.g. `(CommitVote, Abort)`
+pub trait MessageSet {
+    ///type names, as reported by `ActorMessage::message_type`
+    fn message_types() -> Vec<&'static str>;
+}
+
+impl<M: Message> MessageSet for M {
+    fn message_types() -> Vec<&'static str> {
+        vec![std::any::type_name::<M>()]
+    }
+}
+
+macro_rules! message_set {
+    ($($ty:ident),+) => {
+        impl<$($ty: Message),+> MessageSet for ($($ty,)+) {
+            fn message_types() -> Vec<&'static str> {
+                vec![$(std::any::type_name::<$ty>()),+]
+            }
+        }
+    };
+}
+
+message_set!(M1);
+message_set!(M1, M2);
+message_set!(M1, M2, M3);
+message_set!(M1, M2, M3, M4);
+message_set!(M1, M2, M3, M4, M5);
+message_set!(M1, M2, M3, M4, M5, M6);
+message_set!(M1, M2, M3, M4, M5, M6, M7);
+message_set!(M1, M2, M3, M4, M5, M6, M7, M8);
+
 /// Sent to watchers when a watched actor stops
 #[derive(Debug, Clone)]
 pub struct Terminated {
     pub id: ActorId,
+    pub reason: TerminationReason,
+    ///name given with `SpawnOptions::name`, if any
+    pub name: Option<Arc<str>>,
+    ///place in the supervision tree, see `Addr::path`
+    pub path: Arc<str>,
+    ///backtrace of the panic that ended the actor, when backtraces are enabled
+    pub backtrace: Option<Arc<Backtrace>>,
+}
+
+/// Sent to an actor once a stream attached with `Context::add_message_stream` ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamFinished {
+    ///id returned by `add_message_stream`
+    pub id: u64,
+}
+
+impl Message for StreamFinished {
+    type Result = ();
+}
+
+/// Delivered to a parent when a child with the Escalate strategy panics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildFailed {
+    pub id: ActorId,
+    ///panic message of the failed child
+    pub error: String,
+}
+
+impl Message for ChildFailed {
+    type Result = ();
+}
+
+/// Why a watched actor stopped
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TerminationReason {
+    /// Stopped on request or because its mailbox was closed
+    #[default]
+    Normal,
+    /// A handler (or an escalating child) panicked and the strategy did not
+    /// restart it; carries the panic message
+    Panicked(String),
+    /// Kept failing and used up its restart budget
+    RestartLimitExceeded,
+    /// Cut short by system shutdown or `Addr::kill`
+    Killed,
+    /// Every `Addr` to a top-level actor was dropped
+    MailboxDropped,
 }
 
 impl Message for Terminated {
     type Result = ();
 }
+
+/// Where an unhandled message came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageOrigin {
+    Local,
+    Remote,
+}
+
+/// Describes a message no handler was found for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnhandledMessage {
+    pub message_type: String,
+    pub origin: MessageOrigin,
+}
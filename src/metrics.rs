@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::{actor::ActorId, envelope::ActorMessage, Actor};
+
+/// Measurements of one handled message, see `MetricsSink`
+#[derive(Debug, Clone)]
+pub struct MessageMetrics<'a> {
+    pub actor: ActorId,
+    ///place in the supervision tree, see `Addr::path`
+    pub path: &'a str,
+    pub actor_type: &'static str,
+    pub message_type: &'static str,
+    ///messages still queued when this one was taken out of the mailbox
+    pub queue_depth: usize,
+    ///time between sending and dequeueing, None for custom envelopes that don't record it
+    pub queue_latency: Option<Duration>,
+    ///time spent in the handler, including awaits of async handlers
+    pub handler_duration: Duration,
+}
+
+/// What happened on a connection to another node, see `RemoteMetrics`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemoteEvent {
+    ///an envelope of `bytes` (encoded) went out
+    Sent { bytes: usize },
+    Received { bytes: usize },
+    ///an envelope couldn't be encoded, or was too large for the transport
+    EncodeError,
+    ///what arrived couldn't be decoded as an envelope
+    DecodeError,
+    ///a connection to the peer was opened again after the first one
+    Reconnected,
+    ///time between sending a request and receiving its response
+    RoundTrip(Duration),
+}
+
+/// One event on the connection to `peer`, see `MetricsSink::record_remote`
+#[derive(Debug, Clone)]
+pub struct RemoteMetrics<'a> {
+    ///address of the other node
+    pub peer: &'a str,
+    pub event: RemoteEvent,
+}
+
+/// Receives per-message measurements from every actor of a system,
+/// install one with `ActorSystem::set_metrics_sink`
+/// Called on the actor's task after each message, so it should return quickly
+pub trait MetricsSink: Send + Sync + 'static {
+    fn record(&self, metrics: &MessageMetrics<'_>);
+
+    /// Traffic of remote connections metered for the system, see
+    /// `remote::MeteredTransport`; ignored unless overridden
+    /// Called on the connection's task, so it should return quickly too
+    fn record_remote(&self, _metrics: &RemoteMetrics<'_>) {}
+}
+
+///The sink of one system, shared by its actors so installing it later still reaches them
+#[derive(Default)]
+pub(crate) struct MetricsSlot {
+    sink: RwLock<Option<Arc<dyn MetricsSink>>>,
+}
+
+///Slots of every system, keyed like the directory (see `directory::system_key`)
+static SLOTS: Mutex<Option<HashMap<u64, Arc<MetricsSlot>>>> = Mutex::new(None);
+
+pub(crate) fn slot(system: u64) -> Arc<MetricsSlot> {
+    let mut slots = SLOTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    slots
+        .get_or_insert_with(HashMap::new)
+        .entry(system)
+        .or_default()
+        .clone()
+}
+
+pub(crate) fn remove_slot(system: u64) {
+    let mut slots = SLOTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(map) = slots.as_mut() {
+        map.remove(&system);
+    }
+}
+
+impl MetricsSlot {
+    pub(crate) fn set(&self, sink: Option<Arc<dyn MetricsSink>>) {
+        *self
+            .sink
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = sink;
+    }
+
+    pub(crate) fn sink(&self) -> Option<Arc<dyn MetricsSink>> {
+        self.sink
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    ///start measuring a dequeued message, None when no sink is installed
+    pub(crate) fn start<A: Actor>(
+        &self,
+        msg: &ActorMessage<A>,
+        queue_depth: usize,
+    ) -> Option<Measurement> {
+        let sink = self
+            .sink
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()?;
+        Some(Measurement {
+            sink,
+            message_type: msg.message_type(),
+            queue_depth,
+            queue_latency: msg.sent_at().map(|sent| sent.elapsed()),
+            started: Instant::now(),
+        })
+    }
+}
+
+///A message being handled, reported to the sink by `finish`
+pub(crate) struct Measurement {
+    sink: Arc<dyn MetricsSink>,
+    message_type: &'static str,
+    queue_depth: usize,
+    queue_latency: Option<Duration>,
+    started: Instant,
+}
+
+impl Measurement {
+    pub(crate) fn finish<A: Actor>(self, actor: ActorId, path: &str) {
+        self.sink.record(&MessageMetrics {
+            actor,
+            path,
+            actor_type: std::any::type_name::<A>(),
+            message_type: self.message_type,
+            queue_depth: self.queue_depth,
+            queue_latency: self.queue_latency,
+            handler_duration: self.started.elapsed(),
+        });
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use crate::{actor::ActorId, envelope::ActorMessage, Actor};
+
+/// What middleware decided about a message, see `Middleware::before`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    ///hand the message on to the next layer and the handler
+    Continue,
+    ///drop the message unhandled, a waiting sender gets `MailboxError::MailboxClosed`
+    Reject,
+}
+
+/// A message passing through middleware
+#[derive(Debug)]
+pub struct Intercepted<'a> {
+    pub actor: ActorId,
+    ///place in the supervision tree, see `Addr::path`
+    pub path: &'a str,
+    pub message_type: &'static str,
+    message: Option<&'a mut dyn Any>,
+}
+
+impl<'a> Intercepted<'a> {
+    /// The message, None if it isn't an `M` or its envelope doesn't expose it
+    /// (async handlers, custom envelopes, and always in `after`)
+    pub fn message<M: 'static>(&self) -> Option<&M> {
+        self.message.as_deref()?.downcast_ref()
+    }
+
+    /// The message for in-place changes, see `message`
+    pub fn message_mut<M: 'static>(&mut self) -> Option<&mut M> {
+        self.message.as_deref_mut()?.downcast_mut()
+    }
+}
+
+/// Code run around every handler invocation of an actor (logging, auth
+/// checks, metrics, rewriting messages, ...)
+/// Install it per actor with `SpawnOptions::middleware` or for every actor
+/// of a system with `ActorSystem::add_middleware`. System layers run first;
+/// `after` runs in reverse order, only for messages that were handled
+pub trait Middleware: Send + Sync + 'static {
+    fn before(&self, _msg: &mut Intercepted<'_>) -> Flow {
+        Flow::Continue
+    }
+
+    fn after(&self, _msg: &Intercepted<'_>) {}
+}
+
+type Layers = Vec<Arc<dyn Middleware>>;
+
+///Per-actor layers, as collected by `SpawnOptions::middleware`
+#[derive(Clone, Default)]
+pub(crate) struct MiddlewareStack(Layers);
+
+impl MiddlewareStack {
+    pub(crate) fn push(&mut self, layer: Arc<dyn Middleware>) {
+        self.0.push(layer);
+    }
+}
+
+impl fmt::Debug for MiddlewareStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MiddlewareStack({} layers)", self.0.len())
+    }
+}
+
+///System-wide layers, shared by the system's actors so layers added later still apply
+#[derive(Default)]
+pub(crate) struct SystemLayers(RwLock<Layers>);
+
+impl SystemLayers {
+    pub(crate) fn add(&self, layer: Arc<dyn Middleware>) {
+        self.0
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(layer);
+    }
+}
+
+///System layers of every system, keyed like the directory (see `directory::system_key`)
+static SYSTEM_LAYERS: Mutex<Option<HashMap<u64, Arc<SystemLayers>>>> = Mutex::new(None);
+
+pub(crate) fn system_layers(system: u64) -> Arc<SystemLayers> {
+    let mut layers = SYSTEM_LAYERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    layers
+        .get_or_insert_with(HashMap::new)
+        .entry(system)
+        .or_default()
+        .clone()
+}
+
+pub(crate) fn remove_system_layers(system: u64) {
+    let mut layers = SYSTEM_LAYERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(map) = layers.as_mut() {
+        map.remove(&system);
+    }
+}
+
+///Everything wrapping one actor's handlers: its system's layers, then its own
+pub(crate) struct Chain {
+    system: Arc<SystemLayers>,
+    actor: MiddlewareStack,
+}
+
+impl Chain {
+    pub(crate) fn new(system: u64, actor: MiddlewareStack) -> Self {
+        Self {
+            system: system_layers(system),
+            actor,
+        }
+    }
+
+    ///run the `before` hooks, returning the layers to call `after` on,
+    /// or None if one rejected the message
+    pub(crate) fn before<A: Actor>(
+        &self,
+        msg: &mut ActorMessage<A>,
+        actor: ActorId,
+        path: &str,
+    ) -> Option<Layers> {
+        let mut layers: Layers = self
+            .system
+            .0
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        layers.extend(self.actor.0.iter().cloned());
+        if layers.is_empty() {
+            return Some(layers);
+        }
+
+        let message_type = msg.message_type();
+        let mut intercepted = Intercepted {
+            actor,
+            path,
+            message_type,
+            message: msg.message_mut(),
+        };
+        for layer in &layers {
+            if layer.before(&mut intercepted) == Flow::Reject {
+                return None;
+            }
+        }
+        Some(layers)
+    }
+
+    pub(crate) fn after(
+        layers: &[Arc<dyn Middleware>],
+        actor: ActorId,
+        path: &str,
+        message_type: &'static str,
+    ) {
+        let intercepted = Intercepted {
+            actor,
+            path,
+            message_type,
+            message: None,
+        };
+        for layer in layers.iter().rev() {
+            layer.after(&intercepted);
+        }
+    }
+}
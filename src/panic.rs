@@ -0,0 +1,148 @@
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    cell::RefCell,
+    collections::HashMap,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, Once, RwLock},
+};
+
+use crate::actor::{self, ActorId};
+
+/// A handler panic, as reported to `ActorSystem::on_actor_panic` hooks
+#[derive(Debug, Clone)]
+pub struct PanicInfo {
+    pub id: ActorId,
+    ///name given with `SpawnOptions::name`, if any
+    pub name: Option<Arc<str>>,
+    ///place in the supervision tree, see `Addr::path`
+    pub path: Arc<str>,
+    ///text of the panic payload
+    pub message: String,
+    ///captured when `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` enables it
+    pub backtrace: Option<Arc<Backtrace>>,
+}
+
+pub(crate) type PanicHook = Arc<dyn Fn(&PanicInfo) + Send + Sync>;
+
+/// What a system does with an actor whose handler panicked, set with
+/// `ActorSystemConfig::panic_policy`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicPolicy {
+    ///the actor's supervisor strategy decides (default)
+    #[default]
+    Supervise,
+    ///stop the actor, whatever its strategy; contained like `SupervisorStrategy::Stop`
+    StopActor,
+    ///start a fresh instance, whatever its strategy, until its restart budget
+    /// runs out; strategies without one get 10 restarts per minute
+    RestartActor,
+    ///shut the whole system down, `ActorSystem::terminated` resolves with 1
+    EscalateToSystem,
+    ///abort the process once the panic hooks ran, for fail-fast deployments
+    AbortProcess,
+}
+
+///ends a system for `PanicPolicy::EscalateToSystem`
+pub(crate) type Terminate = Arc<dyn Fn() + Send + Sync>;
+
+///Panic hooks of every system, keyed like the directory (see `directory::system_key`)
+static HOOKS: RwLock<Option<HashMap<u64, Vec<PanicHook>>>> = RwLock::new(None);
+
+///Panic policy of every system that set one, keyed like the hooks
+static POLICIES: RwLock<Option<HashMap<u64, (PanicPolicy, Terminate)>>> = RwLock::new(None);
+
+thread_local! {
+    ///backtrace of the last panic raised by an actor on this thread
+    static LAST_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+///Chain a process panic hook that keeps the backtrace of panics raised
+/// inside actor loops, so the loop can pick it up after catching the unwind
+pub(crate) fn capture_backtraces() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if actor::current().is_some() {
+                let backtrace = Backtrace::capture();
+                if backtrace.status() == BacktraceStatus::Captured {
+                    LAST_BACKTRACE.with(|last| *last.borrow_mut() = Some(backtrace));
+                }
+            }
+            previous(info);
+        }));
+    });
+}
+
+///Backtrace of the panic just caught on this thread, if one was captured
+pub(crate) fn take_backtrace() -> Option<Arc<Backtrace>> {
+    LAST_BACKTRACE
+        .with(|last| last.borrow_mut().take())
+        .map(Arc::new)
+}
+
+pub(crate) fn add_hook(system: u64, hook: PanicHook) {
+    let mut hooks = match HOOKS.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    hooks
+        .get_or_insert_with(HashMap::new)
+        .entry(system)
+        .or_default()
+        .push(hook);
+}
+
+pub(crate) fn remove_hooks(system: u64) {
+    let mut hooks = match HOOKS.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(map) = hooks.as_mut() {
+        map.remove(&system);
+    }
+    let mut policies = match POLICIES.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(map) = policies.as_mut() {
+        map.remove(&system);
+    }
+}
+
+pub(crate) fn set_policy(system: u64, policy: PanicPolicy, terminate: Terminate) {
+    let mut policies = match POLICIES.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    policies
+        .get_or_insert_with(HashMap::new)
+        .insert(system, (policy, terminate));
+}
+
+///The system's policy, with what ends the system when it escalates
+pub(crate) fn policy(system: u64) -> Option<(PanicPolicy, Terminate)> {
+    let policies = match POLICIES.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    policies.as_ref()?.get(&system).cloned()
+}
+
+///Run the system's hooks, outside the table lock so a hook may add another
+/// A hook that panics itself is skipped rather than taking the actor down
+pub(crate) fn report(system: u64, info: &PanicInfo) {
+    let registered: Vec<PanicHook> = {
+        let hooks = match HOOKS.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match hooks.as_ref().and_then(|map| map.get(&system)) {
+            Some(registered) => registered.clone(),
+            None => return,
+        }
+    };
+    for hook in registered {
+        let _ = catch_unwind(AssertUnwindSafe(|| hook(info)));
+    }
+}
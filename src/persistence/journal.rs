@@ -0,0 +1,90 @@
+use std::{collections::HashMap, io, sync::Mutex};
+
+/// An event as a journal stores it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistedEvent {
+    pub persistence_id: String,
+    ///position in the actor's history, the first event is 1
+    pub sequence: u64,
+    ///serializer the payload was written with, see `remote::codec`
+    pub codec: u32,
+    pub payload: Vec<u8>,
+}
+
+/// Storage for the events of persistent actors, one history per persistence id
+/// Calls run on tokio's blocking pool, one at a time for the actors spawned
+/// with the same `PersistenceOptions`, so they may block on disk or network
+/// A call that panics fails like one that returned an error
+pub trait Journal: Send + Sync + 'static {
+    /// Store `event` at the end of its history
+    /// Its sequence must follow the highest one stored, otherwise another
+    /// incarnation of the actor wrote in between and it fails with `AlreadyExists`
+    fn append(&self, event: PersistedEvent) -> io::Result<()>;
+
+    /// Events of `persistence_id` from `from_sequence` on, oldest first
+    fn replay(&self, persistence_id: &str, from_sequence: u64) -> io::Result<Vec<PersistedEvent>>;
+
+    /// Highest sequence stored for `persistence_id`, 0 if it has no events
+    fn highest_sequence(&self, persistence_id: &str) -> io::Result<u64>;
+}
+
+///fail unless `event` directly follows the `highest` one stored for its id
+pub(crate) fn check_sequence(highest: u64, event: &PersistedEvent) -> io::Result<()> {
+    if event.sequence == highest + 1 {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!(
+            "event {} of {} does not follow {}",
+            event.sequence, event.persistence_id, highest
+        ),
+    ))
+}
+
+/// Journal kept in memory, for tests and actors that only need to survive
+/// restarts within the process
+#[derive(Default)]
+pub struct InMemoryJournal {
+    events: Mutex<HashMap<String, Vec<PersistedEvent>>>,
+}
+
+impl InMemoryJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn events(&self) -> std::sync::MutexGuard<'_, HashMap<String, Vec<PersistedEvent>>> {
+        self.events.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+impl Journal for InMemoryJournal {
+    fn append(&self, event: PersistedEvent) -> io::Result<()> {
+        let mut events = self.events();
+        let history = events.entry(event.persistence_id.clone()).or_default();
+        check_sequence(history.last().map_or(0, |last| last.sequence), &event)?;
+        history.push(event);
+        Ok(())
+    }
+
+    fn replay(&self, persistence_id: &str, from_sequence: u64) -> io::Result<Vec<PersistedEvent>> {
+        let events = self.events();
+        let Some(history) = events.get(persistence_id) else {
+            return Ok(Vec::new());
+        };
+        Ok(history
+            .iter()
+            .filter(|event| event.sequence >= from_sequence)
+            .cloned()
+            .collect())
+    }
+
+    fn highest_sequence(&self, persistence_id: &str) -> io::Result<u64> {
+        let events = self.events();
+        Ok(events
+            .get(persistence_id)
+            .and_then(|history| history.last())
+            .map_or(0, |last| last.sequence))
+    }
+}
@@ -0,0 +1,423 @@
+mod journal;
+mod snapshot;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+use std::{
+    collections::VecDeque,
+    io,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use tokio::sync::{oneshot, Notify};
+
+use crate::{
+    actor::BoxFuture,
+    envelope::{panic_message, ActorMessage, ContinuationEnvelope},
+    remote::{Serializer, WireFormat},
+    system::{start_hook, StartHook},
+    Actor, Context,
+};
+
+pub use journal::{InMemoryJournal, Journal, PersistedEvent};
+pub use snapshot::{InMemorySnapshotStore, PersistedSnapshot, Snapshot, SnapshotStore};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteJournal;
+
+///Actor whose state is the sum of the events its handlers persisted
+/// Handlers record what happened with `Context::persist`; the events are
+/// appended to a journal under the actor's persistence id, and every time the
+/// actor (re)starts the journal is replayed through `recover` before
+/// `started`, so the state outlives restarts and the process.
+/// Spawn it with `ActorSystem::spawn_persistent`
+pub trait PersistentActor: Actor {
+    /// What the handlers persist, stored with its serializer
+    type Event: WireFormat + Send + 'static;
+
+    /// Key of the actor's history in the journal, the same for every incarnation
+    fn persistence_id(&self) -> String;
+
+    /// Apply a persisted event while recovering
+    /// Should only change state, the effects already happened the first time
+    fn recover(&mut self, event: Self::Event, ctx: &mut Context<Self>);
+
+    /// Called once the journal was replayed, before `started`
+    fn recovered(&mut self, _ctx: &mut Context<Self>) {}
+
+    /// State to save when `PersistenceOptions::snapshot_every` says so,
+    /// None skips the snapshot
+    fn snapshot(&self) -> Option<Snapshot> {
+        None
+    }
+
+    /// Restore the state from the latest snapshot, before the events after it
+    /// are replayed
+    /// On error the whole journal is replayed instead, so leave the state as
+    /// it was
+    fn recover_snapshot(&mut self, _snapshot: Snapshot, _ctx: &mut Context<Self>) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "recover_snapshot is not implemented",
+        ))
+    }
+}
+
+/// Where a persistent actor keeps its history, see `ActorSystem::spawn_persistent`
+#[derive(Clone)]
+pub struct PersistenceOptions {
+    journal: Arc<dyn Journal>,
+    snapshots: Option<Arc<dyn SnapshotStore>>,
+    snapshot_every: u64,
+    ///shared by the clones, so an actor spawned with them recovers only once
+    /// an earlier incarnation's writes are done
+    writer: Arc<Writer>,
+}
+
+impl PersistenceOptions {
+    pub fn new(journal: Arc<dyn Journal>) -> Self {
+        Self {
+            journal,
+            snapshots: None,
+            snapshot_every: 0,
+            writer: Arc::default(),
+        }
+    }
+
+    /// Store for `Context::save_snapshot`, recovery starts from the latest one
+    pub fn snapshots(mut self, store: Arc<dyn SnapshotStore>) -> Self {
+        self.snapshots = Some(store);
+        self
+    }
+
+    /// Save `PersistentActor::snapshot` after every `events` persisted events,
+    /// needs a snapshot store. 0 turns it off, the default
+    pub fn snapshot_every(mut self, events: u64) -> Self {
+        self.snapshot_every = events;
+        self
+    }
+}
+
+impl From<Arc<dyn Journal>> for PersistenceOptions {
+    fn from(journal: Arc<dyn Journal>) -> Self {
+        Self::new(journal)
+    }
+}
+
+///Journal or snapshot store write, see `Writer`
+enum Write {
+    Event(PersistedEvent),
+    Snapshot(PersistedSnapshot),
+}
+
+///Writes of the actors spawned with one `PersistenceOptions`, made in order
+/// on the blocking pool so slow storage never stalls a runtime worker
+#[derive(Default)]
+struct Writer {
+    queue: Mutex<WriteQueue>,
+    idle: Notify,
+}
+
+#[derive(Default)]
+struct WriteQueue {
+    writes: VecDeque<(Write, oneshot::Sender<io::Result<()>>)>,
+    ///a blocking task is draining `writes`
+    running: bool,
+}
+
+impl Writer {
+    ///queue `write` behind the earlier ones, the receiver gets its outcome
+    fn push(&self, options: &PersistenceOptions, write: Write) -> oneshot::Receiver<io::Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let mut queue = self.queue();
+        queue.writes.push_back((write, tx));
+        if !queue.running {
+            queue.running = true;
+            let options = options.clone();
+            tokio::task::spawn_blocking(move || options.writer.drain(&options));
+        }
+        rx
+    }
+
+    fn drain(&self, options: &PersistenceOptions) {
+        loop {
+            let next = {
+                let mut queue = self.queue();
+                let next = queue.writes.pop_front();
+                queue.running = next.is_some();
+                next
+            };
+            let Some((write, written)) = next else {
+                self.idle.notify_waiters();
+                return;
+            };
+            //a panicking store fails its write, not the writes queued after it
+            let result = catch_unwind(AssertUnwindSafe(|| match write {
+                Write::Event(event) => options.journal.append(event),
+                Write::Snapshot(snapshot) => match &options.snapshots {
+                    Some(store) => store.save(snapshot),
+                    None => Err(io::Error::other("actor was spawned without a snapshot store")),
+                },
+            }))
+            .unwrap_or_else(|payload| {
+                Err(io::Error::other(format!(
+                    "write panicked: {}",
+                    panic_message(&payload)
+                )))
+            });
+            let _ = written.send(result);
+        }
+    }
+
+    fn queue(&self) -> MutexGuard<'_, WriteQueue> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    ///resolves once every write pushed so far is done
+    async fn idle(&self) {
+        loop {
+            let notified = self.idle.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if !self.queue().running {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+///Journal and place in it of a running persistent actor
+pub(crate) struct Persistence {
+    options: PersistenceOptions,
+    persistence_id: String,
+    ///sequence of the last event persisted or replayed
+    sequence: u64,
+    ///sequence of the last event applied to the actor, behind `sequence`
+    /// while `persist` handlers are queued
+    applied: u64,
+}
+
+impl Persistence {
+    ///queue the snapshot behind the events already persisted
+    fn save_snapshot(&self, snapshot: Snapshot) -> io::Result<oneshot::Receiver<io::Result<()>>> {
+        if self.options.snapshots.is_none() {
+            return Err(io::Error::other("actor was spawned without a snapshot store"));
+        }
+        let snapshot = PersistedSnapshot {
+            persistence_id: self.persistence_id.clone(),
+            sequence: self.applied,
+            snapshot,
+        };
+        Ok(self.options.writer.push(&self.options, Write::Snapshot(snapshot)))
+    }
+}
+
+fn encode<E: WireFormat>(event: &E) -> (u32, Vec<u8>) {
+    (
+        <E::Serializer as Serializer<E>>::CODEC,
+        <E::Serializer as Serializer<E>>::encode(event),
+    )
+}
+
+fn decode<E: WireFormat>(event: &PersistedEvent) -> io::Result<E> {
+    <E::Serializer as Serializer<E>>::decode_as(event.codec, &event.payload)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+///run a journal or snapshot store read on the blocking pool
+async fn blocking<T, F>(read: F) -> io::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(read).await.map_err(io::Error::other)?
+}
+
+///restore the latest snapshot, the sequence it was taken at or 0 without one
+/// snapshots only save time, so failing to restore one isn't fatal
+async fn recover_snapshot<A: PersistentActor>(
+    actor: &mut A,
+    ctx: &mut Context<A>,
+    persistence_id: &str,
+    store: Arc<dyn SnapshotStore>,
+) -> u64 {
+    let id = persistence_id.to_string();
+    let restored = blocking(move || store.load(&id)).await.and_then(|snapshot| match snapshot {
+        Some(snapshot) => actor
+            .recover_snapshot(snapshot.snapshot, ctx)
+            .map(|()| snapshot.sequence),
+        None => Ok(0),
+    });
+    restored.unwrap_or_else(|e| {
+        eprintln!(
+            "Persistent actor {} replays its whole journal, snapshot not restored: {:?}",
+            persistence_id, e
+        );
+        0
+    })
+}
+
+///replay the actor's history into a fresh instance, once the writes of the
+/// incarnations before it are done
+async fn recover<A: PersistentActor>(
+    actor: &mut A,
+    ctx: &mut Context<A>,
+    options: PersistenceOptions,
+) -> io::Result<()> {
+    options.writer.idle().await;
+    let persistence_id = actor.persistence_id();
+    let mut sequence = match &options.snapshots {
+        Some(store) => recover_snapshot(actor, ctx, &persistence_id, store.clone()).await,
+        None => 0,
+    };
+    let journal = options.journal.clone();
+    let id = persistence_id.clone();
+    for event in blocking(move || journal.replay(&id, sequence + 1)).await? {
+        actor.recover(decode(&event)?, ctx);
+        sequence = event.sequence;
+    }
+    ctx.set_persistence(Persistence {
+        options,
+        persistence_id,
+        sequence,
+        applied: sequence,
+    });
+    actor.recovered(ctx);
+    Ok(())
+}
+
+fn recovering<'a, A: PersistentActor>(
+    actor: &'a mut A,
+    ctx: &'a mut Context<A>,
+    options: PersistenceOptions,
+) -> BoxFuture<'a, ()> {
+    Box::pin(async move {
+        if let Err(e) = recover(actor, ctx, options).await {
+            eprintln!(
+                "Persistent actor {} failed to recover: {:?}",
+                actor.persistence_id(),
+                e
+            );
+            ctx.stop();
+        }
+    })
+}
+
+///recovery run on every start of an actor spawned with `options`
+/// an actor that can't read its history stops rather than run on partial state
+pub(crate) fn recovery<A: PersistentActor>(options: PersistenceOptions) -> StartHook<A> {
+    start_hook(move |actor, ctx| recovering(actor, ctx, options.clone()))
+}
+
+impl<A: PersistentActor> Context<A> {
+    /// Append `event` to the actor's journal, then apply it with `handler`
+    /// The append runs on the blocking pool once the current handler returns,
+    /// holding back the actor's mail until it is done; the handler then runs
+    /// ahead of any queued message. Returns the event's sequence. An event
+    /// the journal rejects is never applied and stops the actor
+    pub fn persist<F>(&mut self, event: A::Event, handler: F) -> io::Result<u64>
+    where
+        F: FnOnce(&mut A, A::Event) + Send + 'static,
+    {
+        let persistence = self.persistence_mut().ok_or_else(|| {
+            io::Error::other("actor was not spawned with a journal, see spawn_persistent")
+        })?;
+        let (codec, payload) = encode(&event);
+        let sequence = persistence.sequence + 1;
+        let persistence_id = persistence.persistence_id.clone();
+        let write = Write::Event(PersistedEvent {
+            persistence_id: persistence_id.clone(),
+            sequence,
+            codec,
+            payload,
+        });
+        let rx = persistence.options.writer.push(&persistence.options, write);
+        persistence.sequence = sequence;
+
+        //the outcome of the append, for the handler queued behind it
+        let outcome = Arc::new(Mutex::new(None));
+        let written = outcome.clone();
+        self.wait(async move {
+            let result = rx
+                .await
+                .unwrap_or_else(|_| Err(io::Error::other("journal write was dropped")));
+            *written.lock().unwrap_or_else(|p| p.into_inner()) = Some(result);
+        });
+        let apply = ContinuationEnvelope {
+            f: move |actor: &mut A, ctx: &mut Context<A>| match outcome
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .take()
+            {
+                Some(Ok(())) => {
+                    handler(actor, event);
+                    ctx.applied(actor, sequence);
+                }
+                failed => {
+                    eprintln!(
+                        "Persistent actor {} failed to persist event {}: {:?}",
+                        persistence_id, sequence, failed
+                    );
+                    ctx.stop();
+                }
+            },
+        };
+        self.notify_envelope(ActorMessage::Sync(Box::new(apply)));
+        Ok(sequence)
+    }
+
+    /// Sequence of the last event persisted or recovered, 0 before the first
+    pub fn last_sequence(&self) -> u64 {
+        self.persistence().map_or(0, |p| p.sequence)
+    }
+
+    /// Save `state` as the actor's snapshot, recovery then restores it with
+    /// `PersistentActor::recover_snapshot` and only replays the events after it
+    /// `state` should not include events whose `persist` handler hasn't run yet
+    /// Like `persist`, mail waits until it is stored; a failure is only logged
+    pub fn save_snapshot<S: WireFormat>(&mut self, state: &S) -> io::Result<()> {
+        self.write_snapshot(Snapshot::new(state))
+    }
+
+    fn write_snapshot(&mut self, snapshot: Snapshot) -> io::Result<()> {
+        let persistence = self.persistence().ok_or_else(|| {
+            io::Error::other("actor was not spawned with a journal, see spawn_persistent")
+        })?;
+        let written = persistence.save_snapshot(snapshot)?;
+        let persistence_id = persistence.persistence_id.clone();
+        self.wait(async move {
+            if let Ok(Err(e)) = written.await {
+                eprintln!(
+                    "Persistent actor {} failed to save a snapshot: {:?}",
+                    persistence_id, e
+                );
+            }
+        });
+        Ok(())
+    }
+
+    ///`sequence` was applied, take a snapshot if the policy says so
+    fn applied(&mut self, actor: &A, sequence: u64) {
+        let Some(persistence) = self.persistence_mut() else {
+            return;
+        };
+        persistence.applied = sequence;
+        let every = persistence.options.snapshot_every;
+        if every == 0 || !sequence.is_multiple_of(every) {
+            return;
+        }
+        let Some(snapshot) = actor.snapshot() else {
+            return;
+        };
+        let persistence_id = persistence.persistence_id.clone();
+        if let Err(e) = self.write_snapshot(snapshot) {
+            eprintln!(
+                "Persistent actor {} failed to save a snapshot: {:?}",
+                persistence_id, e
+            );
+        }
+    }
+}
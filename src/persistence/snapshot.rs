@@ -0,0 +1,78 @@
+use std::{collections::HashMap, io, sync::Mutex};
+
+use crate::remote::{Serializer, WireFormat};
+
+/// Encoded state of a persistent actor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    ///serializer the payload was written with, see `remote::codec`
+    pub codec: u32,
+    pub payload: Vec<u8>,
+}
+
+impl Snapshot {
+    pub fn new<S: WireFormat>(state: &S) -> Self {
+        Self {
+            codec: <S::Serializer as Serializer<S>>::CODEC,
+            payload: <S::Serializer as Serializer<S>>::encode(state),
+        }
+    }
+
+    /// Decode the state, with the codec it was written in
+    pub fn state<S: WireFormat>(&self) -> io::Result<S> {
+        <S::Serializer as Serializer<S>>::decode_as(self.codec, &self.payload)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// A snapshot as a store keeps it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistedSnapshot {
+    pub persistence_id: String,
+    ///sequence of the last event the state includes
+    pub sequence: u64,
+    pub snapshot: Snapshot,
+}
+
+/// Storage for the snapshots of persistent actors
+/// Only the latest snapshot of an actor is ever read back
+pub trait SnapshotStore: Send + Sync + 'static {
+    fn save(&self, snapshot: PersistedSnapshot) -> io::Result<()>;
+
+    /// Snapshot of `persistence_id` with the highest sequence, if any
+    fn load(&self, persistence_id: &str) -> io::Result<Option<PersistedSnapshot>>;
+}
+
+/// Snapshot store kept in memory, holds the latest snapshot of each actor
+#[derive(Default)]
+pub struct InMemorySnapshotStore {
+    snapshots: Mutex<HashMap<String, PersistedSnapshot>>,
+}
+
+impl InMemorySnapshotStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn snapshots(&self) -> std::sync::MutexGuard<'_, HashMap<String, PersistedSnapshot>> {
+        self.snapshots.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+impl SnapshotStore for InMemorySnapshotStore {
+    fn save(&self, snapshot: PersistedSnapshot) -> io::Result<()> {
+        let mut snapshots = self.snapshots();
+        match snapshots.get(&snapshot.persistence_id) {
+            //a slower incarnation saving an older state
+            Some(latest) if latest.sequence > snapshot.sequence => {}
+            _ => {
+                snapshots.insert(snapshot.persistence_id.clone(), snapshot);
+            }
+        }
+        Ok(())
+    }
+
+    fn load(&self, persistence_id: &str) -> io::Result<Option<PersistedSnapshot>> {
+        Ok(self.snapshots().get(persistence_id).cloned())
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    io,
+    path::Path,
+    sync::{Mutex, MutexGuard},
+};
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::persistence::{
+    journal::check_sequence, Journal, PersistedEvent, PersistedSnapshot, Snapshot, SnapshotStore,
+};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS events (
+        persistence_id TEXT NOT NULL,
+        sequence INTEGER NOT NULL,
+        codec INTEGER NOT NULL,
+        payload BLOB NOT NULL,
+        PRIMARY KEY (persistence_id, sequence)
+    );
+    CREATE TABLE IF NOT EXISTS snapshots (
+        persistence_id TEXT PRIMARY KEY,
+        sequence INTEGER NOT NULL,
+        codec INTEGER NOT NULL,
+        payload BLOB NOT NULL
+    );
+";
+
+fn io_error(e: rusqlite::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+/// Journal and snapshot store in a SQLite database, events survive the process
+/// One file can hold any number of actors, and serve as both stores. Calls
+/// wait for the connection on the blocking pool, never on a runtime worker:
+///
+/// ```ignore
+/// let store = Arc::new(SqliteJournal::open("cinema.db")?);
+/// let options = PersistenceOptions::new(store.clone()).snapshots(store);
+/// ```
+pub struct SqliteJournal {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteJournal {
+    /// Open or create the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::with_connection(Connection::open(path).map_err(io_error)?)
+    }
+
+    /// Database that lives as long as the journal, for tests
+    pub fn in_memory() -> io::Result<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(io_error)?)
+    }
+
+    fn with_connection(conn: Connection) -> io::Result<Self> {
+        conn.execute_batch(SCHEMA).map_err(io_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+fn highest_sequence(conn: &Connection, persistence_id: &str) -> rusqlite::Result<u64> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(sequence), 0) FROM events WHERE persistence_id = ?1",
+        params![persistence_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|sequence| sequence as u64)
+}
+
+impl Journal for SqliteJournal {
+    fn append(&self, event: PersistedEvent) -> io::Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(io_error)?;
+        check_sequence(
+            highest_sequence(&tx, &event.persistence_id).map_err(io_error)?,
+            &event,
+        )?;
+        tx.execute(
+            "INSERT INTO events (persistence_id, sequence, codec, payload) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                event.persistence_id,
+                event.sequence as i64,
+                event.codec,
+                event.payload
+            ],
+        )
+        .map_err(io_error)?;
+        tx.commit().map_err(io_error)
+    }
+
+    fn replay(&self, persistence_id: &str, from_sequence: u64) -> io::Result<Vec<PersistedEvent>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT sequence, codec, payload FROM events
+                 WHERE persistence_id = ?1 AND sequence >= ?2 ORDER BY sequence",
+            )
+            .map_err(io_error)?;
+        let events = stmt
+            .query_map(params![persistence_id, from_sequence as i64], |row| {
+                Ok(PersistedEvent {
+                    persistence_id: persistence_id.to_string(),
+                    sequence: row.get::<_, i64>(0)? as u64,
+                    codec: row.get(1)?,
+                    payload: row.get(2)?,
+                })
+            })
+            .map_err(io_error)?;
+        events.collect::<Result<_, _>>().map_err(io_error)
+    }
+
+    fn highest_sequence(&self, persistence_id: &str) -> io::Result<u64> {
+        highest_sequence(&self.conn(), persistence_id).map_err(io_error)
+    }
+}
+
+impl SnapshotStore for SqliteJournal {
+    fn save(&self, snapshot: PersistedSnapshot) -> io::Result<()> {
+        //an older state never replaces a newer one
+        self.conn()
+            .execute(
+                "INSERT INTO snapshots (persistence_id, sequence, codec, payload)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (persistence_id) DO UPDATE
+                 SET sequence = excluded.sequence, codec = excluded.codec, payload = excluded.payload
+                 WHERE excluded.sequence >= snapshots.sequence",
+                params![
+                    snapshot.persistence_id,
+                    snapshot.sequence as i64,
+                    snapshot.snapshot.codec,
+                    snapshot.snapshot.payload
+                ],
+            )
+            .map(|_| ())
+            .map_err(io_error)
+    }
+
+    fn load(&self, persistence_id: &str) -> io::Result<Option<PersistedSnapshot>> {
+        self.conn()
+            .query_row(
+                "SELECT sequence, codec, payload FROM snapshots WHERE persistence_id = ?1",
+                params![persistence_id],
+                |row| {
+                    Ok(PersistedSnapshot {
+                        persistence_id: persistence_id.to_string(),
+                        sequence: row.get::<_, i64>(0)? as u64,
+                        snapshot: Snapshot {
+                            codec: row.get(1)?,
+                            payload: row.get(2)?,
+                        },
+                    })
+                },
+            )
+            .optional()
+            .map_err(io_error)
+    }
+}
@@ -31,7 +31,7 @@ impl Registry {
 
         // Spawn watcher task for auto-unregister
         let name = name.to_string();
-        let addr_watch = addr;
+        let addr_watch = addr.downgrade();
         tokio::spawn(async move {
             while addr_watch.is_alive() {
                 tokio::time::sleep(Duration::from_millis(50)).await;
@@ -67,3 +67,9 @@ impl Registry {
         map.remove(name);
     }
 }
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
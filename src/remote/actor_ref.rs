@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use crate::{
+    remote::{proto::Envelope, RemoteAddr, RemoteMessage, TransportError},
+    Actor, Addr, Handler, MailboxError,
+};
+
+/// Why a message sent through an `ActorRef` failed
+#[derive(Debug)]
+pub enum SendError {
+    ///the local actor's mailbox turned it away
+    Mailbox(MailboxError),
+    ///the remote actor's node couldn't be reached, or its answer not decoded
+    Transport(TransportError),
+}
+
+impl From<MailboxError> for SendError {
+    fn from(err: MailboxError) -> Self {
+        SendError::Mailbox(err)
+    }
+}
+
+impl From<TransportError> for SendError {
+    fn from(err: TransportError) -> Self {
+        SendError::Transport(err)
+    }
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Mailbox(err) => write!(f, "{}", err),
+            SendError::Transport(err) => write!(f, "Remote send failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SendError::Mailbox(err) => Some(err),
+            SendError::Transport(err) => Some(err),
+        }
+    }
+}
+
+///Location-transparent address of an actor, on this node or another one
+/// Sends take the same messages either way (they must be `RemoteMessage`s,
+/// answered with `RemoteMessage`s) and return the decoded result, so callers
+/// and routers hold one kind of handle whatever side of the wire the actor is on
+pub enum ActorRef<A: Actor> {
+    Local(Addr<A>),
+    Remote(RemoteAddr<A>),
+}
+
+impl<A: Actor> Clone for ActorRef<A> {
+    fn clone(&self) -> Self {
+        match self {
+            ActorRef::Local(addr) => ActorRef::Local(addr.clone()),
+            ActorRef::Remote(addr) => ActorRef::Remote(addr.clone()),
+        }
+    }
+}
+
+impl<A: Actor> From<Addr<A>> for ActorRef<A> {
+    fn from(addr: Addr<A>) -> Self {
+        ActorRef::Local(addr)
+    }
+}
+
+impl<A: Actor> From<RemoteAddr<A>> for ActorRef<A> {
+    fn from(addr: RemoteAddr<A>) -> Self {
+        ActorRef::Remote(addr)
+    }
+}
+
+impl<A: Actor> ActorRef<A> {
+    pub fn is_local(&self) -> bool {
+        matches!(self, ActorRef::Local(_))
+    }
+
+    ///the local address, if the actor runs on this node
+    pub fn local(&self) -> Option<&Addr<A>> {
+        match self {
+            ActorRef::Local(addr) => Some(addr),
+            ActorRef::Remote(_) => None,
+        }
+    }
+
+    ///Send message and wait for its result
+    pub async fn send<M>(&self, msg: M) -> Result<M::Result, SendError>
+    where
+        A: Handler<M>,
+        M: RemoteMessage,
+        M::Result: RemoteMessage,
+    {
+        match self {
+            ActorRef::Local(addr) => Ok(addr.send(msg).await?),
+            ActorRef::Remote(addr) => decode::<M>(addr.send(msg).await?),
+        }
+    }
+
+    pub async fn send_timeout<M>(&self, msg: M, timeout: Duration) -> Result<M::Result, SendError>
+    where
+        A: Handler<M>,
+        M: RemoteMessage,
+        M::Result: RemoteMessage,
+    {
+        match self {
+            ActorRef::Local(addr) => Ok(addr.send_timeout(msg, timeout).await?),
+            ActorRef::Remote(addr) => decode::<M>(addr.send_timeout(msg, timeout).await?),
+        }
+    }
+
+    ///Fire and forget message sending
+    pub async fn do_send<M>(&self, msg: M) -> Result<(), SendError>
+    where
+        A: Handler<M>,
+        M: RemoteMessage,
+    {
+        match self {
+            ActorRef::Local(addr) => Ok(addr.do_send(msg).await?),
+            ActorRef::Remote(addr) => Ok(addr.do_send(msg).await?),
+        }
+    }
+}
+
+fn decode<M>(response: Envelope) -> Result<M::Result, SendError>
+where
+    M: RemoteMessage,
+    M::Result: RemoteMessage,
+{
+    response.message::<M::Result>().map_err(|e| {
+        SendError::Transport(TransportError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("failed to decode response: {}", e),
+        )))
+    })
+}
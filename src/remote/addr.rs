@@ -1,9 +1,14 @@
 use std::{
     marker::PhantomData,
     sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
 };
 
-use crate::remote::{proto::Envelope, RemoteClient, RemoteMessage, TransportError};
+use crate::remote::{
+    proto::{Envelope, RemoteRef},
+    DeliveryConfig, LocalNode, ReconnectPolicy, ReliableAddr, RemoteClient, RemoteMessage,
+    TcpTransport, TransportError,
+};
 
 ///global correlation id counter
 static CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
@@ -16,6 +21,31 @@ pub(crate) fn next_correlation_id() -> u64 {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct NodeId(pub String);
 
+impl NodeId {
+    /// A random (version 4) UUID, for nodes without a name of their own
+    /// Persist it to keep the id across restarts; the node's generation is
+    /// what tells the restarts apart
+    pub fn random() -> Self {
+        let bits = rand::random::<u128>() & !(0xf000 << 64) & !(0xc000 << 48);
+        let bits = bits | (0x4000 << 64) | (0x8000 << 48);
+        let hex = format!("{:032x}", bits);
+        NodeId(format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        ))
+    }
+}
+
+impl std::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 ///address of a remote actor
 #[derive(Debug, Clone)]
 pub struct RemoteActorId {
@@ -31,6 +61,17 @@ pub struct RemoteAddr<A> {
     _phantom: PhantomData<A>,
 }
 
+impl<A> Clone for RemoteAddr<A> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            local_node: self.local_node.clone(),
+            client: self.client.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
 impl<A> RemoteAddr<A> {
     pub fn new(local_node_id: &str, remote_node_id: &str, actor_name: &str, client: RemoteClient) -> Self {
         Self {
@@ -59,6 +100,7 @@ impl<A> RemoteAddr<A> {
         self.client.do_send(envelope).await
     }
 
+    ///send and wait for the response envelope, matched by a fresh correlation id
     pub async fn send<M>(&self, msg: M) -> Result<Envelope, TransportError>
     where
         M: RemoteMessage,
@@ -67,4 +109,36 @@ impl<A> RemoteAddr<A> {
             Envelope::from_message(&msg, next_correlation_id(), &self.local_node.0, &self.id.actor_name);
         self.client.send(envelope).await
     }
+
+    ///send and wait at most `timeout` for the response, see `RemoteClient::send_timeout`
+    pub async fn send_timeout<M>(&self, msg: M, timeout: Duration) -> Result<Envelope, TransportError>
+    where
+        M: RemoteMessage,
+    {
+        let envelope =
+            Envelope::from_message(&msg, next_correlation_id(), &self.local_node.0, &self.id.actor_name);
+        self.client.send_timeout(envelope, timeout).await
+    }
+
+    ///same actor, with tells delivered at least once, see `ReliableAddr`
+    /// pair it with a reconnecting client so retries outlive a dropped connection
+    pub fn reliable(&self, config: DeliveryConfig) -> ReliableAddr<A> {
+        ReliableAddr::new(self.id.clone(), &self.local_node.0, self.client.clone(), config)
+    }
+}
+
+impl RemoteRef {
+    ///materialize the reference over an existing connection to its node
+    pub fn with_client<A>(&self, local: &LocalNode, client: RemoteClient) -> RemoteAddr<A> {
+        RemoteAddr::new(&local.id.0, &self.node_addr, &self.actor_name, client)
+    }
+
+    ///connect back to the owning node and materialize the reference
+    /// the connection is redialed if the node restarts, see `ReconnectPolicy`
+    pub async fn connect<A>(&self, local: &LocalNode) -> Result<RemoteAddr<A>, TransportError> {
+        let client =
+            RemoteClient::connect_with_reconnect(TcpTransport, &self.node_addr, ReconnectPolicy::default())
+                .await?;
+        Ok(self.with_client(local, client))
+    }
 }
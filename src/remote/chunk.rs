@@ -0,0 +1,218 @@
+use std::{collections::HashMap, future::Future, io, pin::Pin};
+
+use crate::remote::{
+    proto::{Chunk, Envelope},
+    transport::{Connection, Transport, TransportError},
+};
+
+///How payloads are split up and how much a peer may send in pieces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkConfig {
+    ///payloads larger than this travel as fragments of at most this size,
+    /// keep it well below the codec's max frame size
+    pub chunk_size: usize,
+    ///largest payload reassembled from a peer's fragments, also the most
+    /// buffered at once across all messages still arriving
+    pub max_message_size: usize,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: 1024 * 1024,
+            max_message_size: 256 * 1024 * 1024,
+        }
+    }
+}
+
+impl ChunkConfig {
+    pub fn chunk_size(mut self, bytes: usize) -> Self {
+        self.chunk_size = bytes.max(1);
+        self
+    }
+
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = bytes;
+        self
+    }
+}
+
+///fragment being put back together
+struct Partial {
+    head: Envelope,
+    count: u32,
+    next: u32,
+}
+
+fn invalid(msg: String) -> TransportError {
+    TransportError::Io(io::Error::new(io::ErrorKind::InvalidData, msg))
+}
+
+///Connection wrapper that sends large payloads as sequenced fragments
+/// Envelopes with payloads over `chunk_size` are split into fragments
+/// carrying the original header plus their position (`Envelope::chunk`); no
+/// single frame then has to hold the whole payload. Fragments from the peer
+/// are reassembled before `recv` returns; a peer sending more than
+/// `max_message_size` or fragments out of order gets an error instead
+pub struct ChunkedConnection<C> {
+    inner: C,
+    config: ChunkConfig,
+    next_id: u64,
+    partial: HashMap<u64, Partial>,
+    ///payload bytes held in `partial`
+    buffered: usize,
+}
+
+impl<C: Connection> ChunkedConnection<C> {
+    pub fn new(inner: C, config: ChunkConfig) -> Self {
+        Self {
+            inner,
+            config,
+            next_id: 0,
+            partial: HashMap::new(),
+            buffered: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    ///the envelope itself when it's small enough, its fragments otherwise
+    fn split(&mut self, mut envelope: Envelope) -> Vec<Envelope> {
+        if envelope.payload.len() <= self.config.chunk_size {
+            return vec![envelope];
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let payload = std::mem::take(&mut envelope.payload);
+        let count = payload.len().div_ceil(self.config.chunk_size) as u32;
+        payload
+            .chunks(self.config.chunk_size)
+            .enumerate()
+            .map(|(index, piece)| Envelope {
+                payload: piece.to_vec(),
+                chunk: Some(Chunk {
+                    id,
+                    index: index as u32,
+                    count,
+                }),
+                ..envelope.clone()
+            })
+            .collect()
+    }
+
+    ///take in a fragment, returning the envelope once its last one arrived
+    fn reassemble(&mut self, mut fragment: Envelope) -> Result<Option<Envelope>, TransportError> {
+        let Some(chunk) = fragment.chunk.take() else {
+            return Ok(Some(fragment));
+        };
+        let piece = std::mem::take(&mut fragment.payload);
+
+        let partial = self.partial.entry(chunk.id).or_insert_with(|| Partial {
+            head: fragment,
+            count: chunk.count,
+            next: 0,
+        });
+        if chunk.index != partial.next || chunk.count != partial.count {
+            return Err(invalid(format!(
+                "fragment {} of {} out of sequence for chunked message {}",
+                chunk.index, chunk.count, chunk.id
+            )));
+        }
+        if self.buffered + piece.len() > self.config.max_message_size {
+            return Err(invalid(format!(
+                "chunked messages exceed the {} byte limit",
+                self.config.max_message_size
+            )));
+        }
+
+        self.buffered += piece.len();
+        partial.head.payload.extend_from_slice(&piece);
+        partial.next += 1;
+        if partial.next < partial.count {
+            return Ok(None);
+        }
+        let done = self.partial.remove(&chunk.id).map(|done| done.head);
+        self.buffered -= done.as_ref().map_or(0, |e| e.payload.len());
+        Ok(done)
+    }
+}
+
+impl<C: Connection> Connection for ChunkedConnection<C> {
+    fn send(
+        &mut self,
+        envelope: Envelope,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        let fragments = self.split(envelope);
+        Box::pin(async move {
+            //one frame at a time, nothing holds the whole payload encoded
+            for fragment in fragments {
+                self.inner.send(fragment).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn send_batch(
+        &mut self,
+        envelopes: Vec<Envelope>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        let fragments = envelopes
+            .into_iter()
+            .flat_map(|envelope| self.split(envelope))
+            .collect();
+        self.inner.send_batch(fragments)
+    }
+
+    fn recv(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            loop {
+                let fragment = self.inner.recv().await?;
+                if let Some(envelope) = self.reassemble(fragment)? {
+                    return Ok(envelope);
+                }
+            }
+        })
+    }
+
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        self.inner.flush()
+    }
+
+    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        self.inner.close()
+    }
+
+    fn local_addr(&self) -> &str {
+        self.inner.local_addr()
+    }
+}
+
+///Transport whose connections are wrapped in `ChunkedConnection`
+#[derive(Debug, Clone)]
+pub struct ChunkedTransport<T> {
+    inner: T,
+    config: ChunkConfig,
+}
+
+impl<T: Transport> ChunkedTransport<T> {
+    pub fn new(inner: T, config: ChunkConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+impl<T: Transport> Transport for ChunkedTransport<T> {
+    type Conn = ChunkedConnection<T::Conn>;
+
+    fn connect(
+        &self,
+        addr: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Conn, TransportError>> + Send + '_>> {
+        let connecting = self.inner.connect(addr);
+        Box::pin(async move { Ok(ChunkedConnection::new(connecting.await?, self.config)) })
+    }
+}
@@ -1,66 +1,130 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use tokio::{
     sync::{mpsc, oneshot, Mutex},
     time::timeout,
 };
 
-use crate::remote::{proto::Envelope, Connection, RemoteAddr, TcpConnection, TransportError};
+use crate::remote::{failure, proto::Envelope, Connection, RemoteAddr, Transport, TransportError};
 
 ///a pending request waiting for a response
 type PendingRequest = oneshot::Sender<Result<Envelope, TransportError>>;
 
+///requests awaiting a response, by correlation id
+type PendingTable = Arc<Mutex<HashMap<u64, PendingRequest>>>;
+
+///how often requests whose caller gave up (timed out or dropped the future)
+/// are removed from the pending table
+const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
 enum ClientCommand {
     Send {
-        envelope: Envelope,
+        ///boxed, an envelope is much larger than the other variants
+        envelope: Box<Envelope>,
         response_tx: Option<PendingRequest>,
     },
     #[allow(dead_code)]
     Close,
 }
 
+///How outbound envelopes are grouped into a single write
+#[derive(Debug, Clone, Copy)]
+pub struct CoalesceConfig {
+    ///most envelopes written per flush
+    pub max_batch: usize,
+    ///how long to wait for more envelopes before flushing a partial batch
+    /// zero only groups what is already queued, so it never adds latency
+    pub max_delay: Duration,
+}
+
+impl Default for CoalesceConfig {
+    fn default() -> Self {
+        Self {
+            max_batch: 64,
+            max_delay: Duration::ZERO,
+        }
+    }
+}
+
+impl CoalesceConfig {
+    ///one write per envelope
+    pub fn disabled() -> Self {
+        Self {
+            max_batch: 1,
+            max_delay: Duration::ZERO,
+        }
+    }
+}
+
 ///remote client for sending messages to remote actors
 #[derive(Clone)]
 pub struct RemoteClient {
     cmd_tx: mpsc::Sender<ClientCommand>,
     local_addr: String,
+    pending: PendingTable,
 }
 
 impl RemoteClient {
-    pub fn new(mut conn: TcpConnection) -> Self {
+    pub fn new<C: Connection + 'static>(conn: C) -> Self {
+        Self::with_coalescing(conn, CoalesceConfig::default())
+    }
+
+    ///client that batches outbound envelopes according to `config`
+    pub fn with_coalescing<C: Connection + 'static>(conn: C, config: CoalesceConfig) -> Self {
+        Self::spawn(conn, config, None)
+    }
+
+    ///connect to `addr` and dial it again whenever the connection drops,
+    /// so the client outlives restarts of the peer, see `ReconnectPolicy`
+    pub async fn connect_with_reconnect<T>(
+        transport: T,
+        addr: &str,
+        policy: ReconnectPolicy,
+    ) -> Result<Self, TransportError>
+    where
+        T: Transport + 'static,
+    {
+        let transport = Arc::new(transport);
+        let conn = transport.connect(addr).await?;
+        let addr = addr.to_string();
+        let redial = Redial {
+            connect: Box::new(move || {
+                let transport = transport.clone();
+                let addr = addr.clone();
+                Box::pin(async move { transport.connect(&addr).await })
+            }),
+            policy,
+        };
+        Ok(Self::spawn(conn, CoalesceConfig::default(), Some(redial)))
+    }
+
+    fn spawn<C: Connection + 'static>(
+        mut conn: C,
+        config: CoalesceConfig,
+        redial: Option<Redial<C>>,
+    ) -> Self {
         let local_addr = conn.local_addr().to_string();
         let (cmd_tx, mut cmd_rx) = mpsc::channel::<ClientCommand>(32);
-        let pending_requests: Arc<Mutex<HashMap<u64, PendingRequest>>> =
-            Arc::new(Mutex::new(HashMap::new()));
+        let pending_requests: PendingTable = Arc::new(Mutex::new(HashMap::new()));
 
         let pending_clone = pending_requests.clone();
+        let max_batch = config.max_batch.max(1);
+        let reconnects = redial.is_some();
 
         tokio::spawn(async move {
+            let mut sweep = tokio::time::interval(SWEEP_INTERVAL);
             loop {
-                tokio::select! {
+                let lost = tokio::select! {
                     Some(cmd) = cmd_rx.recv() => {
-                        match cmd {
-                            ClientCommand::Send {envelope, response_tx} => {
-                                let correlation_id = envelope.correlation_id;
-
-                                //track pending request if response is expected
-                                if let Some(tx) = response_tx {
-                                    let mut pending = pending_clone.lock().await;
-                                    pending.insert(correlation_id, tx);
-                                }
+                        let (batch, closing) = collect_batch(cmd, &mut cmd_rx, max_batch, config.max_delay).await;
 
-                                //send the envelope
-                                if let Err(e) = conn.send(envelope).await {
-                                    if let Some(tx) = pending_clone.lock().await.remove(&correlation_id) {
-                                    let _ = tx.send(Err(e));
-                                    }
-                                }
-                            }
+                        //send the whole batch with one flush
+                        let written = flush(&mut conn, batch, &pending_clone, reconnects).await;
 
-                            ClientCommand::Close => {
-                                break;
-                            }
+                        if closing {
+                            break;
                         }
+                        (!written).then_some(Lost::Write)
                     }
                     //incoming message
                     result = conn.recv() => {
@@ -68,19 +132,47 @@ impl RemoteClient {
                             Ok(envelope) => {
                                 if envelope.is_response {
                                     if let Some(tx) = pending_clone.lock().await.remove(&envelope.correlation_id) {
-                                        let _ = tx.send(Ok(envelope));
+                                        let _ = tx.send(failure::check(envelope));
                                     }
                                 }
+                                //heartbeats the connection queued meanwhile
+                                conn.flush().await.is_err().then_some(Lost::Write)
                             }
-                            Err(TransportError::Disconnected) => break,
-                            Err(_) => continue,
+                            Err(TransportError::Disconnected) => Some(Lost::Closed),
+                            Err(_) => None,
                         }
                     }
+                    //a late response to an abandoned request is dropped like an unknown one
+                    _ = sweep.tick() => {
+                        pending_clone.lock().await.retain(|_, tx| !tx.is_closed());
+                        None
+                    }
+                };
+
+                match (lost, &redial) {
+                    (None, _) | (Some(Lost::Write), None) => {}
+                    (Some(Lost::Closed), None) => break,
+                    (Some(_), Some(redial)) => match redial.run(&mut cmd_rx, &pending_clone).await {
+                        Some(fresh) => conn = fresh,
+                        None => break,
+                    },
                 }
             }
+
+            //nothing will answer what is still waiting
+            fail_pending(&pending_clone, TransportError::Disconnected).await;
         });
 
-        Self { cmd_tx, local_addr }
+        Self {
+            cmd_tx,
+            local_addr,
+            pending: pending_requests,
+        }
+    }
+
+    /// Requests sent and still waiting for their response
+    pub async fn pending_requests(&self) -> usize {
+        self.pending.lock().await.len()
     }
 
     /// Get the local socket address (auto-derived identity)
@@ -97,7 +189,7 @@ impl RemoteClient {
     pub async fn do_send(&self, envelope: Envelope) -> Result<(), TransportError> {
         self.cmd_tx
             .send(ClientCommand::Send {
-                envelope,
+                envelope: Box::new(envelope),
                 response_tx: None,
             })
             .await
@@ -110,7 +202,7 @@ impl RemoteClient {
 
         self.cmd_tx
             .send(ClientCommand::Send {
-                envelope,
+                envelope: Box::new(envelope),
                 response_tx: Some(tx),
             })
             .await
@@ -119,14 +211,269 @@ impl RemoteClient {
         rx.await.map_err(|_| TransportError::Disconnected)?
     }
 
+    /// Send and wait at most `duration` for the response, failing with Timeout
+    /// The abandoned request is dropped from the pending table shortly after
+    /// The peer learns the deadline too and answers with DeadlineExceeded
+    /// instead of doing work nobody waits for, see `make_handler`
     pub async fn send_timeout(
         &self,
-        envelope: Envelope,
+        mut envelope: Envelope,
         duration: Duration,
     ) -> Result<Envelope, TransportError> {
+        envelope.set_timeout(duration);
         match timeout(duration, self.send(envelope)).await {
             Ok(result) => result,
             Err(_) => Err(TransportError::Timeout),
         }
     }
 }
+
+type Outbound = (Envelope, Option<PendingRequest>);
+
+///gather queued sends behind `first`, waiting at most `max_delay` for stragglers
+/// returns the batch and whether a Close was seen
+async fn collect_batch(
+    first: ClientCommand,
+    cmd_rx: &mut mpsc::Receiver<ClientCommand>,
+    max_batch: usize,
+    max_delay: Duration,
+) -> (Vec<Outbound>, bool) {
+    let mut batch = Vec::new();
+    let mut next = Some(first);
+    let deadline = tokio::time::Instant::now() + max_delay;
+
+    loop {
+        match next.take() {
+            Some(ClientCommand::Send {
+                envelope,
+                response_tx,
+            }) => batch.push((*envelope, response_tx)),
+            Some(ClientCommand::Close) => return (batch, true),
+            None => {}
+        }
+
+        if batch.len() >= max_batch {
+            return (batch, false);
+        }
+
+        next = match cmd_rx.try_recv() {
+            Ok(cmd) => Some(cmd),
+            Err(_) if max_delay.is_zero() => return (batch, false),
+            Err(_) => match tokio::time::timeout_at(deadline, cmd_rx.recv()).await {
+                Ok(Some(cmd)) => Some(cmd),
+                _ => return (batch, false),
+            },
+        };
+    }
+}
+
+///why the connection loop stopped using its connection
+enum Lost {
+    ///a write failed, the socket is likely gone
+    Write,
+    ///the peer closed the connection
+    Closed,
+}
+
+///write a batch, failing its requests if the write doesn't go through
+/// while reconnecting those fail with `Reconnecting` instead of the raw error
+async fn flush<C: Connection>(
+    conn: &mut C,
+    batch: Vec<Outbound>,
+    pending: &PendingTable,
+    reconnecting: bool,
+) -> bool {
+    let mut envelopes = Vec::with_capacity(batch.len());
+    let mut correlation_ids = Vec::new();
+    for (envelope, response_tx) in batch {
+        //track pending request if response is expected
+        if let Some(tx) = response_tx {
+            correlation_ids.push(envelope.correlation_id);
+            pending.lock().await.insert(envelope.correlation_id, tx);
+        }
+        envelopes.push(envelope);
+    }
+
+    if envelopes.is_empty() {
+        return true;
+    }
+    match conn.send_batch(envelopes).await {
+        Ok(()) => true,
+        Err(e) => {
+            let mut pending = pending.lock().await;
+            for id in correlation_ids {
+                if let Some(tx) = pending.remove(&id) {
+                    let err = if reconnecting {
+                        TransportError::Reconnecting
+                    } else {
+                        clone_error(&e)
+                    };
+                    let _ = tx.send(Err(err));
+                }
+            }
+            false
+        }
+    }
+}
+
+///fail every request still waiting for a response
+async fn fail_pending(pending: &PendingTable, err: TransportError) {
+    for (_, tx) in pending.lock().await.drain() {
+        let _ = tx.send(Err(clone_error(&err)));
+    }
+}
+
+///Backoff between attempts to re-establish a dropped connection
+/// Attempt n waits `min * multiplier^(n-1)`, capped at `max`, less a random
+/// share of up to `jitter` of it so clients cut off together don't redial together
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReconnectPolicy {
+    pub min: Duration,
+    pub max: Duration,
+    pub multiplier: f64,
+    ///fraction of each delay that is randomized, 0.0 to 1.0
+    pub jitter: f64,
+    ///sends held while reconnecting and written once the connection is back,
+    /// beyond this they fail with `TransportError::Reconnecting`
+    pub buffer: usize,
+    ///attempts before the client gives up for good, `None` retries forever
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(100), Duration::from_secs(10))
+    }
+}
+
+impl ReconnectPolicy {
+    ///doubling backoff between `min` and `max` with 20% jitter, retrying forever
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max,
+            multiplier: 2.0,
+            jitter: 0.2,
+            buffer: 1024,
+            max_attempts: None,
+        }
+    }
+
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn buffer(mut self, buffer: usize) -> Self {
+        self.buffer = buffer;
+        self
+    }
+
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    ///delay before the given attempt (1 = first attempt after the drop)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.min.as_secs_f64() * self.multiplier.powi(exponent);
+        let base = if secs.is_finite() && secs < self.max.as_secs_f64() {
+            Duration::from_secs_f64(secs)
+        } else {
+            self.max
+        };
+        base.mul_f64(1.0 - self.jitter * rand::random::<f64>())
+    }
+}
+
+///dials a fresh connection to the same peer
+type Connect<C> =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = Result<C, TransportError>> + Send>> + Send + Sync>;
+
+struct Redial<C> {
+    connect: Connect<C>,
+    policy: ReconnectPolicy,
+}
+
+impl<C: Connection> Redial<C> {
+    ///dial again with backoff, holding sends meanwhile
+    /// `None` once the attempts run out or the client is closed
+    async fn run(
+        &self,
+        cmd_rx: &mut mpsc::Receiver<ClientCommand>,
+        pending: &PendingTable,
+    ) -> Option<C> {
+        //their responses were lost with the old connection
+        fail_pending(pending, TransportError::Reconnecting).await;
+
+        let mut held: Vec<Outbound> = Vec::new();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            if self.policy.max_attempts.is_some_and(|max| attempt > max) {
+                break;
+            }
+
+            let backoff = tokio::time::sleep(self.policy.delay(attempt));
+            tokio::pin!(backoff);
+            loop {
+                tokio::select! {
+                    _ = &mut backoff => break,
+                    cmd = cmd_rx.recv() => match cmd {
+                        Some(ClientCommand::Send { envelope, response_tx }) => {
+                            if held.len() < self.policy.buffer {
+                                held.push((*envelope, response_tx));
+                            } else if let Some(tx) = response_tx {
+                                let _ = tx.send(Err(TransportError::Reconnecting));
+                            }
+                        }
+                        Some(ClientCommand::Close) | None => {
+                            fail_held(held, TransportError::Disconnected);
+                            return None;
+                        }
+                    },
+                }
+            }
+
+            if let Ok(mut conn) = (self.connect)().await {
+                flush(&mut conn, held, pending, true).await;
+                return Some(conn);
+            }
+        }
+
+        fail_held(held, TransportError::Disconnected);
+        None
+    }
+}
+
+fn fail_held(held: Vec<Outbound>, err: TransportError) {
+    for (_, response_tx) in held {
+        if let Some(tx) = response_tx {
+            let _ = tx.send(Err(clone_error(&err)));
+        }
+    }
+}
+
+///TransportError holds an io::Error, which is not Clone
+fn clone_error(e: &TransportError) -> TransportError {
+    match e {
+        TransportError::Io(err) => TransportError::Io(std::io::Error::new(err.kind(), err.to_string())),
+        TransportError::Decode(err) => TransportError::Decode(err.clone()),
+        TransportError::Disconnected => TransportError::Disconnected,
+        TransportError::Timeout => TransportError::Timeout,
+        TransportError::CircuitOpen => TransportError::CircuitOpen,
+        TransportError::Reconnecting => TransportError::Reconnecting,
+        TransportError::Handshake(reason) => TransportError::Handshake(reason.clone()),
+        TransportError::DeadlineExceeded => TransportError::DeadlineExceeded,
+        TransportError::UnknownMessageType(reason) => {
+            TransportError::UnknownMessageType(reason.clone())
+        }
+        TransportError::Remote(reason) => TransportError::Remote(reason.clone()),
+    }
+}
@@ -1,15 +1,36 @@
-use crate::remote::{
-    proto::{cluster_message, ActorLocation, ClusterMessage, Envelope, GossipMessage, NodeInfo},
-    Connection, EnvelopeHandler, TcpConnection, TcpTransport, Transport, TransportError,
+use crate::{
+    event_bus::EventBus,
+    message::Message as ActorMessage,
+    remote::{
+        codec,
+        datagram::GossipSocket,
+        handshake::{self, is_handshake},
+        proto::{
+            cluster_message, ActorLocation, ClusterMessage, Envelope, GossipMessage, NodeInfo,
+            NodeTopics, Ping, PingAck, PingReq,
+        },
+        pubsub::Subscribers,
+        swim::Updates,
+        CompressedConnection, CompressionConfig, Connection, EnvelopeHandler, FailureDetection,
+        FailureDetector, HandshakeConfig, HandshakeTransport, ReconnectPolicy, SwimConfig,
+        TcpConnection, TcpTransport, Transport, TransportError, Verdict,
+    },
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
 };
-use std::{collections::HashMap, sync::Arc};
 
 use bytes::BytesMut;
 use prost::Message;
 use rand::seq::IteratorRandom;
 use tokio::{net::TcpListener, sync::RwLock, time::{Duration, Instant}};
 
-#[derive(Clone, PartialEq, Eq)]
+///how long gossip sent as a datagram waits for the peer's
+const GOSSIP_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Node {
     pub id: String,
     pub addr: String, //for tcp host:port
@@ -23,19 +44,123 @@ pub enum NodeStatus {
     Down,
 }
 
+impl NodeStatus {
+    ///at the same incarnation Down beats Suspect beats Up
+    fn rank(&self) -> u8 {
+        match self {
+            NodeStatus::Up => 0,
+            NodeStatus::Suspect => 1,
+            NodeStatus::Down => 2,
+        }
+    }
+}
+
+///a member as this node sees it
+struct Member {
+    node: Node,
+    ///when the member's process started, 0 until heard from it
+    generation: u64,
+    ///the member's own counter, raised to refute suspicion of it
+    incarnation: u64,
+    ///since when it is suspect, swim declares it down after `suspect_timeout`
+    suspected_at: Option<Instant>,
+}
+
+impl Member {
+    fn new(node: Node) -> Self {
+        Self {
+            node,
+            generation: 0,
+            incarnation: 0,
+            suspected_at: None,
+        }
+    }
+
+    ///whether `generation` is a restart of the member we knew
+    fn restarted_as(&self, generation: u64) -> bool {
+        self.generation != 0 && generation > self.generation
+    }
+
+    fn info(&self) -> NodeInfo {
+        self.declared(self.node.status.clone())
+    }
+
+    ///the member's entry with `status`, at its current incarnation
+    fn declared(&self, status: NodeStatus) -> NodeInfo {
+        let node = Node {
+            status,
+            ..self.node.clone()
+        };
+        NodeInfo {
+            incarnation: self.incarnation,
+            generation: self.generation,
+            ..NodeInfo::from(&node)
+        }
+    }
+}
+
+/// Published when a member joins the cluster or is reachable again
+#[derive(Debug, Clone)]
+pub struct MemberUp(pub Node);
+
+/// Published when a member stops answering and is suspected to have failed
+#[derive(Debug, Clone)]
+pub struct MemberSuspect(pub Node);
+
+/// Published when a member is declared down, its actors are dropped from the registry
+#[derive(Debug, Clone)]
+pub struct MemberDown(pub Node);
+
+impl ActorMessage for MemberUp {
+    type Result = ();
+}
+
+impl ActorMessage for MemberSuspect {
+    type Result = ();
+}
+
+impl ActorMessage for MemberDown {
+    type Result = ();
+}
+
 /// Represents a node in the cluster along with its members.
 pub struct ClusterNode {
-    ///our own node information
+    ///our own node information, `addr` being the advertised address
     pub local_node: Node,
-    ///cluster members(node id -> Node)
-    members: Arc<RwLock<HashMap<String, Node>>>,
-    ///last heartbeat time for each node
-    last_heartbeat: Arc<RwLock<HashMap<String, Instant>>>,
-    ///actor_id -> (node_id, actor_type)
-    actor_registry: Arc<RwLock<HashMap<String, (String, String)>>>,
+    ///when this node started, tells peers a restart apart from a reconnect
+    generation: u64,
+    ///where `serve` listens, if not on the advertised port of all interfaces
+    bind: Option<String>,
+    ///gossip and swim over UDP, set through `gossip_over_udp`
+    udp: bool,
+    ///bound next to the server's listener when `udp` is set
+    datagrams: OnceLock<GossipSocket>,
+    ///cluster members(node id -> Member)
+    members: Arc<RwLock<HashMap<String, Member>>>,
+    ///heartbeat arrivals per node, judging who is still alive
+    detector: Arc<RwLock<FailureDetector>>,
+    ///set through `failure_detection`, otherwise `start_periodic_gossip` picks
+    detection: Option<FailureDetection>,
+    swim: SwimConfig,
+    ///membership changes to piggyback on swim messages
+    updates: Arc<Mutex<Updates>>,
+    ///checked against peers that introduce themselves, presented by `join`
+    handshake: Option<HandshakeConfig>,
+    ///backoff between `join` rounds while no seed is reachable
+    join_retry: ReconnectPolicy,
+    ///actor_id -> where it runs
+    actor_registry: Arc<RwLock<HashMap<String, ActorLocation>>>,
+    ///where membership changes are published
+    events: EventBus,
+    ///local pub-sub subscribers, see `DistributedPubSub`
+    subscribers: Arc<Subscribers>,
+    ///pub-sub topics of the other nodes (node id -> their latest list)
+    topics: Arc<RwLock<HashMap<String, NodeTopics>>>,
 }
 
 impl ClusterNode {
+    /// `addr` is the address peers reach this node at, gossiped to them in
+    /// `NodeInfo`; see `bind` for when it differs from the listening socket
     pub fn new(id: String, addr: String) -> Self {
         let local_node = Node {
             id: id.clone(),
@@ -43,105 +168,383 @@ impl ClusterNode {
             status: NodeStatus::Up,
         };
 
+        //wall clock based, so a restarted node comes back with a newer one
+        let generation = wall_clock_micros();
         let mut members = HashMap::new();
-        members.insert(id.clone(), local_node.clone());
-
-        let mut heartbeats = HashMap::new();
-        heartbeats.insert(id, Instant::now());
+        members.insert(
+            id.clone(),
+            Member {
+                generation,
+                ..Member::new(local_node.clone())
+            },
+        );
 
         Self {
             local_node,
+            generation,
+            bind: None,
+            udp: false,
+            datagrams: OnceLock::new(),
             members: Arc::new(RwLock::new(members)),
-            last_heartbeat: Arc::new(RwLock::new(heartbeats)),
+            detector: Arc::new(RwLock::new(FailureDetector::new(FailureDetection::default()))),
+            detection: None,
+            swim: SwimConfig::default(),
+            updates: Arc::default(),
+            handshake: None,
+            join_retry: ReconnectPolicy::default(),
             actor_registry: Arc::new(RwLock::new(HashMap::new())),
+            events: EventBus::new(),
+            subscribers: Arc::default(),
+            topics: Arc::default(),
+        }
+    }
+
+    /// How members are judged Up, Suspect or Down, e.g. phi accrual instead of
+    /// the plain timeout `start_periodic_gossip` falls back to
+    pub fn failure_detection(mut self, detection: FailureDetection) -> Self {
+        self.detector = Arc::new(RwLock::new(FailureDetector::new(detection)));
+        self.detection = Some(detection);
+        self
+    }
+
+    /// Timing of the SWIM protocol run by `start_swim`
+    pub fn swim(mut self, config: SwimConfig) -> Self {
+        self.swim = config;
+        self
+    }
+
+    /// Cluster name and token this node joins with, and that peers
+    /// introducing themselves to its server must match
+    /// Connections to the server must then open with the handshake, others
+    /// are closed; this node handshakes on every connection it opens anyway
+    pub fn handshake(mut self, config: HandshakeConfig) -> Self {
+        self.handshake = Some(config.generation(self.generation));
+        self
+    }
+
+    /// Backoff between `join` rounds, `max_attempts` bounds how long to wait
+    /// for a seed (by default forever)
+    pub fn join_retry(mut self, policy: ReconnectPolicy) -> Self {
+        self.join_retry = policy;
+        self
+    }
+
+    /// Address `serve` listens on when peers can't dial the socket directly,
+    /// e.g. `0.0.0.0:9000` in a container advertised as `node-3.cinema.svc:9000`
+    pub fn bind(mut self, addr: impl Into<String>) -> Self {
+        self.bind = Some(addr.into());
+        self
+    }
+
+    /// Exchange gossip and swim probes as UDP datagrams instead of opening
+    /// a TCP connection for each, on the same port number as the server
+    /// Messages over `MAX_DATAGRAM` still go over TCP; actor messages,
+    /// pub-sub and joins always do. Every member must enable this alike,
+    /// since probes are sent to peers' UDP port. Datagrams can't carry the
+    /// handshake, so anyone reaching the port could fake membership changes:
+    /// serving a node that also has a `handshake` fails with `InvalidInput`
+    pub fn gossip_over_udp(mut self) -> Self {
+        self.udp = true;
+        self
+    }
+
+    /// When this node started (wall clock, in microseconds), sent in its
+    /// `NodeInfo` and handshake. A node that restarts under the same id comes
+    /// back with a newer generation, and peers drop what they knew of the old one
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The address peers dial this node at
+    pub fn advertised_addr(&self) -> &str {
+        &self.local_node.addr
+    }
+
+    /// The address `serve` listens on, by default every interface on the
+    /// advertised port
+    pub fn bind_addr(&self) -> String {
+        if let Some(bind) = &self.bind {
+            return bind.clone();
+        }
+        match self.local_node.addr.rsplit_once(':') {
+            Some((_, port)) => format!("0.0.0.0:{}", port),
+            None => self.local_node.addr.clone(),
         }
     }
 
     ///add or update a member in the cluster
     pub async fn add_member(&self, node: Node) {
         let mut members = self.members.write().await;
-        members.insert(node.id.clone(), node.clone());
+        let previous = members.get(&node.id).map(|m| m.node.status.clone());
+        let member = members
+            .entry(node.id.clone())
+            .or_insert_with(|| Member::new(node.clone()));
+        member.node = node.clone();
+        self.gossip_change(member.info());
+        if previous.as_ref() != Some(&node.status) {
+            self.announce(&node);
+        }
 
         // Record heartbeat time
-        let mut heartbeats = self.last_heartbeat.write().await;
-        heartbeats.insert(node.id, Instant::now());
+        let mut detector = self.detector.write().await;
+        detector.heartbeat(&node.id, Instant::now());
+    }
+
+    /// Membership changes as actor messages
+    /// Subscribe to `MemberUp`, `MemberSuspect` or `MemberDown` to react to
+    /// topology changes instead of polling `get_members`, e.g.
+    /// `cluster.events().subscribe::<MemberDown, _>(&rebalancer)`
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    ///publish a member's new status to `events` subscribers
+    fn announce(&self, node: &Node) {
+        match node.status {
+            NodeStatus::Up => self.events.publish(MemberUp(node.clone())),
+            NodeStatus::Suspect => self.events.publish(MemberSuspect(node.clone())),
+            NodeStatus::Down => self.events.publish(MemberDown(node.clone())),
+        };
     }
 
     ///get all members in the cluster
     pub async fn get_members(&self) -> Vec<Node> {
         let members = self.members.read().await;
-        members.values().cloned().collect()
+        members.values().map(|m| m.node.clone()).collect()
     }
 
     ///register an actor running on this node
+    /// Replaces a registration of the same id elsewhere, the latest one wins
+    /// on every node as gossip spreads it
     pub async fn register_actor(&self, actor_id: String, actor_type: String) {
+        self.locate_actor(actor_id, self.local_node.id.clone(), actor_type)
+            .await;
+    }
+
+    ///record that the actor registered here as `actor_id` moved to `node_id`,
+    /// ahead of the registration the node makes there reaching us by gossip
+    pub(crate) async fn relocate_actor(&self, actor_id: &str, node_id: &str) {
+        let Some((_, actor_type)) = self.lookup_actor(actor_id).await else {
+            return;
+        };
+        self.locate_actor(actor_id.to_string(), node_id.to_string(), actor_type)
+            .await;
+    }
+
+    async fn locate_actor(&self, actor_id: String, node_id: String, actor_type: String) {
         let mut registry = self.actor_registry.write().await;
-        registry.insert(actor_id, (self.local_node.id.clone(), actor_type));
+        let version = match registry.get(&actor_id) {
+            Some(known) => wall_clock_micros().max(known.version + 1),
+            None => wall_clock_micros(),
+        };
+        registry.insert(
+            actor_id.clone(),
+            ActorLocation {
+                actor_id,
+                node_id,
+                actor_type,
+                version,
+            },
+        );
     }
 
     ///lookup which node an actor is running on
     pub async fn lookup_actor(&self, actor_id: &str) -> Option<(String, String)> {
         let registry = self.actor_registry.read().await;
-        registry.get(actor_id).cloned()
+        registry
+            .get(actor_id)
+            .map(|loc| (loc.node_id.clone(), loc.actor_type.clone()))
     }
 
     /// Test helper: manually insert an actor location (for testing failure scenarios)
     #[doc(hidden)]
     pub async fn test_insert_actor(&self, actor_id: String, node_id: String, actor_type: String) {
         let mut registry = self.actor_registry.write().await;
-        registry.insert(actor_id, (node_id, actor_type));
+        registry.insert(
+            actor_id.clone(),
+            ActorLocation {
+                actor_id,
+                node_id,
+                actor_type,
+                version: 0,
+            },
+        );
     }
 
     ///create a gossip message with current cluster members
     pub async fn create_gossip_message(&self) -> GossipMessage {
         let members = self.members.read().await;
-        let node_infos = members.values().map(|n| NodeInfo::from(n)).collect();
+        let node_infos = members.values().map(Member::info).collect();
 
         let registry = self.actor_registry.read().await;
-        let actor_locations = registry
-            .iter()
-            .map(|(actor_id, (node_id, actor_type))| ActorLocation {
-                actor_id: actor_id.clone(),
-                node_id: node_id.clone(),
-                actor_type: actor_type.clone(),
-            })
-            .collect();
+        let actor_locations = registry.values().cloned().collect();
+
+        let mut topics: Vec<NodeTopics> = self.topics.read().await.values().cloned().collect();
+        topics.push(self.subscribers.snapshot(&self.local_node.id));
 
         GossipMessage {
             members: node_infos,
             actors: actor_locations,
+            topics,
         }
     }
 
     pub async fn merge_gossip(&self, gossip: GossipMessage, sender_node_id: &str) {
         let mut members = self.members.write().await;
-        let mut heartbeats = self.last_heartbeat.write().await;
+        let mut detector = self.detector.write().await;
 
+        let now = Instant::now();
+        let mut down_nodes = Vec::new();
+        let mut restarted = Vec::new();
+        //nodes the sender only knows a previous generation of
+        let mut outdated = Vec::new();
         for node_info in gossip.members {
-            let node: Node = node_info.into();
-
-            //update if dont know this node or status changed
-            members
-                .entry(node.id.clone())
-                .and_modify(|existing_node| {
-                    if existing_node.status != node.status {
-                        *existing_node = node.clone();
-                    }
-                })
-                .or_insert(node.clone());
+            let id = node_info.id.clone();
+            if members
+                .get(&id)
+                .is_some_and(|m| node_info.generation != 0 && node_info.generation < m.generation)
+            {
+                outdated.push(id.clone());
+            }
+            if id != self.local_node.id
+                && members
+                    .get(&id)
+                    .is_some_and(|m| m.restarted_as(node_info.generation))
+            {
+                println!(
+                    "[{}] {} restarted, forgetting its previous generation",
+                    self.local_node.id, id
+                );
+                detector.remove(&id);
+                restarted.push(id.clone());
+            }
+            if self.apply(&mut members, node_info, now) == Some(NodeStatus::Down) {
+                detector.remove(&id);
+                down_nodes.push(id);
+            }
         }
 
         // only update heartbeat for the actual sender, not all nodes in gossip
-        heartbeats.insert(sender_node_id.to_string(), Instant::now());
+        if sender_node_id != self.local_node.id {
+            detector.heartbeat(sender_node_id, Instant::now());
+        }
+
+        drop(detector);
 
-        // Merge actor locations
+        //take each node's newest topic list, a node we consider down keeps none;
+        // lists of a generation the node has since restarted from are ignored
+        let mut topics = self.topics.write().await;
+        for node_id in &restarted {
+            topics.remove(node_id);
+        }
+        for node_topics in gossip.topics {
+            let down = members
+                .get(&node_topics.node_id)
+                .is_none_or(|m| m.node.status == NodeStatus::Down);
+            let outdated = outdated.contains(&node_topics.node_id);
+            if node_topics.node_id == self.local_node.id || down || outdated {
+                continue;
+            }
+            match topics.get(&node_topics.node_id) {
+                Some(known) if known.version >= node_topics.version => {}
+                _ => {
+                    topics.insert(node_topics.node_id.clone(), node_topics);
+                }
+            }
+        }
+        drop(topics);
+        drop(members);
+
+        // Merge actor locations, those of a previous generation are stale
         let mut registry = self.actor_registry.write().await;
+        registry.retain(|_, loc| !restarted.contains(&loc.node_id));
         for actor_loc in gossip.actors {
-            registry.insert(
-                actor_loc.actor_id,
-                (actor_loc.node_id, actor_loc.actor_type),
-            );
+            if outdated.contains(&actor_loc.node_id) {
+                continue;
+            }
+            match registry.get(&actor_loc.actor_id) {
+                Some(known) if known.version > actor_loc.version => {}
+                _ => {
+                    registry.insert(actor_loc.actor_id.clone(), actor_loc);
+                }
+            }
         }
+        drop(registry);
+        self.forget_down_nodes(&down_nodes).await;
+    }
+
+    ///apply a membership change heard from another node, SWIM style: the
+    /// newer generation wins, then the higher incarnation, at the same one
+    /// Down beats Suspect beats Up. Suspicion of ourselves is refuted with a
+    /// higher incarnation instead. Returns the member's new status if it changed
+    fn apply(
+        &self,
+        members: &mut HashMap<String, Member>,
+        info: NodeInfo,
+        now: Instant,
+    ) -> Option<NodeStatus> {
+        let incarnation = info.incarnation;
+        //0 from peers that don't tell, taken as the generation we know
+        let generation = match (info.generation, members.get(&info.id)) {
+            (0, Some(member)) => member.generation,
+            (generation, _) => generation,
+        };
+        let node: Node = info.into();
+
+        if node.id == self.local_node.id {
+            let local = members.get_mut(&node.id)?;
+            //news of a previous life of ours is outranked already
+            if node.status != NodeStatus::Up
+                && generation == local.generation
+                && incarnation >= local.incarnation
+            {
+                local.incarnation = incarnation + 1;
+                println!(
+                    "[{}] Refuting {:?}, now at incarnation {}",
+                    self.local_node.id, node.status, local.incarnation
+                );
+                self.gossip_change(local.info());
+            }
+            return None;
+        }
+
+        let id = node.id.clone();
+        let previous = members.get(&id).map(|m| m.node.status.clone());
+        match members.get_mut(&id) {
+            Some(member)
+                if (generation, incarnation, node.status.rank())
+                    <= (
+                        member.generation,
+                        member.incarnation,
+                        member.node.status.rank(),
+                    ) =>
+            {
+                return None
+            }
+            Some(member) => {
+                member.generation = generation;
+                member.incarnation = incarnation;
+                member.node = node;
+            }
+            None => {
+                members.insert(
+                    id.clone(),
+                    Member {
+                        generation,
+                        incarnation,
+                        ..Member::new(node)
+                    },
+                );
+            }
+        }
+
+        let member = members.get_mut(&id)?;
+        member.suspected_at = (member.node.status == NodeStatus::Suspect).then_some(now);
+        self.gossip_change(member.info());
+        if previous.as_ref() != Some(&member.node.status) {
+            self.announce(&member.node);
+        }
+        Some(member.node.status.clone())
     }
 
     ///start cluster server (handles both gossip and actor messages)
@@ -152,7 +555,109 @@ impl ClusterNode {
     ) -> std::io::Result<()> {
         let addr = format!("0.0.0.0:{}", port);
         let listener = TcpListener::bind(&addr).await?;
+        self.accept(listener, actor_handler).await
+    }
+
+    /// Start the cluster server on `bind_addr`, like `start_server`
+    pub async fn serve(
+        self: Arc<Self>,
+        actor_handler: Option<EnvelopeHandler>,
+    ) -> std::io::Result<()> {
+        let listener = TcpListener::bind(self.bind_addr()).await?;
+        self.accept(listener, actor_handler).await
+    }
 
+    async fn accept(
+        self: Arc<Self>,
+        listener: TcpListener,
+        actor_handler: Option<EnvelopeHandler>,
+    ) -> std::io::Result<()> {
+        if !self.udp {
+            return self.accept_streams(listener, actor_handler).await;
+        }
+        if self.handshake.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "UDP gossip is unauthenticated and can't be combined with a handshake",
+            ));
+        }
+        let socket = GossipSocket::bind(listener.local_addr()?).await?;
+        //a node served twice keeps answering on its first socket
+        let _ = self.datagrams.set(socket);
+        tokio::select! {
+            result = self.clone().accept_streams(listener, actor_handler) => result,
+            result = self.clone().serve_datagrams() => result,
+        }
+    }
+
+    ///answer gossip and swim datagrams, each on its own task
+    async fn serve_datagrams(self: Arc<Self>) -> std::io::Result<()> {
+        let Some(socket) = self.datagrams.get() else {
+            return Ok(());
+        };
+        loop {
+            let (request, from) = socket.recv().await?;
+            let cluster = self.clone();
+            tokio::spawn(async move {
+                if let Some(response) = cluster.on_datagram(&request).await {
+                    if let Some(socket) = cluster.datagrams.get() {
+                        socket.reply(from, &request, response).await;
+                    }
+                }
+            });
+        }
+    }
+
+    ///the answer to a gossip or swim datagram, trimmed to fit in one
+    async fn on_datagram(&self, request: &Envelope) -> Option<Envelope> {
+        match ClusterMessage::decode(request.payload.as_slice()).ok()?.payload? {
+            cluster_message::Payload::Gossip(gossip) => {
+                self.merge_gossip(gossip, &request.sender_node).await;
+                let mut ours = self.create_gossip_message().await;
+                let response = self.envelope(cluster_message::Payload::Gossip(ours.clone()), true);
+                if GossipSocket::fits(&response) {
+                    return Some(response);
+                }
+                //actors and topics then spread with the TCP gossip this node starts
+                ours.actors.clear();
+                ours.topics.clear();
+                let response = self.envelope(cluster_message::Payload::Gossip(ours), true);
+                GossipSocket::fits(&response).then_some(response)
+            }
+            cluster_message::Payload::Ping(ping) => {
+                let ack = self.on_ping(ping.source, ping.updates).await;
+                Some(self.ack_datagram(ack))
+            }
+            cluster_message::Payload::PingReq(req) => {
+                let ack = self.on_ping_req(req).await;
+                Some(self.ack_datagram(ack))
+            }
+            _ => None,
+        }
+    }
+
+    ///wrap `ack`, leaving out piggybacked updates until it fits a datagram
+    fn ack_datagram(&self, mut ack: PingAck) -> Envelope {
+        loop {
+            let response = self.envelope(cluster_message::Payload::PingAck(ack.clone()), true);
+            if GossipSocket::fits(&response) || ack.updates.pop().is_none() {
+                return response;
+            }
+        }
+    }
+
+    ///the datagram socket, if `envelope` should go over it
+    fn datagram_socket(&self, envelope: &Envelope) -> Option<&GossipSocket> {
+        self.datagrams
+            .get()
+            .filter(|_| GossipSocket::fits(envelope))
+    }
+
+    async fn accept_streams(
+        self: Arc<Self>,
+        listener: TcpListener,
+        actor_handler: Option<EnvelopeHandler>,
+    ) -> std::io::Result<()> {
         loop {
             let (stream, _peer) = listener.accept().await?;
             let cluster = self.clone();
@@ -160,22 +665,72 @@ impl ClusterNode {
 
             tokio::spawn(async move {
                 let mut conn = TcpConnection::new(stream);
+                let mut compression = CompressionConfig::default();
+                //like `RemoteServer`, a configured handshake must come first
+                if let Some(config) = &cluster.handshake {
+                    match handshake::accept(&mut conn, config).await {
+                        Ok((hello, agreed)) => {
+                            cluster.introduced(&hello.node_id, hello.generation).await;
+                            compression = agreed;
+                        }
+                        Err(e) => {
+                            eprintln!("Handshake failed: {:?}", e);
+                            return;
+                        }
+                    }
+                }
+                let mut conn = CompressedConnection::new(conn, compression);
 
-                loop {
-                    match conn.recv().await {
-                        Ok(envelope) => {
-                            //decode as clustermessage
-                            if let Ok(cluster_msg) = ClusterMessage::decode(envelope.payload.as_slice()) {
-                                match cluster_msg.payload {
-                                    Some(cluster_message::Payload::Gossip(gossip)) => {
-                                        cluster.merge_gossip(gossip, &envelope.sender_node).await;
-
-                                        //send our gossip back
-                                        let our_gossip = cluster.create_gossip_message().await;
-                                        let mut buf = BytesMut::new();
+                while let Ok(envelope) = conn.recv().await {
+                    if is_handshake(&envelope) && !envelope.is_response {
+                        let (ack, verdict) =
+                            handshake::answer(cluster.handshake.as_ref(), &envelope);
+                        let _ = conn.send(ack).await;
+                        match verdict {
+                            Ok(hello) => cluster.introduced(&hello.node_id, hello.generation).await,
+                            Err(reason) => {
+                                eprintln!("Handshake failed: {}", reason);
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+
+                    //decode as clustermessage
+                    if let Ok(cluster_msg) = ClusterMessage::decode(envelope.payload.as_slice()) {
+                        match cluster_msg.payload {
+                            Some(cluster_message::Payload::Gossip(gossip)) => {
+                                cluster.merge_gossip(gossip, &envelope.sender_node).await;
+
+                                //send our gossip back
+                                let our_gossip = cluster.create_gossip_message().await;
+                                let mut buf = BytesMut::new();
 
+                                let cluster_resp = ClusterMessage {
+                                    payload: Some(cluster_message::Payload::Gossip(our_gossip)),
+                                };
+
+                                if cluster_resp.encode(&mut buf).is_ok() {
+                                    let resp = Envelope {
+                                        message_type: "cluster".to_string(),
+                                        payload: buf.to_vec(),
+                                        correlation_id: 0,
+                                        sender_node: cluster.local_node.id.clone(),
+                                        target_actor: "".to_string(),
+                                        is_response: true,
+                                        codec: codec::PROST,
+                                        ..Default::default()
+                                    };
+                                    let _ = conn.send(resp).await;
+                                }
+                            }
+                            Some(cluster_message::Payload::Envelope(actor_envelope)) => {
+                                if let Some(ref handler) = handler {
+                                    if let Some(response) = handler(actor_envelope).await {
+                                        //wrap response in clustermessage
+                                        let mut buf = BytesMut::new();
                                         let cluster_resp = ClusterMessage {
-                                            payload: Some(cluster_message::Payload::Gossip(our_gossip)),
+                                            payload: Some(cluster_message::Payload::Envelope(response)),
                                         };
 
                                         if cluster_resp.encode(&mut buf).is_ok() {
@@ -186,38 +741,33 @@ impl ClusterNode {
                                                 sender_node: cluster.local_node.id.clone(),
                                                 target_actor: "".to_string(),
                                                 is_response: true,
+                                                codec: codec::PROST,
+                                                ..Default::default()
                                             };
                                             let _ = conn.send(resp).await;
                                         }
                                     }
-                                    Some(cluster_message::Payload::Envelope(actor_envelope)) => {
-                                        if let Some(ref handler) = handler {
-                                            if let Some(response) = handler(actor_envelope).await {
-                                                //wrap response in clustermessage
-                                                let mut buf = BytesMut::new();
-                                                let cluster_resp = ClusterMessage {
-                                                    payload: Some(cluster_message::Payload::Envelope(response)),
-                                                };
-
-                                                if cluster_resp.encode(&mut buf).is_ok() {
-                                                    let resp = Envelope {
-                                                        message_type: "cluster".to_string(),
-                                                        payload: buf.to_vec(),
-                                                        correlation_id: 0,
-                                                        sender_node: cluster.local_node.id.clone(),
-                                                        target_actor: "".to_string(),
-                                                        is_response: true,
-                                                    };
-                                                    let _ = conn.send(resp).await;
-                                                }
-                                            }
-                                        }
-                                    }
-                                    None => {}
                                 }
                             }
+                            Some(cluster_message::Payload::Ping(ping)) => {
+                                let ack = cluster.on_ping(ping.source, ping.updates).await;
+                                let resp = cluster
+                                    .envelope(cluster_message::Payload::PingAck(ack), true);
+                                let _ = conn.send(resp).await;
+                            }
+                            Some(cluster_message::Payload::PingReq(req)) => {
+                                let ack = cluster.on_ping_req(req).await;
+                                let resp = cluster
+                                    .envelope(cluster_message::Payload::PingAck(ack), true);
+                                let _ = conn.send(resp).await;
+                            }
+                            Some(cluster_message::Payload::Publish(publish)) => {
+                                if let Some(message) = publish.message {
+                                    cluster.subscribers.deliver(&publish.topic, &message);
+                                }
+                            }
+                            Some(cluster_message::Payload::PingAck(_)) | None => {}
                         }
-                        Err(_) => break,
                     }
                 }
             });
@@ -254,89 +804,246 @@ impl ClusterNode {
             sender_node: self.local_node.id.clone(),
             target_actor: "".to_string(),
             is_response: false,
+            codec: codec::PROST,
+            ..Default::default()
         };
 
+        if let Some(socket) = self.datagram_socket(&envelope) {
+            let response = socket.request(&peer.addr, envelope, GOSSIP_TIMEOUT).await?;
+            return self.merge_answer(response).await;
+        }
+
         //connect to peer
-        let transport = TcpTransport;
-        let mut conn = transport.connect(&peer.addr).await?;
+        let mut conn = self.transport().connect(&peer.addr).await?;
 
         //send gossip
         conn.send(envelope).await?;
 
         //receive their gossip
-        if let Ok(response) = conn.recv().await {
-            if let Ok(cluster_resp) = ClusterMessage::decode(response.payload.as_slice()) {
-                if let Some(cluster_message::Payload::Gossip(their_gossip)) = cluster_resp.payload {
-                    self.merge_gossip(their_gossip, &response.sender_node).await;
+        let _ = self.receive_gossip(&mut conn).await;
+
+        Ok(())
+    }
+
+    ///merge the gossip a peer answered with
+    async fn receive_gossip<C: Connection>(&self, conn: &mut C) -> Result<(), TransportError> {
+        let response = conn.recv().await?;
+        self.merge_answer(response).await
+    }
+
+    async fn merge_answer(&self, response: Envelope) -> Result<(), TransportError> {
+        match ClusterMessage::decode(response.payload.as_slice())?.payload {
+            Some(cluster_message::Payload::Gossip(their_gossip)) => {
+                self.merge_gossip(their_gossip, &response.sender_node).await;
+                Ok(())
+            }
+            _ => Err(TransportError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("expected gossip from {}", response.sender_node),
+            ))),
+        }
+    }
+
+    ///Enter the cluster through its seed nodes
+    /// Each seed is connected to, handshaken with (see `handshake`), sent our
+    /// gossip and asked for its own, so this node ends up with the seed's
+    /// membership and actor registry and the seed learns about us; gossip
+    /// (or swim) then spreads the news. Rounds over all seeds repeat with
+    /// `join_retry` backoff until at least one answered. A seed rejecting the
+    /// handshake fails the join right away. With no seeds (other than
+    /// ourselves) there's nothing to join: this node starts the cluster
+    pub async fn join(&self, seeds: &[&str]) -> Result<(), TransportError> {
+        let seeds: Vec<&str> = seeds
+            .iter()
+            .copied()
+            .filter(|&addr| addr != self.local_node.addr)
+            .collect();
+        if seeds.is_empty() {
+            return Ok(());
+        }
+        let config = self.introduction();
+
+        let mut attempt = 0;
+        loop {
+            let mut last_error = None;
+            let mut joined = false;
+            for &seed in &seeds {
+                match self.join_seed(seed, &config).await {
+                    Ok(()) => joined = true,
+                    Err(e @ TransportError::Handshake(_)) => return Err(e),
+                    Err(e) => {
+                        eprintln!(
+                            "[{}] Seed {} unreachable: {:?}",
+                            self.local_node.id, seed, e
+                        );
+                        last_error = Some(e);
+                    }
                 }
             }
+            if joined {
+                //piggyback our arrival on swim messages too
+                let local = self.local_info().await;
+                self.gossip_change(local);
+                return Ok(());
+            }
+
+            attempt += 1;
+            if self
+                .join_retry
+                .max_attempts
+                .is_some_and(|max| attempt >= max)
+            {
+                return Err(last_error.unwrap_or(TransportError::Disconnected));
+            }
+            tokio::time::sleep(self.join_retry.delay(attempt)).await;
         }
+    }
 
-        Ok(())
+    ///handshake with one seed and swap gossip with it
+    async fn join_seed(&self, seed: &str, config: &HandshakeConfig) -> Result<(), TransportError> {
+        let (ack, mut conn) = handshake::introduce(TcpTransport.connect(seed).await?, config).await?;
+        self.introduced(&ack.node_id, ack.generation).await;
+
+        let gossip = self.create_gossip_message().await;
+        conn.send(self.envelope(cluster_message::Payload::Gossip(gossip), false))
+            .await?;
+        match tokio::time::timeout(config.timeout, self.receive_gossip(&mut conn)).await {
+            Ok(received) => received,
+            Err(_) => Err(TransportError::Timeout),
+        }
+    }
+
+    ///how this node introduces itself, an open-cluster hello without `handshake`
+    fn introduction(&self) -> HandshakeConfig {
+        self.handshake.clone().unwrap_or_else(|| {
+            HandshakeConfig::new(&self.local_node.id, "").generation(self.generation)
+        })
+    }
+
+    ///dials peers, handshaking first since their servers may require it
+    pub(crate) fn transport(&self) -> HandshakeTransport<TcpTransport> {
+        HandshakeTransport::new(TcpTransport, self.introduction())
+    }
+
+    ///judge every member by the failure detector and update the membership
+    /// Up members become Suspect, Suspect ones Down or, heard from again, Up.
+    /// Actors of members marked Down are dropped from the registry.
+    /// Returns the members marked Down by this call
+    pub async fn detect_failures(&self) -> Vec<String> {
+        let now = Instant::now();
+        let mut down_nodes = Vec::new();
+        {
+            let mut members = self.members.write().await;
+            let mut detector = self.detector.write().await;
+
+            for (node_id, member) in members.iter_mut() {
+                let node = &mut member.node;
+                if node_id == &self.local_node.id || node.status == NodeStatus::Down {
+                    continue;
+                }
+
+                match detector.check(node_id, now) {
+                    Some(Verdict::Dead) => {
+                        println!("[{}] Marking {} as DOWN", self.local_node.id, node_id);
+                        node.status = NodeStatus::Down;
+                        //a returning node starts over with fresh samples
+                        detector.remove(node_id);
+                        down_nodes.push(node_id.clone());
+                    }
+                    Some(Verdict::Suspect) if node.status == NodeStatus::Up => {
+                        println!("[{}] Marking {} as SUSPECT", self.local_node.id, node_id);
+                        node.status = NodeStatus::Suspect;
+                    }
+                    Some(Verdict::Healthy) if node.status == NodeStatus::Suspect => {
+                        println!("[{}] Marking {} as UP again", self.local_node.id, node_id);
+                        node.status = NodeStatus::Up;
+                    }
+                    _ => continue,
+                }
+                self.gossip_change(member.info());
+                self.announce(&member.node);
+            }
+        }
+
+        self.forget_down_nodes(&down_nodes).await;
+        down_nodes
+    }
+
+    /// Clean up actors and pub-sub topics of DOWN nodes
+    async fn forget_down_nodes(&self, down_nodes: &[String]) {
+        if !down_nodes.is_empty() {
+            let mut topics = self.topics.write().await;
+            for down_node_id in down_nodes {
+                topics.remove(down_node_id);
+            }
+            drop(topics);
+
+            let mut registry = self.actor_registry.write().await;
+            for down_node_id in down_nodes {
+                registry.retain(|actor_id, loc| {
+                    if &loc.node_id == down_node_id {
+                        println!(
+                            "[{}] Removing actor {} from DOWN node {}",
+                            self.local_node.id, actor_id, down_node_id
+                        );
+                        false
+                    } else {
+                        true
+                    }
+                });
+            }
+        }
+    }
+
+    pub(crate) fn subscribers(&self) -> &Arc<Subscribers> {
+        &self.subscribers
+    }
+
+    /// Members other than this node with pub-sub subscribers for `topic`
+    pub async fn subscribed_nodes(&self, topic: &str) -> Vec<Node> {
+        let topics = self.topics.read().await;
+        let members = self.members.read().await;
+        topics
+            .values()
+            .filter(|t| t.topics.iter().any(|t| t == topic))
+            .filter_map(|t| members.get(&t.node_id))
+            .filter(|m| m.node.status != NodeStatus::Down)
+            .map(|m| m.node.clone())
+            .collect()
+    }
+
+    /// Phi of the member's current silence, 0 unless phi accrual detection is used
+    pub async fn phi(&self, node_id: &str) -> f64 {
+        self.detector.read().await.phi(node_id, Instant::now())
     }
 
     /// Start periodic gossip to random peers with integrated failure detection
+    /// Members are judged by the detector set with `failure_detection`, or else
+    /// suspected after `suspect_timeout` of silence and marked down after twice that
     pub fn start_periodic_gossip(
         self: Arc<Self>,
         interval: Duration,
         suspect_timeout: Duration,
     ) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
+            if self.detection.is_none() {
+                let detection = FailureDetection::timeout(suspect_timeout);
+                self.detector.write().await.set_detection(detection);
+            }
             let mut ticker = tokio::time::interval(interval);
 
             loop {
                 ticker.tick().await;
 
                 // Check for failed nodes (failure detection)
-                let now = Instant::now();
-                let mut down_nodes = Vec::new();
-                {
-                    let mut members = self.members.write().await;
-                    let heartbeats = self.last_heartbeat.read().await;
-
-                    for (node_id, node) in members.iter_mut() {
-                        if node_id == &self.local_node.id {
-                            continue; // Skip self
-                        }
-
-                        if let Some(last_seen) = heartbeats.get(node_id) {
-                            let elapsed = now.duration_since(*last_seen);
-
-                            if elapsed > suspect_timeout * 2 && node.status != NodeStatus::Down {
-                                println!("[{}] Marking {} as DOWN", self.local_node.id, node_id);
-                                node.status = NodeStatus::Down;
-                                down_nodes.push(node_id.clone());
-                            } else if elapsed > suspect_timeout && node.status == NodeStatus::Up {
-                                println!("[{}] Marking {} as SUSPECT", self.local_node.id, node_id);
-                                node.status = NodeStatus::Suspect;
-                            }
-                        }
-                    }
-                }
-
-                // Clean up actors from DOWN nodes
-                if !down_nodes.is_empty() {
-                    let mut registry = self.actor_registry.write().await;
-                    for down_node_id in &down_nodes {
-                        registry.retain(|actor_id, (node_id, _)| {
-                            if node_id == down_node_id {
-                                println!(
-                                    "[{}] Removing actor {} from DOWN node {}",
-                                    self.local_node.id, actor_id, down_node_id
-                                );
-                                false
-                            } else {
-                                true
-                            }
-                        });
-                    }
-                }
+                self.detect_failures().await;
 
                 // Pick random peer (excluding self)
                 let peer = {
                     let members = self.members.read().await;
                     members
                         .values()
+                        .map(|m| &m.node)
                         .filter(|n| n.id != self.local_node.id)
                         .choose(&mut rand::rng())
                         .cloned()
@@ -348,6 +1055,281 @@ impl ClusterNode {
             }
         })
     }
+
+    ///queue a membership change for piggybacking on swim messages
+    fn gossip_change(&self, info: NodeInfo) {
+        self.updates
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .push(info);
+    }
+
+    ///changes to send along with the next swim message
+    async fn piggyback(&self) -> Vec<NodeInfo> {
+        let size = self.members.read().await.len();
+        self.updates
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .take(&self.swim, size)
+    }
+
+    async fn local_info(&self) -> NodeInfo {
+        let members = self.members.read().await;
+        members
+            .get(&self.local_node.id)
+            .map(Member::info)
+            .unwrap_or_else(|| NodeInfo::from(&self.local_node))
+    }
+
+    ///a peer introduced itself in a handshake: a member back under a new
+    /// generation is taken as restarted right away, not once gossip says so
+    async fn introduced(&self, node_id: &str, generation: u64) {
+        let info = match self.members.read().await.get(node_id) {
+            Some(member) if member.restarted_as(generation) => NodeInfo {
+                incarnation: 0,
+                generation,
+                ..member.declared(NodeStatus::Up)
+            },
+            _ => return,
+        };
+        self.merge_updates(vec![info]).await;
+    }
+
+    ///merge changes piggybacked on a swim message
+    async fn merge_updates(&self, updates: Vec<NodeInfo>) {
+        let gossip = GossipMessage {
+            members: updates,
+            actors: Vec::new(),
+            topics: Vec::new(),
+        };
+        self.merge_gossip(gossip, &self.local_node.id).await;
+    }
+
+    ///wrap a cluster protocol message for the wire
+    fn envelope(&self, payload: cluster_message::Payload, is_response: bool) -> Envelope {
+        let cluster_msg = ClusterMessage {
+            payload: Some(payload),
+        };
+        Envelope {
+            message_type: "cluster".to_string(),
+            payload: cluster_msg.encode_to_vec(),
+            correlation_id: 0,
+            sender_node: self.local_node.id.clone(),
+            target_actor: "".to_string(),
+            is_response,
+            codec: codec::PROST,
+            ..Default::default()
+        }
+    }
+
+    ///send a swim message to `addr` and wait up to `timeout` for the ack
+    async fn request(
+        &self,
+        addr: &str,
+        payload: cluster_message::Payload,
+        timeout: Duration,
+    ) -> Option<PingAck> {
+        let envelope = self.envelope(payload, false);
+        let response = match self.datagram_socket(&envelope) {
+            Some(socket) => socket.request(addr, envelope, timeout).await.ok()?,
+            None => {
+                let exchange = async {
+                    let mut conn = self.transport().connect(addr).await?;
+                    conn.send(envelope).await?;
+                    conn.recv().await
+                };
+                tokio::time::timeout(timeout, exchange).await.ok()?.ok()?
+            }
+        };
+        match ClusterMessage::decode(response.payload.as_slice()).ok()?.payload {
+            Some(cluster_message::Payload::PingAck(ack)) => Some(ack),
+            _ => None,
+        }
+    }
+
+    ///ping `target` directly, true if it answered within `ping_timeout`
+    async fn probe(&self, target: &Node) -> bool {
+        let ping = Ping {
+            source: Some(self.local_info().await),
+            updates: self.piggyback().await,
+        };
+        let ack = self
+            .request(
+                &target.addr,
+                cluster_message::Payload::Ping(ping),
+                self.swim.ping_timeout,
+            )
+            .await;
+        match ack {
+            Some(ack) => {
+                self.merge_updates(ack.updates).await;
+                ack.alive
+            }
+            None => false,
+        }
+    }
+
+    ///ask `helper` to ping `target` for us
+    async fn probe_via(&self, helper: &Node, target: &Node, timeout: Duration) -> bool {
+        let req = PingReq {
+            source: Some(self.local_info().await),
+            target: Some(NodeInfo::from(target)),
+            updates: self.piggyback().await,
+        };
+        let ack = self
+            .request(&helper.addr, cluster_message::Payload::PingReq(req), timeout)
+            .await;
+        match ack {
+            Some(ack) => {
+                self.merge_updates(ack.updates).await;
+                ack.alive
+            }
+            None => false,
+        }
+    }
+
+    ///answer a ping, telling the sender if we think it's suspect or down so
+    /// it gets the chance to refute
+    async fn on_ping(&self, source: Option<NodeInfo>, updates: Vec<NodeInfo>) -> PingAck {
+        let mut updates = updates;
+        let mut ours = None;
+        if let Some(source) = source {
+            self.detector
+                .write()
+                .await
+                .heartbeat(&source.id, Instant::now());
+            ours = self
+                .members
+                .read()
+                .await
+                .get(&source.id)
+                .filter(|m| {
+                    m.node.status != NodeStatus::Up
+                        && !m.restarted_as(source.generation)
+                        && m.incarnation >= source.incarnation
+                })
+                .map(Member::info);
+            updates.push(source);
+        }
+        self.merge_updates(updates).await;
+
+        let mut updates = self.piggyback().await;
+        updates.extend(ours);
+        PingAck {
+            alive: true,
+            updates,
+        }
+    }
+
+    async fn on_ping_req(&self, req: PingReq) -> PingAck {
+        let PingAck { updates, .. } = self.on_ping(req.source, req.updates).await;
+        let alive = match req.target {
+            Some(target) => self.probe(&target.into()).await,
+            None => false,
+        };
+        PingAck { alive, updates }
+    }
+
+    ///mark `target` suspect at its current incarnation
+    async fn suspect(&self, target: &str) {
+        let mut members = self.members.write().await;
+        if let Some(member) = members.get(target) {
+            if member.node.status == NodeStatus::Up {
+                println!("[{}] Marking {} as SUSPECT", self.local_node.id, target);
+                let info = member.declared(NodeStatus::Suspect);
+                self.apply(&mut members, info, Instant::now());
+            }
+        }
+    }
+
+    ///declare down the members that stayed suspect for `suspect_timeout`
+    async fn expire_suspects(&self) {
+        let now = Instant::now();
+        let mut down_nodes = Vec::new();
+        {
+            let mut members = self.members.write().await;
+            let expired: Vec<NodeInfo> = members
+                .values()
+                .filter(|m| {
+                    m.suspected_at
+                        .is_some_and(|at| now.duration_since(at) >= self.swim.suspect_timeout)
+                })
+                .map(|m| m.declared(NodeStatus::Down))
+                .collect();
+            for info in expired {
+                println!("[{}] Marking {} as DOWN", self.local_node.id, info.id);
+                down_nodes.push(info.id.clone());
+                self.apply(&mut members, info, now);
+            }
+        }
+        let mut detector = self.detector.write().await;
+        for node_id in &down_nodes {
+            detector.remove(node_id);
+        }
+        drop(detector);
+        self.forget_down_nodes(&down_nodes).await;
+    }
+
+    ///one swim protocol period: probe a random member, directly and, failing
+    /// that, through `indirect_probes` others, and suspect it if nobody reached it
+    async fn swim_round(&self) {
+        self.expire_suspects().await;
+
+        let (target, helpers) = {
+            let members = self.members.read().await;
+            let mut rng = rand::rng();
+            let peers = members
+                .values()
+                .map(|m| &m.node)
+                .filter(|n| n.id != self.local_node.id && n.status != NodeStatus::Down);
+            let Some(target) = peers.clone().choose(&mut rng).cloned() else {
+                return;
+            };
+            let helpers: Vec<Node> = peers
+                .filter(|n| n.id != target.id)
+                .cloned()
+                .choose_multiple(&mut rng, self.swim.indirect_probes);
+            (target, helpers)
+        };
+
+        if self.probe(&target).await {
+            return;
+        }
+
+        let timeout = self
+            .swim
+            .protocol_period
+            .saturating_sub(self.swim.ping_timeout);
+        let probes = helpers
+            .iter()
+            .map(|helper| self.probe_via(helper, &target, timeout));
+        if !futures::future::join_all(probes)
+            .await
+            .into_iter()
+            .any(|alive| alive)
+        {
+            self.suspect(&target.id).await;
+        }
+    }
+
+    /// Run the SWIM membership protocol (see `SwimConfig`)
+    /// Each protocol period one random member is probed and membership changes
+    /// are piggybacked on the pings, so joins, suspicions and failures spread
+    /// to every node without a coordinator or full member lists on the wire.
+    /// The cluster server (`start_server`) must be running to answer probes
+    pub fn start_swim(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let local = self.local_info().await;
+            self.gossip_change(local);
+
+            let mut ticker = tokio::time::interval(self.swim.protocol_period);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.swim_round().await;
+            }
+        })
+    }
 }
 
 impl From<&Node> for NodeInfo {
@@ -360,6 +1342,8 @@ impl From<&Node> for NodeInfo {
                 NodeStatus::Suspect => 1,
                 NodeStatus::Down => 2,
             },
+            incarnation: 0,
+            generation: 0,
         }
     }
 }
@@ -378,3 +1362,10 @@ impl From<NodeInfo> for Node {
         }
     }
 }
+
+///microseconds since the epoch, for counters that must keep growing across restarts
+fn wall_clock_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(1, |d| d.as_micros() as u64)
+}
@@ -1,62 +1,30 @@
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::Mutex;
+use std::sync::Arc;
 
 use crate::remote::{
-    cluster::ClusterNode,
+    cluster::{ClusterNode, Node},
     proto::{cluster_message, ClusterMessage, Envelope},
-    RemoteClient, TcpTransport, Transport, TransportError,
+    codec, failure, ConnectionManager, HandshakeTransport, RemoteClient, TcpTransport,
+    TransportError,
 };
 use bytes::BytesMut;
 use prost::Message;
 
-///manages persistent connections to cluster nodes using remoteclient
-struct ConnectionPool {
-    ///node_addr -> remoteclient
-    clients: HashMap<String, RemoteClient>,
-}
-
-impl ConnectionPool {
-    fn new() -> Self {
-        Self {
-            clients: HashMap::new(),
-        }
-    }
-
-    ///get or create a remoteclient for a node
-    async fn get_or_connect(
-        &mut self,
-        node_addr: &str,
-        transport: &TcpTransport,
-    ) -> Result<RemoteClient, TransportError> {
-        if !self.clients.contains_key(node_addr) {
-            let tcp_conn = transport.connect(node_addr).await?;
-            let client = RemoteClient::new(tcp_conn);
-            self.clients.insert(node_addr.to_string(), client);
-        }
-        Ok(self.clients.get(node_addr).unwrap().clone())
-    }
-
-    fn remove(&mut self, node_addr: &str) {
-        self.clients.remove(node_addr);
-    }
-}
-
 ///client for sending messages to actors in the cluster
 ///uses cluster registry for discovery and remoteclient for correlation tracking
 pub struct ClusterClient {
     cluster: Arc<ClusterNode>,
-    pool: Arc<Mutex<ConnectionPool>>,
-    transport: TcpTransport,
+    ///one reconnecting connection per node, shared by every request to it
+    pool: ConnectionManager<HandshakeTransport<TcpTransport>>,
     local_node_id: String,
 }
 
 impl ClusterClient {
     pub fn new(cluster: Arc<ClusterNode>) -> Self {
         let local_node_id = cluster.local_node.id.clone();
+        let pool = ConnectionManager::new(cluster.transport());
         Self {
             cluster,
-            pool: Arc::new(Mutex::new(ConnectionPool::new())),
-            transport: TcpTransport,
+            pool,
             local_node_id,
         }
     }
@@ -92,51 +60,53 @@ impl ClusterClient {
             ))
         })?;
 
-        //wrap actor envelope in clustermessage
-        let cluster_msg = ClusterMessage {
-            payload: Some(cluster_message::Payload::Envelope(envelope)),
-        };
+        self.send_to_node(node, envelope).await
+    }
 
-        let mut buf = BytesMut::new();
-        cluster_msg.encode(&mut buf).map_err(|e| {
-            TransportError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
-        })?;
+    ///send request-response message to an actor on `node`, bypassing the registry
+    pub(crate) async fn send_to_node(
+        &self,
+        node: &Node,
+        envelope: Envelope,
+    ) -> Result<Envelope, TransportError> {
+        let client = self.connection(node).await?;
+        self.send_over(&client, node, envelope).await
+    }
 
-        //wrap in transport envelope
-        let transport_envelope = Envelope {
-            message_type: "cluster".to_string(),
-            payload: buf.to_vec(),
-            correlation_id: 0,
-            sender_node: self.local_node_id.clone(),
-            target_actor: "".to_string(),
-            is_response: false,
-        };
+    ///the pooled connection to `node`, dialed if there is none yet
+    ///nothing has been sent when this fails
+    pub(crate) async fn connection(&self, node: &Node) -> Result<RemoteClient, TransportError> {
+        self.pool.client(&node.addr).await
+    }
 
-        //get or create connection - remoteclient handles correlation tracking
-        let mut pool = self.pool.lock().await;
-        let client = pool.get_or_connect(&node.addr, &self.transport).await.map_err(|e| {
-            //on connection failure, remove from pool to force reconnect next time
-            pool.remove(&node.addr);
-            e
-        })?;
-        drop(pool); //release lock before async send
+    ///send request-response message to an actor on `node` over its pooled `client`
+    pub(crate) async fn send_over(
+        &self,
+        client: &RemoteClient,
+        node: &Node,
+        envelope: Envelope,
+    ) -> Result<Envelope, TransportError> {
+        let transport_envelope = self.wrap(envelope)?;
 
         //send via remoteclient (handles correlation id tracking internally)
-        let response = client.send(transport_envelope).await.map_err(|e| {
+        let response = client.send(transport_envelope).await.inspect_err(|e| {
+            //the client is already redialing on its own
+            if matches!(e, TransportError::Reconnecting) {
+                return;
+            }
             //on send/recv failure, clear connection from pool
             let pool = self.pool.clone();
             let node_addr = node.addr.clone();
             tokio::spawn(async move {
-                pool.lock().await.remove(&node_addr);
+                pool.remove(&node_addr).await;
             });
-            e
         })?;
 
         //unwrap clustermessage
         if let Ok(cluster_resp) = ClusterMessage::decode(response.payload.as_slice()) {
             if let Some(cluster_message::Payload::Envelope(actor_response)) = cluster_resp.payload
             {
-                return Ok(actor_response);
+                return failure::check(actor_response);
             }
         }
 
@@ -173,7 +143,31 @@ impl ClusterClient {
             ))
         })?;
 
-        //wrap actor envelope in clustermessage
+        self.do_send_to_node(node, envelope).await
+    }
+
+    ///send fire-and-forget message to an actor on `node`, bypassing the registry
+    pub(crate) async fn do_send_to_node(
+        &self,
+        node: &Node,
+        envelope: Envelope,
+    ) -> Result<(), TransportError> {
+        let client = self.connection(node).await?;
+        self.do_send_over(&client, envelope).await
+    }
+
+    ///send fire-and-forget message to an actor over a pooled `client`
+    pub(crate) async fn do_send_over(
+        &self,
+        client: &RemoteClient,
+        envelope: Envelope,
+    ) -> Result<(), TransportError> {
+        let transport_envelope = self.wrap(envelope)?;
+        client.do_send(transport_envelope).await
+    }
+
+    ///wrap an actor envelope in the clustermessage transport envelope
+    fn wrap(&self, envelope: Envelope) -> Result<Envelope, TransportError> {
         let cluster_msg = ClusterMessage {
             payload: Some(cluster_message::Payload::Envelope(envelope)),
         };
@@ -183,26 +177,16 @@ impl ClusterClient {
             TransportError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
         })?;
 
-        //wrap in transport envelope
-        let transport_envelope = Envelope {
+        Ok(Envelope {
             message_type: "cluster".to_string(),
             payload: buf.to_vec(),
             correlation_id: 0,
             sender_node: self.local_node_id.clone(),
             target_actor: "".to_string(),
             is_response: false,
-        };
-
-        //get or create connection
-        let mut pool = self.pool.lock().await;
-        let client = pool.get_or_connect(&node.addr, &self.transport).await.map_err(|e| {
-            pool.remove(&node.addr);
-            e
-        })?;
-        drop(pool);
-
-        //fire-and-forget send
-        client.do_send(transport_envelope).await
+            codec: codec::PROST,
+            ..Default::default()
+        })
     }
 
     ///create a remote address for an actor (doesn't lookup yet, lookup happens on send)
@@ -216,7 +200,7 @@ impl ClusterClient {
 
     ///clear connection to a specific node (useful after network errors)
     pub async fn clear_connection(&self, node_addr: &str) {
-        self.pool.lock().await.remove(node_addr);
+        self.pool.remove(node_addr).await;
     }
 }
 
@@ -225,7 +209,6 @@ impl Clone for ClusterClient {
         Self {
             cluster: self.cluster.clone(),
             pool: self.pool.clone(),
-            transport: TcpTransport,
             local_node_id: self.local_node_id.clone(),
         }
     }
@@ -264,12 +247,10 @@ impl<A> ClusterRemoteAddr<A> {
         M: crate::remote::RemoteMessage,
         M::Result: crate::remote::RemoteMessage,
     {
-        use prost::Message as ProstMessage;
-
         let response_envelope = self.send(msg).await?;
 
         //decode the response
-        M::Result::decode(response_envelope.payload.as_slice()).map_err(|e| {
+        response_envelope.message::<M::Result>().map_err(|e| {
             TransportError::Io(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 format!("failed to decode response: {}", e),
@@ -0,0 +1,198 @@
+use std::{
+    any::type_name,
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::remote::{
+    addr::next_correlation_id,
+    cluster::{ClusterNode, Node, NodeStatus},
+    proto::Envelope,
+    ClusterClient, RemoteMessage, TransportError,
+};
+
+/// Which routee a `ClusterRouter` hands the next message to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Routing {
+    ///every routee in turn
+    RoundRobin,
+    ///the routee that has gone longest without a message
+    LeastRecentlyUsed,
+}
+
+///Routes messages over the actor of one name on the other members of the cluster
+/// Each hosting node runs its own actor under `name` (resolved by that
+/// node's handler) and announces it with `ClusterRouter::register`. The
+/// routees are the Up members with such a registration, the local node
+/// excluded, taken from the current membership on every send. A routee that
+/// can't be dialed is skipped and the message goes to the next one; once a
+/// message went out it is not resent, the routee may have handled it
+pub struct ClusterRouter<A> {
+    client: ClusterClient,
+    cluster: Arc<ClusterNode>,
+    name: String,
+    routing: Routing,
+    next: AtomicUsize,
+    ///node id -> tick of the last message routed there
+    last_used: Mutex<HashMap<String, u64>>,
+    tick: AtomicU64,
+    _phantom: PhantomData<A>,
+}
+
+impl<A> ClusterRouter<A> {
+    pub fn new(cluster: Arc<ClusterNode>, name: &str, routing: Routing) -> Self {
+        Self {
+            client: ClusterClient::new(cluster.clone()),
+            cluster,
+            name: name.to_string(),
+            routing,
+            next: AtomicUsize::new(0),
+            last_used: Mutex::new(HashMap::new()),
+            tick: AtomicU64::new(0),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn round_robin(cluster: Arc<ClusterNode>, name: &str) -> Self {
+        Self::new(cluster, name, Routing::RoundRobin)
+    }
+
+    pub fn least_recently_used(cluster: Arc<ClusterNode>, name: &str) -> Self {
+        Self::new(cluster, name, Routing::LeastRecentlyUsed)
+    }
+
+    pub fn routing(&self) -> Routing {
+        self.routing
+    }
+
+    /// Announce that this node hosts a routee of `name`, for the routers on
+    /// the other nodes to find. The registration goes into the cluster's
+    /// actor registry under `<name>@<node id>` and spreads with the gossip;
+    /// it is dropped when the node goes down
+    pub async fn register(cluster: &ClusterNode, name: &str) {
+        cluster
+            .register_actor(
+                routee_key(name, &cluster.local_node.id),
+                type_name::<A>().to_string(),
+            )
+            .await;
+    }
+
+    ///the members messages are currently routed over, by node id
+    pub async fn routees(&self) -> Vec<Node> {
+        let mut routees = Vec::new();
+        for node in self.cluster.get_members().await {
+            if node.status != NodeStatus::Up || node.id == self.cluster.local_node.id {
+                continue;
+            }
+            let hosted = self
+                .cluster
+                .lookup_actor(&routee_key(&self.name, &node.id))
+                .await;
+            if hosted.is_some_and(|(host, actor_type)| {
+                host == node.id && actor_type == type_name::<A>()
+            }) {
+                routees.push(node);
+            }
+        }
+        routees.sort_by(|a, b| a.id.cmp(&b.id));
+        routees
+    }
+
+    ///the routees in the order to try them for the next message
+    async fn candidates(&self) -> Result<Vec<Node>, TransportError> {
+        let mut routees = self.routees().await;
+        if routees.is_empty() {
+            return Err(TransportError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no routees for {} in the cluster", self.name),
+            )));
+        }
+        match self.routing {
+            Routing::RoundRobin => {
+                let first = self.next.fetch_add(1, Ordering::Relaxed) % routees.len();
+                routees.rotate_left(first);
+            }
+            Routing::LeastRecentlyUsed => {
+                let last_used = self.last_used.lock().unwrap_or_else(|p| p.into_inner());
+                //stable, so routees never used yet go in node id order
+                routees.sort_by_key(|node| last_used.get(&node.id).copied().unwrap_or(0));
+            }
+        }
+        Ok(routees)
+    }
+
+    fn used(&self, node: &Node) {
+        let tick = self.tick.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut last_used = self.last_used.lock().unwrap_or_else(|p| p.into_inner());
+        last_used.insert(node.id.clone(), tick);
+    }
+
+    fn envelope<M: RemoteMessage>(&self, msg: &M) -> Envelope {
+        Envelope::from_message(
+            msg,
+            next_correlation_id(),
+            &self.cluster.local_node.id,
+            &self.name,
+        )
+    }
+
+    ///send request-response message to the next routee and return raw envelope
+    pub async fn send<M>(&self, msg: M) -> Result<Envelope, TransportError>
+    where
+        M: RemoteMessage,
+    {
+        let envelope = self.envelope(&msg);
+        let mut last_error = None;
+        for node in self.candidates().await? {
+            self.used(&node);
+            match self.client.connection(&node).await {
+                Ok(client) => return self.client.send_over(&client, &node, envelope).await,
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or(TransportError::Disconnected))
+    }
+
+    ///send request-response message to the next routee and decode typed response
+    pub async fn call<M>(&self, msg: M) -> Result<M::Result, TransportError>
+    where
+        M: RemoteMessage,
+        M::Result: RemoteMessage,
+    {
+        let response_envelope = self.send(msg).await?;
+
+        response_envelope.message::<M::Result>().map_err(|e| {
+            TransportError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("failed to decode response: {}", e),
+            ))
+        })
+    }
+
+    ///send fire-and-forget message to the next routee
+    pub async fn do_send<M>(&self, msg: M) -> Result<(), TransportError>
+    where
+        M: RemoteMessage,
+    {
+        let envelope = self.envelope(&msg);
+        let mut last_error = None;
+        for node in self.candidates().await? {
+            self.used(&node);
+            match self.client.connection(&node).await {
+                Ok(client) => return self.client.do_send_over(&client, envelope).await,
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or(TransportError::Disconnected))
+    }
+}
+
+///registry key of the routee of `name` hosted on `node_id`
+fn routee_key(name: &str, node_id: &str) -> String {
+    format!("{}@{}", name, node_id)
+}
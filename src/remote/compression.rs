@@ -0,0 +1,243 @@
+use std::{future::Future, pin::Pin};
+
+use prost::DecodeError;
+
+use crate::remote::{
+    proto::Envelope,
+    transport::{Connection, TransportError},
+    DEFAULT_MAX_FRAME_SIZE,
+};
+
+///Algorithm an envelope payload is compressed with, carried in `Envelope::compression`
+/// Each algorithm is only available with its cargo feature (`lz4`, `zstd`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    #[cfg(feature = "lz4")]
+    Lz4,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Wire id of the algorithm
+    pub fn id(self) -> u32 {
+        match self {
+            Compression::None => 0,
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => 1,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => 2,
+        }
+    }
+
+    ///the algorithm with wire id `id`, `None` if this build doesn't support it
+    pub fn from_id(id: u32) -> Option<Self> {
+        match id {
+            0 => Some(Compression::None),
+            #[cfg(feature = "lz4")]
+            1 => Some(Compression::Lz4),
+            #[cfg(feature = "zstd")]
+            2 => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    fn compress(self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Compression::None => bytes.to_vec(),
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => lz4_flex::compress_prepend_size(bytes),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::bulk::compress(bytes, 0).expect("compress failed"),
+        }
+    }
+
+    ///decompress at most `limit` bytes, a payload inflating past it is refused
+    #[cfg_attr(not(any(feature = "lz4", feature = "zstd")), allow(unused_variables))]
+    fn decompress(self, bytes: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+        match self {
+            Compression::None => Ok(bytes.to_vec()),
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => {
+                //the size the peer prepended is checked before it is allocated
+                let size = bytes
+                    .get(..4)
+                    .map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize)
+                    .ok_or_else(|| DecodeError::new("lz4 payload without its size"))?;
+                if size > limit {
+                    return Err(too_large(limit));
+                }
+                lz4_flex::decompress_size_prepended(bytes)
+                    .map_err(|e| DecodeError::new(e.to_string()))
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                use std::io::Read;
+
+                let decoder = zstd::stream::read::Decoder::new(bytes)
+                    .map_err(|e| DecodeError::new(e.to_string()))?;
+                let mut out = Vec::new();
+                decoder
+                    .take(limit as u64 + 1)
+                    .read_to_end(&mut out)
+                    .map_err(|e| DecodeError::new(e.to_string()))?;
+                if out.len() > limit {
+                    return Err(too_large(limit));
+                }
+                Ok(out)
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "lz4", feature = "zstd"))]
+fn too_large(limit: usize) -> DecodeError {
+    DecodeError::new(format!("decompressed payload exceeds {} bytes", limit))
+}
+
+///Which algorithm to compress outgoing payloads with and from what size on
+/// Small payloads aren't worth the CPU, only those of at least `threshold`
+/// bytes are compressed (and only kept compressed if that made them smaller)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionConfig {
+    pub algorithm: Compression,
+    pub threshold: usize,
+}
+
+impl CompressionConfig {
+    pub fn new(algorithm: Compression) -> Self {
+        Self {
+            algorithm,
+            threshold: 4 * 1024,
+        }
+    }
+
+    pub fn threshold(mut self, bytes: usize) -> Self {
+        self.threshold = bytes;
+        self
+    }
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self::new(Compression::None)
+    }
+}
+
+///compress the payload in place when it's large enough and shrinks
+pub(crate) fn compress(envelope: &mut Envelope, config: &CompressionConfig) {
+    if config.algorithm == Compression::None
+        || envelope.compression != 0
+        || envelope.payload.len() < config.threshold
+    {
+        return;
+    }
+    let compressed = config.algorithm.compress(&envelope.payload);
+    if compressed.len() < envelope.payload.len() {
+        envelope.payload = compressed;
+        envelope.compression = config.algorithm.id();
+    }
+}
+
+///undo `compress`, refusing any algorithm but the `agreed` one and payloads
+/// inflating past `limit` bytes
+pub(crate) fn decompress(
+    envelope: &mut Envelope,
+    agreed: Compression,
+    limit: usize,
+) -> Result<(), TransportError> {
+    if envelope.compression == 0 {
+        return Ok(());
+    }
+    if agreed == Compression::None || envelope.compression != agreed.id() {
+        return Err(DecodeError::new(format!(
+            "payload compressed with algorithm {} but {:?} was agreed",
+            envelope.compression, agreed
+        ))
+        .into());
+    }
+    envelope.payload = agreed.decompress(&envelope.payload, limit)?;
+    envelope.compression = 0;
+    Ok(())
+}
+
+///Connection wrapper compressing large outgoing payloads and decompressing
+/// incoming ones, set up by the handshake once both nodes agreed on an algorithm
+/// (see `HandshakeConfig::compression`)
+/// Incoming payloads compressed with any other algorithm, or with none
+/// agreed, are refused, as are payloads inflating past the size limit
+pub struct CompressedConnection<C> {
+    inner: C,
+    config: CompressionConfig,
+    max_size: usize,
+}
+
+impl<C: Connection> CompressedConnection<C> {
+    pub fn new(inner: C, config: CompressionConfig) -> Self {
+        Self {
+            inner,
+            config,
+            max_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+
+    /// Refuse payloads decompressing to more than `bytes`, instead of
+    /// `DEFAULT_MAX_FRAME_SIZE`
+    pub fn max_size(mut self, bytes: usize) -> Self {
+        self.max_size = bytes;
+        self
+    }
+
+    /// The algorithm outgoing payloads are compressed with
+    pub fn compression(&self) -> Compression {
+        self.config.algorithm
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: Connection> Connection for CompressedConnection<C> {
+    fn send(
+        &mut self,
+        mut envelope: Envelope,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        compress(&mut envelope, &self.config);
+        self.inner.send(envelope)
+    }
+
+    fn send_batch(
+        &mut self,
+        mut envelopes: Vec<Envelope>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        for envelope in &mut envelopes {
+            compress(envelope, &self.config);
+        }
+        self.inner.send_batch(envelopes)
+    }
+
+    fn recv(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            let mut envelope = self.inner.recv().await?;
+            decompress(&mut envelope, self.config.algorithm, self.max_size)?;
+            Ok(envelope)
+        })
+    }
+
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        self.inner.flush()
+    }
+
+    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        self.inner.close()
+    }
+
+    fn local_addr(&self) -> &str {
+        self.inner.local_addr()
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use prost::Message;
+use tokio::{net::UdpSocket, sync::oneshot};
+
+use crate::remote::{proto::Envelope, TransportError};
+
+///Largest envelope sent as one datagram, small enough to cross a typical
+/// network without IP fragmentation. Bigger messages go over TCP instead
+pub const MAX_DATAGRAM: usize = 1400;
+
+///Datagram socket for the cluster's gossip and swim traffic
+/// Requests carry a correlation id and the answer to one comes back as a
+/// single datagram with the same id, which doubles as its ack. Nothing is
+/// retransmitted: a lost request or answer just times out, which the
+/// protocols on top already expect
+pub(crate) struct GossipSocket {
+    socket: UdpSocket,
+    ///correlation id -> who waits for its answer
+    pending: Mutex<HashMap<u64, oneshot::Sender<Envelope>>>,
+    next_id: AtomicU64,
+}
+
+impl GossipSocket {
+    pub(crate) async fn bind(addr: SocketAddr) -> io::Result<Self> {
+        Ok(Self {
+            socket: UdpSocket::bind(addr).await?,
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    ///whether `envelope` can go out as one datagram
+    pub(crate) fn fits(envelope: &Envelope) -> bool {
+        envelope.encoded_len() <= MAX_DATAGRAM
+    }
+
+    ///send `envelope` to `addr` and wait up to `timeout` for the answer
+    pub(crate) async fn request(
+        &self,
+        addr: &str,
+        mut envelope: Envelope,
+        timeout: Duration,
+    ) -> Result<Envelope, TransportError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        envelope.correlation_id = id;
+        let (tx, rx) = oneshot::channel();
+        self.pending().insert(id, tx);
+
+        let exchange = async {
+            self.socket.send_to(&envelope.to_bytes(), addr).await?;
+            rx.await.map_err(|_| TransportError::Disconnected)
+        };
+        let result = tokio::time::timeout(timeout, exchange).await;
+        self.pending().remove(&id);
+        result.map_err(|_| TransportError::Timeout)?
+    }
+
+    ///answer a request received from `to`
+    pub(crate) async fn reply(&self, to: SocketAddr, request: &Envelope, mut response: Envelope) {
+        response.correlation_id = request.correlation_id;
+        response.is_response = true;
+        if let Err(e) = self.socket.send_to(&response.to_bytes(), to).await {
+            eprintln!("Failed to send datagram to {}: {:?}", to, e);
+        }
+    }
+
+    ///next request from a peer; answers to our own requests are handed to
+    /// whoever waits for them on the way
+    pub(crate) async fn recv(&self) -> io::Result<(Envelope, SocketAddr)> {
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let (len, from) = self.socket.recv_from(&mut buf).await?;
+            let envelope = match Envelope::from_bytes(&buf[..len]) {
+                Ok(envelope) => envelope,
+                Err(e) => {
+                    eprintln!("Dropping malformed datagram from {}: {}", from, e);
+                    continue;
+                }
+            };
+            if !envelope.is_response {
+                return Ok((envelope, from));
+            }
+            //late answers find nobody waiting
+            if let Some(tx) = self.pending().remove(&envelope.correlation_id) {
+                let _ = tx.send(envelope);
+            }
+        }
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, HashMap<u64, oneshot::Sender<Envelope>>> {
+        self.pending.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::remote::proto::Envelope;
+
+/// Reserved message type of the answer to a request whose deadline passed
+/// before the receiving node could handle it, surfaced to the caller as
+/// `TransportError::DeadlineExceeded`
+pub const DEADLINE_EXCEEDED: &str = "cinema::deadline_exceeded";
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+impl Envelope {
+    ///tell the receiving node the sender waits at most `timeout` from now
+    /// an earlier deadline already set is kept
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        let deadline = unix_millis(SystemTime::now() + timeout).max(1);
+        if self.deadline_ms == 0 || deadline < self.deadline_ms {
+            self.deadline_ms = deadline;
+        }
+    }
+
+    ///when the sender stops waiting for the answer, None if it waits for good
+    /// compared against the local clock, so nodes' clocks should be in sync
+    pub fn deadline(&self) -> Option<SystemTime> {
+        match self.deadline_ms {
+            0 => None,
+            millis => Some(UNIX_EPOCH + Duration::from_millis(millis)),
+        }
+    }
+
+    ///time left before the deadline, zero once it passed
+    pub fn remaining(&self) -> Option<Duration> {
+        let deadline = self.deadline()?;
+        Some(
+            deadline
+                .duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO),
+        )
+    }
+}
+
+///answer to `request` saying its deadline passed on `node_id`
+pub(crate) fn exceeded(request: &Envelope, node_id: &str) -> Envelope {
+    Envelope {
+        message_type: DEADLINE_EXCEEDED.to_string(),
+        correlation_id: request.correlation_id,
+        sender_node: node_id.to_string(),
+        target_actor: request.sender_node.clone(),
+        is_response: true,
+        ..Default::default()
+    }
+}
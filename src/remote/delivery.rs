@@ -0,0 +1,291 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::sync::{mpsc, Notify};
+
+use crate::remote::{
+    addr::next_correlation_id, failure, proto::Envelope, EnvelopeHandler, ReconnectPolicy, RemoteActorId,
+    RemoteClient, RemoteMessage, TransportError,
+};
+
+/// Reserved message type of delivery acknowledgements, never passed to handlers
+pub const ACK: &str = "cinema::ack";
+
+///How reliable tells are retried until the peer acknowledges them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeliveryConfig {
+    ///how long to wait for an ack before sending again
+    pub ack_timeout: Duration,
+    ///backoff between attempts, `max_attempts` bounds how often a message is
+    /// tried before it is dropped (logged), by default never
+    pub retry: ReconnectPolicy,
+    ///unacknowledged messages queued before `do_send` waits for room
+    pub capacity: usize,
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            ack_timeout: Duration::from_secs(5),
+            retry: ReconnectPolicy::default(),
+            capacity: 10_000,
+        }
+    }
+}
+
+impl DeliveryConfig {
+    pub fn ack_timeout(mut self, timeout: Duration) -> Self {
+        self.ack_timeout = timeout;
+        self
+    }
+
+    pub fn retry(mut self, policy: ReconnectPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+}
+
+pub(crate) fn is_ack(envelope: &Envelope) -> bool {
+    envelope.message_type == ACK
+}
+
+fn ack(request: &Envelope) -> Envelope {
+    Envelope {
+        message_type: ACK.to_string(),
+        correlation_id: request.correlation_id,
+        sender_node: request.target_actor.clone(),
+        target_actor: request.sender_node.clone(),
+        is_response: true,
+        delivery_id: request.delivery_id,
+        ..Default::default()
+    }
+}
+
+///counts messages not yet acknowledged, for `ReliableAddr::flush`
+#[derive(Default)]
+struct Unacked {
+    count: AtomicUsize,
+    drained: Notify,
+}
+
+impl Unacked {
+    fn done(&self) {
+        if self.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.drained.notify_waiters();
+        }
+    }
+}
+
+///Remote address whose tells are delivered at least once
+/// `do_send` queues the message in an outbox that sends it with a delivery id
+/// and keeps resending (with backoff, across reconnects of the underlying
+/// client) until the peer acknowledges it. The receiving server drops
+/// duplicates by delivery id, so handlers see each message once as long as
+/// the retry lands within its dedup window. Messages go out one at a time in
+/// the order they were sent. The outbox is in memory: what is still
+/// unacknowledged when the process exits is lost
+pub struct ReliableAddr<A> {
+    pub id: RemoteActorId,
+    local_node: String,
+    outbox: mpsc::Sender<Envelope>,
+    next_delivery: Arc<AtomicU64>,
+    unacked: Arc<Unacked>,
+    _phantom: PhantomData<A>,
+}
+
+impl<A> Clone for ReliableAddr<A> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            local_node: self.local_node.clone(),
+            outbox: self.outbox.clone(),
+            next_delivery: self.next_delivery.clone(),
+            unacked: self.unacked.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<A> ReliableAddr<A> {
+    pub(crate) fn new(
+        id: RemoteActorId,
+        local_node: &str,
+        client: RemoteClient,
+        config: DeliveryConfig,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(config.capacity);
+        let unacked = Arc::new(Unacked::default());
+        tokio::spawn(deliver(rx, client, config, unacked.clone()));
+        Self {
+            id,
+            local_node: local_node.to_string(),
+            outbox: tx,
+            //random start so a restarted sender isn't taken for a duplicate
+            next_delivery: Arc::new(AtomicU64::new(rand::random::<u64>() >> 1 | 1)),
+            unacked,
+            _phantom: PhantomData,
+        }
+    }
+
+    ///queue a tell for delivery, waiting only when the outbox is full
+    /// Returns once the message is queued, not once it is acknowledged
+    pub async fn do_send<M>(&self, msg: M) -> Result<(), TransportError>
+    where
+        M: RemoteMessage,
+    {
+        let mut envelope = Envelope::from_message(&msg, 0, &self.local_node, &self.id.actor_name);
+        envelope.delivery_id = self.next_delivery.fetch_add(1, Ordering::Relaxed);
+
+        self.unacked.count.fetch_add(1, Ordering::AcqRel);
+        if self.outbox.send(envelope).await.is_err() {
+            self.unacked.done();
+            return Err(TransportError::Disconnected);
+        }
+        Ok(())
+    }
+
+    /// Messages queued or sent but not yet acknowledged
+    pub fn unacked(&self) -> usize {
+        self.unacked.count.load(Ordering::Acquire)
+    }
+
+    ///wait until everything sent so far has been acknowledged (or given up on)
+    pub async fn flush(&self) {
+        loop {
+            let drained = self.unacked.drained.notified();
+            if self.unacked() == 0 {
+                return;
+            }
+            drained.await;
+        }
+    }
+}
+
+///outbox loop: one message at a time, resent until acknowledged
+async fn deliver(
+    mut rx: mpsc::Receiver<Envelope>,
+    client: RemoteClient,
+    config: DeliveryConfig,
+    unacked: Arc<Unacked>,
+) {
+    while let Some(mut envelope) = rx.recv().await {
+        let mut attempt = 0;
+        loop {
+            envelope.correlation_id = next_correlation_id();
+            let sent = client
+                .send_timeout(envelope.clone(), config.ack_timeout)
+                .await;
+            match sent {
+                Ok(response) if is_ack(&response) => break,
+                //resending can't fix these
+                Err(e @ (TransportError::UnknownMessageType(_) | TransportError::Decode(_))) => {
+                    eprintln!(
+                        "Dropping delivery {} to {}: {:?}",
+                        envelope.delivery_id, envelope.target_actor, e
+                    );
+                    break;
+                }
+                _ => {}
+            }
+            attempt += 1;
+            if config.retry.max_attempts.is_some_and(|max| attempt >= max) {
+                eprintln!(
+                    "Giving up on delivery {} to {} after {} attempts",
+                    envelope.delivery_id, envelope.target_actor, attempt
+                );
+                break;
+            }
+            tokio::time::sleep(config.retry.delay(attempt)).await;
+        }
+        unacked.done();
+    }
+}
+
+///delivery ids recently seen from one sender node, and whether their
+/// handler has finished
+#[derive(Default)]
+struct Seen {
+    done: HashMap<u64, bool>,
+    order: VecDeque<u64>,
+}
+
+/// How many delivery ids per sender are remembered for deduplication
+const DEDUP_WINDOW: usize = 16 * 1024;
+
+type SeenBySender = Arc<Mutex<HashMap<String, Seen>>>;
+
+///Wrap a server's handler so reliable tells are deduplicated and acknowledged
+/// The ack goes out once the handler has taken the message (for tells: once
+/// it's in the actor's mailbox). A redelivery is acked without running the
+/// handler again, or left unanswered while the first delivery is still being
+/// handled, so the sender can't move on to the next message before it.
+/// When the handler fails its failure answer goes back instead of the ack,
+/// and the delivery id is forgotten so the resend runs the handler again
+pub(crate) fn acknowledging(handler: EnvelopeHandler) -> EnvelopeHandler {
+    let seen: SeenBySender = Arc::default();
+    Arc::new(move |envelope: Envelope| {
+        if envelope.delivery_id == 0 {
+            return handler(envelope);
+        }
+        let handler = handler.clone();
+        let seen = seen.clone();
+        Box::pin(async move {
+            let ack = ack(&envelope);
+            let (sender, id) = (envelope.sender_node.clone(), envelope.delivery_id);
+            match first_delivery(&seen, &sender, id) {
+                Some(true) => return Some(ack),
+                Some(false) => return None,
+                None => {}
+            }
+            let response = handler(envelope).await;
+            let failed = response.as_ref().is_some_and(failure::is_failure);
+            let mut seen = lock(&seen);
+            let Some(seen) = seen.get_mut(&sender) else {
+                return if failed { response } else { Some(ack) };
+            };
+            if failed {
+                seen.done.remove(&id);
+                seen.order.retain(|&old| old != id);
+                return response;
+            }
+            if let Some(done) = seen.done.get_mut(&id) {
+                *done = true;
+            }
+            Some(ack)
+        })
+    })
+}
+
+fn lock(seen: &SeenBySender) -> std::sync::MutexGuard<'_, HashMap<String, Seen>> {
+    seen.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+///`None` the first time `id` arrives (and records it), otherwise whether the
+/// first delivery has been handled
+fn first_delivery(seen: &SeenBySender, sender: &str, id: u64) -> Option<bool> {
+    let mut seen = lock(seen);
+    let sender = seen.entry(sender.to_string()).or_default();
+    if let Some(&done) = sender.done.get(&id) {
+        return Some(done);
+    }
+    sender.done.insert(id, false);
+    sender.order.push_back(id);
+    if sender.order.len() > DEDUP_WINDOW {
+        if let Some(old) = sender.order.pop_front() {
+            sender.done.remove(&old);
+        }
+    }
+    None
+}
@@ -0,0 +1,45 @@
+use crate::remote::{deadline::DEADLINE_EXCEEDED, proto::Envelope, TransportError};
+
+/// Reserved message type of the answer to a request the receiving node has
+/// no route for, surfaced to the caller as `TransportError::UnknownMessageType`
+pub const UNKNOWN_MESSAGE_TYPE: &str = "cinema::unknown_message_type";
+
+/// Reserved message type of the answer to a request whose payload the
+/// receiving node couldn't decode, surfaced as `TransportError::Decode`
+pub const DECODE_FAILED: &str = "cinema::decode_failed";
+
+/// Reserved message type of the answer to a request the remote actor
+/// couldn't take, e.g. because it stopped, surfaced as `TransportError::Remote`
+pub const HANDLER_FAILED: &str = "cinema::handler_failed";
+
+///answer to `request` saying it failed, the reason travels as the payload
+pub(crate) fn answer(request: &Envelope, message_type: &str, reason: String) -> Envelope {
+    Envelope {
+        message_type: message_type.to_string(),
+        payload: reason.into_bytes(),
+        correlation_id: request.correlation_id,
+        target_actor: request.sender_node.clone(),
+        is_response: true,
+        ..Default::default()
+    }
+}
+
+///whether `response` is one of the failure answers above (or a missed deadline)
+pub(crate) fn is_failure(response: &Envelope) -> bool {
+    matches!(
+        response.message_type.as_str(),
+        DEADLINE_EXCEEDED | UNKNOWN_MESSAGE_TYPE | DECODE_FAILED | HANDLER_FAILED
+    )
+}
+
+///the response, or the error it stands for
+pub(crate) fn check(response: Envelope) -> Result<Envelope, TransportError> {
+    let reason = || String::from_utf8_lossy(&response.payload).into_owned();
+    match response.message_type.as_str() {
+        DEADLINE_EXCEEDED => Err(TransportError::DeadlineExceeded),
+        UNKNOWN_MESSAGE_TYPE => Err(TransportError::UnknownMessageType(reason())),
+        DECODE_FAILED => Err(TransportError::Decode(prost::DecodeError::new(reason()))),
+        HANDLER_FAILED => Err(TransportError::Remote(reason())),
+        _ => Ok(response),
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+///How a cluster node decides that a member stopped responding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FailureDetection {
+    ///suspect a member after `suspect_after` without hearing from it,
+    /// declare it down after `down_after`
+    Timeout {
+        suspect_after: Duration,
+        down_after: Duration,
+    },
+    ///suspect a member once the phi value of its silence crosses the
+    /// threshold, see `PhiAccrualConfig`
+    PhiAccrual(PhiAccrualConfig),
+}
+
+impl FailureDetection {
+    ///timeout detection, down at twice the suspect timeout
+    pub fn timeout(suspect_after: Duration) -> Self {
+        FailureDetection::Timeout {
+            suspect_after,
+            down_after: suspect_after * 2,
+        }
+    }
+}
+
+impl Default for FailureDetection {
+    fn default() -> Self {
+        FailureDetection::PhiAccrual(PhiAccrualConfig::default())
+    }
+}
+
+///Phi accrual failure detection (Hayashibara et al.)
+/// Instead of a fixed timeout, each member's heartbeat inter-arrival times are
+/// sampled and phi = -log10(P(a heartbeat arrives later than now)) is derived
+/// from their normal distribution. Phi 1 means a 10% chance of a false
+/// suspicion, 8 about 1 in 10^8, so the threshold adapts to how regularly each
+/// member is actually heard from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhiAccrualConfig {
+    ///phi at which a member becomes suspect
+    pub threshold: f64,
+    ///how long a member stays suspect before it is declared down
+    pub down_after: Duration,
+    ///inter-arrival samples kept per member
+    pub max_samples: usize,
+    ///floor for the standard deviation, so very regular heartbeats don't make
+    /// the smallest delay look fatal
+    pub min_std_deviation: Duration,
+    ///extra silence tolerated on top of the mean, e.g. for GC pauses
+    pub acceptable_pause: Duration,
+    ///assumed interval until a member's first two heartbeats have been seen
+    pub first_heartbeat_estimate: Duration,
+}
+
+impl Default for PhiAccrualConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 8.0,
+            down_after: Duration::from_secs(10),
+            max_samples: 200,
+            min_std_deviation: Duration::from_millis(100),
+            acceptable_pause: Duration::ZERO,
+            first_heartbeat_estimate: Duration::from_secs(1),
+        }
+    }
+}
+
+impl PhiAccrualConfig {
+    pub fn threshold(mut self, phi: f64) -> Self {
+        self.threshold = phi;
+        self
+    }
+
+    pub fn down_after(mut self, duration: Duration) -> Self {
+        self.down_after = duration;
+        self
+    }
+
+    pub fn max_samples(mut self, samples: usize) -> Self {
+        self.max_samples = samples.max(1);
+        self
+    }
+
+    pub fn min_std_deviation(mut self, deviation: Duration) -> Self {
+        self.min_std_deviation = deviation;
+        self
+    }
+
+    pub fn acceptable_pause(mut self, pause: Duration) -> Self {
+        self.acceptable_pause = pause;
+        self
+    }
+
+    pub fn first_heartbeat_estimate(mut self, interval: Duration) -> Self {
+        self.first_heartbeat_estimate = interval;
+        self
+    }
+}
+
+/// What the detector makes of a member right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Healthy,
+    Suspect,
+    Dead,
+}
+
+///heartbeats seen from one member
+struct History {
+    last: Instant,
+    ///inter-arrival times in milliseconds, oldest first
+    intervals: VecDeque<f64>,
+    ///since when phi has been over the threshold
+    suspected_since: Option<Instant>,
+}
+
+impl History {
+    fn mean_and_std_deviation(&self, config: &PhiAccrualConfig) -> (f64, f64) {
+        let (mean, std_deviation) = if self.intervals.is_empty() {
+            let mean = config.first_heartbeat_estimate.as_secs_f64() * 1000.0;
+            (mean, mean / 4.0)
+        } else {
+            let n = self.intervals.len() as f64;
+            let mean = self.intervals.iter().sum::<f64>() / n;
+            let variance = self.intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / n;
+            (mean, variance.sqrt())
+        };
+        let min = config.min_std_deviation.as_secs_f64() * 1000.0;
+        (
+            mean + config.acceptable_pause.as_secs_f64() * 1000.0,
+            std_deviation.max(min),
+        )
+    }
+}
+
+///Tracks heartbeat arrivals per member and judges their health
+pub struct FailureDetector {
+    detection: FailureDetection,
+    members: HashMap<String, History>,
+}
+
+impl FailureDetector {
+    pub fn new(detection: FailureDetection) -> Self {
+        Self {
+            detection,
+            members: HashMap::new(),
+        }
+    }
+
+    pub fn detection(&self) -> FailureDetection {
+        self.detection
+    }
+
+    ///switch how members are judged, keeping what was seen of them so far
+    pub fn set_detection(&mut self, detection: FailureDetection) {
+        self.detection = detection;
+    }
+
+    ///a heartbeat (or any sign of life) from `member` arrived at `now`
+    pub fn heartbeat(&mut self, member: &str, now: Instant) {
+        let max_samples = match self.detection {
+            FailureDetection::PhiAccrual(config) => config.max_samples,
+            FailureDetection::Timeout { .. } => PhiAccrualConfig::default().max_samples,
+        };
+        match self.members.get_mut(member) {
+            Some(history) => {
+                let interval = now.duration_since(history.last).as_secs_f64() * 1000.0;
+                history.intervals.push_back(interval);
+                while history.intervals.len() > max_samples {
+                    history.intervals.pop_front();
+                }
+                history.last = now;
+            }
+            None => {
+                self.members.insert(
+                    member.to_string(),
+                    History {
+                        last: now,
+                        intervals: VecDeque::new(),
+                        suspected_since: None,
+                    },
+                );
+            }
+        }
+    }
+
+    ///phi of `member`'s current silence, 0 for timeout detection and unknown members
+    pub fn phi(&self, member: &str, now: Instant) -> f64 {
+        match (self.detection, self.members.get(member)) {
+            (FailureDetection::PhiAccrual(config), Some(history)) => {
+                let elapsed = now.duration_since(history.last).as_secs_f64() * 1000.0;
+                let (mean, std_deviation) = history.mean_and_std_deviation(&config);
+                phi(elapsed, mean, std_deviation)
+            }
+            _ => 0.0,
+        }
+    }
+
+    ///judge `member`, `None` if it has never been heard from
+    pub fn check(&mut self, member: &str, now: Instant) -> Option<Verdict> {
+        let phi = self.phi(member, now);
+        let history = self.members.get_mut(member)?;
+        let silence = now.duration_since(history.last);
+
+        let verdict = match self.detection {
+            FailureDetection::Timeout {
+                suspect_after,
+                down_after,
+            } => {
+                if silence > down_after {
+                    Verdict::Dead
+                } else if silence > suspect_after {
+                    Verdict::Suspect
+                } else {
+                    Verdict::Healthy
+                }
+            }
+            FailureDetection::PhiAccrual(config) => {
+                if phi < config.threshold {
+                    history.suspected_since = None;
+                    Verdict::Healthy
+                } else {
+                    let since = *history.suspected_since.get_or_insert(now);
+                    if now.duration_since(since) >= config.down_after {
+                        Verdict::Dead
+                    } else {
+                        Verdict::Suspect
+                    }
+                }
+            }
+        };
+        Some(verdict)
+    }
+
+    ///stop tracking `member`, e.g. once it left the cluster
+    pub fn remove(&mut self, member: &str) {
+        self.members.remove(member);
+    }
+}
+
+///-log10 of the probability that the next heartbeat is still to come after
+/// `elapsed`, with the normal CDF approximated by a logistic function
+fn phi(elapsed: f64, mean: f64, std_deviation: f64) -> f64 {
+    let y = (elapsed - mean) / std_deviation;
+    let e = (-y * (1.5976 + 0.070566 * y * y)).exp();
+    let later = if elapsed > mean {
+        e / (1.0 + e)
+    } else {
+        1.0 - 1.0 / (1.0 + e)
+    };
+    -later.max(f64::MIN_POSITIVE).log10()
+}
@@ -0,0 +1,310 @@
+use std::{
+    convert::Infallible,
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{Arc, OnceLock},
+    task::{Context, Poll},
+};
+
+use futures::StreamExt;
+use hyper_util::rt::TokioIo;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{
+    codegen::{http, Body, BoxFuture, BoxStream, Service, StdError},
+    metadata::{MetadataKey, MetadataValue},
+    server::StreamingService,
+    transport::{server::TcpIncoming, Endpoint},
+    Status, Streaming,
+};
+use tonic_prost::ProstCodec;
+
+use crate::remote::{
+    proto::Envelope,
+    server::{serve_with, ConnOptions},
+    transport::{Connection, Transport, TransportError},
+    EnvelopeHandler, DEFAULT_MAX_FRAME_SIZE,
+};
+
+///the one bidirectional streaming method envelopes travel over
+const PATH: &str = "/cinema.Remote/Stream";
+
+///envelopes queued on a stream before senders wait
+const BUFFER: usize = 32;
+
+fn grpc_error(err: impl std::fmt::Display) -> TransportError {
+    TransportError::Io(io::Error::other(err.to_string()))
+}
+
+fn invalid(err: impl std::fmt::Display) -> TransportError {
+    TransportError::Io(io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))
+}
+
+///gRPC connection, one bidirectional stream carrying envelopes both ways
+/// in the order they were sent, so it behaves like a TCP connection to the
+/// layers above; only the framing is HTTP/2
+pub struct GrpcConnection {
+    outgoing: Option<mpsc::Sender<Envelope>>,
+    incoming: mpsc::Receiver<Result<Envelope, TransportError>>,
+    local_addr: String,
+}
+
+impl GrpcConnection {
+    fn new(
+        outgoing: mpsc::Sender<Envelope>,
+        mut stream: Streaming<Envelope>,
+        local_addr: String,
+    ) -> Self {
+        //the stream isn't Sync, so it is read on its own task
+        let (incoming_tx, incoming) = mpsc::channel(BUFFER);
+        tokio::spawn(async move {
+            loop {
+                let item = match stream.message().await {
+                    Ok(Some(envelope)) => Ok(envelope),
+                    Ok(None) => break,
+                    Err(status) => Err(grpc_error(status)),
+                };
+                let failed = item.is_err();
+                if incoming_tx.send(item).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        GrpcConnection {
+            outgoing: Some(outgoing),
+            incoming,
+            local_addr,
+        }
+    }
+
+    /// Get the local socket address as a string
+    pub fn local_addr(&self) -> &str {
+        &self.local_addr
+    }
+}
+
+impl Connection for GrpcConnection {
+    fn send(
+        &mut self,
+        envelope: Envelope,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            let outgoing = self.outgoing.as_ref().ok_or(TransportError::Disconnected)?;
+            outgoing
+                .send(envelope)
+                .await
+                .map_err(|_| TransportError::Disconnected)
+        })
+    }
+
+    fn recv(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            match self.incoming.recv().await {
+                Some(item) => item,
+                None => Err(TransportError::Disconnected),
+            }
+        })
+    }
+
+    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            //ends our half of the stream, the peer sees it finish
+            self.outgoing = None;
+            Ok(())
+        })
+    }
+
+    fn local_addr(&self) -> &str {
+        &self.local_addr
+    }
+}
+
+/// Client side of gRPC remote connections
+/// Speaks plaintext HTTP/2 (h2c), for meshes and proxies that terminate TLS
+/// themselves; `header` adds metadata such as the credentials an
+/// authenticating proxy expects
+#[derive(Clone)]
+pub struct GrpcTransport {
+    headers: Vec<(String, String)>,
+    max_message_size: usize,
+}
+
+impl Default for GrpcTransport {
+    fn default() -> Self {
+        Self {
+            headers: Vec::new(),
+            max_message_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+}
+
+impl GrpcTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send `key: value` as metadata when opening the stream
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_lowercase(), value.to_string()));
+        self
+    }
+
+    /// Refuse envelopes over `bytes` either way, `DEFAULT_MAX_FRAME_SIZE` unless set
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = bytes;
+        self
+    }
+
+    fn request(
+        &self,
+        outgoing: mpsc::Receiver<Envelope>,
+    ) -> Result<tonic::Request<ReceiverStream<Envelope>>, TransportError> {
+        let mut request = tonic::Request::new(ReceiverStream::new(outgoing));
+        for (key, value) in &self.headers {
+            let key = MetadataKey::from_bytes(key.as_bytes()).map_err(invalid)?;
+            let value: MetadataValue<_> = value.parse().map_err(invalid)?;
+            request.metadata_mut().insert(key, value);
+        }
+        Ok(request)
+    }
+}
+
+impl Transport for GrpcTransport {
+    type Conn = GrpcConnection;
+
+    fn connect(
+        &self,
+        addr: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Conn, TransportError>> + Send + '_>> {
+        let addr = addr.to_string();
+        Box::pin(async move {
+            let endpoint = Endpoint::from_shared(format!("http://{}", addr)).map_err(invalid)?;
+
+            //dial the socket ourselves to learn the address we send from
+            let local_addr = Arc::new(OnceLock::new());
+            let local = local_addr.clone();
+            let connector = tower::service_fn(move |_| {
+                let addr = addr.clone();
+                let local = local.clone();
+                async move {
+                    let stream = TcpStream::connect(&addr).await?;
+                    stream.set_nodelay(true)?;
+                    let _ = local.set(stream.local_addr()?.to_string());
+                    Ok::<_, io::Error>(TokioIo::new(stream))
+                }
+            });
+            let channel = endpoint
+                .connect_with_connector(connector)
+                .await
+                .map_err(grpc_error)?;
+
+            let mut grpc = tonic::client::Grpc::new(channel)
+                .max_decoding_message_size(self.max_message_size)
+                .max_encoding_message_size(self.max_message_size);
+            grpc.ready().await.map_err(grpc_error)?;
+
+            let (outgoing, outgoing_rx) = mpsc::channel(BUFFER);
+            let response = grpc
+                .streaming(
+                    self.request(outgoing_rx)?,
+                    http::uri::PathAndQuery::from_static(PATH),
+                    ProstCodec::default(),
+                )
+                .await
+                .map_err(grpc_error)?;
+
+            let local_addr = local_addr
+                .get()
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string());
+            Ok(GrpcConnection::new(
+                outgoing,
+                response.into_inner(),
+                local_addr,
+            ))
+        })
+    }
+}
+
+///answers the stream method, each call becoming a connection served like a
+/// TCP one
+#[derive(Clone)]
+struct EnvelopeService {
+    handler: EnvelopeHandler,
+    options: ConnOptions,
+}
+
+impl StreamingService<Envelope> for EnvelopeService {
+    type Response = Envelope;
+    type ResponseStream = BoxStream<Envelope>;
+    type Future = BoxFuture<tonic::Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: tonic::Request<Streaming<Envelope>>) -> Self::Future {
+        let peer = request
+            .remote_addr()
+            .map(|a| a.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        println!("Accepted connection from {}", peer);
+        let local_addr = request
+            .local_addr()
+            .map(|a| a.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let (outgoing, outgoing_rx) = mpsc::channel(BUFFER);
+        let conn = GrpcConnection::new(outgoing, request.into_inner(), local_addr);
+        tokio::spawn(serve_with(
+            conn,
+            peer,
+            self.handler.clone(),
+            self.options.clone(),
+        ));
+
+        let responses: BoxStream<Envelope> = Box::pin(ReceiverStream::new(outgoing_rx).map(Ok));
+        Box::pin(async move { Ok(tonic::Response::new(responses)) })
+    }
+}
+
+impl<B> Service<http::Request<B>> for EnvelopeService
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if req.uri().path() != PATH {
+            let status = Status::unimplemented(format!("no method {}", req.uri().path()));
+            return Box::pin(async move { Ok(status.into_http()) });
+        }
+        let max = self.options.max_frame_size;
+        let service = self.clone();
+        Box::pin(async move {
+            let mut grpc = tonic::server::Grpc::new(ProstCodec::default())
+                .max_decoding_message_size(max)
+                .max_encoding_message_size(max);
+            Ok(grpc.streaming(service, req).await)
+        })
+    }
+}
+
+///serve the stream method on `listener` until it fails
+pub(super) async fn run(listener: TcpListener, handler: EnvelopeHandler, options: ConnOptions) {
+    let service = EnvelopeService { handler, options };
+    let result = tonic::transport::Server::builder()
+        .serve_with_incoming(service, TcpIncoming::from(listener))
+        .await;
+    if let Err(e) = result {
+        eprintln!("gRPC server failed: {:?}", e);
+    }
+}
@@ -1,25 +1,62 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
 
-use bytes::BytesMut;
-use prost::Message as ProstMessage;
+use tokio::time::timeout;
 
-use crate::{remote::proto::Envelope, Actor, Addr, Handler};
+use crate::{
+    message::MessageOrigin,
+    remote::proto::{Envelope, RemoteRef},
+    Actor, ActorSystem, Addr, Handler,
+};
 
-use super::{EnvelopeHandler, NodeId, RemoteAddr, RemoteClient, RemoteMessage};
+#[cfg(feature = "otel")]
+use super::trace_span;
+use super::{
+    deadline, failure, register_message, EnvelopeHandler, NodeId, RemoteAddr, RemoteClient,
+    RemoteMessage, DECODE_FAILED, HANDLER_FAILED, UNKNOWN_MESSAGE_TYPE,
+};
 
 /// Represents this node's identity - used for creating handlers and remote addresses
 #[derive(Clone)]
 pub struct LocalNode {
     pub id: NodeId,
+    ///address our server listens on, needed to hand out references
+    pub addr: Option<String>,
 }
 
 impl LocalNode {
     pub fn new(id: &str) -> Self {
         Self {
             id: NodeId(id.to_string()),
+            addr: None,
         }
     }
 
+    /// Node identity plus the address peers can reach it on
+    pub fn with_addr(id: &str, addr: &str) -> Self {
+        Self {
+            id: NodeId(id.to_string()),
+            addr: Some(addr.to_string()),
+        }
+    }
+
+    /// Reference to a locally registered actor that can be embedded in remote messages
+    /// None if no actor of type A is registered under that name, or the node has no address
+    pub fn export<A: Actor>(&self, sys: &ActorSystem, name: &str) -> Option<RemoteRef> {
+        sys.lookup::<A>(name)?;
+        Some(RemoteRef {
+            node_id: self.id.0.clone(),
+            node_addr: self.addr.clone()?,
+            actor_name: name.to_string(),
+        })
+    }
+
     /// Create request-response handler for actor/message pair
     pub fn handler<A, M>(&self, addr: Addr<A>) -> EnvelopeHandler
     where
@@ -46,7 +83,12 @@ impl LocalNode {
 }
 
 /// Create request-response handler for actor/message pair
-/// Both the message M and its result M::Result must be RemoteMessage (protobuf)
+/// Both the message M and its result M::Result must be RemoteMessage, each
+/// encoded with its own serializer
+/// Requests carrying a deadline are answered with `DEADLINE_EXCEEDED` once it
+/// passes: right away if it already has, otherwise the actor's work is
+/// abandoned when time runs out. A payload that doesn't decode is answered
+/// with `DECODE_FAILED`, a send the actor turns away with `HANDLER_FAILED`
 pub fn make_handler<A, M>(addr: Addr<A>, node_id: &str) -> EnvelopeHandler
 where
     A: Actor + Handler<M>,
@@ -57,27 +99,53 @@ where
     Arc::new(move |envelope: Envelope| {
         let addr = addr.clone();
         let node_id = node_id.clone();
-        Box::pin(async move {
-            // 1. Decode incoming message
-            let msg = M::decode(envelope.payload.as_slice()).ok()?;
+        //the actor handles the message and the response is sent in the
+        // sender's trace
+        #[cfg(feature = "otel")]
+        let span = trace_span(&envelope);
+        let handled = async move {
+            // 1. Decode incoming message, unless the sender already gave up
+            let remaining = envelope.remaining();
+            if remaining == Some(Duration::ZERO) {
+                return Some(deadline::exceeded(&envelope, &node_id));
+            }
+            let msg = match envelope.message::<M>() {
+                Ok(msg) => msg,
+                Err(err) => {
+                    return Some(failure::answer(&envelope, DECODE_FAILED, err.to_string()))
+                }
+            };
 
             // 2. Send to actor, get result
-            let result = addr.send(msg).await.ok()?;
-
-            // 3. Encode result as protobuf
-            let mut buf = BytesMut::new();
-            result.encode(&mut buf).ok()?;
-
-            // 4. Build response envelope
-            Some(Envelope {
-                message_type: <M::Result as RemoteMessage>::type_id().to_string(),
-                payload: buf.to_vec(),
-                correlation_id: envelope.correlation_id,
-                sender_node: node_id,
-                target_actor: envelope.sender_node.clone(),
-                is_response: true,
-            })
-        })
+            let result = match remaining {
+                None => addr.send(msg).await,
+                //dropping the send abandons the request, the actor skips it
+                // if it hasn't got to it yet
+                Some(remaining) => match timeout(remaining, addr.send(msg)).await {
+                    Ok(result) => result,
+                    Err(_) => return Some(deadline::exceeded(&envelope, &node_id)),
+                },
+            };
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    return Some(failure::answer(&envelope, HANDLER_FAILED, err.to_string()))
+                }
+            };
+
+            // 3. Encode result into the response envelope
+            let mut response = Envelope::from_message(
+                &result,
+                envelope.correlation_id,
+                &node_id,
+                &envelope.sender_node,
+            );
+            response.is_response = true;
+            Some(response)
+        };
+        #[cfg(feature = "otel")]
+        let handled = tracing::Instrument::instrument(handled, span);
+        Box::pin(handled)
     })
 }
 
@@ -89,18 +157,46 @@ where
 {
     Arc::new(move |envelope: Envelope| {
         let addr = addr.clone();
-        Box::pin(async move {
-            if let Ok(msg) = M::decode(envelope.payload.as_slice()) {
-                let _ = addr.do_send(msg);
+        #[cfg(feature = "otel")]
+        let span = trace_span(&envelope);
+        let handled = async move {
+            if let Ok(msg) = envelope.message::<M>() {
+                let _ = addr.do_send(msg).await;
             }
             None // no response
-        })
+        };
+        #[cfg(feature = "otel")]
+        let handled = tracing::Instrument::instrument(handled, span);
+        Box::pin(handled)
     })
 }
 
-/// Router dispatches envelopes to handlers based on message_type
+///remote envelopes that no route matched, across all routers
+static UNHANDLED_REMOTE: AtomicU64 = AtomicU64::new(0);
+
+/// Number of remote envelopes no handler was found for
+pub fn unhandled_remote_messages() -> u64 {
+    UNHANDLED_REMOTE.load(Ordering::Relaxed)
+}
+
+///answer to a request no route matched
+fn unknown_type(request: &Envelope) -> Envelope {
+    let reason = format!("{} (target: {})", request.message_type, request.target_actor);
+    failure::answer(request, UNKNOWN_MESSAGE_TYPE, reason)
+}
+
+///reports an unhandled message type to an actor's hook
+type UnhandledSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Router dispatches envelopes to handlers based on target_actor and message_type
+/// Actor-specific routes win over type-only routes, which win over the default
+/// Envelopes nothing matches are answered with `UNKNOWN_MESSAGE_TYPE`
 pub struct MessageRouter {
     handlers: HashMap<String, EnvelopeHandler>,
+    ///(actor name, message type) -> handler
+    actor_handlers: HashMap<(String, String), EnvelopeHandler>,
+    ///actor name -> unhandled hook
+    unhandled: HashMap<String, UnhandledSink>,
     default_handler: Option<EnvelopeHandler>,
 }
 
@@ -108,6 +204,8 @@ impl MessageRouter {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            actor_handlers: HashMap::new(),
+            unhandled: HashMap::new(),
             default_handler: None,
         }
     }
@@ -124,6 +222,35 @@ impl MessageRouter {
         self
     }
 
+    /// Route messages of type M addressed to a named actor
+    pub fn route_actor<M: RemoteMessage>(self, actor_name: &str, handler: EnvelopeHandler) -> Self {
+        self.route_actor_type(actor_name, M::type_id(), handler)
+    }
+
+    /// Route by actor name and explicit type string
+    pub fn route_actor_type(
+        mut self,
+        actor_name: &str,
+        type_id: &str,
+        handler: EnvelopeHandler,
+    ) -> Self {
+        self.actor_handlers
+            .insert((actor_name.to_string(), type_id.to_string()), handler);
+        self
+    }
+
+    /// Report envelopes addressed to a named actor that no route matched
+    /// to the actor's `unhandled` hook
+    pub fn unhandled_to<A: Actor>(mut self, actor_name: &str, addr: Addr<A>) -> Self {
+        self.unhandled.insert(
+            actor_name.to_string(),
+            Arc::new(move |message_type: &str| {
+                addr.notify_unhandled(message_type, MessageOrigin::Remote)
+            }),
+        );
+        self
+    }
+
     /// Fallback for unknown message types
     pub fn default(mut self, handler: EnvelopeHandler) -> Self {
         self.default_handler = Some(handler);
@@ -133,20 +260,35 @@ impl MessageRouter {
     /// Build into a single EnvelopeHandler
     pub fn build(self) -> EnvelopeHandler {
         let handlers = Arc::new(self.handlers);
+        let actor_handlers = Arc::new(self.actor_handlers);
+        let unhandled = Arc::new(self.unhandled);
         let default = self.default_handler;
 
         Arc::new(move |envelope: Envelope| {
             let handlers = handlers.clone();
+            let actor_handlers = actor_handlers.clone();
+            let unhandled = unhandled.clone();
             let default = default.clone();
 
             Box::pin(async move {
-                if let Some(handler) = handlers.get(&envelope.message_type) {
+                let key = (envelope.target_actor.clone(), envelope.message_type.clone());
+                if let Some(handler) = actor_handlers.get(&key) {
+                    handler(envelope).await
+                } else if let Some(handler) = handlers.get(&envelope.message_type) {
                     handler(envelope).await
                 } else if let Some(ref default_handler) = default {
                     default_handler(envelope).await
                 } else {
-                    eprintln!("No handler for message type: {}", envelope.message_type);
-                    None
+                    UNHANDLED_REMOTE.fetch_add(1, Ordering::Relaxed);
+                    if let Some(sink) = unhandled.get(&envelope.target_actor) {
+                        sink(&envelope.message_type);
+                    } else {
+                        eprintln!(
+                            "No handler for message type: {} (target: {})",
+                            envelope.message_type, envelope.target_actor
+                        );
+                    }
+                    Some(unknown_type(&envelope))
                 }
             })
         })
@@ -158,3 +300,66 @@ impl Default for MessageRouter {
         Self::new()
     }
 }
+
+///actors made reachable with `ActorSystem::expose`, keyed like
+/// `MessageRouter::route_actor`; unlike a router it takes new routes
+/// while a listener is already serving it
+#[derive(Default)]
+pub(crate) struct ExposedActors {
+    routes: RwLock<HashMap<(String, String), EnvelopeHandler>>,
+    unhandled: RwLock<HashMap<String, UnhandledSink>>,
+}
+
+impl ExposedActors {
+    pub(crate) fn expose<A, M>(&self, actor_name: &str, addr: Addr<A>, node_id: &str)
+    where
+        A: Actor + Handler<M>,
+        M: RemoteMessage,
+        M::Result: RemoteMessage,
+    {
+        register_message::<M>();
+        let sink: UnhandledSink = {
+            let addr = addr.clone();
+            Arc::new(move |message_type: &str| {
+                addr.notify_unhandled(message_type, MessageOrigin::Remote)
+            })
+        };
+        self.routes.write().unwrap_or_else(|p| p.into_inner()).insert(
+            (actor_name.to_string(), M::type_id().to_string()),
+            make_handler(addr, node_id),
+        );
+        self.unhandled
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(actor_name.to_string(), sink);
+    }
+
+    ///the handler a listener serves, looking routes up per envelope
+    pub(crate) fn handler(self: &Arc<Self>) -> EnvelopeHandler {
+        let exposed = self.clone();
+        Arc::new(move |envelope: Envelope| {
+            let key = (envelope.target_actor.clone(), envelope.message_type.clone());
+            let route = exposed
+                .routes
+                .read()
+                .unwrap_or_else(|p| p.into_inner())
+                .get(&key)
+                .cloned();
+            if let Some(handler) = route {
+                return handler(envelope);
+            }
+            UNHANDLED_REMOTE.fetch_add(1, Ordering::Relaxed);
+            let unhandled = exposed.unhandled.read().unwrap_or_else(|p| p.into_inner());
+            if let Some(sink) = unhandled.get(&envelope.target_actor) {
+                sink(&envelope.message_type);
+            } else {
+                eprintln!(
+                    "No exposed actor {} handles message type: {}",
+                    envelope.target_actor, envelope.message_type
+                );
+            }
+            let answer = unknown_type(&envelope);
+            Box::pin(async { Some(answer) })
+        })
+    }
+}
@@ -0,0 +1,320 @@
+use std::{future::Future, pin::Pin, time::Duration};
+
+use prost::Message;
+use tokio::time::timeout;
+
+use crate::remote::{
+    proto::{Envelope, Handshake, HandshakeAck},
+    transport::{Connection, Transport, TransportError},
+    CompressedConnection, Compression, CompressionConfig,
+};
+
+/// Reserved message type of handshake envelopes, never passed to handlers
+pub const HANDSHAKE: &str = "cinema::handshake";
+
+/// Version of the wire protocol spoken by this build, nodes only talk to
+/// peers on the same version
+pub const PROTOCOL_VERSION: u32 = 1;
+
+///Who this node is and which peers it accepts
+/// Exchanged right after connecting, so a node from another cluster, an
+/// incompatible build or without the shared token is turned away up front
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandshakeConfig {
+    pub node_id: String,
+    pub cluster_name: String,
+    ///shared secret both sides must present, `None` for open clusters
+    pub token: Option<String>,
+    ///how long to wait for the peer's side of the handshake
+    pub timeout: Duration,
+    ///payload compression to offer (or accept) during the handshake
+    pub compression: Option<CompressionConfig>,
+    ///when this node's process started, so peers can tell a restart from a
+    /// reconnect; 0 if unknown. `ClusterNode` fills in its own
+    pub generation: u64,
+}
+
+impl HandshakeConfig {
+    pub fn new(node_id: &str, cluster_name: &str) -> Self {
+        Self {
+            node_id: node_id.to_string(),
+            cluster_name: cluster_name.to_string(),
+            token: None,
+            timeout: Duration::from_secs(5),
+            compression: None,
+            generation: 0,
+        }
+    }
+
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn generation(mut self, generation: u64) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Compress large payloads once the peer agrees to it
+    /// A client offers `config.algorithm`; a server accepts its own algorithm
+    /// when offered, else the first offered one it supports. Connections where
+    /// either side has no compression configured stay uncompressed
+    pub fn compression(mut self, config: CompressionConfig) -> Self {
+        self.compression = Some(config);
+        self
+    }
+
+    fn hello(&self) -> Handshake {
+        Handshake {
+            node_id: self.node_id.clone(),
+            protocol_version: PROTOCOL_VERSION,
+            cluster_name: self.cluster_name.clone(),
+            token: self.token.clone().unwrap_or_default(),
+            compression: self
+                .compression
+                .map(|c| c.algorithm)
+                .filter(|&algorithm| algorithm != Compression::None)
+                .map(|algorithm| vec![algorithm.id()])
+                .unwrap_or_default(),
+            generation: self.generation,
+        }
+    }
+
+    ///server side: the algorithm to use given what the client offered
+    fn negotiate(&self, offered: &[u32]) -> Compression {
+        let Some(ours) = self.compression else {
+            return Compression::None;
+        };
+        if ours.algorithm == Compression::None || offered.contains(&ours.algorithm.id()) {
+            return ours.algorithm;
+        }
+        offered
+            .iter()
+            .filter_map(|&id| Compression::from_id(id))
+            .find(|&algorithm| algorithm != Compression::None)
+            .unwrap_or(Compression::None)
+    }
+
+    ///our compression settings with the algorithm the handshake settled on
+    fn with_algorithm(&self, algorithm: Compression) -> CompressionConfig {
+        CompressionConfig {
+            algorithm,
+            ..self.compression.unwrap_or_default()
+        }
+    }
+
+    ///reason to turn the peer away, if any
+    fn check(&self, hello: &Handshake) -> Result<(), String> {
+        check_version(&hello.node_id, hello.protocol_version)?;
+        if hello.cluster_name != self.cluster_name {
+            return Err(format!(
+                "node {} belongs to cluster '{}', expected '{}'",
+                hello.node_id, hello.cluster_name, self.cluster_name
+            ));
+        }
+        if let Some(token) = &self.token {
+            if !same_secret(token.as_bytes(), hello.token.as_bytes()) {
+                return Err(format!("node {} presented an invalid token", hello.node_id));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_version(node_id: &str, version: u32) -> Result<(), String> {
+    if version != PROTOCOL_VERSION {
+        return Err(format!(
+            "node {} speaks protocol version {}, this node speaks {}",
+            node_id, version, PROTOCOL_VERSION
+        ));
+    }
+    Ok(())
+}
+
+///compare without bailing out at the first differing byte
+fn same_secret(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub(crate) fn is_handshake(envelope: &Envelope) -> bool {
+    envelope.message_type == HANDSHAKE
+}
+
+fn envelope(payload: Vec<u8>, sender_node: &str, is_response: bool) -> Envelope {
+    Envelope {
+        message_type: HANDSHAKE.to_string(),
+        payload,
+        sender_node: sender_node.to_string(),
+        is_response,
+        ..Default::default()
+    }
+}
+
+///check a handshake request against `config` when there is one
+/// without a config any peer on the same protocol version is accepted
+fn review(
+    config: Option<&HandshakeConfig>,
+    request: &Envelope,
+) -> (HandshakeAck, Result<Handshake, String>) {
+    let verdict = match Handshake::decode(request.payload.as_slice()) {
+        Ok(hello) => match config {
+            Some(config) => config.check(&hello),
+            None => check_version(&hello.node_id, hello.protocol_version),
+        }
+        .map(|_| hello),
+        Err(e) => Err(format!("malformed handshake: {}", e)),
+    };
+
+    let ack = HandshakeAck {
+        accepted: verdict.is_ok(),
+        reason: verdict.as_ref().err().cloned().unwrap_or_default(),
+        node_id: config.map(|c| c.node_id.clone()).unwrap_or_default(),
+        protocol_version: PROTOCOL_VERSION,
+        cluster_name: config.map(|c| c.cluster_name.clone()).unwrap_or_default(),
+        compression: Compression::None.id(),
+        generation: config.map_or(0, |c| c.generation),
+    };
+    (ack, verdict)
+}
+
+///answer a handshake request that arrives mid-connection, with the peer's
+/// introduction if it was accepted
+/// nothing is negotiated at that point, the connection stays uncompressed
+pub(crate) fn answer(
+    config: Option<&HandshakeConfig>,
+    request: &Envelope,
+) -> (Envelope, Result<Handshake, String>) {
+    let (ack, verdict) = review(config, request);
+    let ack = envelope(ack.encode_to_vec(), &ack.node_id, true);
+    (ack, verdict)
+}
+
+///introduce this node on a fresh connection and wait for the peer to accept
+/// Fails with `TransportError::Handshake` carrying the reason when either side
+/// turns the other away. The ack's `compression` is the algorithm agreed on
+pub async fn handshake<C: Connection>(
+    conn: &mut C,
+    config: &HandshakeConfig,
+) -> Result<HandshakeAck, TransportError> {
+    let request = envelope(config.hello().encode_to_vec(), &config.node_id, false);
+    conn.send(request).await?;
+
+    let response = match timeout(config.timeout, conn.recv()).await {
+        Ok(response) => response?,
+        Err(_) => return Err(TransportError::Timeout),
+    };
+    if !is_handshake(&response) || !response.is_response {
+        return Err(TransportError::Handshake(format!(
+            "expected a handshake answer, got {}",
+            response.message_type
+        )));
+    }
+
+    let ack = HandshakeAck::decode(response.payload.as_slice())?;
+    if !ack.accepted {
+        return Err(TransportError::Handshake(ack.reason));
+    }
+    check_version(&ack.node_id, ack.protocol_version).map_err(TransportError::Handshake)?;
+    if !ack.cluster_name.is_empty() && ack.cluster_name != config.cluster_name {
+        return Err(TransportError::Handshake(format!(
+            "node {} belongs to cluster '{}', expected '{}'",
+            ack.node_id, ack.cluster_name, config.cluster_name
+        )));
+    }
+    if ack.compression != 0 && !config.hello().compression.contains(&ack.compression) {
+        return Err(TransportError::Handshake(format!(
+            "node {} picked compression {}, which wasn't offered",
+            ack.node_id, ack.compression
+        )));
+    }
+    Ok(ack)
+}
+
+///server side: the first envelope must be an acceptable handshake
+/// returns the peer's introduction and the compression settings agreed on
+/// for the connection
+pub(crate) async fn accept<C: Connection>(
+    conn: &mut C,
+    config: &HandshakeConfig,
+) -> Result<(Handshake, CompressionConfig), TransportError> {
+    let request = match timeout(config.timeout, conn.recv()).await {
+        Ok(request) => request?,
+        Err(_) => return Err(TransportError::Timeout),
+    };
+    if !is_handshake(&request) {
+        let ack = HandshakeAck {
+            accepted: false,
+            reason: "expected a handshake first".to_string(),
+            node_id: config.node_id.clone(),
+            protocol_version: PROTOCOL_VERSION,
+            cluster_name: config.cluster_name.clone(),
+            compression: Compression::None.id(),
+            generation: config.generation,
+        };
+        let _ = conn
+            .send(envelope(ack.encode_to_vec(), &config.node_id, true))
+            .await;
+        return Err(TransportError::Handshake(format!(
+            "expected a handshake, got {}",
+            request.message_type
+        )));
+    }
+
+    let (mut ack, verdict) = review(Some(config), &request);
+    let algorithm = match &verdict {
+        Ok(hello) => config.negotiate(&hello.compression),
+        Err(_) => Compression::None,
+    };
+    ack.compression = algorithm.id();
+    conn.send(envelope(ack.encode_to_vec(), &config.node_id, true))
+        .await?;
+    let hello = verdict.map_err(TransportError::Handshake)?;
+    Ok((hello, config.with_algorithm(algorithm)))
+}
+
+///client side: `handshake`, then compress the connection as agreed
+pub(crate) async fn introduce<C: Connection>(
+    mut conn: C,
+    config: &HandshakeConfig,
+) -> Result<(HandshakeAck, CompressedConnection<C>), TransportError> {
+    let ack = handshake(&mut conn, config).await?;
+    let algorithm = Compression::from_id(ack.compression).unwrap_or_default();
+    let conn = CompressedConnection::new(conn, config.with_algorithm(algorithm));
+    Ok((ack, conn))
+}
+
+///Transport that runs `handshake` on every connection it opens,
+/// e.g. for `RemoteClient::connect_with_reconnect`
+/// Its connections compress payloads when the handshake agreed on it
+#[derive(Debug, Clone)]
+pub struct HandshakeTransport<T> {
+    inner: T,
+    config: HandshakeConfig,
+}
+
+impl<T: Transport> HandshakeTransport<T> {
+    pub fn new(inner: T, config: HandshakeConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+impl<T: Transport> Transport for HandshakeTransport<T> {
+    type Conn = CompressedConnection<T::Conn>;
+
+    fn connect(
+        &self,
+        addr: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Conn, TransportError>> + Send + '_>> {
+        let connecting = self.inner.connect(addr);
+        Box::pin(async move {
+            let (_, conn) = introduce(connecting.await?, &self.config).await?;
+            Ok(conn)
+        })
+    }
+}
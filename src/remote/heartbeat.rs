@@ -0,0 +1,171 @@
+use std::{future::Future, pin::Pin, time::Duration};
+
+use tokio::time::{sleep_until, Instant};
+
+use crate::remote::{
+    proto::Envelope,
+    transport::{Connection, Transport, TransportError},
+};
+
+/// Reserved message type of heartbeat envelopes, never passed to handlers
+pub const HEARTBEAT: &str = "cinema::heartbeat";
+
+///How often a connection is probed and how long it may stay silent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatConfig {
+    ///a heartbeat is sent this often
+    pub interval: Duration,
+    ///the connection is considered dead after this long without receiving anything
+    pub timeout: Duration,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            timeout: Duration::from_secs(15),
+        }
+    }
+}
+
+pub(crate) fn is_heartbeat(envelope: &Envelope) -> bool {
+    envelope.message_type == HEARTBEAT
+}
+
+fn heartbeat(is_response: bool) -> Envelope {
+    Envelope {
+        message_type: HEARTBEAT.to_string(),
+        is_response,
+        ..Default::default()
+    }
+}
+
+///answer to a heartbeat request
+pub(crate) fn pong() -> Envelope {
+    heartbeat(true)
+}
+
+///Connection wrapper that keeps the peer probed
+/// Sends a heartbeat every `interval` and answers the peer's. When nothing at
+/// all has arrived for `timeout`, `recv` reports `Disconnected`, so a
+/// half-open connection is torn down (or redialed) instead of leaving
+/// requests waiting forever. `recv` only queues heartbeats and answers, then
+/// returns a heartbeat answer (which owners skip) so they get to `flush` them;
+/// `send` writes them along as well
+pub struct HeartbeatConnection<C> {
+    inner: C,
+    config: HeartbeatConfig,
+    last_seen: Instant,
+    next_ping: Instant,
+    queued: Vec<Envelope>,
+}
+
+impl<C: Connection> HeartbeatConnection<C> {
+    pub fn new(inner: C, config: HeartbeatConfig) -> Self {
+        let now = Instant::now();
+        Self {
+            inner,
+            config,
+            last_seen: now,
+            next_ping: now + config.interval,
+            queued: Vec::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: Connection> Connection for HeartbeatConnection<C> {
+    fn send(
+        &mut self,
+        envelope: Envelope,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        if self.queued.is_empty() {
+            return self.inner.send(envelope);
+        }
+        self.send_batch(vec![envelope])
+    }
+
+    fn send_batch(
+        &mut self,
+        envelopes: Vec<Envelope>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        let mut batch = std::mem::take(&mut self.queued);
+        batch.extend(envelopes);
+        self.inner.send_batch(batch)
+    }
+
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        if self.queued.is_empty() {
+            return self.inner.flush();
+        }
+        self.send_batch(Vec::new())
+    }
+
+    fn recv(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            loop {
+                let deadline = self.last_seen + self.config.timeout;
+                tokio::select! {
+                    biased;
+                    result = self.inner.recv() => {
+                        let envelope = result?;
+                        //anything from the peer proves it alive
+                        self.last_seen = Instant::now();
+                        if !is_heartbeat(&envelope) {
+                            return Ok(envelope);
+                        }
+                        if !envelope.is_response {
+                            self.queued.push(pong());
+                            return Ok(pong());
+                        }
+                    }
+                    _ = sleep_until(self.next_ping) => {
+                        self.next_ping = Instant::now() + self.config.interval;
+                        self.queued.push(heartbeat(false));
+                        return Ok(pong());
+                    }
+                    _ = sleep_until(deadline) => return Err(TransportError::Disconnected),
+                }
+            }
+        })
+    }
+
+    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        self.inner.close()
+    }
+
+    fn local_addr(&self) -> &str {
+        self.inner.local_addr()
+    }
+}
+
+///Transport whose connections are wrapped in `HeartbeatConnection`,
+/// e.g. for `RemoteClient::connect_with_reconnect`
+#[derive(Debug, Clone)]
+pub struct HeartbeatTransport<T> {
+    inner: T,
+    config: HeartbeatConfig,
+}
+
+impl<T: Transport> HeartbeatTransport<T> {
+    pub fn new(inner: T, config: HeartbeatConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+impl<T: Transport> Transport for HeartbeatTransport<T> {
+    type Conn = HeartbeatConnection<T::Conn>;
+
+    fn connect(
+        &self,
+        addr: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Conn, TransportError>> + Send + '_>> {
+        let connecting = self.inner.connect(addr);
+        Box::pin(async move { Ok(HeartbeatConnection::new(connecting.await?, self.config)) })
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use prost::Message;
+
+use crate::{
+    metrics::{MetricsSink, MetricsSlot, RemoteEvent, RemoteMetrics},
+    remote::{
+        proto::Envelope,
+        transport::{Connection, Transport, TransportError},
+    },
+    ActorSystem,
+};
+
+///requests tracked for round trips before unanswered ones are forgotten
+const MAX_IN_FLIGHT: usize = 1024;
+
+///how long a request can go unanswered before it stops counting as in flight
+/// (tells are never answered)
+const UNANSWERED: Duration = Duration::from_secs(60);
+
+///whether the envelope couldn't be encoded or decoded, as opposed to the
+/// connection failing
+fn codec_error(error: &TransportError) -> bool {
+    match error {
+        TransportError::Decode(_) => true,
+        TransportError::Io(e) => e.kind() == io::ErrorKind::InvalidData,
+        _ => false,
+    }
+}
+
+///the correlation id to time, if `envelope` is a request, and its size
+fn measure(envelope: &Envelope) -> (Option<u64>, usize) {
+    let request = !envelope.is_response && envelope.correlation_id != 0;
+    (
+        request.then_some(envelope.correlation_id),
+        envelope.encoded_len(),
+    )
+}
+
+///Reports the traffic of a connection to a system's `MetricsSink`, through
+/// `record_remote`: envelopes and bytes each way, codec errors, and the round
+/// trip of every request answered on it
+/// Nothing is measured while the system has no sink installed
+pub struct MeteredConnection<C> {
+    inner: C,
+    peer: String,
+    slot: Option<Arc<MetricsSlot>>,
+    ///correlation id -> when the request went out
+    in_flight: HashMap<u64, Instant>,
+}
+
+impl<C: Connection> MeteredConnection<C> {
+    /// Meter `inner`, reporting it as the connection to `peer`
+    pub fn new(inner: C, peer: &str, system: &ActorSystem) -> Self {
+        Self::with_slot(inner, peer.to_string(), Some(system.metrics_slot()))
+    }
+
+    pub(crate) fn with_slot(inner: C, peer: String, slot: Option<Arc<MetricsSlot>>) -> Self {
+        Self {
+            inner,
+            peer,
+            slot,
+            in_flight: HashMap::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    fn sink(&self) -> Option<Arc<dyn MetricsSink>> {
+        self.slot.as_ref()?.sink()
+    }
+
+    fn record(&self, sink: &dyn MetricsSink, event: RemoteEvent) {
+        sink.record_remote(&RemoteMetrics {
+            peer: &self.peer,
+            event,
+        });
+    }
+
+    ///note when a request went out, for its round trip
+    fn requested(&mut self, correlation_id: Option<u64>, now: Instant) {
+        let Some(correlation_id) = correlation_id else {
+            return;
+        };
+        if self.in_flight.len() >= MAX_IN_FLIGHT {
+            self.in_flight
+                .retain(|_, sent| now.duration_since(*sent) < UNANSWERED);
+        }
+        if self.in_flight.len() < MAX_IN_FLIGHT {
+            self.in_flight.insert(correlation_id, now);
+        }
+    }
+}
+
+impl<C: Connection> Connection for MeteredConnection<C> {
+    fn send(
+        &mut self,
+        envelope: Envelope,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            let Some(sink) = self.sink() else {
+                return self.inner.send(envelope).await;
+            };
+            let (request, bytes) = measure(&envelope);
+            match self.inner.send(envelope).await {
+                Ok(()) => {
+                    self.requested(request, Instant::now());
+                    self.record(&*sink, RemoteEvent::Sent { bytes });
+                    Ok(())
+                }
+                Err(e) => {
+                    if codec_error(&e) {
+                        self.record(&*sink, RemoteEvent::EncodeError);
+                    }
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    fn send_batch(
+        &mut self,
+        envelopes: Vec<Envelope>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            let Some(sink) = self.sink() else {
+                return self.inner.send_batch(envelopes).await;
+            };
+            let measured: Vec<_> = envelopes.iter().map(measure).collect();
+            match self.inner.send_batch(envelopes).await {
+                Ok(()) => {
+                    let now = Instant::now();
+                    for (request, bytes) in measured {
+                        self.requested(request, now);
+                        self.record(&*sink, RemoteEvent::Sent { bytes });
+                    }
+                    Ok(())
+                }
+                Err(e) => {
+                    if codec_error(&e) {
+                        self.record(&*sink, RemoteEvent::EncodeError);
+                    }
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    fn recv(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            let result = self.inner.recv().await;
+            let Some(sink) = self.sink() else {
+                return result;
+            };
+            match &result {
+                Ok(envelope) => {
+                    let bytes = envelope.encoded_len();
+                    self.record(&*sink, RemoteEvent::Received { bytes });
+                    if envelope.is_response {
+                        if let Some(sent) = self.in_flight.remove(&envelope.correlation_id) {
+                            self.record(&*sink, RemoteEvent::RoundTrip(sent.elapsed()));
+                        }
+                    }
+                }
+                Err(e) if codec_error(e) => self.record(&*sink, RemoteEvent::DecodeError),
+                Err(_) => {}
+            }
+            result
+        })
+    }
+
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        self.inner.flush()
+    }
+
+    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        self.inner.close()
+    }
+
+    fn local_addr(&self) -> &str {
+        self.inner.local_addr()
+    }
+}
+
+/// Transport metering every connection it opens, see `MeteredConnection`
+/// Connecting to an address again, as `RemoteClient` does to recover a lost
+/// connection, is reported as `RemoteEvent::Reconnected`
+#[derive(Clone)]
+pub struct MeteredTransport<T> {
+    inner: T,
+    slot: Arc<MetricsSlot>,
+    ///addresses connected to before
+    connected: Arc<Mutex<HashSet<String>>>,
+}
+
+impl<T: Transport> MeteredTransport<T> {
+    pub fn new(inner: T, system: &ActorSystem) -> Self {
+        Self {
+            inner,
+            slot: system.metrics_slot(),
+            connected: Arc::default(),
+        }
+    }
+}
+
+impl<T: Transport> Transport for MeteredTransport<T> {
+    type Conn = MeteredConnection<T::Conn>;
+
+    fn connect(
+        &self,
+        addr: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Conn, TransportError>> + Send + '_>> {
+        let addr = addr.to_string();
+        Box::pin(async move {
+            let conn = self.inner.connect(&addr).await?;
+            let again = !self
+                .connected
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .insert(addr.clone());
+            if let (true, Some(sink)) = (again, self.slot.sink()) {
+                sink.record_remote(&RemoteMetrics {
+                    peer: &addr,
+                    event: RemoteEvent::Reconnected,
+                });
+            }
+            Ok(MeteredConnection::with_slot(
+                conn,
+                addr,
+                Some(self.slot.clone()),
+            ))
+        })
+    }
+}
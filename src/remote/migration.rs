@@ -0,0 +1,413 @@
+use std::{
+    any::type_name,
+    collections::HashMap,
+    io,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use futures::future::BoxFuture;
+use prost::Message as ProstMessage;
+use tokio::sync::{oneshot, watch};
+
+use crate::{
+    envelope::{ActorMessage, ContinuationEnvelope},
+    persistence::Snapshot,
+    remote::{
+        addr::next_correlation_id,
+        cluster::ClusterNode,
+        proto::{Envelope, Migration},
+        ClusterClient, EnvelopeHandler, TransportError,
+    },
+    Actor, Addr, Context,
+};
+
+/// Reserved message type carrying an actor's state to the node it moves to,
+/// answered with an empty envelope of the same type, or one holding the reason
+/// the node refused it
+pub const MIGRATE: &str = "cinema::migrate";
+
+/// Reserved message type wrapping an envelope the node an actor left passes
+/// on to where it went, and the answer to it
+pub const FORWARD: &str = "cinema::forward";
+
+/// Actor that can move between nodes with `Migrations::migrate`
+pub trait Migratable: Actor + Sized {
+    /// State to carry over, e.g. `Snapshot::new(&self.state)`
+    fn hand_off(&self) -> Snapshot;
+
+    /// Rebuild the actor on the node it moved to
+    fn take_over(snapshot: Snapshot) -> io::Result<Self>;
+
+    /// Name of the type on the wire, which the node it moves to must `accept`
+    /// Defaults to `std::any::type_name`, which may change with the compiler
+    /// or the build; give a fixed one when the nodes aren't built together
+    fn actor_type() -> &'static str {
+        type_name::<Self>()
+    }
+}
+
+///takes the state out of a hosted actor and stops it
+type HandOff = Arc<dyn Fn() -> BoxFuture<'static, Option<Snapshot>> + Send + Sync>;
+
+///spawns a migrated actor under a key, serving it with the handler it returns
+type Restore = Arc<dyn Fn(&str, Snapshot) -> io::Result<Hosted> + Send + Sync>;
+
+#[derive(Clone)]
+struct Hosted {
+    actor_type: &'static str,
+    handler: EnvelopeHandler,
+    hand_off: HandOff,
+}
+
+enum Entry {
+    Hosted(Hosted),
+    ///envelopes wait for the move to finish either way
+    Moving(watch::Receiver<bool>),
+    ///forwarded to the node, until the senders learn of the move by gossip
+    Moved(String),
+}
+
+///where an envelope for a key goes, decided without holding the lock
+enum Route {
+    Local(EnvelopeHandler),
+    Wait(watch::Receiver<bool>),
+    Forward(String),
+    Unknown,
+}
+
+fn hand_off<A: Migratable>(addr: Addr<A>) -> HandOff {
+    Arc::new(move || {
+        let addr = addr.clone();
+        Box::pin(async move {
+            let (tx, rx) = oneshot::channel();
+            //queued behind the mail already delivered, which the state includes
+            let envelope = ContinuationEnvelope {
+                f: move |actor: &mut A, ctx: &mut Context<A>| {
+                    let _ = tx.send(actor.hand_off());
+                    ctx.stop();
+                },
+            };
+            addr.send_envelope(ActorMessage::Sync(Box::new(envelope)))
+                .await
+                .ok()?;
+            rx.await.ok()
+        })
+    })
+}
+
+fn invalid(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> TransportError {
+    TransportError::Io(io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+struct Inner {
+    cluster: Arc<ClusterNode>,
+    client: ClusterClient,
+    entries: Mutex<HashMap<String, Entry>>,
+    ///`Migratable::actor_type` -> how to bring one up here
+    accepted: Mutex<HashMap<&'static str, Restore>>,
+}
+
+///Moves keyed actors between the nodes of a cluster
+/// A node serves its movable actors through `handler`, which wraps the rest
+/// of its routes. `migrate` stops the actor here and restarts it from its
+/// state on another node that `accept`s its type: the cluster registry entry
+/// of its key switches over, envelopes arriving meanwhile wait, and those
+/// reaching this node after the move are forwarded, so senders don't lose
+/// mail while they learn of the new location
+/// Messages sent to the actor through a local `Addr` once it is moving are
+/// not carried over
+#[derive(Clone)]
+pub struct Migrations {
+    inner: Arc<Inner>,
+}
+
+impl Migrations {
+    pub fn new(cluster: Arc<ClusterNode>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                client: ClusterClient::new(cluster.clone()),
+                cluster,
+                entries: Mutex::new(HashMap::new()),
+                accepted: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Take in actors of type `A` moved here, `spawn` starts each restored
+    /// actor under its key and returns it with the handler serving it
+    pub fn accept<A, F>(&self, spawn: F)
+    where
+        A: Migratable,
+        F: Fn(&str, A) -> (Addr<A>, EnvelopeHandler) + Send + Sync + 'static,
+    {
+        let restore: Restore = Arc::new(move |key: &str, snapshot: Snapshot| {
+            let (addr, handler) = spawn(key, A::take_over(snapshot)?);
+            Ok(Hosted {
+                actor_type: A::actor_type(),
+                handler,
+                hand_off: hand_off(addr),
+            })
+        });
+        self.inner.accepted().insert(A::actor_type(), restore);
+    }
+
+    /// Serve the actor at `addr` under `key` through `handler`, and register
+    /// the key cluster-wide like `Receptionist::register`
+    pub async fn host<A: Migratable>(&self, key: &str, addr: Addr<A>, handler: EnvelopeHandler) {
+        let hosted = Hosted {
+            actor_type: A::actor_type(),
+            handler,
+            hand_off: hand_off(addr),
+        };
+        self.inner.host(key, hosted).await;
+    }
+
+    /// Move the actor hosted here under `key` to `node_id`
+    /// It stops once the mail delivered before is handled; if the other node
+    /// can't take it, it is restored here from the same state and the error
+    /// is returned
+    pub async fn migrate(&self, key: &str, node_id: &str) -> Result<(), TransportError> {
+        let members = self.inner.cluster.get_members().await;
+        let node = members.iter().find(|n| n.id == node_id).ok_or_else(|| {
+            TransportError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("node {} not found", node_id),
+            ))
+        })?;
+
+        let (done, moving) = watch::channel(false);
+        let hosted = {
+            let mut entries = self.inner.entries();
+            match entries.get(key) {
+                Some(Entry::Hosted(hosted)) => {
+                    let hosted = hosted.clone();
+                    entries.insert(key.to_string(), Entry::Moving(moving));
+                    hosted
+                }
+                _ => {
+                    return Err(TransportError::Io(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("{} is not hosted here", key),
+                    )))
+                }
+            }
+        };
+
+        let Some(snapshot) = (hosted.hand_off)().await else {
+            //the actor was already gone, nothing to move
+            self.inner.entries().remove(key);
+            let _ = done.send(true);
+            return Err(TransportError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} stopped before it could move", key),
+            )));
+        };
+
+        let migration = Migration {
+            actor_type: hosted.actor_type.to_string(),
+            codec: snapshot.codec,
+            state: snapshot.payload.clone(),
+        };
+        let request = Envelope {
+            message_type: MIGRATE.to_string(),
+            payload: migration.encode_to_vec(),
+            correlation_id: next_correlation_id(),
+            sender_node: self.inner.cluster.local_node.id.clone(),
+            target_actor: key.to_string(),
+            ..Default::default()
+        };
+        let moved = match self.inner.client.send_to_node(node, request).await {
+            Ok(ack) if ack.payload.is_empty() => Ok(()),
+            Ok(refusal) => Err(invalid(
+                String::from_utf8_lossy(&refusal.payload).into_owned(),
+            )),
+            Err(e) => Err(e),
+        };
+
+        match &moved {
+            Ok(()) => {
+                self.inner.cluster.relocate_actor(key, node_id).await;
+                self.inner
+                    .entries()
+                    .insert(key.to_string(), Entry::Moved(node_id.to_string()));
+            }
+            Err(_) => {
+                //back where it was, from the state it left with
+                let restore = self.inner.accepted().get(hosted.actor_type).cloned();
+                let restored = restore.map(|restore| restore(key, snapshot));
+                let mut entries = self.inner.entries();
+                match restored {
+                    Some(Ok(hosted)) => {
+                        entries.insert(key.to_string(), Entry::Hosted(hosted));
+                    }
+                    _ => {
+                        eprintln!("Actor {} could not move nor be restored here", key);
+                        entries.remove(key);
+                    }
+                }
+            }
+        }
+        let _ = done.send(true);
+        moved
+    }
+
+    /// The node hosting `key` as far as this node knows, `None` if it is not
+    /// hosted here and did not move from here
+    pub fn location(&self, key: &str) -> Option<String> {
+        match self.inner.entries().get(key)? {
+            Entry::Hosted(_) | Entry::Moving(_) => Some(self.inner.cluster.local_node.id.clone()),
+            Entry::Moved(node_id) => Some(node_id.clone()),
+        }
+    }
+
+    /// Handler to serve the node with: it takes in migrating actors, sends
+    /// envelopes for hosted keys to their actor, forwards those of keys that
+    /// moved away, and passes everything else to `fallback`
+    pub fn handler(&self, fallback: Option<EnvelopeHandler>) -> EnvelopeHandler {
+        let inner = self.inner.clone();
+        Arc::new(move |envelope: Envelope| {
+            let inner = inner.clone();
+            let fallback = fallback.clone();
+            Box::pin(async move {
+                match envelope.message_type.as_str() {
+                    MIGRATE => Some(inner.take_over(envelope).await),
+                    FORWARD => Some(inner.forwarded(envelope, fallback).await),
+                    _ => inner.dispatch(envelope, fallback).await,
+                }
+            })
+        })
+    }
+}
+
+impl Inner {
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    fn accepted(&self) -> MutexGuard<'_, HashMap<&'static str, Restore>> {
+        self.accepted.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    async fn host(&self, key: &str, hosted: Hosted) {
+        let actor_type = hosted.actor_type;
+        self.entries()
+            .insert(key.to_string(), Entry::Hosted(hosted));
+        self.cluster
+            .register_actor(key.to_string(), actor_type.to_string())
+            .await;
+    }
+
+    fn route(&self, key: &str) -> Route {
+        match self.entries().get(key) {
+            Some(Entry::Hosted(hosted)) => Route::Local(hosted.handler.clone()),
+            Some(Entry::Moving(moving)) => Route::Wait(moving.clone()),
+            Some(Entry::Moved(node_id)) => Route::Forward(node_id.clone()),
+            None => Route::Unknown,
+        }
+    }
+
+    fn dispatch(
+        self: Arc<Self>,
+        envelope: Envelope,
+        fallback: Option<EnvelopeHandler>,
+    ) -> BoxFuture<'static, Option<Envelope>> {
+        Box::pin(async move {
+            loop {
+                match self.route(&envelope.target_actor) {
+                    Route::Local(handler) => return handler(envelope).await,
+                    Route::Wait(mut moving) => {
+                        let _ = moving.wait_for(|done| *done).await;
+                    }
+                    Route::Forward(node_id) => return self.forward(&node_id, envelope).await,
+                    Route::Unknown => return fallback?(envelope).await,
+                }
+            }
+        })
+    }
+
+    ///pass `envelope` on to the node its actor moved to, and its answer back
+    async fn forward(&self, node_id: &str, envelope: Envelope) -> Option<Envelope> {
+        let members = self.cluster.get_members().await;
+        let node = members.iter().find(|n| n.id == node_id)?;
+        let request = Envelope {
+            message_type: FORWARD.to_string(),
+            payload: envelope.encode_to_vec(),
+            correlation_id: next_correlation_id(),
+            sender_node: self.cluster.local_node.id.clone(),
+            target_actor: envelope.target_actor.clone(),
+            trace: envelope.trace.clone(),
+            deadline_ms: envelope.deadline_ms,
+            ..Default::default()
+        };
+        match self.client.send_to_node(node, request).await {
+            Ok(answer) if answer.payload.is_empty() => None,
+            Ok(answer) => Envelope::decode(answer.payload.as_slice()).ok(),
+            Err(e) => {
+                eprintln!(
+                    "Failed to forward {} to {} on {}: {:?}",
+                    envelope.message_type, envelope.target_actor, node_id, e
+                );
+                None
+            }
+        }
+    }
+
+    ///answer a forwarded envelope, always, so the forwarding node stops waiting
+    async fn forwarded(
+        self: Arc<Self>,
+        request: Envelope,
+        fallback: Option<EnvelopeHandler>,
+    ) -> Envelope {
+        let response = match Envelope::decode(request.payload.as_slice()) {
+            Ok(envelope) => self.clone().dispatch(envelope, fallback).await,
+            Err(_) => None,
+        };
+        Envelope {
+            message_type: FORWARD.to_string(),
+            payload: response.map(|r| r.encode_to_vec()).unwrap_or_default(),
+            correlation_id: request.correlation_id,
+            sender_node: self.cluster.local_node.id.clone(),
+            target_actor: request.sender_node,
+            is_response: true,
+            ..Default::default()
+        }
+    }
+
+    ///bring up an actor moving here
+    async fn take_over(&self, request: Envelope) -> Envelope {
+        let refusal = match self.restore(&request) {
+            Ok(hosted) => {
+                self.host(&request.target_actor, hosted).await;
+                String::new()
+            }
+            Err(reason) => reason,
+        };
+        Envelope {
+            message_type: MIGRATE.to_string(),
+            payload: refusal.into_bytes(),
+            correlation_id: request.correlation_id,
+            sender_node: self.cluster.local_node.id.clone(),
+            target_actor: request.sender_node,
+            is_response: true,
+            ..Default::default()
+        }
+    }
+
+    fn restore(&self, request: &Envelope) -> Result<Hosted, String> {
+        let migration = Migration::decode(request.payload.as_slice()).map_err(|e| e.to_string())?;
+        if let Some(Entry::Hosted(_) | Entry::Moving(_)) = self.entries().get(&request.target_actor)
+        {
+            return Err(format!("{} is already hosted here", request.target_actor));
+        }
+        let restore = self
+            .accepted()
+            .get(migration.actor_type.as_str())
+            .cloned()
+            .ok_or_else(|| format!("{} is not accepted here", migration.actor_type))?;
+        let snapshot = Snapshot {
+            codec: migration.codec,
+            payload: migration.state,
+        };
+        restore(&request.target_actor, snapshot).map_err(|e| e.to_string())
+    }
+}
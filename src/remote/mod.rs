@@ -1,20 +1,83 @@
+mod actor_ref;
 mod addr;
+mod chunk;
 mod client;
 pub mod cluster;
 mod cluster_client;
+mod cluster_router;
+mod compression;
+mod datagram;
+mod deadline;
+mod delivery;
+mod failure;
+mod failure_detector;
+#[cfg(feature = "grpc")]
+mod grpc;
 mod handler;
+mod handshake;
+mod heartbeat;
+mod metered;
+mod migration;
+mod pool;
+mod pubsub;
+#[cfg(feature = "quic")]
+mod quic;
+mod receptionist;
 mod registry;
+mod serializer;
 mod server;
+mod swim;
 mod tcp;
+#[cfg(feature = "tls")]
+mod tls;
+mod trace;
 mod transport;
 
+pub use actor_ref::{ActorRef, SendError};
 pub use addr::{NodeId, RemoteActorId, RemoteAddr};
-pub use client::RemoteClient;
+pub use chunk::{ChunkConfig, ChunkedConnection, ChunkedTransport};
+pub use client::{CoalesceConfig, ReconnectPolicy, RemoteClient};
 pub use cluster_client::{ClusterClient, ClusterRemoteAddr};
-pub use handler::{make_handler, make_tell_handler, LocalNode, MessageRouter};
-pub use registry::{deserialize_payload, register_message};
+pub use cluster_router::{ClusterRouter, Routing};
+pub use compression::{CompressedConnection, Compression, CompressionConfig};
+pub use datagram::MAX_DATAGRAM;
+pub use deadline::DEADLINE_EXCEEDED;
+pub use delivery::{DeliveryConfig, ReliableAddr, ACK};
+pub use failure::{DECODE_FAILED, HANDLER_FAILED, UNKNOWN_MESSAGE_TYPE};
+pub use failure_detector::{
+    FailureDetection, FailureDetector, PhiAccrualConfig, Verdict,
+};
+#[cfg(feature = "grpc")]
+pub use grpc::{GrpcConnection, GrpcTransport};
+pub use handler::{
+    make_handler, make_tell_handler, unhandled_remote_messages, LocalNode, MessageRouter,
+};
+pub(crate) use handler::ExposedActors;
+pub use handshake::{
+    handshake, HandshakeConfig, HandshakeTransport, HANDSHAKE, PROTOCOL_VERSION,
+};
+pub use heartbeat::{HeartbeatConfig, HeartbeatConnection, HeartbeatTransport, HEARTBEAT};
+pub use metered::{MeteredConnection, MeteredTransport};
+pub use migration::{Migratable, Migrations, FORWARD, MIGRATE};
+pub use pool::ConnectionManager;
+pub use pubsub::DistributedPubSub;
+#[cfg(feature = "quic")]
+pub use quic::{QuicConnection, QuicTransport};
+pub use receptionist::Receptionist;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub use registry::AutoRegistration;
+pub use registry::{deserialize_envelope, deserialize_payload, register_message};
+#[cfg(feature = "serde")]
+pub use serializer::{Bincode, Json};
+pub use serializer::{codec, Prost, Serializer, WireFormat};
 pub use server::{EnvelopeHandler, RemoteServer};
-pub use tcp::{EnvelopeCodec, TcpConnection, TcpTransport};
+pub use swim::SwimConfig;
+pub use tcp::{EnvelopeCodec, TcpConnection, TcpTransport, DEFAULT_MAX_FRAME_SIZE};
+#[cfg(feature = "tls")]
+pub use tls::{TlsConnection, TlsServerConfig, TlsTransport};
+#[cfg(feature = "otel")]
+pub use trace::trace_span;
 pub use transport::{Connection, Transport, TransportError};
 
 use bytes::{Bytes, BytesMut};
@@ -26,36 +89,58 @@ pub mod proto {
     include!(concat!(env!("OUT_DIR"), "/cinema.rs"));
 }
 
+/// Implement `RemoteMessage` and register the type for deserialization at
+/// link time, no `register_message` call needed
+#[cfg(feature = "derive")]
+pub use cinema_derive::RemoteMessage;
+
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use inventory;
+}
+
 /// Trait for remote messages (can be sent over the network).
 /// To be remotable, a message must implement this trait.
 /// The type_id is auto-derived from Rust's type name.
-pub trait RemoteMessage: Message + ProstMessage + Default + Clone {
+/// Protobuf messages are encoded with prost; other types choose a
+/// serializer through `WireFormat`.
+pub trait RemoteMessage: Message + WireFormat + Clone {
     fn type_id() -> &'static str {
         std::any::type_name::<Self>()
     }
 }
 
 impl Envelope {
-    ///create an envelope from a remote message
+    ///create an envelope from a remote message, carrying the trace context of
+    /// the current span with the otel feature
     pub fn from_message<M: RemoteMessage>(
         msg: &M,
         correlation_id: u64,
         sender_node: &str,
         target_actor: &str,
     ) -> Self {
-        let mut payload = BytesMut::new();
-        msg.encode(&mut payload).expect("encode failed");
-
         Envelope {
             message_type: M::type_id().to_string(),
-            payload: payload.to_vec(),
+            payload: <M::Serializer as Serializer<M>>::encode(msg),
             correlation_id,
             sender_node: sender_node.to_string(),
             target_actor: target_actor.to_string(),
             is_response: false,
+            codec: <M::Serializer as Serializer<M>>::CODEC,
+            compression: 0,
+            chunk: None,
+            delivery_id: 0,
+            trace: trace::current(),
+            deadline_ms: 0,
         }
     }
 
+    ///decode the payload as `M`, honoring the codec the sender used
+    pub fn message<M: RemoteMessage>(&self) -> Result<M, prost::DecodeError> {
+        <M::Serializer as Serializer<M>>::decode_as(self.codec, &self.payload)
+    }
+
     ///serialize the envelope to bytes
     pub fn to_bytes(&self) -> Bytes {
         let mut buf = BytesMut::new();
@@ -0,0 +1,141 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::remote::{
+    proto::Envelope, ReconnectPolicy, RemoteAddr, RemoteClient, TcpTransport, Transport,
+    TransportError,
+};
+
+///Shared connections to peer nodes
+/// Keeps up to `connections_per_peer` reconnecting clients per node address,
+/// dialed on first use and reused by every caller. Each client multiplexes
+/// requests by correlation id, so concurrent asks to a node share its
+/// connections instead of each opening (and waiting on) its own
+pub struct ConnectionManager<T = TcpTransport> {
+    transport: T,
+    peers: Arc<Mutex<HashMap<String, Arc<Peer>>>>,
+    per_peer: usize,
+    policy: ReconnectPolicy,
+    next: Arc<AtomicUsize>,
+}
+
+impl<T: Clone> Clone for ConnectionManager<T> {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            peers: self.peers.clone(),
+            per_peer: self.per_peer,
+            policy: self.policy,
+            next: self.next.clone(),
+        }
+    }
+}
+
+impl Default for ConnectionManager<TcpTransport> {
+    fn default() -> Self {
+        Self::new(TcpTransport)
+    }
+}
+
+impl<T> ConnectionManager<T>
+where
+    T: Transport + Clone + 'static,
+{
+    ///one connection per peer, redialed with the default `ReconnectPolicy`
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            peers: Arc::new(Mutex::new(HashMap::new())),
+            per_peer: 1,
+            policy: ReconnectPolicy::default(),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///spread requests to each peer over up to `n` connections, opened as needed
+    pub fn connections_per_peer(mut self, n: usize) -> Self {
+        self.per_peer = n.max(1);
+        self
+    }
+
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    ///a client for `node_addr`, dialing a new connection while the peer has
+    /// fewer than `connections_per_peer`, otherwise taking turns over them
+    /// A dial only holds up requests to the same peer
+    pub async fn client(&self, node_addr: &str) -> Result<RemoteClient, TransportError> {
+        let peer = self.peer(node_addr);
+        let mut clients = peer.clients.lock().await;
+
+        if clients.len() < self.per_peer {
+            let client =
+                RemoteClient::connect_with_reconnect(self.transport.clone(), node_addr, self.policy)
+                    .await?;
+            clients.push(client.clone());
+            return Ok(client);
+        }
+
+        let turn = self.next.fetch_add(1, Ordering::Relaxed);
+        Ok(clients[turn % clients.len()].clone())
+    }
+
+    /// Send and wait for the response over a pooled connection
+    pub async fn send(&self, node_addr: &str, envelope: Envelope) -> Result<Envelope, TransportError> {
+        self.client(node_addr).await?.send(envelope).await
+    }
+
+    /// Fire-and-forget send over a pooled connection
+    pub async fn do_send(&self, node_addr: &str, envelope: Envelope) -> Result<(), TransportError> {
+        self.client(node_addr).await?.do_send(envelope).await
+    }
+
+    ///typed address of `actor_name` on the node at `node_addr`
+    pub async fn remote_addr<A>(
+        &self,
+        node_addr: &str,
+        actor_name: &str,
+    ) -> Result<RemoteAddr<A>, TransportError> {
+        Ok(self.client(node_addr).await?.remote_addr(node_addr, actor_name))
+    }
+
+    /// Connections currently open to `node_addr`
+    pub async fn connections(&self, node_addr: &str) -> usize {
+        let peer = self.lock_peers().get(node_addr).cloned();
+        match peer {
+            Some(peer) => peer.clients.lock().await.len(),
+            None => 0,
+        }
+    }
+
+    ///forget the peer's connections, the next request dials afresh
+    /// (they close once the last clone of their clients is dropped)
+    pub async fn remove(&self, node_addr: &str) {
+        self.lock_peers().remove(node_addr);
+    }
+
+    fn peer(&self, node_addr: &str) -> Arc<Peer> {
+        self.lock_peers()
+            .entry(node_addr.to_string())
+            .or_default()
+            .clone()
+    }
+
+    fn lock_peers(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<Peer>>> {
+        self.peers.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+///one node's connections, locked on their own so dialing a slow peer
+/// doesn't hold up requests to the others
+#[derive(Default)]
+struct Peer {
+    clients: tokio::sync::Mutex<Vec<RemoteClient>>,
+}
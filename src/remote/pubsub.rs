@@ -0,0 +1,222 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::join_all;
+use prost::Message;
+
+use crate::{
+    actor::ActorId,
+    message::Terminated,
+    remote::{
+        cluster::ClusterNode,
+        codec,
+        proto::{cluster_message, ClusterMessage, Envelope, NodeTopics, Publish},
+        ConnectionManager, HandshakeTransport, RemoteMessage, TcpTransport,
+    },
+    watcher::Watcher,
+    Actor, Addr, Handler,
+};
+
+///hands a published envelope to one subscriber
+type Deliver = Arc<dyn Fn(&Envelope) + Send + Sync>;
+
+struct Subscription {
+    actor: ActorId,
+    deliver: Deliver,
+}
+
+#[derive(Default)]
+struct State {
+    topics: HashMap<String, Vec<Subscription>>,
+    ///raised whenever the set of topics changes, peers keep the newest list
+    version: u64,
+}
+
+impl State {
+    fn bump(&mut self) {
+        //wall clock based, so a restarted node still outranks what peers remember
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_micros() as u64);
+        self.version = now.max(self.version + 1);
+    }
+}
+
+///This node's pub-sub subscribers, by topic
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    state: Mutex<State>,
+}
+
+impl Subscribers {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    ///false if the actor already was subscribed to the topic
+    fn add(&self, topic: &str, actor: ActorId, deliver: Deliver) -> bool {
+        let mut state = self.state();
+        let subscribers = state.topics.entry(topic.to_string()).or_default();
+        if subscribers.iter().any(|sub| sub.actor == actor) {
+            return false;
+        }
+        subscribers.push(Subscription { actor, deliver });
+        if subscribers.len() == 1 {
+            state.bump();
+        }
+        true
+    }
+
+    fn remove(&self, topic: &str, actor: ActorId) -> bool {
+        let mut state = self.state();
+        let Some(subscribers) = state.topics.get_mut(topic) else {
+            return false;
+        };
+        let before = subscribers.len();
+        subscribers.retain(|sub| sub.actor != actor);
+        let removed = subscribers.len() != before;
+        if subscribers.is_empty() {
+            state.topics.remove(topic);
+            state.bump();
+        }
+        removed
+    }
+
+    ///hand `envelope` to the topic's local subscribers, returns how many there were
+    pub(crate) fn deliver(&self, topic: &str, envelope: &Envelope) -> usize {
+        //deliver outside the lock, a subscriber may be subscribing right now
+        let subscribers: Vec<Deliver> = match self.state().topics.get(topic) {
+            Some(subscribers) => subscribers.iter().map(|sub| sub.deliver.clone()).collect(),
+            None => return 0,
+        };
+        for deliver in &subscribers {
+            deliver(envelope);
+        }
+        subscribers.len()
+    }
+
+    ///the topics this node has subscribers for, as gossiped to peers
+    pub(crate) fn snapshot(&self, node_id: &str) -> NodeTopics {
+        let state = self.state();
+        let mut topics: Vec<String> = state.topics.keys().cloned().collect();
+        topics.sort();
+        NodeTopics {
+            node_id: node_id.to_string(),
+            version: state.version,
+            topics,
+        }
+    }
+}
+
+///Publish/subscribe by topic across every node of the cluster
+/// Like `EventBus`, but keyed by topic name and reaching subscribers on other
+/// nodes: `publish` delivers to the local subscribers and sends the message
+/// once to every member with subscribers for the topic, which hands it to
+/// theirs. Which node subscribes to what spreads with the membership gossip,
+/// members that go down are forgotten. Subscribers are dropped automatically
+/// when their actor stops. Delivery is at most once
+pub struct DistributedPubSub {
+    cluster: Arc<ClusterNode>,
+    pool: ConnectionManager<HandshakeTransport<TcpTransport>>,
+}
+
+impl DistributedPubSub {
+    pub fn new(cluster: Arc<ClusterNode>) -> Self {
+        let pool = ConnectionManager::new(cluster.transport());
+        Self { cluster, pool }
+    }
+
+    /// Deliver every `M` published to `topic`, on any node, to `addr`
+    /// Messages of other types published to the topic are skipped
+    pub fn subscribe<M, A>(&self, topic: &str, addr: &Addr<A>)
+    where
+        M: RemoteMessage,
+        A: Actor + Handler<M>,
+    {
+        let target = addr.uncounted();
+        let deliver: Deliver = Arc::new(move |envelope: &Envelope| {
+            if envelope.message_type != M::type_id() {
+                return;
+            }
+            match envelope.message::<M>() {
+                Ok(msg) => {
+                    let _ = target.try_send(msg);
+                }
+                Err(e) => eprintln!("Failed to decode {}: {}", envelope.message_type, e),
+            }
+        });
+
+        let subscribers = self.cluster.subscribers();
+        if !subscribers.add(topic, addr.id(), deliver) {
+            return;
+        }
+        addr.add_watcher_erased(Arc::new(Unsubscriber {
+            subscribers: Arc::downgrade(subscribers),
+            topic: topic.to_string(),
+            actor: addr.id(),
+        }));
+    }
+
+    /// Stop delivering `topic` to `addr`, false if it wasn't subscribed
+    pub fn unsubscribe<A: Actor>(&self, topic: &str, addr: &Addr<A>) -> bool {
+        self.cluster.subscribers().remove(topic, addr.id())
+    }
+
+    /// Send `msg` to every subscriber of `topic` in the cluster
+    /// Returns the local subscribers plus the nodes it was sent to; a node
+    /// that can't be reached is skipped (and logged)
+    pub async fn publish<M: RemoteMessage>(&self, topic: &str, msg: M) -> usize {
+        let local_node = &self.cluster.local_node.id;
+        let message = Envelope::from_message(&msg, 0, local_node, "");
+        let local = self.cluster.subscribers().deliver(topic, &message);
+
+        let publish = ClusterMessage {
+            payload: Some(cluster_message::Payload::Publish(Publish {
+                topic: topic.to_string(),
+                message: Some(message),
+            })),
+        };
+        let envelope = Envelope {
+            message_type: "cluster".to_string(),
+            payload: publish.encode_to_vec(),
+            correlation_id: 0,
+            sender_node: local_node.clone(),
+            target_actor: "".to_string(),
+            is_response: false,
+            codec: codec::PROST,
+            ..Default::default()
+        };
+
+        let nodes = self.cluster.subscribed_nodes(topic).await;
+        let sends = nodes.iter().map(|node| async {
+            match self.pool.do_send(&node.addr, envelope.clone()).await {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("Failed to publish {} to {}: {:?}", topic, node.id, e);
+                    self.pool.remove(&node.addr).await;
+                    false
+                }
+            }
+        });
+        let remote = join_all(sends).await.into_iter().filter(|&sent| sent).count();
+        local + remote
+    }
+}
+
+///drops a subscription when its actor stops
+struct Unsubscriber {
+    subscribers: Weak<Subscribers>,
+    topic: String,
+    actor: ActorId,
+}
+
+impl Watcher for Unsubscriber {
+    fn notify(&self, _msg: Terminated) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers.remove(&self.topic, self.actor);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    future::Future,
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::future::try_join_all;
+use quinn::{
+    crypto::rustls::{QuicClientConfig, QuicServerConfig},
+    Endpoint, RecvStream, SendStream,
+};
+use tokio::sync::mpsc;
+
+use crate::remote::{
+    handshake::{self, is_handshake},
+    heartbeat::{is_heartbeat, pong},
+    proto::Envelope,
+    tls::invalid,
+    transport::{Connection, Transport, TransportError},
+    EnvelopeHandler, TlsServerConfig, TlsTransport,
+};
+
+///largest envelope read from a single stream
+const MAX_ENVELOPE: usize = 64 * 1024 * 1024;
+
+///pings sent on a quiet connection so the idle timeout doesn't close it
+const KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+fn quic_error(err: impl std::fmt::Display) -> TransportError {
+    TransportError::Io(io::Error::other(err.to_string()))
+}
+
+///write one envelope as the whole of a stream's send side
+async fn write_envelope(send: &mut SendStream, envelope: &Envelope) -> Result<(), TransportError> {
+    send.write_all(&envelope.to_bytes()).await.map_err(io::Error::from)?;
+    send.finish().map_err(quic_error)?;
+    Ok(())
+}
+
+///read the envelope filling a stream's receive side, `None` if it finished empty
+async fn read_envelope(recv: &mut RecvStream) -> Result<Option<Envelope>, TransportError> {
+    let bytes = recv.read_to_end(MAX_ENVELOPE).await.map_err(quic_error)?;
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Envelope::from_bytes(&bytes)?))
+}
+
+///QUIC connection, every envelope sent gets its own bidirectional stream
+/// and the answer comes back on that stream, so a large payload only holds
+/// up its own request instead of everything queued behind it
+pub struct QuicConnection {
+    //kept alive for as long as the connection is used
+    _endpoint: Endpoint,
+    conn: quinn::Connection,
+    responses_tx: mpsc::UnboundedSender<Result<Envelope, TransportError>>,
+    responses: mpsc::UnboundedReceiver<Result<Envelope, TransportError>>,
+    local_addr: String,
+}
+
+impl QuicConnection {
+    fn new(endpoint: Endpoint, conn: quinn::Connection) -> Self {
+        let local_addr = endpoint
+            .local_addr()
+            .map(|a| a.to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+        let (responses_tx, responses) = mpsc::unbounded_channel();
+        QuicConnection {
+            _endpoint: endpoint,
+            conn,
+            responses_tx,
+            responses,
+            local_addr,
+        }
+    }
+
+    /// Get the local socket address as a string
+    pub fn local_addr(&self) -> &str {
+        &self.local_addr
+    }
+
+    ///open a stream for `envelope` and collect whatever is answered on it
+    async fn request(&self, envelope: Envelope) -> Result<(), TransportError> {
+        let (mut send, mut recv) = self.conn.open_bi().await.map_err(io::Error::from)?;
+        write_envelope(&mut send, &envelope).await?;
+
+        let responses = self.responses_tx.clone();
+        tokio::spawn(async move {
+            match read_envelope(&mut recv).await {
+                Ok(Some(response)) => {
+                    let _ = responses.send(Ok(response));
+                }
+                //tells are finished without an answer
+                Ok(None) => {}
+                Err(e) => {
+                    let _ = responses.send(Err(e));
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl Connection for QuicConnection {
+    fn send(
+        &mut self,
+        envelope: Envelope,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(self.request(envelope))
+    }
+
+    fn send_batch(
+        &mut self,
+        envelopes: Vec<Envelope>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            //streams are written side by side rather than one after another
+            let this = &*self;
+            try_join_all(envelopes.into_iter().map(|envelope| this.request(envelope))).await?;
+            Ok(())
+        })
+    }
+
+    fn recv(&mut self) -> Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            tokio::select! {
+                //the sender half lives in self, so the channel never closes
+                Some(response) = self.responses.recv() => response,
+                _ = self.conn.closed() => Err(TransportError::Disconnected),
+            }
+        })
+    }
+
+    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            self.conn.close(0u32.into(), b"closed");
+            Ok(())
+        })
+    }
+
+    fn local_addr(&self) -> &str {
+        &self.local_addr
+    }
+}
+
+/// Client side of QUIC remote connections
+/// QUIC always runs over TLS 1.3, so certificates, SNI and client identity
+/// come from the wrapped `TlsTransport`. Every connection (clones of the
+/// transport included) goes out through one local endpoint per address
+/// family, bound on the first connect
+#[derive(Clone)]
+pub struct QuicTransport {
+    tls: TlsTransport,
+    config: quinn::ClientConfig,
+    ///client endpoints for IPv4 and IPv6 peers
+    endpoints: Arc<Mutex<[Option<Endpoint>; 2]>>,
+}
+
+impl QuicTransport {
+    /// Connect with the certificates and server name configured on `tls`
+    pub fn new(tls: TlsTransport) -> Result<Self, TransportError> {
+        let crypto = QuicClientConfig::try_from(tls.config.clone()).map_err(invalid)?;
+        let mut transport = quinn::TransportConfig::default();
+        transport.keep_alive_interval(Some(KEEP_ALIVE));
+        let mut config = quinn::ClientConfig::new(Arc::new(crypto));
+        config.transport_config(Arc::new(transport));
+        Ok(Self {
+            tls,
+            config,
+            endpoints: Arc::new(Mutex::new([None, None])),
+        })
+    }
+
+    ///the endpoint to reach `remote` from, bound the first time it's needed
+    fn endpoint(&self, remote: SocketAddr) -> Result<Endpoint, TransportError> {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|p| p.into_inner());
+        let slot = &mut endpoints[remote.is_ipv6() as usize];
+        if let Some(endpoint) = slot {
+            return Ok(endpoint.clone());
+        }
+        let bind: SocketAddr = if remote.is_ipv6() {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        };
+        let mut endpoint = Endpoint::client(bind)?;
+        endpoint.set_default_client_config(self.config.clone());
+        *slot = Some(endpoint.clone());
+        Ok(endpoint)
+    }
+}
+
+impl Transport for QuicTransport {
+    type Conn = QuicConnection;
+
+    fn connect(
+        &self,
+        addr: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Conn, TransportError>> + Send + '_>> {
+        let addr = addr.to_string();
+        Box::pin(async move {
+            let name = self.tls.name_for(&addr)?;
+            let remote = tokio::net::lookup_host(&addr)
+                .await?
+                .next()
+                .ok_or_else(|| invalid(format!("{} did not resolve", addr)))?;
+
+            let endpoint = self.endpoint(remote)?;
+            let conn = endpoint
+                .connect(remote, &name.to_str())
+                .map_err(quic_error)?
+                .await
+                .map_err(io::Error::from)?;
+            Ok(QuicConnection::new(endpoint, conn))
+        })
+    }
+}
+
+///server endpoint for `RemoteServer::bind_quic`
+pub(super) fn server_endpoint(addr: SocketAddr, tls: &TlsServerConfig) -> io::Result<Endpoint> {
+    let crypto = QuicServerConfig::try_from(tls.config.clone())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+    let config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+    Endpoint::server(config, addr)
+}
+
+///accept connections and answer each stream on its own task
+pub(super) async fn run(endpoint: Endpoint, handler: EnvelopeHandler) {
+    while let Some(incoming) = endpoint.accept().await {
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            let conn = match incoming.await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    eprintln!("QUIC handshake failed: {:?}", e);
+                    return;
+                }
+            };
+            println!("Accepted connection from {:?}", conn.remote_address());
+
+            //loop until conn closed
+            while let Ok((mut send, mut recv)) = conn.accept_bi().await {
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let envelope = match read_envelope(&mut recv).await {
+                        Ok(Some(envelope)) => envelope,
+                        Ok(None) => return,
+                        Err(e) => {
+                            eprintln!("Failed to read envelope: {:?}", e);
+                            return;
+                        }
+                    };
+                    if is_handshake(&envelope) {
+                        let (ack, _) = handshake::answer(None, &envelope);
+                        if let Err(e) = write_envelope(&mut send, &ack).await {
+                            eprintln!("Failed to send response: {:?}", e);
+                        }
+                        return;
+                    }
+                    if is_heartbeat(&envelope) {
+                        if let Err(e) = write_envelope(&mut send, &pong()).await {
+                            eprintln!("Failed to send response: {:?}", e);
+                        }
+                        return;
+                    }
+                    let result = match (handler)(envelope).await {
+                        Some(response) => write_envelope(&mut send, &response).await,
+                        None => send.finish().map_err(quic_error),
+                    };
+                    if let Err(e) = result {
+                        eprintln!("Failed to send response: {:?}", e);
+                    }
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,75 @@
+use std::{any::type_name, sync::Arc};
+
+use crate::{
+    remote::{
+        cluster::ClusterNode, ClusterClient, ClusterRemoteAddr, EnvelopeHandler, ExposedActors,
+        RemoteMessage,
+    },
+    Actor, Addr, Handler,
+};
+
+///Cluster-wide service keys for actors
+/// An actor registered under a key can be found from any node: the
+/// registration goes into the cluster's actor registry and spreads with the
+/// gossip. Messages addressed to the key reach the actor through `handler`,
+/// which the hosting node's server must serve. Registering a key on another
+/// node moves it there, the latest registration wins everywhere;
+/// registrations of a node that goes down are dropped
+pub struct Receptionist {
+    cluster: Arc<ClusterNode>,
+    client: ClusterClient,
+    ///routes to the actors registered on this node
+    local: Arc<ExposedActors>,
+}
+
+impl Receptionist {
+    pub fn new(cluster: Arc<ClusterNode>) -> Self {
+        Self {
+            client: ClusterClient::new(cluster.clone()),
+            cluster,
+            local: Arc::new(ExposedActors::default()),
+        }
+    }
+
+    /// Register the actor at `addr` under `key` for messages of type `M`,
+    /// taking the key over from wherever it was. The type `A` is recorded for
+    /// `lookup_remote` to check. Register once per message type the actor
+    /// should answer
+    pub async fn register<M, A>(&self, key: &str, addr: &Addr<A>)
+    where
+        A: Actor + Handler<M>,
+        M: RemoteMessage,
+        M::Result: RemoteMessage,
+    {
+        self.local
+            .expose::<A, M>(key, addr.clone(), &self.cluster.local_node.id);
+        self.cluster
+            .register_actor(key.to_string(), type_name::<A>().to_string())
+            .await;
+    }
+
+    /// Handler for this node's server, routing messages to the actors
+    /// registered here: `cluster.serve(Some(receptionist.handler()))`, or
+    /// `MessageRouter::default` behind routes of its own
+    pub fn handler(&self) -> EnvelopeHandler {
+        self.local.handler()
+    }
+
+    /// The node hosting `key`, if it is registered
+    pub async fn host(&self, key: &str) -> Option<String> {
+        let (node_id, _) = self.cluster.lookup_actor(key).await?;
+        Some(node_id)
+    }
+
+    /// Address of the actor registered under `key`, `None` if the key is
+    /// unknown or registered by an actor other than `A`
+    /// The address looks the key up again on every send, so it follows the
+    /// key when it moves to another node
+    pub async fn lookup_remote<A: Actor>(&self, key: &str) -> Option<ClusterRemoteAddr<A>> {
+        let (_, actor_type) = self.cluster.lookup_actor(key).await?;
+        if actor_type != type_name::<A>() {
+            return None;
+        }
+        Some(self.client.remote_addr(key))
+    }
+}
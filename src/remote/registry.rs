@@ -1,13 +1,45 @@
+#[cfg(feature = "derive")]
+use std::sync::Once;
 use std::{any::Any, collections::HashMap, sync::RwLock};
 
-use crate::remote::RemoteMessage;
+use crate::remote::{proto::Envelope, RemoteMessage, Serializer};
 
-type DeserializeFn = fn(&[u8]) -> Result<Box<dyn Any + Send>, prost::DecodeError>;
+///(codec, payload) -> message
+type DeserializeFn = fn(u32, &[u8]) -> Result<Box<dyn Any + Send>, prost::DecodeError>;
 
 ///Global registry for remote message types
-static REGISTRY: RwLock<Option<HashMap<String, DeserializeFn>>> = RwLock::new(None);
+///type id -> (codec the type is written in, deserializer)
+static REGISTRY: RwLock<Option<HashMap<String, (u32, DeserializeFn)>>> = RwLock::new(None);
+
+///A message type to register on first use of the registry, submitted by
+/// `#[derive(RemoteMessage)]` through `inventory` so it is collected at link time
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub struct AutoRegistration {
+    pub register: fn(),
+}
+
+#[cfg(feature = "derive")]
+inventory::collect!(AutoRegistration);
+
+#[cfg(feature = "derive")]
+static AUTO_REGISTERED: Once = Once::new();
+
+///register every type that derives `RemoteMessage`, once
+#[cfg(feature = "derive")]
+fn register_derived() {
+    AUTO_REGISTERED.call_once(|| {
+        for registration in inventory::iter::<AutoRegistration> {
+            (registration.register)();
+        }
+    });
+}
+
+#[cfg(not(feature = "derive"))]
+fn register_derived() {}
 
 ///register a remote message type for deserialization
+/// Types deriving `RemoteMessage` are registered automatically
 pub fn register_message<M: RemoteMessage + 'static>() {
     let mut registry = match REGISTRY.write() {
         Ok(guard) => guard,
@@ -16,17 +48,36 @@ pub fn register_message<M: RemoteMessage + 'static>() {
 
     let map = registry.get_or_insert_with(HashMap::new);
 
-    map.insert(M::type_id().to_string(), |bytes| {
-        let msg = M::decode(bytes)?;
+    let deserialize: DeserializeFn = |codec, bytes| {
+        let msg = <M::Serializer as Serializer<M>>::decode_as(codec, bytes)?;
         Ok(Box::new(msg))
-    });
+    };
+    map.insert(
+        M::type_id().to_string(),
+        (<M::Serializer as Serializer<M>>::CODEC, deserialize),
+    );
 }
 
 ///deserialize a payload into a remote message
+/// the payload is taken to be in the message type's own codec
 pub fn deserialize_payload(
     type_id: &str,
     payload: &[u8],
 ) -> Result<Box<dyn Any + Send>, prost::DecodeError> {
+    deserialize(type_id, None, payload)
+}
+
+///deserialize an envelope's payload with the codec it was sent in
+pub fn deserialize_envelope(envelope: &Envelope) -> Result<Box<dyn Any + Send>, prost::DecodeError> {
+    deserialize(&envelope.message_type, Some(envelope.codec), &envelope.payload)
+}
+
+fn deserialize(
+    type_id: &str,
+    codec: Option<u32>,
+    payload: &[u8],
+) -> Result<Box<dyn Any + Send>, prost::DecodeError> {
+    register_derived();
     let registry = match REGISTRY.read() {
         Ok(guard) => guard,
         Err(poisoned) => poisoned.into_inner(),
@@ -36,9 +87,9 @@ pub fn deserialize_payload(
         .as_ref()
         .ok_or_else(|| prost::DecodeError::new("No messages registered"))?;
 
-    let deserialize_fn = map
+    let (native, deserialize_fn) = map
         .get(type_id)
         .ok_or_else(|| prost::DecodeError::new("Unknown message type"))?;
 
-    deserialize_fn(payload)
+    deserialize_fn(codec.unwrap_or(*native), payload)
 }
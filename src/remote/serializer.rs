@@ -0,0 +1,127 @@
+use prost::{DecodeError, Message as ProstMessage};
+
+/// Ids of the built-in serializers, carried in `Envelope::codec`
+pub mod codec {
+    /// Protocol Buffers via prost, also what envelopes without a codec decode as
+    pub const PROST: u32 = 0;
+    /// serde + bincode
+    pub const BINCODE: u32 = 1;
+    /// serde + JSON
+    pub const JSON: u32 = 2;
+}
+
+///Turns a remote message into payload bytes and back
+/// `CODEC` is written into each envelope so the receiving node decodes the
+/// payload the way it was encoded
+pub trait Serializer<M> {
+    const CODEC: u32;
+
+    fn encode(msg: &M) -> Vec<u8>;
+
+    fn decode(bytes: &[u8]) -> Result<M, DecodeError>;
+
+    ///decode a payload another node wrote with `codec`
+    /// only the serializer's own codec is understood unless it says otherwise
+    fn decode_as(codec: u32, bytes: &[u8]) -> Result<M, DecodeError> {
+        if codec == Self::CODEC {
+            Self::decode(bytes)
+        } else {
+            Err(unsupported(codec))
+        }
+    }
+}
+
+fn unsupported(codec: u32) -> DecodeError {
+    DecodeError::new(format!("payload codec {} is not supported for this message", codec))
+}
+
+///How a message type goes over the wire
+/// Protobuf messages get `Prost` automatically; other types pick a serializer:
+///
+/// ```ignore
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Deposit { amount: u64 }
+///
+/// impl WireFormat for Deposit { type Serializer = Bincode; }
+/// impl RemoteMessage for Deposit {}
+/// ```
+pub trait WireFormat: Sized {
+    type Serializer: Serializer<Self>;
+}
+
+impl<M: ProstMessage + Default> WireFormat for M {
+    type Serializer = Prost;
+}
+
+/// Protocol Buffers, the default for prost messages
+pub struct Prost;
+
+impl<M: ProstMessage + Default> Serializer<M> for Prost {
+    const CODEC: u32 = codec::PROST;
+
+    fn encode(msg: &M) -> Vec<u8> {
+        msg.encode_to_vec()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<M, DecodeError> {
+        M::decode(bytes)
+    }
+}
+
+/// Compact binary serde encoding
+/// Reads JSON payloads too, so nodes can move between the two one at a time
+#[cfg(feature = "serde")]
+pub struct Bincode;
+
+#[cfg(feature = "serde")]
+impl<M> Serializer<M> for Bincode
+where
+    M: serde::Serialize + serde::de::DeserializeOwned,
+{
+    const CODEC: u32 = codec::BINCODE;
+
+    fn encode(msg: &M) -> Vec<u8> {
+        bincode::serialize(msg).expect("encode failed")
+    }
+
+    fn decode(bytes: &[u8]) -> Result<M, DecodeError> {
+        bincode::deserialize(bytes).map_err(|e| DecodeError::new(e.to_string()))
+    }
+
+    fn decode_as(codec: u32, bytes: &[u8]) -> Result<M, DecodeError> {
+        match codec {
+            codec::BINCODE => Self::decode(bytes),
+            codec::JSON => <Json as Serializer<M>>::decode(bytes),
+            other => Err(unsupported(other)),
+        }
+    }
+}
+
+/// Human-readable serde encoding
+/// Reads bincode payloads too, see `Bincode`
+#[cfg(feature = "serde")]
+pub struct Json;
+
+#[cfg(feature = "serde")]
+impl<M> Serializer<M> for Json
+where
+    M: serde::Serialize + serde::de::DeserializeOwned,
+{
+    const CODEC: u32 = codec::JSON;
+
+    fn encode(msg: &M) -> Vec<u8> {
+        serde_json::to_vec(msg).expect("encode failed")
+    }
+
+    fn decode(bytes: &[u8]) -> Result<M, DecodeError> {
+        serde_json::from_slice(bytes).map_err(|e| DecodeError::new(e.to_string()))
+    }
+
+    fn decode_as(codec: u32, bytes: &[u8]) -> Result<M, DecodeError> {
+        match codec {
+            codec::JSON => Self::decode(bytes),
+            codec::BINCODE => <Bincode as Serializer<M>>::decode(bytes),
+            other => Err(unsupported(other)),
+        }
+    }
+}
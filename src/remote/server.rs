@@ -1,61 +1,300 @@
 use std::{future::Future, io, pin::Pin, sync::Arc};
 
+use futures::{stream::FuturesUnordered, StreamExt};
 use tokio::net::TcpListener;
 
-use crate::remote::{proto::Envelope, Connection, TcpConnection};
+#[cfg(feature = "grpc")]
+use crate::remote::grpc;
+#[cfg(feature = "quic")]
+use crate::remote::quic;
+#[cfg(feature = "tls")]
+use crate::remote::TlsServerConfig;
+use crate::remote::{
+    delivery,
+    handshake::{self, is_handshake},
+    heartbeat::{is_heartbeat, pong},
+    proto::Envelope,
+    ChunkConfig, ChunkedConnection, CompressedConnection, CompressionConfig, Connection,
+    HandshakeConfig, HeartbeatConfig, HeartbeatConnection, MeteredConnection, TcpConnection,
+    DEFAULT_MAX_FRAME_SIZE,
+};
+use crate::{metrics::MetricsSlot, ActorSystem};
 
 /// Async handler for incoming envelopes
 pub type EnvelopeHandler = Arc<
     dyn Fn(Envelope) -> Pin<Box<dyn Future<Output = Option<Envelope>> + Send>> + Send + Sync
 >;
 
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(feature = "quic")]
+    Quic(quinn::Endpoint),
+    #[cfg(feature = "grpc")]
+    Grpc(TcpListener),
+}
+
 ///remote server accepts connections and dispatches to local actors
 pub struct RemoteServer {
-    listener: TcpListener,
+    listener: Listener,
     handler: EnvelopeHandler,
+    options: ConnOptions,
+    #[cfg(feature = "tls")]
+    tls: Option<TlsServerConfig>,
+}
+
+///what every accepted connection goes through besides the handler
+#[derive(Clone)]
+pub(super) struct ConnOptions {
+    heartbeat: Option<HeartbeatConfig>,
+    handshake: Option<HandshakeConfig>,
+    chunking: Option<ChunkConfig>,
+    pub(super) max_frame_size: usize,
+    metrics: Option<Arc<MetricsSlot>>,
+}
+
+impl Default for ConnOptions {
+    fn default() -> Self {
+        Self {
+            heartbeat: None,
+            handshake: None,
+            chunking: None,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            metrics: None,
+        }
+    }
 }
 
 impl RemoteServer {
     pub async fn bind(addr: &str, handler: EnvelopeHandler) -> io::Result<Self> {
         let listener = TcpListener::bind(addr).await?;
-        Ok(Self { listener, handler })
+        Ok(Self {
+            listener: Listener::Tcp(listener),
+            handler: delivery::acknowledging(handler),
+            options: ConnOptions::default(),
+            #[cfg(feature = "tls")]
+            tls: None,
+        })
+    }
+
+    /// Like `bind`, but every accepted connection must complete a TLS handshake first
+    #[cfg(feature = "tls")]
+    pub async fn bind_tls(
+        addr: &str,
+        handler: EnvelopeHandler,
+        tls: TlsServerConfig,
+    ) -> io::Result<Self> {
+        let mut server = Self::bind(addr, handler).await?;
+        server.tls = Some(tls);
+        Ok(server)
+    }
+
+    /// Serve over QUIC on a UDP socket, each request handled on its own stream
+    #[cfg(feature = "quic")]
+    pub async fn bind_quic(
+        addr: &str,
+        handler: EnvelopeHandler,
+        tls: TlsServerConfig,
+    ) -> io::Result<Self> {
+        let addr = tokio::net::lookup_host(addr)
+            .await?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "address did not resolve"))?;
+        Ok(Self {
+            listener: Listener::Quic(quic::server_endpoint(addr, &tls)?),
+            handler: delivery::acknowledging(handler),
+            options: ConnOptions::default(),
+            tls: None,
+        })
+    }
+
+    /// Serve a bidirectional gRPC stream per peer over plaintext HTTP/2, for
+    /// clients connecting with `GrpcTransport`
+    /// Each stream is treated like a TCP connection, so handshakes,
+    /// heartbeats and chunking apply to it the same way
+    #[cfg(feature = "grpc")]
+    pub async fn bind_grpc(addr: &str, handler: EnvelopeHandler) -> io::Result<Self> {
+        let mut server = Self::bind(addr, handler).await?;
+        if let Listener::Tcp(listener) = server.listener {
+            server.listener = Listener::Grpc(listener);
+        }
+        Ok(server)
+    }
+
+    /// Probe every TCP connection and drop the ones silent for longer than
+    /// `config.timeout`; peers stay connected by heartbeating themselves
+    /// (see `HeartbeatConnection`) or by sending traffic
+    /// Heartbeats from peers are answered whether or not this is set
+    pub fn heartbeat(mut self, config: HeartbeatConfig) -> Self {
+        self.options.heartbeat = Some(config);
+        self
+    }
+
+    /// Require every TCP connection to open with a handshake accepted by
+    /// `config`: same protocol version, same cluster name and, if set, the token
+    /// Peers failing it are told why and disconnected
+    pub fn handshake(mut self, config: HandshakeConfig) -> Self {
+        self.options.handshake = Some(config);
+        self
+    }
+
+    /// Split responses larger than `config.chunk_size` into fragments
+    /// Fragmented requests are reassembled either way, up to the default
+    /// `ChunkConfig::max_message_size` unless this sets another
+    pub fn chunking(mut self, config: ChunkConfig) -> Self {
+        self.options.chunking = Some(config);
+        self
+    }
+
+    /// Drop TCP connections sending frames over `bytes`,
+    /// `DEFAULT_MAX_FRAME_SIZE` unless set
+    pub fn max_frame_size(mut self, bytes: usize) -> Self {
+        self.options.max_frame_size = bytes;
+        self
+    }
+
+    /// Report the traffic of every TCP or gRPC connection to `system`'s
+    /// `MetricsSink`, see `MeteredConnection`
+    pub fn metrics(mut self, system: &ActorSystem) -> Self {
+        self.options.metrics = Some(system.metrics_slot());
+        self
     }
 
     pub fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
-        self.listener.local_addr()
+        match &self.listener {
+            Listener::Tcp(listener) => listener.local_addr(),
+            #[cfg(feature = "quic")]
+            Listener::Quic(endpoint) => endpoint.local_addr(),
+            #[cfg(feature = "grpc")]
+            Listener::Grpc(listener) => listener.local_addr(),
+        }
     }
 
     ///run the server to accept connections
     pub async fn run(self) {
+        match self.listener {
+            Listener::Tcp(ref listener) => self.run_tcp(listener).await,
+            #[cfg(feature = "quic")]
+            Listener::Quic(endpoint) => quic::run(endpoint, self.handler).await,
+            #[cfg(feature = "grpc")]
+            Listener::Grpc(listener) => grpc::run(listener, self.handler, self.options).await,
+        }
+    }
+
+    async fn run_tcp(&self, listener: &TcpListener) {
         loop {
-            match self.listener.accept().await {
+            match listener.accept().await {
                 Ok((stream, peer)) => {
                     println!("Accepted connection from {:?}", peer);
                     let handler = self.handler.clone();
-                    tokio::spawn(async move {
-                        let mut conn = TcpConnection::new(stream);
-
-                        loop {
-                            match conn.recv().await {
-                                Ok(envelope) => {
-                                    println!("Received: target={}", envelope.target_actor);
-
-                                    //call handler to process (async)
-                                    if let Some(response) = (handler)(envelope).await {
-                                        if let Err(e) = conn.send(response).await {
-                                            eprintln!("Failed to send response: {:?}", e);
-                                            break;
-                                        }
-                                    }
-                                }
+                    let options = self.options.clone();
 
-                                Err(_) => break, //conn closed
+                    #[cfg(feature = "tls")]
+                    if let Some(tls) = self.tls.clone() {
+                        tokio::spawn(async move {
+                            match tls.accept(stream).await {
+                                Ok(conn) => {
+                                    let conn = conn.max_frame_size(options.max_frame_size);
+                                    serve_with(conn, peer.to_string(), handler, options).await
+                                }
+                                Err(e) => eprintln!("TLS handshake with {:?} failed: {:?}", peer, e),
                             }
-                        }
-                    });
+                        });
+                        continue;
+                    }
+
+                    let conn = TcpConnection::new(stream).max_frame_size(options.max_frame_size);
+                    tokio::spawn(serve_with(conn, peer.to_string(), handler, options));
                 }
                 Err(e) => eprintln!("Accept error: {:?}", e),
             }
         }
     }
 }
+
+pub(super) async fn serve_with<C: Connection>(
+    conn: C,
+    peer: String,
+    handler: EnvelopeHandler,
+    options: ConnOptions,
+) {
+    let mut conn = MeteredConnection::with_slot(conn, peer, options.metrics.clone());
+    let mut compression = CompressionConfig::default();
+    if let Some(config) = &options.handshake {
+        match handshake::accept(&mut conn, config).await {
+            Ok((_, agreed)) => compression = agreed,
+            Err(e) => {
+                eprintln!("Handshake failed: {:?}", e);
+                return;
+            }
+        }
+    }
+    //peers only get fragments back when chunking was configured,
+    // clients that don't reassemble would take the first one for the answer
+    let chunking = options.chunking.unwrap_or(ChunkConfig {
+        chunk_size: usize::MAX,
+        ..ChunkConfig::default()
+    });
+    let conn = CompressedConnection::new(ChunkedConnection::new(conn, chunking), compression)
+        .max_size(options.max_frame_size);
+    match options.heartbeat {
+        Some(config) => serve(HeartbeatConnection::new(conn, config), handler, options.handshake).await,
+        None => serve(conn, handler, options.handshake).await,
+    }
+}
+
+///answer envelopes on one connection until it closes
+/// handlers run side by side so a slow ask doesn't hold up the ones behind it;
+/// they start in arrival order and each response goes out as soon as it's ready
+async fn serve<C: Connection>(mut conn: C, handler: EnvelopeHandler, handshake: Option<HandshakeConfig>) {
+    let mut in_flight = FuturesUnordered::new();
+
+    //loop until conn closed
+    loop {
+        tokio::select! {
+            biased;
+            Some(response) = in_flight.next(), if !in_flight.is_empty() => {
+                if let Some(response) = response {
+                    if let Err(e) = conn.send(response).await {
+                        eprintln!("Failed to send response: {:?}", e);
+                        break;
+                    }
+                }
+            }
+            result = conn.recv() => match result {
+                Ok(envelope) if is_heartbeat(&envelope) => {
+                    //a `HeartbeatConnection` queues its own, and answers for us
+                    let sent = if envelope.is_response {
+                        conn.flush().await
+                    } else {
+                        conn.send(pong()).await
+                    };
+                    if let Err(e) = sent {
+                        eprintln!("Failed to send response: {:?}", e);
+                        break;
+                    }
+                }
+                //peers may introduce themselves even when it isn't required
+                Ok(envelope) if is_handshake(&envelope) && !envelope.is_response => {
+                    let (ack, verdict) = handshake::answer(handshake.as_ref(), &envelope);
+                    let sent = conn.send(ack).await;
+                    if let Err(reason) = verdict {
+                        eprintln!("Handshake failed: {}", reason);
+                        break;
+                    }
+                    if let Err(e) = sent {
+                        eprintln!("Failed to send response: {:?}", e);
+                        break;
+                    }
+                }
+                Ok(envelope) => {
+                    //call handler to process (async)
+                    in_flight.push((handler)(envelope));
+                }
+                Err(_) => break,
+            },
+        }
+    }
+
+    //nobody is left to answer, but tells still get delivered
+    while in_flight.next().await.is_some() {}
+}
@@ -0,0 +1,106 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::remote::proto::NodeInfo;
+
+///How a cluster node runs the SWIM membership protocol
+/// Every `protocol_period` the node probes one random member. If the ping
+/// isn't answered within `ping_timeout`, `indirect_probes` other members are
+/// asked to probe it too; without any answer by the end of the period the
+/// member becomes suspect. A suspect member that doesn't refute the suspicion
+/// within `suspect_timeout` is declared down. Membership changes ride along
+/// on pings and acks, each one `retransmit_multiplier * log2(cluster size)`
+/// times, instead of shipping the whole member list around
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwimConfig {
+    pub protocol_period: Duration,
+    pub ping_timeout: Duration,
+    pub indirect_probes: usize,
+    pub suspect_timeout: Duration,
+    pub retransmit_multiplier: usize,
+    ///most membership changes piggybacked on a single message
+    pub max_piggyback: usize,
+}
+
+impl Default for SwimConfig {
+    fn default() -> Self {
+        Self {
+            protocol_period: Duration::from_secs(1),
+            ping_timeout: Duration::from_millis(300),
+            indirect_probes: 3,
+            suspect_timeout: Duration::from_secs(5),
+            retransmit_multiplier: 3,
+            max_piggyback: 16,
+        }
+    }
+}
+
+impl SwimConfig {
+    pub fn protocol_period(mut self, period: Duration) -> Self {
+        self.protocol_period = period;
+        self
+    }
+
+    pub fn ping_timeout(mut self, timeout: Duration) -> Self {
+        self.ping_timeout = timeout;
+        self
+    }
+
+    pub fn indirect_probes(mut self, probes: usize) -> Self {
+        self.indirect_probes = probes;
+        self
+    }
+
+    pub fn suspect_timeout(mut self, timeout: Duration) -> Self {
+        self.suspect_timeout = timeout;
+        self
+    }
+
+    pub fn retransmit_multiplier(mut self, multiplier: usize) -> Self {
+        self.retransmit_multiplier = multiplier.max(1);
+        self
+    }
+
+    pub fn max_piggyback(mut self, updates: usize) -> Self {
+        self.max_piggyback = updates;
+        self
+    }
+
+    ///how often a change is passed on in a cluster of `members`
+    fn retransmits(&self, members: usize) -> usize {
+        let log = (usize::BITS - members.max(1).leading_zeros()) as usize;
+        self.retransmit_multiplier * log
+    }
+}
+
+///Membership changes waiting to be piggybacked, the latest one per member
+#[derive(Default)]
+pub(crate) struct Updates {
+    pending: HashMap<String, (NodeInfo, usize)>,
+}
+
+impl Updates {
+    ///queue a change, replacing whatever was still queued for that member
+    pub(crate) fn push(&mut self, info: NodeInfo) {
+        self.pending.insert(info.id.clone(), (info, 0));
+    }
+
+    ///the changes to send along with the next message, least sent first;
+    /// those sent often enough are dropped
+    pub(crate) fn take(&mut self, config: &SwimConfig, members: usize) -> Vec<NodeInfo> {
+        let mut queued: Vec<_> = self.pending.values_mut().collect();
+        queued.sort_by_key(|(_, sent)| *sent);
+
+        let taken: Vec<NodeInfo> = queued
+            .into_iter()
+            .take(config.max_piggyback)
+            .map(|(info, sent)| {
+                *sent += 1;
+                info.clone()
+            })
+            .collect();
+
+        let limit = config.retransmits(members);
+        self.pending.retain(|_, (_, sent)| *sent < limit);
+        taken
+    }
+}
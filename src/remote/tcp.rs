@@ -11,9 +11,44 @@ use crate::remote::{
     transport::{Connection, Transport, TransportError},
 };
 
+/// Largest frame `EnvelopeCodec` accepts unless configured otherwise
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
 ///Length prefixed codec for envelope messages over TCP
 /// format : [4 bytes big-endian length][protobuf payload]
-pub struct EnvelopeCodec;
+/// Frames longer than `max_frame_size` are refused, on the way out and, before
+/// any buffering, on the way in, so a peer can't make us allocate whatever
+/// length it announces. Larger payloads go through `ChunkedConnection`
+#[derive(Debug, Clone, Copy)]
+pub struct EnvelopeCodec {
+    max_frame_size: usize,
+}
+
+impl EnvelopeCodec {
+    pub fn new(max_frame_size: usize) -> Self {
+        Self { max_frame_size }
+    }
+
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
+    fn too_large(&self, len: usize) -> std::io::Error {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "frame of {} bytes exceeds the {} byte limit",
+                len, self.max_frame_size
+            ),
+        )
+    }
+}
+
+impl Default for EnvelopeCodec {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_FRAME_SIZE)
+    }
+}
 
 impl Decoder for EnvelopeCodec {
     type Item = Envelope;
@@ -25,6 +60,9 @@ impl Decoder for EnvelopeCodec {
             return Ok(None);
         }
         let len = u32::from_be_bytes([src[0], src[1], src[2], src[3]]) as usize;
+        if len > self.max_frame_size {
+            return Err(self.too_large(len));
+        }
 
         if src.len() < 4 + len {
             //not enough data yet
@@ -48,6 +86,9 @@ impl Encoder<Envelope> for EnvelopeCodec {
 
     fn encode(&mut self, item: Envelope, dst: &mut bytes::BytesMut) -> Result<(), Self::Error> {
         let payload = item.to_bytes();
+        if payload.len() > self.max_frame_size {
+            return Err(self.too_large(payload.len()));
+        }
         let len = payload.len() as u32;
 
         dst.reserve(4 + payload.len());
@@ -69,10 +110,16 @@ impl TcpConnection {
             .local_addr()
             .map(|a| a.to_string())
             .unwrap_or_else(|_| "unknown".to_string());
-        let framed = Framed::new(stream, EnvelopeCodec);
+        let framed = Framed::new(stream, EnvelopeCodec::default());
         TcpConnection { framed, local_addr }
     }
 
+    /// Refuse frames over `bytes` instead of `DEFAULT_MAX_FRAME_SIZE`, both ways
+    pub fn max_frame_size(mut self, bytes: usize) -> Self {
+        *self.framed.codec_mut() = EnvelopeCodec::new(bytes);
+        self
+    }
+
     /// Get the local socket address as a string
     pub fn local_addr(&self) -> &str {
         &self.local_addr
@@ -90,6 +137,20 @@ impl Connection for TcpConnection {
         })
     }
 
+    fn send_batch(
+        &mut self,
+        envelopes: Vec<Envelope>,
+    ) -> std::pin::Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            //frames are buffered and go out with a single flush
+            for envelope in envelopes {
+                self.framed.feed(envelope).await?;
+            }
+            self.framed.flush().await?;
+            Ok(())
+        })
+    }
+
     fn recv(
         &mut self,
     ) -> std::pin::Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>> {
@@ -110,8 +171,13 @@ impl Connection for TcpConnection {
             Ok(())
         })
     }
+
+    fn local_addr(&self) -> &str {
+        &self.local_addr
+    }
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct TcpTransport;
 
 impl Transport for TcpTransport {
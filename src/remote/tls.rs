@@ -0,0 +1,288 @@
+use std::{future::Future, io, pin::Pin, sync::Arc, time::Duration};
+
+use futures::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio_rustls::{
+    rustls::{
+        self,
+        pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, ServerName},
+        server::WebPkiClientVerifier,
+        sign::{CertifiedKey, SingleCertAndKey},
+        ClientConfig, RootCertStore, ServerConfig,
+    },
+    TlsAcceptor, TlsConnector, TlsStream,
+};
+use tokio_util::codec::Framed;
+
+use crate::remote::{
+    proto::Envelope,
+    transport::{Connection, Transport, TransportError},
+    EnvelopeCodec,
+};
+
+///certificate or key material that couldn't be used
+pub(super) fn invalid(err: impl std::fmt::Display) -> TransportError {
+    TransportError::Io(io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))
+}
+
+fn certs(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, TransportError> {
+    let certs = CertificateDer::pem_slice_iter(pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid)?;
+    if certs.is_empty() {
+        return Err(invalid("no certificate found in PEM"));
+    }
+    Ok(certs)
+}
+
+fn key(pem: &[u8]) -> Result<PrivateKeyDer<'static>, TransportError> {
+    PrivateKeyDer::from_pem_slice(pem).map_err(invalid)
+}
+
+fn roots(ca_pem: &[u8]) -> Result<RootCertStore, TransportError> {
+    let mut roots = RootCertStore::empty();
+    for cert in certs(ca_pem)? {
+        roots.add(cert).map_err(invalid)?;
+    }
+    Ok(roots)
+}
+
+///how long an accepted socket may take to complete the TLS handshake, by default
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+///ring is the only provider compiled in; naming it keeps the configs
+/// working when another crate enables a second one
+fn provider() -> Arc<rustls::crypto::CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+///TLS connection wrapper, same framing as `TcpConnection`
+pub struct TlsConnection {
+    framed: Framed<TlsStream<TcpStream>, EnvelopeCodec>,
+    local_addr: String,
+}
+
+impl TlsConnection {
+    fn new(stream: TlsStream<TcpStream>) -> Self {
+        let local_addr = stream
+            .get_ref()
+            .0
+            .local_addr()
+            .map(|a| a.to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+        let framed = Framed::new(stream, EnvelopeCodec::default());
+        TlsConnection { framed, local_addr }
+    }
+
+    /// Refuse frames over `bytes` instead of `DEFAULT_MAX_FRAME_SIZE`, both ways
+    pub fn max_frame_size(mut self, bytes: usize) -> Self {
+        *self.framed.codec_mut() = EnvelopeCodec::new(bytes);
+        self
+    }
+
+    /// Get the local socket address as a string
+    pub fn local_addr(&self) -> &str {
+        &self.local_addr
+    }
+}
+
+impl Connection for TlsConnection {
+    fn send(
+        &mut self,
+        envelope: Envelope,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            self.framed.send(envelope).await?;
+            Ok(())
+        })
+    }
+
+    fn send_batch(
+        &mut self,
+        envelopes: Vec<Envelope>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            for envelope in envelopes {
+                self.framed.feed(envelope).await?;
+            }
+            self.framed.flush().await?;
+            Ok(())
+        })
+    }
+
+    fn recv(&mut self) -> Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            match self.framed.next().await {
+                Some(Ok(envelope)) => Ok(envelope),
+                Some(Err(e)) => Err(TransportError::Io(e)),
+                None => Err(TransportError::Disconnected),
+            }
+        })
+    }
+
+    fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            self.framed.close().await?;
+            Ok(())
+        })
+    }
+
+    fn local_addr(&self) -> &str {
+        &self.local_addr
+    }
+}
+
+/// Client side of TLS remote connections
+/// Verifies the node's certificate against the given CA and, for mutual TLS,
+/// presents its own certificate. The SNI name defaults to the host part of
+/// the address passed to `connect`
+#[derive(Clone)]
+pub struct TlsTransport {
+    pub(super) config: Arc<ClientConfig>,
+    server_name: Option<ServerName<'static>>,
+}
+
+impl TlsTransport {
+    /// Trust the CA certificates in `ca_pem`
+    pub fn new(ca_pem: &[u8]) -> Result<Self, TransportError> {
+        let config = ClientConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()
+            .map_err(invalid)?
+            .with_root_certificates(roots(ca_pem)?)
+            .with_no_client_auth();
+        Ok(Self {
+            config: Arc::new(config),
+            server_name: None,
+        })
+    }
+
+    /// Use a prepared rustls config, e.g. with platform roots or a custom verifier
+    pub fn from_config(config: Arc<ClientConfig>) -> Self {
+        Self {
+            config,
+            server_name: None,
+        }
+    }
+
+    /// Present this certificate chain and key to servers requiring client auth
+    /// Everything else, the trusted roots or verifier of a `from_config`
+    /// config included, stays as configured
+    pub fn with_identity(mut self, cert_pem: &[u8], key_pem: &[u8]) -> Result<Self, TransportError> {
+        let mut config = (*self.config).clone();
+        let identity =
+            CertifiedKey::from_der(certs(cert_pem)?, key(key_pem)?, config.crypto_provider())
+                .map_err(invalid)?;
+        config.client_auth_cert_resolver = Arc::new(SingleCertAndKey::from(identity));
+        self.config = Arc::new(config);
+        Ok(self)
+    }
+
+    /// Name sent as SNI and checked against the certificate, instead of the
+    /// connect address (for nodes reached by IP or through a proxy)
+    pub fn server_name(mut self, name: &str) -> Result<Self, TransportError> {
+        self.server_name = Some(ServerName::try_from(name.to_string()).map_err(invalid)?);
+        Ok(self)
+    }
+
+    pub(super) fn name_for(&self, addr: &str) -> Result<ServerName<'static>, TransportError> {
+        if let Some(name) = &self.server_name {
+            return Ok(name.clone());
+        }
+        let host = match addr.rsplit_once(':') {
+            Some((host, _port)) => host.trim_start_matches('[').trim_end_matches(']'),
+            None => addr,
+        };
+        ServerName::try_from(host.to_string()).map_err(invalid)
+    }
+}
+
+impl Transport for TlsTransport {
+    type Conn = TlsConnection;
+
+    fn connect(
+        &self,
+        addr: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Conn, TransportError>> + Send + '_>> {
+        let addr = addr.to_string();
+        Box::pin(async move {
+            let name = self.name_for(&addr)?;
+            let stream = TcpStream::connect(&addr).await?;
+            let stream = TlsConnector::from(self.config.clone())
+                .connect(name, stream)
+                .await?;
+            Ok(TlsConnection::new(TlsStream::Client(stream)))
+        })
+    }
+}
+
+/// Server side of TLS remote connections, see `RemoteServer::bind_tls`
+#[derive(Clone)]
+pub struct TlsServerConfig {
+    ///kept so client auth can be switched on after `new`; `None` for `from_config`
+    identity: Option<(Vec<CertificateDer<'static>>, Arc<PrivateKeyDer<'static>>)>,
+    pub(super) config: Arc<ServerConfig>,
+    handshake_timeout: Duration,
+}
+
+impl TlsServerConfig {
+    /// Serve with this certificate chain and key, without client auth
+    pub fn new(cert_pem: &[u8], key_pem: &[u8]) -> Result<Self, TransportError> {
+        let cert = certs(cert_pem)?;
+        let key = key(key_pem)?;
+        let config = ServerConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()
+            .map_err(invalid)?
+            .with_no_client_auth()
+            .with_single_cert(cert.clone(), key.clone_key())
+            .map_err(invalid)?;
+        Ok(Self {
+            identity: Some((cert, Arc::new(key))),
+            config: Arc::new(config),
+            handshake_timeout: HANDSHAKE_TIMEOUT,
+        })
+    }
+
+    /// Use a prepared rustls config
+    pub fn from_config(config: Arc<ServerConfig>) -> Self {
+        Self {
+            identity: None,
+            config,
+            handshake_timeout: HANDSHAKE_TIMEOUT,
+        }
+    }
+
+    /// Mutual TLS: only accept clients presenting a certificate issued by a CA in `ca_pem`
+    pub fn require_client_auth(mut self, ca_pem: &[u8]) -> Result<Self, TransportError> {
+        let Some((cert, key)) = &self.identity else {
+            return Err(invalid("client auth must be set up in the rustls config passed to from_config"));
+        };
+        let verifier =
+            WebPkiClientVerifier::builder_with_provider(Arc::new(roots(ca_pem)?), provider())
+                .build()
+                .map_err(invalid)?;
+        let config = ServerConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()
+            .map_err(invalid)?
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(cert.clone(), key.clone_key())
+            .map_err(invalid)?;
+        self.config = Arc::new(config);
+        Ok(self)
+    }
+
+    /// Drop accepted sockets that haven't completed the TLS handshake within
+    /// `timeout`, 10 seconds by default
+    pub fn handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    ///complete the handshake on an accepted socket, `Timeout` if it stalls
+    pub(crate) async fn accept(&self, stream: TcpStream) -> Result<TlsConnection, TransportError> {
+        let handshake = TlsAcceptor::from(self.config.clone()).accept(stream);
+        let stream = tokio::time::timeout(self.handshake_timeout, handshake)
+            .await
+            .map_err(|_| TransportError::Timeout)??;
+        Ok(TlsConnection::new(TlsStream::Server(stream)))
+    }
+}
@@ -0,0 +1,93 @@
+use crate::remote::proto::TraceContext;
+
+#[cfg(feature = "otel")]
+use {
+    crate::remote::proto::Envelope,
+    opentelemetry::{
+        baggage::BaggageExt,
+        propagation::{Extractor, Injector, TextMapPropagator},
+    },
+    opentelemetry_sdk::propagation::{BaggagePropagator, TraceContextPropagator},
+    tracing_opentelemetry::OpenTelemetrySpanExt,
+};
+
+#[cfg(feature = "otel")]
+const TRACEPARENT: &str = "traceparent";
+
+#[cfg(feature = "otel")]
+const BAGGAGE: &str = "baggage";
+
+///the propagators write into and read from the envelope's fields directly
+#[cfg(feature = "otel")]
+impl Injector for TraceContext {
+    fn set(&mut self, key: &str, value: String) {
+        match key {
+            TRACEPARENT => self.traceparent = value,
+            BAGGAGE => self.baggage = value,
+            //tracestate isn't carried
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "otel")]
+impl Extractor for TraceContext {
+    fn get(&self, key: &str) -> Option<&str> {
+        let value = match key {
+            TRACEPARENT => &self.traceparent,
+            BAGGAGE => &self.baggage,
+            _ => return None,
+        };
+        (!value.is_empty()).then_some(value.as_str())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        vec![TRACEPARENT, BAGGAGE]
+    }
+}
+
+///trace context of the current span, for an envelope about to be sent
+/// None when the span isn't part of an OpenTelemetry trace and there is no
+/// baggage to carry
+#[cfg(feature = "otel")]
+pub(crate) fn current() -> Option<TraceContext> {
+    let span_cx = tracing::Span::current().context();
+    let mut trace = TraceContext::default();
+    TraceContextPropagator::new().inject_context(&span_cx, &mut trace);
+
+    //baggage attached after the span was entered is only in the current context
+    let cx = opentelemetry::Context::current();
+    let baggage_cx = if cx.baggage().is_empty() {
+        &span_cx
+    } else {
+        &cx
+    };
+    BaggagePropagator::new().inject_context(baggage_cx, &mut trace);
+
+    (!trace.traceparent.is_empty() || !trace.baggage.is_empty()).then_some(trace)
+}
+
+#[cfg(not(feature = "otel"))]
+pub(crate) fn current() -> Option<TraceContext> {
+    None
+}
+
+/// Span to handle a received envelope in, continuing the trace of the span
+/// that sent it, with its baggage
+/// `make_handler` and `make_tell_handler` already run the actor in it, use this
+/// in hand-written handlers
+#[cfg(feature = "otel")]
+pub fn trace_span(envelope: &Envelope) -> tracing::Span {
+    let span = tracing::info_span!(
+        "remote message",
+        message_type = %envelope.message_type,
+        sender = %envelope.sender_node,
+    );
+    if let Some(trace) = &envelope.trace {
+        let cx = TraceContextPropagator::new().extract(trace);
+        let cx = BaggagePropagator::new().extract_with_context(&cx, trace);
+        //only fails when no OpenTelemetry layer is installed
+        let _ = span.set_parent(cx);
+    }
+    span
+}
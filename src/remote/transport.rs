@@ -10,6 +10,52 @@ pub enum TransportError {
     Decode(prost::DecodeError),
     Disconnected,
     Timeout,
+    ///a circuit breaker is open and failing calls fast, see `CircuitBreaker`
+    CircuitOpen,
+    ///the connection dropped and is being re-established, the request was
+    /// not answered; see `RemoteClient::connect_with_reconnect`
+    Reconnecting,
+    ///the peer was turned away (or turned us away) during the handshake,
+    /// with the reason, see `HandshakeConfig`
+    Handshake(String),
+    ///the peer gave up on the request because the deadline the sender set
+    /// passed first, see `Envelope::set_timeout`
+    DeadlineExceeded,
+    ///the peer has no route for the message type (or for its target actor),
+    /// with the peer's description
+    UnknownMessageType(String),
+    ///the peer's actor couldn't take the message, e.g. it had stopped, with
+    /// the reason
+    Remote(String),
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransportError::Io(err) => write!(f, "Transport I/O failed: {}", err),
+            TransportError::Decode(err) => write!(f, "Failed to decode message: {}", err),
+            TransportError::Disconnected => write!(f, "Connection closed"),
+            TransportError::Timeout => write!(f, "Remote request timed out"),
+            TransportError::CircuitOpen => write!(f, "Circuit breaker is open"),
+            TransportError::Reconnecting => write!(f, "Connection is being re-established"),
+            TransportError::Handshake(reason) => write!(f, "Handshake rejected: {}", reason),
+            TransportError::DeadlineExceeded => write!(f, "Request deadline exceeded"),
+            TransportError::UnknownMessageType(reason) => {
+                write!(f, "No remote handler: {}", reason)
+            }
+            TransportError::Remote(reason) => write!(f, "Remote handler failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransportError::Io(err) => Some(err),
+            TransportError::Decode(err) => Some(err),
+            _ => None,
+        }
+    }
 }
 
 impl From<std::io::Error> for TransportError {
@@ -30,13 +76,38 @@ pub trait Connection: Send + Sync {
         envelope: Envelope,
     ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>>;
 
+    /// Send several envelopes as one write where the transport allows it
+    fn send_batch(
+        &mut self,
+        envelopes: Vec<Envelope>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async move {
+            for envelope in envelopes {
+                self.send(envelope).await?;
+            }
+            Ok(())
+        })
+    }
+
     // Receive an envelope from this connection
     fn recv(
         &mut self,
     ) -> Pin<Box<dyn Future<Output = Result<Envelope, TransportError>> + Send + '_>>;
 
+    /// Write what the connection queued for the peer on its own, e.g.
+    /// heartbeats; `recv` never writes, since its caller may drop it midway.
+    /// Owners call this between `recv`s
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+
     /// Close the connection gracefully
     fn close(&mut self) -> Pin<Box<dyn Future<Output = Result<(), TransportError>> + Send + '_>>;
+
+    /// Local address this connection sends from, used as the node identity
+    fn local_addr(&self) -> &str {
+        "unknown"
+    }
 }
 
 /// Transport abstraction for connecting to remote nodes
@@ -0,0 +1,220 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    actor::{ActorId, BoxFuture},
+    Actor, Addr, Context, Message, WeakAddr,
+};
+
+/// Reply channel handed to `MessageResponse::handle`
+pub use tokio::sync::oneshot::Sender;
+
+/// What a `Handler` returns: the reply itself, or a response completed later
+/// Implemented for common std types; derive it (`#[derive(MessageResponse)]`)
+/// for your own result types, or wrap a generic `M::Result` in `MessageResult`
+pub trait MessageResponse<A: Actor, M: Message> {
+    /// Deliver the result to `tx`, None for fire-and-forget sends
+    fn handle(self, ctx: &mut Context<A>, tx: Option<Sender<M::Result>>);
+}
+
+///hand a finished result to the waiting sender, if any
+pub fn reply<T>(tx: Option<Sender<T>>, value: T) {
+    if let Some(tx) = tx {
+        //error can be ignored if receiver is dropped
+        let _ = tx.send(value);
+    }
+}
+
+/// Reply with a message's result type as-is, for handlers generic over M
+pub struct MessageResult<M: Message>(pub M::Result);
+
+impl<A: Actor, M: Message> MessageResponse<A, M> for MessageResult<M> {
+    fn handle(self, _ctx: &mut Context<A>, tx: Option<Sender<M::Result>>) {
+        reply(tx, self.0)
+    }
+}
+
+/// Reply once a future resolves, without holding up the actor
+/// The future runs next to the actor and is dropped if the actor stops first
+pub struct ResponseFuture<T> {
+    fut: BoxFuture<'static, T>,
+}
+
+impl<T> ResponseFuture<T> {
+    pub fn new<F>(fut: F) -> Self
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        Self { fut: Box::pin(fut) }
+    }
+}
+
+impl<A, M, T> MessageResponse<A, M> for ResponseFuture<T>
+where
+    A: Actor,
+    M: Message<Result = T>,
+    T: Send + 'static,
+{
+    fn handle(self, ctx: &mut Context<A>, tx: Option<Sender<T>>) {
+        let token = ctx.cancellation_token();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                value = self.fut => reply(tx, value),
+            }
+        });
+    }
+}
+
+type Continue<A, T> = Box<dyn FnOnce(&mut Context<A>, Option<Sender<T>>) + Send>;
+
+/// Reply after a future resolves, computing the reply on the actor
+/// `then` gets the future's output plus `&mut` access to the actor, like
+/// `ctx.spawn_future`
+pub struct ResponseActFuture<A: Actor, T> {
+    run: Continue<A, T>,
+}
+
+impl<A: Actor, T: Send + 'static> ResponseActFuture<A, T> {
+    pub fn new<F, C>(fut: F, then: C) -> Self
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+        C: FnOnce(F::Output, &mut A, &mut Context<A>) -> T + Send + 'static,
+    {
+        Self {
+            run: Box::new(move |ctx, tx| {
+                ctx.spawn_future(fut, move |output, actor, ctx| {
+                    reply(tx, then(output, actor, ctx))
+                })
+            }),
+        }
+    }
+}
+
+impl<A, M, T> MessageResponse<A, M> for ResponseActFuture<A, T>
+where
+    A: Actor,
+    M: Message<Result = T>,
+    T: Send + 'static,
+{
+    fn handle(self, ctx: &mut Context<A>, tx: Option<Sender<T>>) {
+        (self.run)(ctx, tx)
+    }
+}
+
+type Hand<T> = Box<dyn FnOnce(Option<Sender<T>>) + Send>;
+
+/// Request handed on to another actor, created with `Context::forward`
+/// The caller's reply channel goes along, so the other actor answers the
+/// caller directly and this actor is free for its next message
+pub struct Forwarded<T> {
+    hand: Hand<T>,
+}
+
+impl<T> Forwarded<T> {
+    pub(crate) fn new(hand: impl FnOnce(Option<Sender<T>>) + Send + 'static) -> Self {
+        Self {
+            hand: Box::new(hand),
+        }
+    }
+}
+
+impl<A, M, T> MessageResponse<A, M> for Forwarded<T>
+where
+    A: Actor,
+    M: Message<Result = T>,
+    T: Send + 'static,
+{
+    fn handle(self, _ctx: &mut Context<A>, tx: Option<Sender<T>>) {
+        (self.hand)(tx)
+    }
+}
+
+///plain values reply right away
+macro_rules! simple_response {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<A: Actor, M: Message<Result = Self>> MessageResponse<A, M> for $ty {
+                fn handle(self, _ctx: &mut Context<A>, tx: Option<Sender<Self>>) {
+                    reply(tx, self)
+                }
+            }
+        )*
+    };
+}
+
+simple_response!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String,
+    &'static str,
+    Duration,
+    Instant,
+    SystemTime,
+    ActorId,
+    bytes::Bytes,
+);
+
+///generic containers reply right away too
+macro_rules! generic_response {
+    ($(<$($param:ident),*> $ty:ty),* $(,)?) => {
+        $(
+            impl<A, M, $($param),*> MessageResponse<A, M> for $ty
+            where
+                A: Actor,
+                M: Message<Result = Self>,
+            {
+                fn handle(self, _ctx: &mut Context<A>, tx: Option<Sender<Self>>) {
+                    reply(tx, self)
+                }
+            }
+        )*
+    };
+}
+
+generic_response!(
+    <T> Option<T>,
+    <T, E> Result<T, E>,
+    <T> Vec<T>,
+    <T> VecDeque<T>,
+    <T> Box<T>,
+    <T> Arc<T>,
+    <K, V, S> HashMap<K, V, S>,
+    <T, S> HashSet<T, S>,
+    <K, V> BTreeMap<K, V>,
+    <T> BTreeSet<T>,
+    <T1, T2> (T1, T2),
+    <T1, T2, T3> (T1, T2, T3),
+);
+
+impl<A: Actor, B: Actor, M: Message<Result = Self>> MessageResponse<A, M> for Addr<B> {
+    fn handle(self, _ctx: &mut Context<A>, tx: Option<Sender<Self>>) {
+        reply(tx, self)
+    }
+}
+
+impl<A: Actor, B: Actor, M: Message<Result = Self>> MessageResponse<A, M> for WeakAddr<B> {
+    fn handle(self, _ctx: &mut Context<A>, tx: Option<Sender<Self>>) {
+        reply(tx, self)
+    }
+}
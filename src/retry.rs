@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use crate::MailboxError;
+
+/// How `Addr::send_retry` retries a full mailbox
+/// Retry n waits `backoff * 2^(n-1)`, capped at `max_backoff`, less a random
+/// share of up to `jitter` of it so senders held up together don't retry together
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    ///tries in total, the first one included
+    pub attempts: u32,
+    ///wait before the first retry
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    ///fraction of each wait that is randomized, 0.0 to 1.0
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    ///`attempts` tries with doubling waits from `backoff`, up to a second, and 20% jitter
+    pub fn new(attempts: u32, backoff: Duration) -> Self {
+        Self {
+            attempts,
+            backoff,
+            max_backoff: Duration::from_secs(1).max(backoff),
+            jitter: 0.2,
+        }
+    }
+
+    pub fn max_backoff(mut self, max: Duration) -> Self {
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    ///wait before the given retry (1 = second attempt)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.backoff.as_secs_f64() * 2f64.powi(exponent);
+        let base = if secs.is_finite() && secs < self.max_backoff.as_secs_f64() {
+            Duration::from_secs_f64(secs)
+        } else {
+            self.max_backoff
+        };
+        base.mul_f64(1.0 - self.jitter * rand::random::<f64>())
+    }
+}
+
+/// Why `Addr::send_retry` gave up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryError {
+    ///what each attempt ran into, in order; the last one ended the retries
+    pub attempts: Vec<MailboxError>,
+    ///time spent, waits included
+    pub elapsed: Duration,
+}
+
+impl RetryError {
+    ///the error that ended the retries
+    pub fn last(&self) -> &MailboxError {
+        self.attempts.last().unwrap_or(&MailboxError::MailboxFull)
+    }
+}
+
+impl std::fmt::Display for RetryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "gave up after {} attempts in {:?}: {}",
+            self.attempts.len(),
+            self.elapsed,
+            self.last()
+        )
+    }
+}
+
+impl std::error::Error for RetryError {}
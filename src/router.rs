@@ -0,0 +1,194 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard, Weak,
+};
+
+use crate::{
+    mailbox::{ChannelMailbox, Mailbox, SharedMailbox},
+    message::{Terminated, TerminationReason},
+    watcher::Watcher,
+    Actor, ActorSystem, Addr, Handler, MailboxError, Message,
+};
+
+struct Pool<W: Actor> {
+    workers: Mutex<Vec<Addr<W>>>,
+    next: AtomicUsize,
+    spawn: Box<dyn Fn() -> Addr<W> + Send + Sync>,
+}
+
+impl<W: Actor> Pool<W> {
+    fn workers(&self) -> MutexGuard<'_, Vec<Addr<W>>> {
+        self.workers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    ///spawn a worker and watch it, without holding the lock:
+    /// a worker that already died notifies its watcher right away
+    fn spawn_worker(self: &Arc<Self>) -> Addr<W> {
+        let addr = (self.spawn)();
+        addr.add_watcher_erased(Arc::new(WorkerWatcher {
+            pool: Arc::downgrade(self),
+        }));
+        addr
+    }
+
+    fn pick(&self) -> Option<Addr<W>> {
+        let workers = self.workers();
+        if workers.is_empty() {
+            return None;
+        }
+        let next = self.next.fetch_add(1, Ordering::Relaxed) % workers.len();
+        Some(workers[next].clone())
+    }
+}
+
+/// Pool of identical workers behind one handle, messages go round-robin,
+/// or to whichever worker is idle with `work_stealing`
+/// Workers that panic are replaced from the factory; workers that stop
+/// any other way leave the pool. Dropping every Router clone lets the workers stop
+pub struct Router<W: Actor> {
+    pool: Arc<Pool<W>>,
+}
+
+impl<W: Actor> Clone for Router<W> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+        }
+    }
+}
+
+impl<W: Actor> Router<W> {
+    /// Spawn `size` top-level workers built by `factory`
+    pub fn round_robin<F>(sys: &ActorSystem, size: usize, factory: F) -> Self
+    where
+        F: Fn() -> W + Send + Sync + 'static,
+    {
+        Self::with_spawner(size, Box::new(sys.spawner(factory)))
+    }
+
+    /// Spawn `size` top-level workers sharing a single queue: each message
+    /// waits there until a worker is free, so a slow message never holds up
+    /// others behind it while a worker sits idle. For stateless workers with
+    /// CPU-bound handlers, where any worker can take any message
+    /// The queue holds the system's default mailbox capacity; mail still
+    /// queued when the last worker stops is dropped
+    pub fn work_stealing<F>(sys: &ActorSystem, size: usize, factory: F) -> Self
+    where
+        F: Fn() -> W + Send + Sync + 'static,
+    {
+        let queue = Arc::new(ChannelMailbox::new(sys.mailbox_capacity()));
+        let spawn = sys.spawner_with_mailbox(factory, move || {
+            Arc::new(SharedMailbox::new(queue.clone())) as Arc<dyn Mailbox<W>>
+        });
+        Self::with_spawner(size, Box::new(spawn))
+    }
+
+    fn with_spawner(size: usize, spawn: Box<dyn Fn() -> Addr<W> + Send + Sync>) -> Self {
+        let router = Self {
+            pool: Arc::new(Pool {
+                workers: Mutex::new(Vec::with_capacity(size)),
+                next: AtomicUsize::new(0),
+                spawn,
+            }),
+        };
+        router.resize(size);
+        router
+    }
+
+    /// Grow or shrink the pool to `size` workers
+    /// Removed workers finish their queued messages, then stop; in a
+    /// work-stealing pool they leave the shared queue to the others
+    pub fn resize(&self, size: usize) {
+        let current = self.len();
+        if size > current {
+            let added: Vec<Addr<W>> = (current..size).map(|_| self.pool.spawn_worker()).collect();
+            self.pool.workers().extend(added);
+        } else {
+            let removed: Vec<Addr<W>> = {
+                let mut workers = self.pool.workers();
+                let keep = size.min(workers.len());
+                workers.drain(keep..).collect()
+            };
+            for worker in removed {
+                worker.close_mailbox();
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pool.workers().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Current workers, in routing order
+    pub fn workers(&self) -> Vec<Addr<W>> {
+        self.pool.workers().clone()
+    }
+
+    /// Send to the next worker and wait for its response
+    pub async fn send<M>(&self, msg: M) -> Result<M::Result, MailboxError>
+    where
+        W: Handler<M>,
+        M: Message,
+    {
+        self.next_worker()?.send(msg).await
+    }
+
+    /// Fire and forget to the next worker
+    pub async fn do_send<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        W: Handler<M>,
+        M: Message,
+    {
+        self.next_worker()?.do_send(msg).await
+    }
+
+    /// Send to the next worker without waiting for mailbox space
+    pub fn try_send<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        W: Handler<M>,
+        M: Message,
+    {
+        self.next_worker()?.try_send(msg)
+    }
+
+    fn next_worker(&self) -> Result<Addr<W>, MailboxError> {
+        self.pool.pick().ok_or(MailboxError::MailboxClosed)
+    }
+}
+
+///replaces a worker that crashed, forgets one that stopped any other way
+struct WorkerWatcher<W: Actor> {
+    pool: Weak<Pool<W>>,
+}
+
+impl<W: Actor> Watcher for WorkerWatcher<W> {
+    fn notify(&self, msg: Terminated) {
+        let Some(pool) = self.pool.upgrade() else {
+            return;
+        };
+        if !matches!(
+            msg.reason,
+            TerminationReason::Panicked(_) | TerminationReason::RestartLimitExceeded
+        ) {
+            pool.workers().retain(|worker| worker.id() != msg.id);
+            return;
+        }
+
+        let replacement = pool.spawn_worker();
+        let mut workers = pool.workers();
+        match workers.iter_mut().find(|worker| worker.id() == msg.id) {
+            Some(slot) => *slot = replacement,
+            //resized away in the meantime
+            None => {
+                drop(workers);
+                replacement.close_mailbox();
+            }
+        }
+    }
+}
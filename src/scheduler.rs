@@ -0,0 +1,360 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::{actor::ActorId, directory::Shutdown, Actor, Addr, Handler, Message};
+
+/// When a scheduled job fires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// Cron expression, see `Schedule::cron`
+    Cron(CronExpr),
+    /// Fixed period, first run one period after scheduling
+    Every(Duration),
+    /// Once, at the given time
+    At(SystemTime),
+}
+
+impl Schedule {
+    /// Parse a cron expression (UTC): `minute hour day-of-month month day-of-week`
+    /// Fields take `*`, numbers, ranges `a-b`, steps `*/n` or `a-b/n` and lists `a,b`;
+    /// day-of-week runs 0-7 with both 0 and 7 meaning Sunday.
+    /// `@yearly`, `@monthly`, `@weekly`, `@daily` and `@hourly` are accepted too
+    pub fn cron(expr: &str) -> Result<Self, ScheduleError> {
+        CronExpr::parse(expr).map(Schedule::Cron)
+    }
+
+    pub fn every(period: Duration) -> Self {
+        Schedule::Every(period)
+    }
+
+    pub fn at(time: SystemTime) -> Self {
+        Schedule::At(time)
+    }
+
+    /// First fire time strictly after `after`, None once the schedule is exhausted
+    pub fn next_after(&self, after: SystemTime) -> Option<SystemTime> {
+        match self {
+            Schedule::Cron(cron) => cron.next_after(after),
+            Schedule::Every(period) => Some(after + *period),
+            Schedule::At(time) => (*time > after).then_some(*time),
+        }
+    }
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Schedule::Cron(cron) => write!(f, "{}", cron.expr),
+            Schedule::Every(period) => write!(f, "every {:?}", period),
+            Schedule::At(time) => write!(f, "at {:?}", time),
+        }
+    }
+}
+
+/// Invalid cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleError {
+    pub expr: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid cron expression '{}': {}",
+            self.expr, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Parsed cron expression, one bit per allowed value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    expr: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    //cron matches either day field when both are restricted
+    any_day: bool,
+    any_weekday: bool,
+}
+
+//how far ahead to look before deciding a schedule never fires (e.g. Feb 30)
+const SEARCH_DAYS: u64 = 366 * 5;
+
+impl CronExpr {
+    fn parse(expr: &str) -> Result<Self, ScheduleError> {
+        let error = |reason: String| ScheduleError {
+            expr: expr.to_string(),
+            reason,
+        };
+        let expanded = match expr.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(error(format!("expected 5 fields, got {}", fields.len())));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7).map_err(error)?;
+        //7 is another name for Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expr: expr.trim().to_string(),
+            minutes: parse_field(fields[0], 0, 59).map_err(error)?,
+            hours: parse_field(fields[1], 0, 23).map_err(error)?,
+            days: parse_field(fields[2], 1, 31).map_err(error)?,
+            months: parse_field(fields[3], 1, 12).map_err(error)?,
+            weekdays,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    fn next_after(&self, after: SystemTime) -> Option<SystemTime> {
+        let secs = after.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        let start = secs / 60 + 1;
+        let first_day = start / 1440;
+
+        for day in first_day..first_day + SEARCH_DAYS {
+            if self.matches_day(day) {
+                let first_minute = if day == first_day { start % 1440 } else { 0 };
+                for minute_of_day in first_minute..1440 {
+                    let (hour, minute) = (minute_of_day / 60, minute_of_day % 60);
+                    if bit(self.hours, hour) && bit(self.minutes, minute) {
+                        let secs = (day * 1440 + minute_of_day) * 60;
+                        return Some(UNIX_EPOCH + Duration::from_secs(secs));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, days_since_epoch: u64) -> bool {
+        let (_, month, day) = civil_from_days(days_since_epoch);
+        if !bit(self.months, month) {
+            return false;
+        }
+        //1970-01-01 was a Thursday
+        let weekday = (days_since_epoch + 4) % 7;
+        let day_ok = bit(self.days, day);
+        let weekday_ok = bit(self.weekdays, weekday);
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day_ok || weekday_ok,
+            _ => day_ok && weekday_ok,
+        }
+    }
+}
+
+fn bit(mask: u64, value: u64) -> bool {
+    mask & (1 << value) != 0
+}
+
+///bitmask of the values a cron field allows
+fn parse_field(field: &str, min: u64, max: u64) -> Result<u64, String> {
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u64 = step.parse().map_err(|_| format!("bad step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be positive".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (low, high) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((low, high)) => (parse_value(low, min, max)?, parse_value(high, min, max)?),
+                //`5/10` means from 5 to the end
+                None if step > 1 => (parse_value(range, min, max)?, max),
+                None => {
+                    let value = parse_value(range, min, max)?;
+                    (value, value)
+                }
+            },
+        };
+        if low > high {
+            return Err(format!("empty range '{}'", range));
+        }
+        for value in (low..=high).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(value: &str, min: u64, max: u64) -> Result<u64, String> {
+    match value.parse::<u64>() {
+        Ok(value) if (min..=max).contains(&value) => Ok(value),
+        _ => Err(format!("'{}' is not in {}-{}", value, min, max)),
+    }
+}
+
+///(year, month, day) of a day count since 1970-01-01 (proleptic Gregorian)
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Id of a scheduled job, stable for the lifetime of the scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobId(u64);
+
+impl std::fmt::Display for JobId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "job-{}", self.0)
+    }
+}
+
+/// Snapshot of a scheduled job, as listed by `Scheduler::jobs`
+#[derive(Debug, Clone)]
+pub struct JobInfo {
+    pub id: JobId,
+    pub target: ActorId,
+    pub schedule: Schedule,
+    pub next_run: Option<SystemTime>,
+}
+
+struct Job {
+    info: JobInfo,
+    cancel: CancellationToken,
+}
+
+/// Delivers messages to actors on cron or calendar schedules
+/// Owned by the ActorSystem (`ActorSystem::scheduler`); cheap to clone.
+/// Jobs end when cancelled, when their schedule is exhausted, when the
+/// target actor stops or when the system shuts down
+#[derive(Clone)]
+pub struct Scheduler {
+    jobs: Arc<Mutex<HashMap<JobId, Job>>>,
+    next_id: Arc<AtomicU64>,
+    shutdown: Arc<Shutdown>,
+}
+
+impl Scheduler {
+    pub(crate) fn new(shutdown: Arc<Shutdown>) -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+            shutdown,
+        }
+    }
+
+    fn jobs_lock(&self) -> MutexGuard<'_, HashMap<JobId, Job>> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Send `make()` to `addr` every time `schedule` fires
+    /// The job holds a weak address, it doesn't keep the actor alive
+    pub fn schedule<A, M, F>(&self, schedule: Schedule, addr: &Addr<A>, make: F) -> JobId
+    where
+        A: Actor + Handler<M>,
+        M: Message,
+        F: Fn() -> M + Send + 'static,
+    {
+        let id = JobId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let cancel = CancellationToken::new();
+        let mut last = SystemTime::now();
+        let next_run = schedule.next_after(last);
+
+        self.jobs_lock().insert(
+            id,
+            Job {
+                info: JobInfo {
+                    id,
+                    target: addr.id(),
+                    schedule: schedule.clone(),
+                    next_run,
+                },
+                cancel: cancel.clone(),
+            },
+        );
+
+        let target = addr.downgrade();
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let mut next_run = next_run;
+            while let Some(next) = next_run {
+                let delay = next.duration_since(SystemTime::now()).unwrap_or_default();
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = cancel.cancelled() => return,
+                    _ = scheduler.shutdown.notified() => break,
+                }
+                let delivered = match target.upgrade() {
+                    Some(addr) => addr.do_send(make()).await.is_ok(),
+                    None => false,
+                };
+                if !delivered {
+                    break;
+                }
+
+                last = last.max(next);
+                next_run = schedule.next_after(last);
+                if let Some(job) = scheduler.jobs_lock().get_mut(&id) {
+                    job.info.next_run = next_run;
+                }
+            }
+            scheduler.jobs_lock().remove(&id);
+        });
+
+        id
+    }
+
+    /// Cancel a job, false if it already ended
+    pub fn cancel(&self, id: JobId) -> bool {
+        match self.jobs_lock().remove(&id) {
+            Some(job) => {
+                job.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Jobs that are still scheduled, ordered by id
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        let mut jobs: Vec<JobInfo> = self
+            .jobs_lock()
+            .values()
+            .map(|job| job.info.clone())
+            .collect();
+        jobs.sort_by_key(|job| job.id);
+        jobs
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use crate::{
+    address::{ErasedAddr, Recipient},
+    directory, Actor, Handler, Message,
+};
+
+/// Actors whose path matches a pattern, created with `ActorSystem::select`
+/// Segments are matched one by one: `*` stands for any run of characters
+/// within a segment, a `**` segment for any number of segments.
+/// The pattern is evaluated again on every call, so actors spawned after
+/// the selection was made are included
+#[derive(Debug, Clone)]
+pub struct ActorSelection {
+    pattern: Arc<str>,
+    system: u64,
+}
+
+impl ActorSelection {
+    pub(crate) fn new(pattern: &str, system: u64) -> Self {
+        Self {
+            pattern: pattern.into(),
+            system,
+        }
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Live actors matching the pattern, sorted by path
+    pub fn actors(&self) -> Vec<ErasedAddr> {
+        let pattern: Vec<&str> = segments(&self.pattern).collect();
+        let mut actors: Vec<ErasedAddr> = directory::members(self.system)
+            .into_iter()
+            .filter(|addr| {
+                let path: Vec<&str> = segments(addr.path()).collect();
+                matches(&pattern, &path)
+            })
+            .collect();
+        actors.sort_by(|a, b| a.path().cmp(b.path()));
+        actors
+    }
+
+    /// Matching actors of type `A`, as recipients of `M`
+    /// Actors of any other type are left out
+    pub fn recipients<A, M>(&self) -> Vec<Recipient<M>>
+    where
+        A: Actor + Handler<M>,
+        M: Message,
+    {
+        self.actors()
+            .iter()
+            .filter_map(ErasedAddr::downcast::<A>)
+            .map(|addr| addr.recipient())
+            .collect()
+    }
+
+    /// Send a clone of `msg` to every matching actor of type `A`,
+    /// returns how many accepted it
+    pub async fn do_send<A, M>(&self, msg: M) -> usize
+    where
+        A: Actor + Handler<M>,
+        M: Message + Clone,
+    {
+        let sends = self
+            .recipients::<A, M>()
+            .into_iter()
+            .map(|recipient| {
+                let msg = msg.clone();
+                async move { recipient.do_send(msg).await.is_ok() }
+            });
+        futures::future::join_all(sends)
+            .await
+            .into_iter()
+            .filter(|delivered| *delivered)
+            .count()
+    }
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+fn matches(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| matches(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path)) => segment_matches(first, segment) && matches(rest, path),
+            None => false,
+        },
+    }
+}
+
+///glob match of one segment, `*` matches any run of characters
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == segment;
+    };
+    let Some(remaining) = segment.strip_prefix(prefix) else {
+        return false;
+    };
+    if !rest.contains('*') {
+        return remaining.ends_with(rest);
+    }
+    (0..=remaining.len())
+        .filter(|&start| remaining.is_char_boundary(start))
+        .any(|start| segment_matches(rest, &remaining[start..]))
+}
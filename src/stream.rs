@@ -1,9 +1,9 @@
 use core::task;
 use std::{pin::Pin, task::Poll};
 
-use futures::Stream;
+use futures::{future::BoxFuture, FutureExt, Sink, Stream};
 
-use crate::{actor::StreamHandler, Actor, Context};
+use crate::{actor::StreamHandler, Actor, Addr, Context, Handler, MailboxError, Message};
 
 ///type erased stream that can call back into actor
 pub trait ActorStream<A: Actor>: Send {
@@ -86,3 +86,74 @@ pub fn poll_streams<A: Actor>(
 
     any_ready
 }
+
+/// An actor's address as a `futures::Sink`, see `Addr::into_sink`
+/// Each item is sent with `do_send`: the sink isn't ready again until the
+/// previous item is in the mailbox, so a full mailbox holds the producer back.
+/// Closing the sink only drops this handle, the actor keeps running
+pub struct AddrSink<A: Actor, M> {
+    addr: Addr<A>,
+    //the item being enqueued, at most one at a time
+    sending: Option<BoxFuture<'static, Result<(), MailboxError>>>,
+    _message: std::marker::PhantomData<fn(M)>,
+}
+
+impl<A: Actor, M> AddrSink<A, M> {
+    pub(crate) fn new(addr: Addr<A>) -> Self {
+        Self {
+            addr,
+            sending: None,
+            _message: std::marker::PhantomData,
+        }
+    }
+
+    /// The address items are sent to
+    pub fn addr(&self) -> &Addr<A> {
+        &self.addr
+    }
+
+    fn poll_sent(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), MailboxError>> {
+        let Some(sending) = self.sending.as_mut() else {
+            return Poll::Ready(Ok(()));
+        };
+        let result = futures::ready!(sending.poll_unpin(cx));
+        self.sending = None;
+        Poll::Ready(result)
+    }
+}
+
+impl<A, M> Sink<M> for AddrSink<A, M>
+where
+    A: Handler<M>,
+    M: Message,
+{
+    type Error = MailboxError;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_sent(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: M) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        let addr = this.addr.clone();
+        this.sending = Some(async move { addr.do_send(item).await }.boxed());
+        Ok(())
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_sent(cx)
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_sent(cx)
+    }
+}
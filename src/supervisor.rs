@@ -1,20 +1,25 @@
-use std::time::{Duration, Instant};
+use std::{sync::Arc, time::Duration};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use tokio::time::Instant;
+
+use crate::{actor::ActorId, mailbox::OverflowStrategy, Actor, SpawnOptions};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum SupervisorStrategy {
     ///stop the actor on failure (default)
+    #[default]
     Stop,
     ///restart the actor on failure
     Restart { max_restarts: u32, within: Duration },
+    ///restart the actor on failure, waiting longer after each consecutive crash
+    Backoff(BackoffPolicy),
     ///escalate to parent supervisor
     Escalate,
 }
 
-impl Default for SupervisorStrategy {
-    fn default() -> Self {
-        Self::Stop
-    }
-}
+///Restart budget of actors restarted by `PanicPolicy::RestartActor` whose
+/// strategy has none, the same as `BackoffPolicy::new`
+pub(crate) const DEFAULT_RESTART_LIMIT: (u32, Duration) = (10, Duration::from_secs(60));
 
 impl SupervisorStrategy {
     pub fn restart(max_restarts: u32, within: Duration) -> Self {
@@ -23,12 +28,206 @@ impl SupervisorStrategy {
             within,
         }
     }
+
+    pub fn backoff(policy: BackoffPolicy) -> Self {
+        Self::Backoff(policy)
+    }
+
+    ///restart budget, None if the strategy never restarts
+    pub(crate) fn restart_limit(&self) -> Option<(u32, Duration)> {
+        match *self {
+            Self::Restart {
+                max_restarts,
+                within,
+            } => Some((max_restarts, within)),
+            Self::Backoff(policy) => Some((policy.max_restarts, policy.within)),
+            Self::Stop | Self::Escalate => None,
+        }
+    }
+
+    ///how long to wait before the given restart (1 = first restart in the window)
+    pub(crate) fn restart_delay(&self, attempt: u32) -> Duration {
+        match self {
+            Self::Backoff(policy) => policy.delay(attempt),
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+/// Everything `Context::spawn_child_spec` needs to start and supervise a child
+/// Builds on the factory, so a restart gets a fresh instance
+pub struct ChildSpec<C: Actor> {
+    pub(crate) factory: Box<dyn FnMut() -> C + Send>,
+    pub(crate) strategy: SupervisorStrategy,
+    pub(crate) significant: bool,
+    pub(crate) options: SpawnOptions,
+}
+
+impl<C: Actor> ChildSpec<C> {
+    /// Child built by `factory`, stopped on failure unless a strategy is set
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: FnMut() -> C + Send + 'static,
+    {
+        Self {
+            factory: Box::new(factory),
+            strategy: SupervisorStrategy::Stop,
+            significant: false,
+            options: SpawnOptions::new(),
+        }
+    }
+
+    pub fn strategy(mut self, strategy: SupervisorStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Restart budget of this child: at most `max_restarts` within `within`
+    /// Keeps a backoff strategy's delays; any other strategy becomes Restart
+    pub fn max_restarts(mut self, max_restarts: u32, within: Duration) -> Self {
+        self.strategy = match self.strategy {
+            SupervisorStrategy::Backoff(policy) => {
+                SupervisorStrategy::Backoff(policy.max_restarts(max_restarts, within))
+            }
+            _ => SupervisorStrategy::restart(max_restarts, within),
+        };
+        self
+    }
+
+    /// A significant child takes its parent down with it: once it fails for
+    /// good (panics and is not restarted, or runs out of restarts) the parent stops
+    pub fn significant(mut self, significant: bool) -> Self {
+        self.significant = significant;
+        self
+    }
+
+    /// Mailbox, name and arbiter of the child
+    pub fn options(mut self, options: SpawnOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Bound of the child's mailbox, `DEFAULT_CAPACITY` unless set, so a
+    /// parent sending faster than the child handles is slowed down
+    pub fn mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.options = self.options.mailbox_capacity(capacity);
+        self
+    }
+
+    /// What to do with messages arriving while the child's mailbox is full
+    pub fn overflow(mut self, strategy: OverflowStrategy) -> Self {
+        self.options = self.options.overflow(strategy);
+        self
+    }
+}
+
+///Exponential backoff between restarts so a crash-looping actor doesn't spin
+/// The n-th restart inside the window waits `min * multiplier^(n-1)`, capped at `max`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackoffPolicy {
+    pub min: Duration,
+    pub max: Duration,
+    pub multiplier: f64,
+    ///restarts allowed within the window before the actor is stopped for good
+    pub max_restarts: u32,
+    pub within: Duration,
+}
+
+impl BackoffPolicy {
+    ///doubling backoff between `min` and `max`, 10 restarts per minute
+    pub fn new(min: Duration, max: Duration) -> Self {
+        let (max_restarts, within) = DEFAULT_RESTART_LIMIT;
+        Self {
+            min,
+            max,
+            multiplier: 2.0,
+            max_restarts,
+            within,
+        }
+    }
+
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn max_restarts(mut self, max_restarts: u32, within: Duration) -> Self {
+        self.max_restarts = max_restarts;
+        self.within = within;
+        self
+    }
+
+    ///delay before the given restart (1 = first restart in the window)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.min.as_secs_f64() * self.multiplier.powi(exponent);
+        if secs.is_finite() && secs < self.max.as_secs_f64() {
+            Duration::from_secs_f64(secs)
+        } else {
+            self.max
+        }
+    }
+}
+
+/// How a parent treats its other children when one of them is restarted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SupervisionMode {
+    ///restart only the child that failed (default)
+    #[default]
+    OneForOne,
+    ///restart every running sibling along with it, for children that share
+    /// state and must start over together
+    AllForOne,
+}
+
+/// What happens to a failed child, decided by its parent at the time of
+/// the failure, see `Supervisor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisorDirective {
+    ///start a fresh instance, keeping the mailbox
+    Restart,
+    ///stop the child for good
+    Stop,
+    ///make the failure the parent's own, as `SupervisorStrategy::Escalate`
+    Escalate,
+}
+
+/// A child's failure, as shown to `Supervisor::on_child_failure`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureInfo {
+    ///place of the child in the supervision tree, see `Addr::path`
+    pub path: Arc<str>,
+    ///panic message of the failed handler
+    pub message: String,
+    ///times the child was already restarted, for a parent keeping a budget
+    pub restarts: u32,
+}
+
+/// Parent that decides per failure what happens to the children it spawned
+/// with `Context::spawn_child_directed`, e.g. restart after an IO error but
+/// stop after a configuration error. The child waits for the decision; if
+/// the parent is gone by then, the child stops
+pub trait Supervisor: Actor {
+    fn on_child_failure(&mut self, child: ActorId, err: &FailureInfo) -> SupervisorDirective;
+}
+
+///Last-resort policy of the system guardian, applied when a top-level
+/// actor fails permanently (its own strategy gave up)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GuardianPolicy {
+    ///stop the failed actor, leave its children running
+    StopActor,
+    ///stop the failed actor and its whole subtree (default)
+    #[default]
+    StopSubtree,
+    ///shut the whole system down with a non-zero exit status
+    TerminateSystem { exit_code: i32 },
 }
 
 ///Track restart history for an actor
 pub struct RestartTracker {
     ///Timestamps of recent restarts
-    restart_times: Vec<std::time::Instant>,
+    restart_times: Vec<Instant>,
     max_restarts: u32,
     within: Duration,
 }
@@ -57,4 +256,9 @@ impl RestartTracker {
             false
         }
     }
+
+    ///restarts recorded inside the current window
+    pub fn recent_restarts(&self) -> u32 {
+        self.restart_times.len() as u32
+    }
 }
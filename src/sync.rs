@@ -0,0 +1,10 @@
+//! Synchronization primitives shared by Addr, TimerHandle and the watcher list.
+//! With the `loom` feature they come from loom so races between watching,
+//! stopping and timer cancellation can be model checked.
+//! Arcs holding trait objects stay on std since loom's Arc can't coerce to dyn.
+
+#[cfg(feature = "loom")]
+pub(crate) use loom::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, AtomicUsize, Ordering};
+
+#[cfg(not(feature = "loom"))]
+pub(crate) use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, AtomicUsize, Ordering};
@@ -0,0 +1,286 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard,
+};
+
+use tokio::{runtime::Handle, sync::Notify};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    actor::{ActorId, CURRENT_ACTOR},
+    dead_letter::{self, DeadLetter},
+    directory::{self, Shutdown},
+    envelope::dispatch,
+    events::{self, SystemEvent},
+    mailbox::{ChannelMailbox, Mailbox, DEFAULT_CAPACITY},
+    message::TerminationReason,
+    metrics::{self, MetricsSlot},
+    middleware::{Chain, MiddlewareStack},
+    panic::{self, PanicPolicy},
+    system::stop_reason,
+    Actor, ActorSystem, Addr, Context,
+};
+
+/// Runs actors whose handlers block (file IO, sqlite, heavy CPU) on
+/// dedicated OS threads instead of the tokio runtime
+pub struct SyncArbiter;
+
+impl SyncArbiter {
+    /// Start `threads` instances of an actor, each on its own thread, behind one `Addr`
+    /// The instances share a mailbox: each message is handled by whichever
+    /// thread is free. An instance whose handler panics is rebuilt from the
+    /// factory, unless the system's `PanicPolicy` stops or ends it. Once the last `Addr` is dropped, the queued mail is handled
+    /// and the threads exit. Streams added with `ctx.add_stream` are not polled.
+    /// Must be called from within a tokio runtime
+    pub fn start<A, F>(sys: &ActorSystem, threads: usize, factory: F) -> Addr<A>
+    where
+        A: Actor,
+        F: Fn() -> A + Send + Sync + 'static,
+    {
+        let threads = threads.max(1);
+        let shutdown = sys.shutdown_signal();
+        let handle = Handle::current();
+        let id = ActorId::new();
+        let stop_signal = Arc::new(Notify::new());
+        let mailbox: Arc<dyn Mailbox<A>> = Arc::new(ChannelMailbox::new(DEFAULT_CAPACITY));
+        let system = directory::system_key(&shutdown);
+        let addr = Addr::new(mailbox.clone(), id, stop_signal.clone()).with_system(system);
+        directory::insert(addr.uncounted().into(), system);
+        events::publish(
+            system,
+            SystemEvent::ActorSpawned {
+                id,
+                path: addr.path_arc(),
+                actor_type: std::any::type_name::<A>(),
+            },
+        );
+
+        let shared = Arc::new(Shared {
+            factory,
+            mailbox,
+            addr: addr.uncounted(),
+            stop_signal: stop_signal.clone(),
+            shutdown: shutdown.clone(),
+            stopping: AtomicBool::new(false),
+            running: AtomicUsize::new(threads),
+            finished: CancellationToken::new(),
+            metrics: metrics::slot(system),
+            chain: Chain::new(system, MiddlewareStack::default()),
+            reason: Mutex::new(TerminationReason::Normal),
+            _listed: directory::Listed(id),
+        });
+
+        //stop requests can't reach a thread blocked in a handler, so one task
+        //watches for them and closes the mailbox for every thread at once
+        let control = shared.clone();
+        tokio::spawn(async move {
+            let reason = tokio::select! {
+                _ = control.shutdown.notified() => TerminationReason::Killed,
+                _ = control.stop_signal.notified() => stop_reason(&control.addr),
+                _ = control.addr.released() => TerminationReason::MailboxDropped,
+                _ = control.finished.cancelled() => return,
+            };
+            //with every Addr gone nothing new can arrive, but what is
+            //queued is still handled before the threads exit
+            if matches!(reason, TerminationReason::MailboxDropped) {
+                if !control.stopping.load(Ordering::SeqCst) {
+                    *control.reason() = reason;
+                }
+                control.addr.close_mailbox();
+            } else {
+                control.stop(reason);
+            }
+        });
+
+        for n in 0..threads {
+            let shared = shared.clone();
+            let handle = handle.clone();
+            std::thread::Builder::new()
+                .name(format!("cinema-sync-{}-{}", id.sequence(), n))
+                .spawn(move || run(shared, handle))
+                .expect("failed to spawn sync arbiter thread");
+        }
+
+        addr
+    }
+}
+
+struct Shared<A: Actor, F> {
+    factory: F,
+    mailbox: Arc<dyn Mailbox<A>>,
+    addr: Addr<A>,
+    stop_signal: Arc<Notify>,
+    shutdown: Arc<Shutdown>,
+    //set on stop or shutdown: queued mail becomes dead letters
+    stopping: AtomicBool,
+    running: AtomicUsize,
+    finished: CancellationToken,
+    metrics: Arc<MetricsSlot>,
+    chain: Chain,
+    //why the control task closed the mailbox
+    reason: Mutex<TerminationReason>,
+    ///removes the directory entry if the workers are dropped with their runtime
+    _listed: directory::Listed,
+}
+
+impl<A: Actor, F> Shared<A, F> {
+    fn reason(&self) -> MutexGuard<'_, TerminationReason> {
+        self.reason
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    ///stop every thread, queued mail becomes dead letters; the first reason
+    /// given is the one watchers see
+    fn stop(&self, reason: TerminationReason) {
+        if !self.stopping.swap(true, Ordering::SeqCst) {
+            *self.reason() = reason;
+        }
+        self.addr.close_mailbox();
+    }
+}
+
+impl<A: Actor, F: Fn() -> A> Shared<A, F> {
+    fn start_instance(&self, handle: &Handle) -> (A, Context<A>) {
+        let mut actor = (self.factory)();
+        let mut ctx = Context::with_stop_signal(
+            self.addr.clone(),
+            self.stop_signal.clone(),
+            self.shutdown.clone(),
+        );
+        actor.started(&mut ctx);
+        handle.block_on(actor.started_async(&mut ctx));
+        (actor, ctx)
+    }
+
+    fn stop_instance(&self, actor: &mut A, ctx: &mut Context<A>, handle: &Handle) {
+        ctx.cancellation_token().cancel();
+        ctx.stop_children();
+        if !self.addr.is_killed() {
+            actor.stopped(ctx);
+            handle.block_on(actor.stopped_async(ctx));
+        }
+    }
+}
+
+///one sync thread: handle mail until the shared mailbox is closed and drained
+fn run<A, F>(shared: Arc<Shared<A, F>>, handle: Handle)
+where
+    A: Actor,
+    F: Fn() -> A + Send + Sync + 'static,
+{
+    //timers and spawned tasks from handlers need a runtime
+    let _runtime = handle.enter();
+    let id = shared.addr.id();
+    let (mut actor, mut ctx) = shared.start_instance(&handle);
+    //this thread holds a message, see `ActorSystem::wait_idle`
+    let mut handling = false;
+
+    loop {
+        if let Some(waiting) = ctx.take_wait() {
+            handle.block_on(waiting);
+            continue;
+        }
+        let next = match ctx.take_notification() {
+            Some(msg) => Some(msg),
+            None => {
+                if handling {
+                    shared.addr.end_handling();
+                    handling = false;
+                }
+                handle.block_on(shared.mailbox.dequeue())
+            }
+        };
+        let Some(mut msg) = next else {
+            break;
+        };
+        if !handling {
+            shared.addr.begin_handling();
+            handling = true;
+        }
+        if shared.stopping.load(Ordering::SeqCst) {
+            dead_letter::publish(
+                shared.addr.system(),
+                DeadLetter {
+                    sender: None,
+                    ..DeadLetter::new(id, shared.addr.path_arc(), msg.message_type())
+                },
+            );
+            continue;
+        }
+
+        let message_type = msg.message_type();
+        let Some(layers) = shared.chain.before(&mut msg, id, shared.addr.path()) else {
+            continue;
+        };
+        let measurement = shared.metrics.start(&msg, shared.mailbox.len());
+        let result = handle.block_on(CURRENT_ACTOR.scope(id, dispatch(msg, &mut actor, &mut ctx)));
+        if let Some(measurement) = measurement {
+            measurement.finish::<A>(id, shared.addr.path());
+        }
+        shared.addr.record_activity();
+        if result.is_ok() {
+            Chain::after(&layers, id, shared.addr.path(), message_type);
+        }
+        if let Err(info) = result {
+            let system = directory::system_key(&shared.shutdown);
+            panic::report(system, &info);
+            events::publish(
+                system,
+                SystemEvent::ActorPanicked {
+                    id,
+                    path: info.path.clone(),
+                    message: info.message.clone(),
+                },
+            );
+            //the system's policy overrides rebuilding the instance
+            match panic::policy(system) {
+                Some((PanicPolicy::AbortProcess, _)) => {
+                    eprintln!("Sync actor {} panicked: {}. Aborting the process.", id, info.message);
+                    std::process::abort();
+                }
+                Some((PanicPolicy::EscalateToSystem, terminate)) => {
+                    eprintln!("Sync actor {} panicked: {}. Terminating the system.", id, info.message);
+                    shared.stop(TerminationReason::Panicked(info.message));
+                    terminate();
+                    break;
+                }
+                Some((PanicPolicy::StopActor, _)) => {
+                    eprintln!("Sync actor {} panicked: {}. Stopping.", id, info.message);
+                    shared.stop(TerminationReason::Panicked(info.message));
+                    break;
+                }
+                _ => eprintln!("Sync actor {} panicked: {}. Restarting...", id, info.message),
+            }
+            shared.stop_instance(&mut actor, &mut ctx, &handle);
+            (actor, ctx) = shared.start_instance(&handle);
+            events::publish(
+                system,
+                SystemEvent::ActorRestarted {
+                    id,
+                    path: shared.addr.path_arc(),
+                },
+            );
+        }
+    }
+
+    if handling {
+        shared.addr.end_handling();
+    }
+    shared.stop_instance(&mut actor, &mut ctx, &handle);
+    //the last thread out reports the actor as stopped
+    if shared.running.fetch_sub(1, Ordering::SeqCst) == 1 {
+        directory::remove(id);
+        let reason = shared.reason().clone();
+        events::publish(
+            directory::system_key(&shared.shutdown),
+            SystemEvent::ActorStopped {
+                id,
+                path: shared.addr.path_arc(),
+                reason: reason.clone(),
+            },
+        );
+        shared.addr.notify_watchers(reason, None);
+        shared.finished.cancel();
+    }
+}
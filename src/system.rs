@@ -1,38 +1,162 @@
-use std::sync::Arc;
+use std::backtrace::Backtrace;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
 use std::task::Poll;
+use std::time::{Duration, SystemTime};
 
-use futures::FutureExt;
-use tokio::sync::{mpsc, Notify};
+use tokio::runtime::{EnterGuard, Handle};
+use tokio::sync::{watch, Notify};
+use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use crate::{
-    actor::ActorId, envelope::ActorMessage, registry::Registry, stream::poll_streams, Actor, Addr,
-    Context,
+    actor::{ActorId, BoxFuture, FnActor, ReceiverActor, CURRENT_ACTOR},
+    address::ErasedAddr,
+    arbiter::Arbiter,
+    dead_letter::{self, DeadLetter},
+    directory::{self, Shutdown},
+    envelope::{dispatch, ActorMessage},
+    events::{self, SystemEvent},
+    message::{ChildFailed, Terminated, TerminationReason},
+    metrics::{self, MetricsSink},
+    middleware::{self, Chain, Middleware, MiddlewareStack},
+    mailbox::{ChannelMailbox, Mailbox, OverflowMailbox, OverflowStrategy, DEFAULT_CAPACITY},
+    panic::{self, PanicHook, PanicInfo, PanicPolicy},
+    persistence::{self, PersistenceOptions, PersistentActor},
+    registry::Registry,
+    remote::{ExposedActors, RemoteMessage, RemoteServer},
+    scheduler::Scheduler,
+    selection::ActorSelection,
+    stream::poll_streams,
+    supervisor::{
+        FailureInfo, GuardianPolicy, RestartTracker, SupervisorDirective, DEFAULT_RESTART_LIMIT,
+    },
+    watcher::Watcher,
+    Actor, Addr, Context, Handler, MailboxError, Message, SupervisorStrategy,
 };
 
-use std::panic::{catch_unwind, AssertUnwindSafe};
+///How often `wait_idle` looks at the mailboxes
+const IDLE_POLL: Duration = Duration::from_millis(1);
 
 ///Actor system for managing actors and their lifecycle
 pub struct ActorSystem {
     //shared notify for graceful shutdown
-    shutdown: Arc<Notify>,
+    shutdown: Arc<Shutdown>,
     ///actor registry
     registry: Arc<Registry>,
+    ///root supervisor of top-level actors
+    guardian: Arc<Guardian>,
+    ///cron and calendar jobs
+    scheduler: Scheduler,
+    name: Arc<str>,
+    ///defaults for top-level actors, see `ActorSystemConfig`
+    mailbox_capacity: usize,
+    strategy: SupervisorStrategy,
+    runtime: Option<Handle>,
+    ///routes served by `remote_listener`
+    exposed: Arc<ExposedActors>,
 }
 
 impl ActorSystem {
+    ///create a system with every setting at its default,
+    /// the same as `ActorSystem::builder().build()`
     pub fn new() -> Self {
+        Self::with_config(ActorSystemConfig::new())
+    }
+
+    /// Configure a system before creating it, see `ActorSystemConfig`
+    pub fn builder() -> ActorSystemConfig {
+        ActorSystemConfig::new()
+    }
+
+    ///create a system whose guardian applies the given last-resort policy
+    /// when a top-level actor fails permanently
+    pub fn with_guardian(policy: GuardianPolicy) -> Self {
+        Self::with_config(ActorSystemConfig::new().guardian(policy))
+    }
+
+    ///create a system with the settings of `config`
+    pub fn with_config(config: ActorSystemConfig) -> Self {
+        panic::capture_backtraces();
+        let shutdown = Arc::new(Shutdown::new());
+        let (exit_tx, _) = watch::channel(None);
+        let guardian = Arc::new(Guardian {
+            policy: config.guardian,
+            shutdown: shutdown.clone(),
+            exit_tx,
+            shutdown_hooks: Mutex::new(Vec::new()),
+        });
+        let system = directory::system_key(&shutdown);
+        if config.panic_policy != PanicPolicy::Supervise {
+            let guardian = guardian.clone();
+            panic::set_policy(
+                system,
+                config.panic_policy,
+                Arc::new(move || guardian.terminate(1)),
+            );
+        }
+        for hook in config.panic_hooks {
+            panic::add_hook(system, hook);
+        }
+        if let Some(sink) = config.metrics_sink {
+            metrics::slot(system).set(Some(sink));
+        }
+        let name: Arc<str> = config.name.unwrap_or_else(|| "cinema".into());
+        if config.dead_letter_log > 0 {
+            log_dead_letters(name.clone(), config.dead_letter_log, &shutdown);
+        }
         Self {
-            shutdown: Arc::new(Notify::new()),
+            guardian,
+            scheduler: Scheduler::new(shutdown.clone()),
+            shutdown,
             registry: Arc::new(Registry::new()),
+            name,
+            mailbox_capacity: config.mailbox_capacity.unwrap_or(DEFAULT_CAPACITY),
+            strategy: config.supervision,
+            runtime: config.runtime,
+            exposed: Arc::new(ExposedActors::default()),
         }
     }
 
-    //spawn a top-level actor with default mailbox capacity of 256
+    /// Name given with `ActorSystemConfig::name`, "cinema" unless set
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    ///enter the configured runtime, so actor tasks are spawned there
+    fn enter(&self) -> Option<EnterGuard<'_>> {
+        self.runtime.as_ref().map(Handle::enter)
+    }
+
+    //spawn a top-level actor with the system's default mailbox capacity, 256 unless configured
     pub fn spawn<A>(&self, actor: A) -> Addr<A>
     where
         A: Actor,
     {
-        self.spawn_with_capacity(actor, 256)
+        self.spawn_with_capacity(actor, self.mailbox_capacity)
+    }
+
+    //spawn a top-level actor configured by SpawnOptions
+    pub fn spawn_with<A>(&self, actor: A, options: SpawnOptions) -> Addr<A>
+    where
+        A: Actor,
+    {
+        let mut options = options.or_defaults(self.mailbox_capacity, self.runtime.as_ref());
+        let hooks = options.hooks();
+        let _runtime = options.enter();
+        let mut actor_opt = Some(actor);
+        spawn_actor(
+            move || actor_opt.take().expect("Factory called more than once"),
+            SupervisorStrategy::Stop,
+            options.mailbox(),
+            self.shutdown.clone(),
+            Supervision::Guardian(self.guardian.clone()),
+            options.name,
+            hooks,
+        )
     }
 
     //spawn a top-level actor with custom mailbox capacity
@@ -40,12 +164,285 @@ impl ActorSystem {
     where
         A: Actor,
     {
-        spawn_with_shutdown(actor, self.shutdown.clone(), capacity)
+        self.spawn_with_mailbox(actor, ChannelMailbox::new(capacity))
+    }
+
+    //spawn a top-level actor backed by a custom mailbox implementation
+    pub fn spawn_with_mailbox<A, M>(&self, actor: A, mailbox: M) -> Addr<A>
+    where
+        A: Actor,
+        M: Mailbox<A>,
+    {
+        let _runtime = self.enter();
+        let mut actor_opt = Some(actor);
+        spawn_actor(
+            move || actor_opt.take().expect("Factory called more than once"),
+            SupervisorStrategy::Stop,
+            Arc::new(mailbox),
+            self.shutdown.clone(),
+            Supervision::Guardian(self.guardian.clone()),
+            None,
+            Hooks::default(),
+        )
+    }
+
+    //spawn a top-level actor rebuilt from the factory according to the strategy
+    //once the strategy gives up, the guardian policy applies
+    pub fn spawn_with_strategy<A, F>(&self, factory: F, strategy: SupervisorStrategy) -> Addr<A>
+    where
+        A: Actor,
+        F: FnMut() -> A + Send + 'static,
+    {
+        let _runtime = self.enter();
+        spawn_actor(
+            factory,
+            strategy,
+            Arc::new(ChannelMailbox::new(self.mailbox_capacity)),
+            self.shutdown.clone(),
+            Supervision::Guardian(self.guardian.clone()),
+            None,
+            Hooks::default(),
+        )
+    }
+
+    //spawn a top-level actor rebuilt from the factory according to the system's
+    //default strategy, see `ActorSystemConfig::supervision`
+    pub fn spawn_supervised<A, F>(&self, factory: F) -> Addr<A>
+    where
+        A: Actor,
+        F: FnMut() -> A + Send + 'static,
+    {
+        self.spawn_with_strategy(factory, self.strategy)
+    }
+
+    ///spawns a fresh top-level actor from `factory` on every call,
+    /// for pools that grow and replace their workers
+    pub(crate) fn spawner<A, F>(&self, factory: F) -> impl Fn() -> Addr<A> + Send + Sync + 'static
+    where
+        A: Actor,
+        F: Fn() -> A + Send + Sync + 'static,
+    {
+        let capacity = self.mailbox_capacity;
+        self.spawner_with_mailbox(factory, move || {
+            Arc::new(ChannelMailbox::new(capacity)) as Arc<dyn Mailbox<A>>
+        })
+    }
+
+    ///`spawner` whose actors get their mailbox from `mailbox`
+    pub(crate) fn spawner_with_mailbox<A, F, M>(
+        &self,
+        factory: F,
+        mailbox: M,
+    ) -> impl Fn() -> Addr<A> + Send + Sync + 'static
+    where
+        A: Actor,
+        F: Fn() -> A + Send + Sync + 'static,
+        M: Fn() -> Arc<dyn Mailbox<A>> + Send + Sync + 'static,
+    {
+        let shutdown = self.shutdown.clone();
+        let guardian = self.guardian.clone();
+        let runtime = self.runtime.clone();
+        move || {
+            let _runtime = runtime.as_ref().map(Handle::enter);
+            let mut actor_opt = Some(factory());
+            spawn_actor(
+                move || actor_opt.take().expect("Factory called more than once"),
+                SupervisorStrategy::Stop,
+                mailbox(),
+                shutdown.clone(),
+                Supervision::Guardian(guardian.clone()),
+                None,
+                Hooks::default(),
+            )
+        }
+    }
+
+    ///default mailbox bound of top-level actors, see `ActorSystemConfig::mailbox_capacity`
+    pub(crate) fn mailbox_capacity(&self) -> usize {
+        self.mailbox_capacity
+    }
+
+    /// Spawn a persistent actor, rebuilt from its history before `started`,
+    /// see `PersistentActor`. Takes a journal or `PersistenceOptions`
+    pub fn spawn_persistent<A>(&self, actor: A, options: impl Into<PersistenceOptions>) -> Addr<A>
+    where
+        A: PersistentActor,
+    {
+        self.spawn_persistent_with(actor, SpawnOptions::default(), options)
+    }
+
+    /// `spawn_with` for persistent actors
+    pub fn spawn_persistent_with<A>(
+        &self,
+        actor: A,
+        options: SpawnOptions,
+        persistence: impl Into<PersistenceOptions>,
+    ) -> Addr<A>
+    where
+        A: PersistentActor,
+    {
+        let mut options = options.or_defaults(self.mailbox_capacity, self.runtime.as_ref());
+        let mut hooks = options.hooks();
+        hooks.start = Some(persistence::recovery(persistence.into()));
+        let _runtime = options.enter();
+        let mut actor_opt = Some(actor);
+        spawn_actor(
+            move || actor_opt.take().expect("Factory called more than once"),
+            SupervisorStrategy::Stop,
+            options.mailbox(),
+            self.shutdown.clone(),
+            Supervision::Guardian(self.guardian.clone()),
+            options.name,
+            hooks,
+        )
+    }
+
+    /// `spawn_with_strategy` for persistent actors, every incarnation the
+    /// factory builds replays the journal first
+    pub fn spawn_persistent_with_strategy<A, F>(
+        &self,
+        factory: F,
+        strategy: SupervisorStrategy,
+        options: impl Into<PersistenceOptions>,
+    ) -> Addr<A>
+    where
+        A: PersistentActor,
+        F: FnMut() -> A + Send + 'static,
+    {
+        let _runtime = self.enter();
+        spawn_actor(
+            factory,
+            strategy,
+            Arc::new(ChannelMailbox::new(self.mailbox_capacity)),
+            self.shutdown.clone(),
+            Supervision::Guardian(self.guardian.clone()),
+            None,
+            Hooks {
+                middleware: MiddlewareStack::default(),
+                start: Some(persistence::recovery(options.into())),
+                timeouts: HashMap::new(),
+            },
+        )
+    }
+
+    //spawn an anonymous actor from a state value and a handler closure
+    pub fn spawn_fn<S, M, F>(&self, state: S, handler: F) -> Addr<FnActor<S, M>>
+    where
+        S: Send + 'static,
+        M: Message,
+        F: FnMut(&mut S, M, &mut Context<FnActor<S, M>>) -> M::Result + Send + 'static,
+    {
+        self.spawn(FnActor::new(state, handler))
+    }
+
+    //spawn an anonymous actor whose received messages come out of the returned stream
+    //lets non-actor code receive replies or events through a normal Addr
+    pub fn spawn_receiver<M>(&self) -> (Addr<ReceiverActor<M>>, UnboundedReceiverStream<M>)
+    where
+        M: Message<Result = ()>,
+    {
+        let (actor, stream) = ReceiverActor::new();
+        (self.spawn(actor), stream)
     }
 
     //gracefully shutdown the actor system
+    //returns right away, the actors stop once the `on_shutdown` hooks finished
     pub fn shutdown(&self) {
-        self.shutdown.notify_waiters();
+        self.guardian.stop_all();
+    }
+
+    /// Make `addr` reachable from other nodes as `actor_name` for messages of
+    /// type `M`: registers `M`, builds its handler and routes envelopes
+    /// targeting `actor_name` to it. Envelopes for `actor_name` of a type it
+    /// was not exposed for go to the actor's `unhandled` hook. Routes can be
+    /// added before or after `remote_listener` starts serving
+    pub fn expose<M, A>(&self, actor_name: &str, addr: Addr<A>)
+    where
+        A: Actor + Handler<M>,
+        M: RemoteMessage,
+        M::Result: RemoteMessage,
+    {
+        self.exposed.expose::<A, M>(actor_name, addr, &self.name);
+    }
+
+    /// Serve the actors given to `expose` on `addr`, e.g. "0.0.0.0:9000",
+    /// with this system's metrics. Returns the bound address, which tells
+    /// the port picked for ":0". The listener stops accepting connections
+    /// when the system shuts down, before any actor is stopped
+    pub async fn remote_listener(&self, addr: &str) -> std::io::Result<std::net::SocketAddr> {
+        let server = RemoteServer::bind(addr, self.exposed.handler())
+            .await?
+            .metrics(self);
+        let local = server.local_addr()?;
+        let serving = {
+            let _runtime = self.enter();
+            tokio::spawn(server.run())
+        };
+        self.on_shutdown(move || async move { serving.abort() });
+        Ok(local)
+    }
+
+    /// Await `hook` when the system shuts down, before any actor is stopped,
+    /// e.g. to stop an HTTP server or a queue consumer from taking new work
+    /// Hooks run once, one after the other in registration order, on
+    /// `shutdown`, `shutdown_graceful` or a guardian terminating the system
+    pub fn on_shutdown<F, Fut>(&self, hook: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hook: ShutdownHook = Box::new(move || Box::pin(hook()));
+        self.guardian.shutdown_hooks.lock().unwrap_or_else(|p| p.into_inner()).push(hook);
+    }
+
+    /// Stop every actor of this system after it has handled its queued mail
+    /// The `on_shutdown` hooks are awaited first. Then mailboxes stop accepting
+    /// messages; each actor drains what is queued, runs its `stopped` hooks and
+    /// stops. Actors still running after `timeout` are stopped forcefully and
+    /// listed in the report
+    pub async fn shutdown_graceful(&self, timeout: Duration) -> ShutdownReport {
+        run_shutdown_hooks(self.guardian.take_shutdown_hooks()).await;
+        let members = directory::members(directory::system_key(&self.shutdown));
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for member in &members {
+            member.add_watcher(Arc::new(TerminationSender(tx.clone())));
+        }
+        drop(tx);
+        for member in &members {
+            member.close();
+        }
+
+        let mut report = ShutdownReport::default();
+        let _ = tokio::time::timeout(timeout, async {
+            while let Some(id) = rx.recv().await {
+                report.stopped.push(id);
+            }
+        })
+        .await;
+
+        for member in members {
+            if !report.stopped.contains(&member.id()) {
+                member.stop();
+                report.timed_out.push(member.id());
+            }
+        }
+        report
+    }
+
+    /// Exit status set when the guardian terminated the system, None while running
+    pub fn exit_code(&self) -> Option<i32> {
+        *self.guardian.exit_tx.borrow()
+    }
+
+    /// Wait until the guardian terminates the system, returning its exit status
+    pub async fn terminated(&self) -> i32 {
+        let mut rx = self.guardian.exit_tx.subscribe();
+        let code = match rx.wait_for(|code| code.is_some()).await {
+            Ok(code) => *code,
+            Err(_) => None,
+        };
+        code.unwrap_or_default()
     }
 
     /// Register actor by name (auto-unregisters when actor dies)
@@ -65,6 +462,124 @@ impl ActorSystem {
     pub fn unregister(&self, name: &str) {
         self.registry.unregister(name);
     }
+
+    /// Deliver every dead letter of this system's actors to `addr` until it stops
+    pub fn subscribe_dead_letters<A>(&self, addr: &Addr<A>)
+    where
+        A: Actor + Handler<DeadLetter>,
+    {
+        //the subscription alone doesn't keep the subscriber alive
+        let addr = addr.uncounted();
+        let system = directory::system_key(&self.shutdown);
+        dead_letter::subscribe(system, Box::new(move |letter| {
+            addr.try_send_quiet(letter.clone()) != Err(MailboxError::MailboxClosed)
+        }));
+    }
+
+    /// Stream of dead letters of this system's actors, ends when the system
+    /// is dropped
+    pub fn dead_letters(&self) -> UnboundedReceiverStream<DeadLetter> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let system = directory::system_key(&self.shutdown);
+        dead_letter::subscribe(system, Box::new(move |letter| tx.send(letter.clone()).is_ok()));
+        UnboundedReceiverStream::new(rx)
+    }
+
+    ///signal broadcast by `shutdown`, for actors run outside spawn_actor
+    pub(crate) fn shutdown_signal(&self) -> Arc<Shutdown> {
+        self.shutdown.clone()
+    }
+
+    /// Scheduler delivering messages on cron or calendar schedules
+    pub fn scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+
+    /// Find a live actor of this system by id (from a Terminated message, a
+    /// log line, ...); actors of other systems in the process aren't found
+    pub fn resolve(&self, id: ActorId) -> Option<ErasedAddr> {
+        directory::lookup(id.sequence(), directory::system_key(&self.shutdown))
+    }
+
+    /// Actors of this system whose path matches `pattern`, e.g. `/user/ingest/*`
+    pub fn select(&self, pattern: &str) -> ActorSelection {
+        ActorSelection::new(pattern, directory::system_key(&self.shutdown))
+    }
+
+    /// Point-in-time view of this system's running actors, sorted by path
+    /// Counters are read one actor at a time, so the view isn't atomic
+    pub fn snapshot(&self) -> Vec<ActorSnapshot> {
+        let members = directory::members(directory::system_key(&self.shutdown));
+        let mut actors: Vec<ActorSnapshot> = members.iter().map(ActorSnapshot::of).collect();
+        actors.sort_by(|a, b| a.path.cmp(&b.path));
+        actors
+    }
+
+    /// Wait until every mailbox of this system is empty and no handler is
+    /// running, false if that didn't happen within `timeout`
+    /// A barrier for tests instead of sleeping; pending timers, streams and
+    /// work spawned outside the actors don't count as activity
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let system = directory::system_key(&self.shutdown);
+        tokio::time::timeout(timeout, async {
+            //idle twice in a row with nothing handled in between, so a message
+            // caught between leaving its mailbox and reaching its handler isn't missed
+            let mut quiet = None;
+            loop {
+                let members = directory::members(system);
+                let processed = members
+                    .iter()
+                    .all(ErasedAddr::is_idle)
+                    .then(|| members.iter().map(ErasedAddr::messages_processed).sum::<u64>());
+                if processed.is_some() && processed == quiet {
+                    return;
+                }
+                quiet = processed;
+                tokio::time::sleep(IDLE_POLL).await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+
+    /// Stream of lifecycle events of this system's actors, ends when the system is dropped
+    /// Only events after the call are delivered
+    pub fn events(&self) -> UnboundedReceiverStream<SystemEvent> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        events::subscribe(directory::system_key(&self.shutdown), tx);
+        UnboundedReceiverStream::new(rx)
+    }
+
+    /// Report per-message measurements of this system's actors to `sink`,
+    /// replacing any sink installed before
+    pub fn set_metrics_sink<S: MetricsSink>(&self, sink: S) {
+        metrics::slot(directory::system_key(&self.shutdown)).set(Some(Arc::new(sink)));
+    }
+
+    /// Stop reporting measurements
+    pub fn clear_metrics_sink(&self) {
+        metrics::slot(directory::system_key(&self.shutdown)).set(None);
+    }
+
+    pub(crate) fn metrics_slot(&self) -> Arc<metrics::MetricsSlot> {
+        metrics::slot(directory::system_key(&self.shutdown))
+    }
+
+    /// Wrap the handlers of every actor of this system in `layer`,
+    /// outside of the actors' own middleware
+    pub fn add_middleware<M: Middleware>(&self, layer: M) {
+        middleware::system_layers(directory::system_key(&self.shutdown)).add(Arc::new(layer));
+    }
+
+    /// Call `hook` whenever a handler of one of this system's actors panics,
+    /// restarted or not, e.g. to raise an alert
+    /// Hooks run on the panicking actor's task and should return quickly
+    pub fn on_actor_panic<F>(&self, hook: F)
+    where
+        F: Fn(&PanicInfo) + Send + Sync + 'static,
+    {
+        panic::add_hook(directory::system_key(&self.shutdown), Arc::new(hook));
+    }
 }
 
 impl Default for ActorSystem {
@@ -73,105 +588,958 @@ impl Default for ActorSystem {
     }
 }
 
-fn spawn_with_shutdown<A>(mut actor: A, shutdown: Arc<Notify>, capacity: usize) -> Addr<A>
+impl Drop for ActorSystem {
+    fn drop(&mut self) {
+        panic::remove_hooks(directory::system_key(&self.shutdown));
+        events::remove_subscribers(directory::system_key(&self.shutdown));
+        dead_letter::remove_subscribers(directory::system_key(&self.shutdown));
+        metrics::remove_slot(directory::system_key(&self.shutdown));
+        middleware::remove_system_layers(directory::system_key(&self.shutdown));
+    }
+}
+
+/// Outcome of `ActorSystem::shutdown_graceful`
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownReport {
+    /// Actors that drained their mailbox and stopped in time
+    pub stopped: Vec<ActorId>,
+    /// Actors still running at the deadline, stopped forcefully
+    pub timed_out: Vec<ActorId>,
+}
+
+impl ShutdownReport {
+    /// True if every actor stopped within the deadline
+    pub fn is_clean(&self) -> bool {
+        self.timed_out.is_empty()
+    }
+}
+
+/// One running actor in `ActorSystem::snapshot`
+#[derive(Debug, Clone)]
+pub struct ActorSnapshot {
+    pub id: ActorId,
+    ///name given with `SpawnOptions::name`, if any
+    pub name: Option<Arc<str>>,
+    ///place in the supervision tree, see `Addr::path`
+    pub path: Arc<str>,
+    pub actor_type: &'static str,
+    ///approximate number of queued messages
+    pub mailbox_len: usize,
+    pub messages_processed: u64,
+    ///when the actor last finished handling a message, None if it never has
+    pub last_active: Option<SystemTime>,
+}
+
+impl ActorSnapshot {
+    fn of(addr: &ErasedAddr) -> Self {
+        Self {
+            id: addr.id(),
+            name: addr.name().map(Arc::from),
+            path: Arc::from(addr.path()),
+            actor_type: addr.actor_type(),
+            mailbox_len: addr.mailbox_len(),
+            messages_processed: addr.messages_processed(),
+            last_active: addr.last_active(),
+        }
+    }
+}
+
+///reports a terminated actor to `shutdown_graceful`
+struct TerminationSender(tokio::sync::mpsc::UnboundedSender<ActorId>);
+
+impl Watcher for TerminationSender {
+    fn notify(&self, msg: Terminated) {
+        let _ = self.0.send(msg.id);
+    }
+}
+
+///Per-actor settings for `ActorSystem::spawn_with` and `Context::spawn_child_with`
+#[derive(Debug, Clone)]
+pub struct SpawnOptions {
+    ///None for the default, `DEFAULT_CAPACITY` or the system's
+    capacity: Option<usize>,
+    overflow: OverflowStrategy,
+    pub(crate) name: Option<Arc<str>>,
+    arbiter: Option<Handle>,
+    pub(crate) middleware: MiddlewareStack,
+    ///message type name -> longest its handler may run
+    handler_timeouts: HashMap<&'static str, Duration>,
+}
+
+impl SpawnOptions {
+    pub fn new() -> Self {
+        Self {
+            capacity: None,
+            overflow: OverflowStrategy::Block,
+            name: None,
+            arbiter: None,
+            middleware: MiddlewareStack::default(),
+            handler_timeouts: HashMap::new(),
+        }
+    }
+
+    /// Bound of the actor's mailbox, senders wait (or get MailboxFull) beyond it
+    pub fn mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// What to do with messages arriving while the mailbox is full
+    pub fn overflow(mut self, strategy: OverflowStrategy) -> Self {
+        self.overflow = strategy;
+        self
+    }
+
+    /// Name shown in logs, on the Addr and in `Terminated`
+    /// Panics if it contains '/', which separates the segments of actor paths
+    pub fn name(mut self, name: impl Into<Arc<str>>) -> Self {
+        let name = name.into();
+        assert!(
+            !name.contains('/'),
+            "actor name {:?} contains '/', the actor path separator",
+            name
+        );
+        self.name = Some(name);
+        self
+    }
+
+    /// Run the actor on an arbiter's runtime instead of the current one
+    pub fn arbiter(mut self, arbiter: &Arbiter) -> Self {
+        self.arbiter = Some(arbiter.handle().clone());
+        self
+    }
+
+    /// Wrap the actor's handlers in `layer`, after any layer added before
+    pub fn middleware<M: Middleware>(mut self, layer: M) -> Self {
+        self.middleware.push(Arc::new(layer));
+        self
+    }
+
+    /// Fail the actor when its handler for `M` runs longer than `limit`
+    /// The handler's future is dropped where it awaits, the sender's request
+    /// fails and the failure goes to the supervisor like a panic would;
+    /// a restarted actor goes on with the next message. Only async handlers
+    /// can be cut short, handlers that return without awaiting run to the end
+    pub fn handler_timeout<M: Message>(mut self, limit: Duration) -> Self {
+        self.handler_timeouts.insert(std::any::type_name::<M>(), limit);
+        self
+    }
+
+    ///take the middleware and handler timeouts, for `spawn_actor`
+    pub(crate) fn hooks<A: Actor>(&mut self) -> Hooks<A> {
+        Hooks {
+            middleware: std::mem::take(&mut self.middleware),
+            start: None,
+            timeouts: std::mem::take(&mut self.handler_timeouts),
+        }
+    }
+
+    ///enter the arbiter's runtime, so the actor task is spawned there
+    pub(crate) fn enter(&self) -> Option<EnterGuard<'_>> {
+        self.arbiter.as_ref().map(Handle::enter)
+    }
+
+    ///fill in the capacity and runtime left unset with a system's defaults
+    pub(crate) fn or_defaults(mut self, capacity: usize, runtime: Option<&Handle>) -> Self {
+        self.capacity.get_or_insert(capacity);
+        if self.arbiter.is_none() {
+            self.arbiter = runtime.cloned();
+        }
+        self
+    }
+
+    pub(crate) fn mailbox<A: Actor>(&self) -> Arc<dyn Mailbox<A>> {
+        let capacity = self.capacity.unwrap_or(DEFAULT_CAPACITY);
+        match self.overflow {
+            OverflowStrategy::Block => Arc::new(ChannelMailbox::new(capacity)),
+            strategy => Arc::new(OverflowMailbox::new(capacity, strategy)),
+        }
+    }
+}
+
+impl Default for SpawnOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// System-wide settings, built up from `ActorSystem::builder()`:
+///
+/// ```ignore
+/// let system = ActorSystem::builder()
+///     .name("ingest")
+///     .mailbox_capacity(1024)
+///     .metrics_sink(Prometheus::new())
+///     .build();
+/// ```
+#[derive(Clone, Default)]
+pub struct ActorSystemConfig {
+    name: Option<Arc<str>>,
+    mailbox_capacity: Option<usize>,
+    supervision: SupervisorStrategy,
+    dead_letter_log: usize,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    runtime: Option<Handle>,
+    guardian: GuardianPolicy,
+    panic_policy: PanicPolicy,
+    panic_hooks: Vec<PanicHook>,
+}
+
+impl ActorSystemConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the system, the same as `ActorSystem::with_config`
+    pub fn build(self) -> ActorSystem {
+        ActorSystem::with_config(self)
+    }
+
+    /// Name of the system, shown in its logs and by `ActorSystem::name`
+    pub fn name(mut self, name: impl Into<Arc<str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Mailbox bound of top-level actors spawned without one, instead of
+    /// `DEFAULT_CAPACITY`
+    pub fn mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_capacity = Some(capacity);
+        self
+    }
+
+    /// Strategy of top-level actors spawned with `ActorSystem::spawn_supervised`
+    pub fn supervision(mut self, strategy: SupervisorStrategy) -> Self {
+        self.supervision = strategy;
+        self
+    }
+
+    /// Print the first `limit` dead letters of the system's actors to
+    /// stderr, none by default
+    pub fn log_dead_letters(mut self, limit: usize) -> Self {
+        self.dead_letter_log = limit;
+        self
+    }
+
+    /// Report measurements to `sink` from the start, see `ActorSystem::set_metrics_sink`
+    pub fn metrics_sink<S: MetricsSink>(mut self, sink: S) -> Self {
+        self.metrics_sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Run top-level actors on `runtime` instead of the one they're spawned
+    /// from, unless `SpawnOptions::arbiter` says otherwise
+    pub fn runtime(mut self, runtime: Handle) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// Last-resort policy for top-level actors that fail permanently
+    pub fn guardian(mut self, policy: GuardianPolicy) -> Self {
+        self.guardian = policy;
+        self
+    }
+
+    /// What happens to an actor whose handler panics, see `PanicPolicy`
+    pub fn panic_policy(mut self, policy: PanicPolicy) -> Self {
+        self.panic_policy = policy;
+        self
+    }
+
+    /// Call `hook` on every handler panic, before the policy applies; the
+    /// same as `ActorSystem::on_actor_panic`, in place before any actor runs
+    pub fn on_panic<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&PanicInfo) + Send + Sync + 'static,
+    {
+        self.panic_hooks.push(Arc::new(hook));
+        self
+    }
+}
+
+///print up to `limit` dead letters of the system's actors
+fn log_dead_letters(name: Arc<str>, limit: usize, shutdown: &Arc<Shutdown>) {
+    let logged = AtomicUsize::new(0);
+    dead_letter::subscribe(directory::system_key(shutdown), Box::new(move |letter| {
+        let count = logged.fetch_add(1, Ordering::Relaxed) + 1;
+        eprintln!(
+            "{}: dead letter {} to {} ({}/{})",
+            name, letter.message_type, letter.recipient_path, count, limit
+        );
+        if count == limit {
+            eprintln!("{}: further dead letters are not logged", name);
+        }
+        count < limit
+    }));
+}
+
+///Root supervisor applying the last-resort policy to top-level actors
+pub(crate) struct Guardian {
+    policy: GuardianPolicy,
+    shutdown: Arc<Shutdown>,
+    exit_tx: watch::Sender<Option<i32>>,
+    ///see `ActorSystem::on_shutdown`, taken by the first shutdown
+    shutdown_hooks: Mutex<Vec<ShutdownHook>>,
+}
+
+///Async closure given to `ActorSystem::on_shutdown`
+type ShutdownHook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+///await the hooks one after the other, in registration order
+async fn run_shutdown_hooks(hooks: Vec<ShutdownHook>) {
+    for hook in hooks {
+        hook().await;
+    }
+}
+
+impl Guardian {
+    fn take_shutdown_hooks(&self) -> Vec<ShutdownHook> {
+        std::mem::take(&mut *self.shutdown_hooks.lock().unwrap_or_else(|p| p.into_inner()))
+    }
+
+    ///stop every actor of the system once the shutdown hooks finished
+    fn stop_all(&self) {
+        let hooks = self.take_shutdown_hooks();
+        if hooks.is_empty() {
+            self.shutdown.notify_waiters();
+            return;
+        }
+        let shutdown = self.shutdown.clone();
+        tokio::spawn(async move {
+            run_shutdown_hooks(hooks).await;
+            shutdown.notify_waiters();
+        });
+    }
+
+    ///apply the policy to a permanently failed top-level actor
+    ///returns whether the actor's children should be stopped
+    fn on_failure(&self, id: ActorId) -> bool {
+        match self.policy {
+            GuardianPolicy::StopActor => {
+                eprintln!("Guardian: top-level actor {:?} failed. Stopping actor.", id);
+                false
+            }
+            GuardianPolicy::StopSubtree => {
+                eprintln!("Guardian: top-level actor {:?} failed. Stopping subtree.", id);
+                true
+            }
+            GuardianPolicy::TerminateSystem { exit_code } => {
+                eprintln!(
+                    "Guardian: top-level actor {:?} failed. Terminating system with status {}.",
+                    id, exit_code
+                );
+                self.terminate(exit_code);
+                true
+            }
+        }
+    }
+
+    ///stop every actor of the system, `terminated` resolves with `exit_code`
+    fn terminate(&self, exit_code: i32) {
+        self.exit_tx.send_replace(Some(exit_code));
+        self.stop_all();
+    }
+}
+
+///Who an actor reports permanent failures to
+pub(crate) enum Supervision {
+    ///top-level actor supervised by the system guardian
+    Guardian(Arc<Guardian>),
+    ///child actor, escalations are reported to the parent as ChildFailed
+    Parent {
+        id: ActorId,
+        report: Arc<dyn Fn(ChildFailed) + Send + Sync>,
+        ///tells the parent the child restarted after a failure
+        restarted: Arc<dyn Fn(ActorId) + Send + Sync>,
+        ///the parent asks the child to start over (AllForOne)
+        restart: Arc<Notify>,
+        ///asks a `Supervisor` parent what to do with a failure, None if the
+        /// strategy decides
+        decide: Option<Decide>,
+        ///path of the parent, the child's path extends it
+        path: Arc<str>,
+    },
+}
+
+///Directive of a `Supervisor` parent for a failed child, None once the
+/// parent is gone
+pub(crate) type Decide =
+    Arc<dyn Fn(ActorId, FailureInfo) -> BoxFuture<'static, Option<SupervisorDirective>> + Send + Sync>;
+
+///Run on every start of an actor, before `started`
+pub(crate) type StartHook<A> =
+    Arc<dyn for<'a> Fn(&'a mut A, &'a mut Context<A>) -> BoxFuture<'a, ()> + Send + Sync>;
+
+///pin a closure to the signature of `StartHook`
+pub(crate) fn start_hook<A, F>(hook: F) -> StartHook<A>
 where
     A: Actor,
+    F: for<'a> Fn(&'a mut A, &'a mut Context<A>) -> BoxFuture<'a, ()> + Send + Sync + 'static,
 {
-    let (tx, mut rx) = mpsc::channel::<ActorMessage<A>>(capacity);
-    let id = ActorId::new();
+    Arc::new(hook)
+}
+
+///Code run around an actor besides its own lifecycle methods
+pub(crate) struct Hooks<A: Actor> {
+    pub(crate) middleware: MiddlewareStack,
+    ///e.g. journal recovery, see `PersistentActor`
+    pub(crate) start: Option<StartHook<A>>,
+    ///see `SpawnOptions::handler_timeout`
+    pub(crate) timeouts: HashMap<&'static str, Duration>,
+}
+
+impl<A: Actor> Default for Hooks<A> {
+    fn default() -> Self {
+        MiddlewareStack::default().into()
+    }
+}
+
+impl<A: Actor> From<MiddlewareStack> for Hooks<A> {
+    fn from(middleware: MiddlewareStack) -> Self {
+        Self {
+            middleware,
+            start: None,
+            timeouts: HashMap::new(),
+        }
+    }
+}
+
+///Spawn the mailbox loop for an actor built from the factory
+/// Restarts reuse the same mailbox and Addr, so holders never re-resolve
+pub(crate) fn spawn_actor<A, F>(
+    mut factory: F,
+    strategy: SupervisorStrategy,
+    mailbox: Arc<dyn Mailbox<A>>,
+    shutdown: Arc<Shutdown>,
+    supervision: Supervision,
+    name: Option<Arc<str>>,
+    hooks: Hooks<A>,
+) -> Addr<A>
+where
+    A: Actor,
+    F: FnMut() -> A + Send + 'static,
+{
+    let id = match &supervision {
+        Supervision::Guardian(_) => ActorId::new(),
+        Supervision::Parent { id, .. } => ActorId::child_of(*id),
+    };
+    let id = match &name {
+        Some(name) => id.named(name),
+        None => id,
+    };
 
     let stop_signal = Arc::new(Notify::new());
 
-    let addr = Addr::new(tx, id, stop_signal.clone());
+    let parent_path = match &supervision {
+        Supervision::Guardian(_) => "/user",
+        Supervision::Parent { path, .. } => path,
+    };
+    let path = match &name {
+        Some(name) => format!("{}/{}", parent_path, name),
+        None => format!("{}/{}", parent_path, id.sequence()),
+    };
+
+    let addr = Addr::new(mailbox.clone(), id, stop_signal.clone())
+        .with_name(name)
+        .with_path(path.into())
+        .with_system(directory::system_key(&shutdown));
+
+    //the actor's own handle must not keep it alive
+    let addr_for_notify = addr.uncounted();
+    //children live as long as their parent decides, whoever holds their Addr
+    let top_level = matches!(supervision, Supervision::Guardian(_));
+    let system = directory::system_key(&shutdown);
+    directory::insert(addr.uncounted().into(), system);
+    let mut ending = Ending {
+        addr: addr.uncounted(),
+        system,
+        done: false,
+    };
+    events::publish(
+        system,
+        SystemEvent::ActorSpawned {
+            id,
+            path: addr.path_arc(),
+            actor_type: std::any::type_name::<A>(),
+        },
+    );
+
+    let metrics_slot = metrics::slot(system);
+    let chain = Chain::new(system, hooks.middleware);
+    let start_hook = hooks.start;
+    let timeouts = hooks.timeouts;
+
+    let restart_signal = match &supervision {
+        Supervision::Parent { restart, .. } => restart.clone(),
+        Supervision::Guardian(_) => Arc::new(Notify::new()),
+    };
 
-    let mut ctx = Context::with_stop_signal(addr.clone(), stop_signal.clone(), shutdown.clone());
+    tokio::spawn(CURRENT_ACTOR.scope(id, async move {
+        let mut tracker = strategy
+            .restart_limit()
+            .map(|(max_restarts, within)| RestartTracker::new(max_restarts, within));
 
-    let addr_for_notify = addr.clone();
+        let final_reason;
+        let mut final_backtrace = None;
+        //holding a message, see `ActorSystem::wait_idle`
+        let mut handling = false;
+        //restarts after failures so far, for `FailureInfo`
+        let mut restart_count = 0;
+        //mail set aside by `Context::receive_only`, kept across restarts
+        let mut deferred: VecDeque<ActorMessage<A>> = VecDeque::new();
+        'restart: loop {
+            let mut actor = factory();
+            let mut ctx = Context::with_stop_signal(
+                addr_for_notify.clone(),
+                stop_signal.clone(),
+                shutdown.clone(),
+            );
 
-    tokio::spawn(async move {
-        //actor lifecycle start
-        actor.started(&mut ctx);
+            //actor lifecycle start
+            if let Some(hook) = &start_hook {
+                hook(&mut actor, &mut ctx).await;
+            }
+            actor.started(&mut ctx);
+            actor.started_async(&mut ctx).await;
 
-        let escalate_signal = ctx.escalate_signal();
+            let escalate_signal = ctx.escalate_signal();
 
-        // Streams are managed outside select to avoid borrow conflicts
-        let mut streams = Vec::new();
+            // Streams are managed outside select to avoid borrow conflicts
+            let mut streams = Vec::new();
+            let mut restart_requested = false;
 
-        let panic_occured = loop {
-            // Grab any new streams added during last iteration
-            streams.append(&mut ctx.take_streams());
+            //Err(panic) when the instance failed
+            let outcome: Result<TerminationReason, PanicInfo> = loop {
+                //ctx.wait holds back mail and streams until its future resolves
+                if let Some(waiting) = ctx.take_wait() {
+                    tokio::select! {
+                        _ = waiting => continue,
+                        _ = shutdown.notified() => break Ok(TerminationReason::Killed),
+                        _ = stop_signal.notified() => break Ok(stop_reason(&addr_for_notify)),
+                    }
+                }
 
-            // Create stream polling future (only if we have streams)
-            let stream_poll = std::future::poll_fn(|task_ctx| {
-                if streams.is_empty() {
-                    // No streams, never ready (will be ignored by select)
-                    Poll::Pending
-                } else if poll_streams(&mut streams, &mut actor, &mut ctx, task_ctx) {
-                    Poll::Ready(())
-                } else {
-                    Poll::Pending
+                // Grab any new streams added during last iteration
+                streams.append(&mut ctx.take_streams());
+                let idle = streams.is_empty();
+                //messages the actor sent itself with ctx.notify go first,
+                //then set-aside mail it accepts again
+                let notification = ctx.take_notification().or_else(|| {
+                    let index = deferred.iter().position(|msg| ctx.accepts(msg.message_type()))?;
+                    deferred.remove(index)
+                });
+                if notification.is_none() && handling {
+                    addr_for_notify.end_handling();
+                    handling = false;
                 }
-            });
-
-            tokio::select! {
-                biased; // Prioritize messages over streams
-
-                msg = rx.recv() => {
-                    match msg {
-                        Some(actor_msg) => {
-                            let result = match actor_msg {
-                                ActorMessage::Sync(envelope) => {
-                                    catch_unwind(AssertUnwindSafe(|| {
-                                        envelope.handle(&mut actor, &mut ctx)
-                                    }))
+
+                // Create stream polling future (only if we have streams)
+                let stream_poll = std::future::poll_fn(|task_ctx| {
+                    if streams.is_empty() {
+                        // No streams, never ready (will be ignored by select)
+                        Poll::Pending
+                    } else if poll_streams(&mut streams, &mut actor, &mut ctx, task_ctx) {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                });
+
+                let next = async {
+                    match notification {
+                        Some(msg) => Some(msg),
+                        //no boxed dequeue future while mail is waiting
+                        None => match mailbox.try_dequeue() {
+                            Some(msg) => Some(msg),
+                            None => mailbox.dequeue().await,
+                        },
+                    }
+                };
+
+                tokio::select! {
+                    biased; // Prioritize messages over streams
+
+                    msg = next => {
+                        match msg {
+                            Some(mut actor_msg) => {
+                                if !ctx.accepts(actor_msg.message_type()) {
+                                    deferred.push_back(actor_msg);
+                                    continue;
+                                }
+                                if !handling {
+                                    addr_for_notify.begin_handling();
+                                    handling = true;
+                                }
+                                let message_type = actor_msg.message_type();
+                                let path = addr_for_notify.path();
+                                let Some(layers) = chain.before(&mut actor_msg, id, path) else {
+                                    continue;
+                                };
+                                let measurement = metrics_slot.start(&actor_msg, mailbox.len());
+                                let token = ctx.cancellation_token();
+                                let grace = ctx.stop_grace();
+                                let limit = timeouts.get(message_type).copied();
+                                let (result, stopping) = {
+                                    let mut handling =
+                                        std::pin::pin!(dispatch_within(limit, actor_msg, &mut actor, &mut ctx));
+
+                                    //a stop arriving mid-handler cancels it instead of waiting forever
+                                    let stop_requested = async {
+                                        tokio::select! {
+                                            _ = shutdown.notified() => TerminationReason::Killed,
+                                            _ = stop_signal.notified() => stop_reason(&addr_for_notify),
+                                        }
+                                    };
+                                    let finished = tokio::select! {
+                                        biased;
+                                        result = &mut handling => Ok(result),
+                                        reason = stop_requested => Err(reason),
+                                    };
+
+                                    match finished {
+                                        Ok(result) => (result, None),
+                                        Err(reason) => {
+                                            token.cancel();
+                                            let result = match grace {
+                                                //killed: dropped where it awaits
+                                                _ if addr_for_notify.is_killed() => Ok(()),
+                                                Some(grace) => tokio::time::timeout(grace, handling)
+                                                    .await
+                                                    .unwrap_or(Ok(())),
+                                                None => handling.await,
+                                            };
+                                            (result, Some(reason))
+                                        }
+                                    }
+                                };
+
+                                if let Some(measurement) = measurement {
+                                    measurement.finish::<A>(id, addr_for_notify.path());
+                                }
+                                addr_for_notify.record_activity();
+                                if result.is_ok() {
+                                    Chain::after(&layers, id, addr_for_notify.path(), message_type);
                                 }
-                                ActorMessage::Async(envelope) => {
-                                    let fut = envelope.handle(&mut actor, &mut ctx);
-                                    AssertUnwindSafe(fut).catch_unwind().await
+                                if let Err(info) = result {
+                                    panic::report(system, &info);
+                                    events::publish(
+                                        system,
+                                        SystemEvent::ActorPanicked {
+                                            id,
+                                            path: info.path.clone(),
+                                            message: info.message.clone(),
+                                        },
+                                    );
+                                    break Err(info);
+                                }
+                                if let Some(child) = ctx.take_escalation() {
+                                    let message = format!("child {} failed: {}", child.id, child.error);
+                                    break Err(escalated(&addr_for_notify, message));
+                                }
+                                if let Some(reason) = stopping {
+                                    break Ok(reason);
                                 }
-                            };
-                            if result.is_err() {
-                                break true;
                             }
-                        }
-                        None => {
-                            break false;
+                            None => break Ok(TerminationReason::Normal),
                         }
                     }
+                    _ = stream_poll => {
+                        // Stream item was handled inside poll_streams
+                        // Continue to check for more items or messages
+                        continue;
+                    }
+                    _ = shutdown.notified() => break Ok(TerminationReason::Killed),
+                    _ = stop_signal.notified() => break Ok(stop_reason(&addr_for_notify)),
+                    //every Addr dropped and nothing else to drive the actor
+                    _ = addr_for_notify.released(), if top_level && idle => {
+                        break Ok(TerminationReason::MailboxDropped)
+                    }
+                    _ = restart_signal.notified() => {
+                        //a sibling failed under AllForOne
+                        restart_requested = true;
+                        break Ok(TerminationReason::Normal);
+                    }
+                    _ = escalate_signal.notified() => {
+                        //a child escalated, treat it as our own failure
+                        eprintln!("Actor {} received escalation from child.", label(&addr_for_notify));
+                        break Err(escalated(&addr_for_notify, "escalated by child".to_string()));
+                    }
                 }
-                _ = stream_poll => {
-                    // Stream item was handled inside poll_streams
-                    // Continue to check for more items or messages
-                    continue;
-                }
-                _ = shutdown.notified() => {
-                    break false;
+            };
+
+            if restart_requested {
+                ctx.cancellation_token().cancel();
+                ctx.stop_children();
+                actor.stopped(&mut ctx);
+                actor.stopped_async(&mut ctx).await;
+                events::publish(
+                    system,
+                    SystemEvent::ActorRestarted {
+                        id,
+                        path: addr_for_notify.path_arc(),
+                    },
+                );
+                continue 'restart;
+            }
+
+            let backtrace = outcome.as_ref().err().and_then(|failure| failure.backtrace.clone());
+            let (policy, terminate) = match panic::policy(system) {
+                Some((policy, terminate)) => (policy, Some(terminate)),
+                None => (PanicPolicy::Supervise, None),
+            };
+            //a `Supervisor` parent decides, the strategy is the fallback
+            let directive = match (&outcome, &supervision) {
+                (Err(failure), Supervision::Parent { decide: Some(decide), .. })
+                    if policy == PanicPolicy::Supervise =>
+                {
+                    let failure = FailureInfo {
+                        path: addr_for_notify.path_arc(),
+                        message: failure.message.clone(),
+                        restarts: restart_count,
+                    };
+                    decide(id, failure).await
                 }
-                _ = stop_signal.notified() => {
-                    break false;
+                _ => None,
+            };
+            let (stop_children, reason) = match outcome {
+                Ok(reason) => (true, reason),
+                Err(failure) if policy == PanicPolicy::AbortProcess => {
+                    eprintln!(
+                        "Actor {} panicked: {}. Aborting the process.",
+                        label(&addr_for_notify),
+                        failure.message
+                    );
+                    std::process::abort();
                 }
-                _ = escalate_signal.notified() => {
-                    //escalation requested, we treat it as panic for top-level actors
-                    eprintln!("Actor received escalation signal. Treating as panic.");
-                    break true;
+                Err(failure) if policy == PanicPolicy::EscalateToSystem => {
+                    eprintln!(
+                        "Actor {} panicked: {}. Terminating the system.",
+                        label(&addr_for_notify),
+                        failure.message
+                    );
+                    if let Some(terminate) = terminate {
+                        terminate();
+                    }
+                    (true, TerminationReason::Panicked(failure.message))
                 }
+                Err(failure) => match (&strategy, &supervision) {
+                    _ if directive == Some(SupervisorDirective::Restart)
+                        || restarts(policy, &strategy, &mut tracker) =>
+                    {
+                        restart_count += 1;
+                        eprintln!(
+                            "Actor {} panicked: {}. Restarting...",
+                            label(&addr_for_notify),
+                            failure.message
+                        );
+                        ctx.cancellation_token().cancel();
+                        ctx.stop_children();
+                        actor.stopped(&mut ctx);
+                        actor.stopped_async(&mut ctx).await;
+                        if let Supervision::Parent { restarted, .. } = &supervision {
+                            restarted(id);
+                        }
+                        events::publish(
+                            system,
+                            SystemEvent::ActorRestarted {
+                                id,
+                                path: addr_for_notify.path_arc(),
+                            },
+                        );
+
+                        let attempt = tracker.as_ref().map_or(1, |t| t.recent_restarts());
+                        let delay = strategy.restart_delay(attempt);
+                        if delay.is_zero() {
+                            continue 'restart;
+                        }
+                        //a stop while backing off ends the actor instead
+                        final_reason = tokio::select! {
+                            _ = tokio::time::sleep(delay) => continue 'restart,
+                            _ = shutdown.notified() => TerminationReason::Killed,
+                            _ = stop_signal.notified() => stop_reason(&addr_for_notify),
+                        };
+                        break 'restart;
+                    }
+                    (strategy, supervision)
+                        if policy == PanicPolicy::RestartActor
+                            || (policy == PanicPolicy::Supervise && strategy.restart_limit().is_some()) =>
+                    {
+                        eprintln!("Actor {} exceeded restart limit. Stopping.", label(&addr_for_notify));
+                        let stop_children = match supervision {
+                            Supervision::Guardian(guardian) => guardian.on_failure(id),
+                            Supervision::Parent { .. } => true,
+                        };
+                        (stop_children, TerminationReason::RestartLimitExceeded)
+                    }
+                    (_, Supervision::Guardian(guardian)) => {
+                        (guardian.on_failure(id), TerminationReason::Panicked(failure.message))
+                    }
+                    (strategy, Supervision::Parent { report, .. })
+                        if policy == PanicPolicy::Supervise
+                            && (directive == Some(SupervisorDirective::Escalate)
+                                || *strategy == SupervisorStrategy::Escalate) =>
+                    {
+                        eprintln!(
+                            "Child {} panicked. Strategy: Escalate. Notifying parent.",
+                            label(&addr_for_notify)
+                        );
+                        report(ChildFailed {
+                            id,
+                            error: failure.message.clone(),
+                        });
+                        (true, TerminationReason::Panicked(failure.message))
+                    }
+                    (_, Supervision::Parent { .. }) => {
+                        eprintln!("Child {} panicked. Strategy: Stop.", label(&addr_for_notify));
+                        (true, TerminationReason::Panicked(failure.message))
+                    }
+                },
+            };
+
+            ctx.cancellation_token().cancel();
+            if stop_children {
+                ctx.stop_children();
             }
-        };
 
-        if panic_occured {
-            //actor panicked, we can log or handle it here
-            eprintln!("Actor panicked during message handling. Stopping gracefully.");
+            //actor lifecycle stop, skipped by a kill
+            if !addr_for_notify.is_killed() {
+                actor.stopped(&mut ctx);
+                actor.stopped_async(&mut ctx).await;
+            }
+            final_reason = reason;
+            final_backtrace = backtrace;
+            break 'restart;
         }
 
         //notify watchers about termination
-        addr_for_notify.notify_watchers();
+        ending.finish(final_reason, final_backtrace);
 
-        //stop all child actors
-        ctx.stop_children();
-
-        //actor lifecycle stop
-        actor.stopped(&mut ctx);
-    });
+        //mail that was set aside or arrived too late to be handled
+        for msg in deferred {
+            dead_letter::publish(
+                system,
+                DeadLetter {
+                    sender: None,
+                    ..DeadLetter::new(id, addr_for_notify.path_arc(), msg.message_type())
+                },
+            );
+        }
+        while let Some(msg) = mailbox.dequeue().await {
+            dead_letter::publish(
+                system,
+                DeadLetter {
+                    sender: None,
+                    ..DeadLetter::new(id, addr_for_notify.path_arc(), msg.message_type())
+                },
+            );
+        }
+    }));
 
     addr
 }
+
+///Unlists a stopped actor and tells its watchers, also when its task is
+/// dropped unfinished with the runtime of a stopped `Arbiter`
+struct Ending<A: Actor> {
+    addr: Addr<A>,
+    system: u64,
+    done: bool,
+}
+
+impl<A: Actor> Ending<A> {
+    fn finish(&mut self, reason: TerminationReason, backtrace: Option<Arc<Backtrace>>) {
+        self.done = true;
+        directory::remove(self.addr.id());
+        events::publish(
+            self.system,
+            SystemEvent::ActorStopped {
+                id: self.addr.id(),
+                path: self.addr.path_arc(),
+                reason: reason.clone(),
+            },
+        );
+        self.addr.notify_watchers(reason, backtrace);
+        self.addr.close_mailbox();
+    }
+}
+
+impl<A: Actor> Drop for Ending<A> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.finish(TerminationReason::Killed, None);
+        }
+    }
+}
+
+///why the stop signal fired
+pub(crate) fn stop_reason<A: Actor>(addr: &Addr<A>) -> TerminationReason {
+    if addr.is_killed() {
+        TerminationReason::Killed
+    } else {
+        TerminationReason::Normal
+    }
+}
+
+///whether a failed instance is replaced by a fresh one, recording the restart
+fn restarts(
+    policy: PanicPolicy,
+    strategy: &SupervisorStrategy,
+    tracker: &mut Option<RestartTracker>,
+) -> bool {
+    match policy {
+        PanicPolicy::StopActor => false,
+        //within the strategy's budget, or the default one if it never restarts
+        PanicPolicy::RestartActor => tracker
+            .get_or_insert_with(|| {
+                let (max_restarts, within) = DEFAULT_RESTART_LIMIT;
+                RestartTracker::new(max_restarts, within)
+            })
+            .record_restart(),
+        _ => {
+            matches!(
+                strategy,
+                SupervisorStrategy::Restart { .. } | SupervisorStrategy::Backoff(_)
+            ) && tracker.as_mut().is_some_and(|t| t.record_restart())
+        }
+    }
+}
+
+///how an actor shows up in logs: its path, which carries its name if it has one
+fn label<A: Actor>(addr: &Addr<A>) -> String {
+    addr.path().to_string()
+}
+
+///dispatch `msg`, failing the actor like a panic once the handler has run
+/// for `limit`, see `SpawnOptions::handler_timeout`
+async fn dispatch_within<A: Actor>(
+    limit: Option<Duration>,
+    msg: ActorMessage<A>,
+    actor: &mut A,
+    ctx: &mut Context<A>,
+) -> Result<(), PanicInfo> {
+    let Some(limit) = limit else {
+        return dispatch(msg, actor, ctx).await;
+    };
+    let message_type = msg.message_type();
+    match tokio::time::timeout(limit, dispatch(msg, actor, ctx)).await {
+        Ok(result) => result,
+        Err(_) => Err(PanicInfo {
+            id: ctx.id(),
+            name: ctx.name().map(Arc::from),
+            path: Arc::from(ctx.path()),
+            message: format!("handler for {} timed out after {:?}", message_type, limit),
+            backtrace: None,
+        }),
+    }
+}
+
+///failure of an actor whose child escalated; the panic itself was the child's
+fn escalated<A: Actor>(addr: &Addr<A>, message: String) -> PanicInfo {
+    PanicInfo {
+        id: addr.id(),
+        name: addr.name().map(Arc::from),
+        path: addr.path_arc(),
+        message,
+        backtrace: None,
+    }
+}
@@ -0,0 +1,85 @@
+use std::{ops::Deref, sync::Mutex, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::ActorSystem;
+
+///polls given to woken actors after the clock moved, enough for a timer
+/// message to be queued, handled and answered
+const SETTLE_YIELDS: usize = 64;
+
+/// An ActorSystem on a virtual clock, for timer-heavy tests that shouldn't
+/// really sleep. Creating one pauses tokio's clock, so `run_later`,
+/// `run_interval`, `send_timeout`, restart backoff and every other tokio
+/// timer only move when `advance` is called (or when everything is idle,
+/// as with `tokio::time::pause`). Cron and `Schedule::at` jobs follow the
+/// wall clock and are unaffected
+/// Needs the `test-util` feature and a current-thread runtime (the
+/// `#[tokio::test]` default); dereferences to the wrapped ActorSystem
+pub struct TestSystem {
+    system: ActorSystem,
+    started: Instant,
+    ///virtual time advanced so far; tokio fires timers on the millisecond
+    /// after their deadline, so the clock itself runs slightly ahead
+    elapsed: Mutex<Duration>,
+}
+
+impl TestSystem {
+    /// Panics outside a current-thread tokio runtime
+    pub fn new() -> Self {
+        Self::with_system(ActorSystem::new())
+    }
+
+    /// Run an already configured system on the virtual clock
+    pub fn with_system(system: ActorSystem) -> Self {
+        tokio::time::pause();
+        Self {
+            system,
+            started: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Move the clock forward, firing due timers in deadline order and
+    /// letting the actors handle what they sent before returning
+    pub async fn advance(&self, by: Duration) {
+        let target = {
+            let mut elapsed = self.elapsed.lock().unwrap_or_else(|p| p.into_inner());
+            *elapsed += by;
+            self.started + *elapsed
+        };
+        //with the clock paused, sleeping jumps from one timer to the next
+        // as soon as every task is idle, so intervals tick once per period
+        tokio::time::sleep_until(target).await;
+        Self::settle().await;
+    }
+
+    /// Virtual time advanced since the system was created
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    pub fn system(&self) -> &ActorSystem {
+        &self.system
+    }
+
+    async fn settle() {
+        for _ in 0..SETTLE_YIELDS {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+impl Default for TestSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for TestSystem {
+    type Target = ActorSystem;
+
+    fn deref(&self) -> &ActorSystem {
+        &self.system
+    }
+}
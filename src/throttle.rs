@@ -0,0 +1,171 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::{actor::AsyncHandler, Actor, Addr, Handler, MailboxError, Message};
+
+///Token bucket shared by every clone of a Throttled handle
+struct TokenBucket {
+    ///tokens added per second
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    ///negative while waiting senders have reserved tokens not yet refilled
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate: f64::from(rate.max(1)),
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                refilled: Instant::now(),
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.refilled).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.burst);
+        state.refilled = now;
+        state
+    }
+
+    ///reserve a token, returning how long to wait before it is available
+    fn reserve(&self) -> Duration {
+        let mut state = self.state();
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+
+    fn try_take(&self) -> bool {
+        let mut state = self.state();
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Address limiting how fast messages reach the actor, created with `Addr::throttled`
+/// Awaiting sends wait for their turn, `try_` sends fail with
+/// `MailboxError::Throttled` instead. Clones share the same budget
+pub struct Throttled<A: Actor> {
+    addr: Addr<A>,
+    bucket: Arc<TokenBucket>,
+}
+
+impl<A: Actor> Clone for Throttled<A> {
+    fn clone(&self) -> Self {
+        Self {
+            addr: self.addr.clone(),
+            bucket: self.bucket.clone(),
+        }
+    }
+}
+
+impl<A: Actor> Throttled<A> {
+    pub(crate) fn new(addr: Addr<A>, rate: u32, burst: u32) -> Self {
+        Self {
+            addr,
+            bucket: Arc::new(TokenBucket::new(rate, burst)),
+        }
+    }
+
+    /// The unthrottled address
+    pub fn addr(&self) -> &Addr<A> {
+        &self.addr
+    }
+
+    ///Send message and wait for response
+    pub async fn send<M>(&self, msg: M) -> Result<M::Result, MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        self.bucket.acquire().await;
+        self.addr.send(msg).await
+    }
+
+    ///Fire and forget message sending
+    pub async fn do_send<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        self.bucket.acquire().await;
+        self.addr.do_send(msg).await
+    }
+
+    /// Send without waiting, neither for the rate limit nor for mailbox space
+    pub fn try_send<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        A: Handler<M>,
+        M: Message,
+    {
+        if !self.bucket.try_take() {
+            return Err(MailboxError::Throttled);
+        }
+        self.addr.try_send(msg)
+    }
+
+    /// Send and wait for response from async handler
+    pub async fn send_async<M>(&self, msg: M) -> Result<M::Result, MailboxError>
+    where
+        A: AsyncHandler<M>,
+        M: Message,
+    {
+        self.bucket.acquire().await;
+        self.addr.send_async(msg).await
+    }
+
+    /// Fire and forget for async handlers
+    pub async fn do_send_async<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        A: AsyncHandler<M>,
+        M: Message,
+    {
+        self.bucket.acquire().await;
+        self.addr.do_send_async(msg).await
+    }
+
+    /// Non-waiting counterpart of `do_send_async`, see `try_send`
+    pub fn try_send_async<M>(&self, msg: M) -> Result<(), MailboxError>
+    where
+        A: AsyncHandler<M>,
+        M: Message,
+    {
+        if !self.bucket.try_take() {
+            return Err(MailboxError::Throttled);
+        }
+        self.addr.try_send_async(msg)
+    }
+}
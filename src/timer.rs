@@ -1,29 +1,222 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
 };
 
+use tokio::{sync::Notify, time::Instant};
+
+use crate::sync::{AtomicBool, Ordering};
+
 /// Handle to a scheduled timer that can be cancelled
 /// When dropped without calling cancel(), the timer continues running
+/// Delayed one-shot timers (`run_later` and friends) can also be moved to a
+/// new deadline, fired right away, or asked how long they have left
 #[derive(Clone)]
 pub struct TimerHandle {
     cancelled: Arc<AtomicBool>,
+    ///deadline of a one-shot timer, None for intervals
+    once: Option<Arc<OneShot>>,
+}
+
+struct OneShot {
+    state: Mutex<OneShotState>,
+    ///wakes the timer task when the deadline moves or the timer is cancelled
+    changed: Notify,
+}
+
+struct OneShotState {
+    deadline: Instant,
+    fired: bool,
 }
 
 impl TimerHandle {
     pub(crate) fn new() -> Self {
         Self {
             cancelled: Arc::new(AtomicBool::new(false)),
+            once: None,
+        }
+    }
+
+    ///one-shot timer due after `delay`
+    pub(crate) fn after(delay: Duration) -> Self {
+        Self {
+            once: Some(Arc::new(OneShot {
+                state: Mutex::new(OneShotState {
+                    deadline: Instant::now() + delay,
+                    fired: false,
+                }),
+                changed: Notify::new(),
+            })),
+            ..Self::new()
         }
     }
 
     /// Cancel the timer - it will not fire
     pub fn cancel(&self) {
         self.cancelled.store(true, Ordering::SeqCst);
+        if let Some(once) = &self.once {
+            once.changed.notify_one();
+        }
     }
 
     /// Check if the timer has been cancelled
     pub fn is_cancelled(&self) -> bool {
         self.cancelled.load(Ordering::SeqCst)
     }
+
+    /// Move a pending one-shot timer to fire `delay` from now
+    /// False if it already fired, was cancelled, or is an interval
+    pub fn reschedule(&self, delay: Duration) -> bool {
+        let Some(once) = &self.once else {
+            return false;
+        };
+        let mut state = once.state();
+        if state.fired || self.is_cancelled() {
+            return false;
+        }
+        state.deadline = Instant::now() + delay;
+        drop(state);
+        once.changed.notify_one();
+        true
+    }
+
+    /// Fire a pending one-shot timer right away, same result as `reschedule`
+    pub fn fire_now(&self) -> bool {
+        self.reschedule(Duration::ZERO)
+    }
+
+    /// Time left before a pending one-shot timer fires
+    /// None once it fired or was cancelled, and for intervals
+    pub fn remaining(&self) -> Option<Duration> {
+        let once = self.once.as_ref()?;
+        let state = once.state();
+        if state.fired || self.is_cancelled() {
+            return None;
+        }
+        Some(state.deadline.saturating_duration_since(Instant::now()))
+    }
+
+    ///wait for the deadline, following reschedules; true if the timer fires,
+    /// false if it was cancelled first
+    pub(crate) async fn expired(&self) -> bool {
+        let Some(once) = &self.once else {
+            return !self.is_cancelled();
+        };
+        loop {
+            let changed = once.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+            let deadline = {
+                let mut state = once.state();
+                if self.is_cancelled() {
+                    return false;
+                }
+                if state.deadline <= Instant::now() {
+                    //from here on the deadline can't move any more
+                    state.fired = true;
+                    return true;
+                }
+                state.deadline
+            };
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => {}
+                _ = changed => {}
+            }
+        }
+    }
+}
+
+impl OneShot {
+    fn state(&self) -> MutexGuard<'_, OneShotState> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// What an interval does after falling behind (a slow handler, a full mailbox)
+/// Mirrors tokio's `MissedTickBehavior`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedTick {
+    /// Fire the missed ticks back to back until caught up
+    #[default]
+    Burst,
+    /// Restart the period from the late tick
+    Delay,
+    /// Drop the missed ticks and resume on the original schedule
+    Skip,
+}
+
+impl From<MissedTick> for tokio::time::MissedTickBehavior {
+    fn from(missed: MissedTick) -> Self {
+        match missed {
+            MissedTick::Burst => tokio::time::MissedTickBehavior::Burst,
+            MissedTick::Delay => tokio::time::MissedTickBehavior::Delay,
+            MissedTick::Skip => tokio::time::MissedTickBehavior::Skip,
+        }
+    }
+}
+
+/// Settings for `Context::run_interval`; a plain Duration is an interval
+/// without jitter that bursts after missed ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    period: Duration,
+    jitter: f64,
+    missed_tick: MissedTick,
+}
+
+impl Interval {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            jitter: 0.0,
+            missed_tick: MissedTick::default(),
+        }
+    }
+
+    /// Spread each tick randomly by ±`fraction` of the period, so many actors
+    /// started together don't fire in lockstep; clamped to 0.0..=0.5
+    pub fn jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 0.5);
+        self
+    }
+
+    pub fn missed_tick(mut self, missed_tick: MissedTick) -> Self {
+        self.missed_tick = missed_tick;
+        self
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    pub(crate) fn ticker(&self) -> Ticker {
+        let mut interval = tokio::time::interval(self.period);
+        interval.set_missed_tick_behavior(self.missed_tick.into());
+        Ticker {
+            interval,
+            spread: self.period.mul_f64(2.0 * self.jitter),
+        }
+    }
+}
+
+impl From<Duration> for Interval {
+    fn from(period: Duration) -> Self {
+        Self::new(period)
+    }
+}
+
+pub(crate) struct Ticker {
+    interval: tokio::time::Interval,
+    spread: Duration,
+}
+
+impl Ticker {
+    ///wait for the next tick, then a random share of the jitter window
+    /// so ticks land within ±jitter of their average spacing
+    pub(crate) async fn tick(&mut self) {
+        self.interval.tick().await;
+        if !self.spread.is_zero() {
+            tokio::time::sleep(self.spread.mul_f64(rand::random::<f64>())).await;
+        }
+    }
 }
@@ -1,6 +1,159 @@
-use crate::actor::ActorId;
+use std::{marker::PhantomData, ptr, sync::Arc};
+
+use crate::{
+    message::Terminated,
+    sync::{AtomicPtr, Ordering},
+    Actor, Addr, Handler, Message,
+};
 
 /// Type-erased watcher that can be notified of actor death
 pub trait Watcher: Send + Sync {
-    fn notify(&self, id: ActorId);
+    fn notify(&self, msg: Terminated);
+}
+
+/// Watchers registered on one actor
+/// A watcher added after the actor stopped is notified right away,
+/// so watching never misses a termination it raced with
+///
+/// Lock free: watchers are pushed on a stack that `notify_all` takes whole,
+/// leaving the termination in its place, so watching never blocks or
+/// deadlocks, even from inside a handler of the watched actor
+pub struct WatcherList {
+    //a `Node` chain while running, a tagged `Terminated` once stopped
+    head: AtomicPtr<Node>,
+}
+
+struct Node {
+    watcher: Arc<dyn Watcher>,
+    next: *mut Node,
+}
+
+//nodes are never aligned on an odd address, the low bit marks the termination
+const TERMINATED: usize = 1;
+
+fn is_terminated(head: *mut Node) -> bool {
+    head.addr() & TERMINATED != 0
+}
+
+fn terminated(head: *mut Node) -> *mut Terminated {
+    head.map_addr(|addr| addr & !TERMINATED).cast()
+}
+
+impl WatcherList {
+    pub fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    pub fn add(&self, watcher: Arc<dyn Watcher>) {
+        let node = Box::into_raw(Box::new(Node {
+            watcher,
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            if is_terminated(head) {
+                // SAFETY: the node was never published, and the termination
+                // stays in place until the list is dropped
+                let (node, msg) = unsafe { (Box::from_raw(node), (*terminated(head)).clone()) };
+                node.watcher.notify(msg);
+                return;
+            }
+            // SAFETY: the node is still ours until the exchange publishes it
+            unsafe { (*node).next = head };
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Notify every watcher once; later additions are notified on `add`
+    pub fn notify_all(&self, msg: Terminated) {
+        let closed = Box::into_raw(Box::new(msg.clone()))
+            .map_addr(|addr| addr | TERMINATED)
+            .cast::<Node>();
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            if is_terminated(head) {
+                //already notified, the first termination stands
+                // SAFETY: never published
+                drop(unsafe { Box::from_raw(terminated(closed)) });
+                return;
+            }
+            match self
+                .head
+                .compare_exchange_weak(head, closed, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+
+        //the chain is ours now; notify in the order watchers were added
+        let mut watchers = Vec::new();
+        while !head.is_null() {
+            // SAFETY: nodes are only freed by whoever swapped them out
+            let node = unsafe { Box::from_raw(head) };
+            head = node.next;
+            watchers.push(node.watcher);
+        }
+        for watcher in watchers.into_iter().rev() {
+            watcher.notify(msg.clone());
+        }
+    }
+}
+
+impl Drop for WatcherList {
+    fn drop(&mut self) {
+        let mut head = self.head.load(Ordering::Acquire);
+        if is_terminated(head) {
+            // SAFETY: published by `notify_all`, nobody else frees it
+            drop(unsafe { Box::from_raw(terminated(head)) });
+            return;
+        }
+        while !head.is_null() {
+            // SAFETY: nobody else holds the list any more
+            let node = unsafe { Box::from_raw(head) };
+            head = node.next;
+        }
+    }
+}
+
+impl Default for WatcherList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Watcher that maps Terminated into a domain-specific message
+pub(crate) struct MappedWatcher<W: Actor, M, F> {
+    addr: Addr<W>,
+    map: F,
+    _phantom: PhantomData<fn() -> M>,
+}
+
+impl<W: Actor, M, F> MappedWatcher<W, M, F> {
+    pub(crate) fn new(addr: Addr<W>, map: F) -> Self {
+        Self {
+            addr,
+            map,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<W, M, F> Watcher for MappedWatcher<W, M, F>
+where
+    W: Actor + Handler<M>,
+    M: Message,
+    F: Fn(Terminated) -> M + Send + Sync,
+{
+    fn notify(&self, msg: Terminated) {
+        let _ = self.addr.try_send((self.map)(msg));
+    }
 }
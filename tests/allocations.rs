@@ -0,0 +1,87 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use cinema::{Actor, ActorSystem, Context, Handler, Message};
+
+///counts every allocation of the test binary
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+struct Counter(u64);
+
+impl Actor for Counter {}
+
+struct Increment;
+
+impl Message for Increment {
+    type Result = u64;
+}
+
+impl Handler<Increment> for Counter {
+    type Result = u64;
+
+    fn handle(&mut self, _msg: Increment, _ctx: &mut Context<Self>) -> u64 {
+        self.0 += 1;
+        self.0
+    }
+}
+
+const MESSAGES: usize = 10_000;
+
+///allocations per message made by `send_all`
+async fn allocations_per_message<F: std::future::Future>(send_all: F) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    send_all.await;
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    (after - before) as f64 / MESSAGES as f64
+}
+
+// one runtime thread, so only the sender and the actor allocate while counting
+#[tokio::test(flavor = "current_thread")]
+async fn do_send_allocates_only_the_envelope() {
+    let system = ActorSystem::new();
+    let counter = system.spawn(Counter(0));
+    counter.send(Increment).await.unwrap();
+
+    let per_message = allocations_per_message(async {
+        for _ in 0..MESSAGES {
+            counter.do_send(Increment).await.unwrap();
+        }
+        counter.send(Increment).await.unwrap();
+    })
+    .await;
+    assert!(per_message < 1.5, "{} allocations per message", per_message);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn send_allocates_the_envelope_and_its_reply() {
+    let system = ActorSystem::new();
+    let counter = system.spawn(Counter(0));
+    counter.send(Increment).await.unwrap();
+
+    // the actor waits for every request, the boxed dequeue future included
+    let per_message = allocations_per_message(async {
+        for _ in 0..MESSAGES {
+            counter.send(Increment).await.unwrap();
+        }
+    })
+    .await;
+    assert!(per_message < 3.5, "{} allocations per message", per_message);
+}
@@ -8,7 +8,11 @@ use std::{
 
 use cinema::{
     actor::{AsyncHandler, BoxFuture},
-    Actor, ActorSystem, Context, Handler, MailboxError, Message, TimerHandle,
+    address::ChildHandle,
+    envelope::{ActorMessage, DispatchHook, Envelope, MessageEnvelope},
+    message::{MessageOrigin, UnhandledMessage},
+    Actor, ActorSystem, Addr, Context, Handler, Interval, MailboxError, Message, MissedTick,
+    TimerHandle,
 };
 
 // ======== Basic Message Passing Tests ========
@@ -33,6 +37,8 @@ impl Actor for PingActor {
 }
 
 impl Handler<Ping> for PingActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) {
         self.count.fetch_add(1, Ordering::SeqCst);
     }
@@ -52,7 +58,7 @@ async fn send_message() {
         addr.do_send(Ping).await.unwrap();
     }
 
-    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(sys.wait_idle(Duration::from_secs(1)).await);
     assert_eq!(count.load(Ordering::SeqCst), 10);
 }
 
@@ -68,6 +74,8 @@ impl Message for Add {
 impl Actor for Calculator {}
 
 impl Handler<Add> for Calculator {
+    type Result = u32;
+
     fn handle(&mut self, msg: Add, _ctx: &mut Context<Self>) -> u32 {
         msg.0 + msg.1
     }
@@ -109,6 +117,233 @@ async fn send_to_stopped_actor_fails() {
     assert_eq!(result.err().unwrap(), MailboxError::MailboxClosed);
 }
 
+#[derive(Message)]
+#[rtype(result = "u32")]
+struct Multiply(u32, u32);
+
+#[derive(Message)]
+#[rtype(Option<T>)]
+struct Echo<T: Send + 'static>(Option<T>);
+
+#[derive(Message)]
+struct Reset;
+
+impl Handler<Multiply> for Calculator {
+    type Result = u32;
+
+    fn handle(&mut self, msg: Multiply, _ctx: &mut Context<Self>) -> u32 {
+        msg.0 * msg.1
+    }
+}
+
+impl Handler<Echo<String>> for Calculator {
+    type Result = Option<String>;
+
+    fn handle(&mut self, msg: Echo<String>, _ctx: &mut Context<Self>) -> Option<String> {
+        msg.0
+    }
+}
+
+impl Handler<Reset> for Calculator {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Reset, _ctx: &mut Context<Self>) {}
+}
+
+#[tokio::test]
+async fn derived_messages() {
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Calculator);
+
+    assert_eq!(addr.send(Multiply(6, 7)).await.unwrap(), 42);
+    assert_eq!(
+        addr.send(Echo(Some("hi".to_string()))).await.unwrap(),
+        Some("hi".to_string())
+    );
+    let () = addr.send(Reset).await.unwrap();
+}
+
+#[derive(Debug, PartialEq, cinema::MessageResponse)]
+struct Summary {
+    total: u32,
+}
+
+#[derive(Message)]
+#[rtype(result = "Summary")]
+struct Summarize;
+
+#[derive(Message)]
+#[rtype(result = "u32")]
+struct SlowDouble(u32);
+
+#[derive(Message)]
+#[rtype(result = "u32")]
+struct AddLater(u32);
+
+struct Accumulator {
+    total: u32,
+}
+
+impl Actor for Accumulator {}
+
+impl Handler<Summarize> for Accumulator {
+    type Result = Summary;
+
+    fn handle(&mut self, _msg: Summarize, _ctx: &mut Context<Self>) -> Summary {
+        Summary { total: self.total }
+    }
+}
+
+impl Handler<SlowDouble> for Accumulator {
+    type Result = cinema::ResponseFuture<u32>;
+
+    fn handle(&mut self, msg: SlowDouble, _ctx: &mut Context<Self>) -> Self::Result {
+        cinema::ResponseFuture::new(async move {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            msg.0 * 2
+        })
+    }
+}
+
+impl Handler<AddLater> for Accumulator {
+    type Result = cinema::ResponseActFuture<Self, u32>;
+
+    fn handle(&mut self, msg: AddLater, _ctx: &mut Context<Self>) -> Self::Result {
+        cinema::ResponseActFuture::new(
+            tokio::time::sleep(Duration::from_millis(10)),
+            move |(), actor: &mut Self, _ctx| {
+                actor.total += msg.0;
+                actor.total
+            },
+        )
+    }
+}
+
+#[tokio::test]
+async fn handlers_reply_with_futures() {
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Accumulator { total: 0 });
+
+    // the slow reply doesn't hold up the mailbox
+    let slow = addr.send(SlowDouble(21));
+    let summary = async {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        addr.send(Summarize).await.unwrap()
+    };
+    let (slow, summary) = tokio::join!(slow, summary);
+    assert_eq!(slow.unwrap(), 42);
+    assert_eq!(summary, Summary { total: 0 });
+
+    assert_eq!(addr.send(AddLater(5)).await.unwrap(), 5);
+    assert_eq!(addr.send(AddLater(7)).await.unwrap(), 12);
+    assert_eq!(addr.send(Summarize).await.unwrap(), Summary { total: 12 });
+}
+
+struct Front {
+    backend: Addr<Accumulator>,
+}
+
+impl Actor for Front {}
+
+impl Handler<AddLater> for Front {
+    type Result = cinema::Forwarded<u32>;
+
+    fn handle(&mut self, msg: AddLater, ctx: &mut Context<Self>) -> Self::Result {
+        ctx.forward(&self.backend, msg)
+    }
+}
+
+impl Handler<Summarize> for Front {
+    type Result = cinema::Forwarded<Summary>;
+
+    fn handle(&mut self, msg: Summarize, ctx: &mut Context<Self>) -> Self::Result {
+        ctx.forward(&self.backend, msg)
+    }
+}
+
+#[tokio::test]
+async fn forwarded_requests_are_answered_by_the_delegate() {
+    let sys = ActorSystem::new();
+    let backend = sys.spawn(Accumulator { total: 0 });
+    let front = sys.spawn(Front {
+        backend: backend.clone(),
+    });
+
+    // the backend's reply reaches the caller, the front doesn't wait for it
+    assert_eq!(front.send(AddLater(5)).await.unwrap(), 5);
+    assert_eq!(front.send(AddLater(7)).await.unwrap(), 12);
+    assert_eq!(
+        front.send(Summarize).await.unwrap(),
+        Summary { total: 12 }
+    );
+
+    ChildHandle::stop(&backend);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(
+        front.send(Summarize).await.unwrap_err(),
+        MailboxError::MailboxClosed
+    );
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct AskDouble(u32, Duration);
+
+#[derive(Message)]
+#[rtype(result = "Vec<Result<u32, MailboxError>>")]
+struct Answers;
+
+struct Asker {
+    backend: Addr<Accumulator>,
+    answers: Vec<Result<u32, MailboxError>>,
+}
+
+impl Actor for Asker {}
+
+impl Handler<AskDouble> for Asker {
+    type Result = ();
+
+    fn handle(&mut self, msg: AskDouble, ctx: &mut Context<Self>) {
+        ctx.request(&self.backend, SlowDouble(msg.0))
+            .timeout(msg.1)
+            .then(|answer, asker: &mut Self, _ctx| asker.answers.push(answer));
+    }
+}
+
+impl Handler<Answers> for Asker {
+    type Result = Vec<Result<u32, MailboxError>>;
+
+    fn handle(&mut self, _msg: Answers, _ctx: &mut Context<Self>) -> Self::Result {
+        std::mem::take(&mut self.answers)
+    }
+}
+
+#[tokio::test]
+async fn requests_answer_on_the_actor_without_blocking_it() {
+    let sys = ActorSystem::new();
+    let asker = sys.spawn(Asker {
+        backend: sys.spawn(Accumulator { total: 0 }),
+        answers: Vec::new(),
+    });
+
+    asker
+        .send(AskDouble(21, Duration::from_secs(1)))
+        .await
+        .unwrap();
+    asker
+        .send(AskDouble(1, Duration::from_millis(5)))
+        .await
+        .unwrap();
+    // still handling mail while both asks are out
+    assert!(asker.send(Answers).await.unwrap().is_empty());
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(
+        asker.send(Answers).await.unwrap(),
+        vec![Err(MailboxError::Timeout), Ok(42)]
+    );
+}
+
 // ======== Timer Tests ========
 
 struct Tick;
@@ -128,6 +363,8 @@ impl Actor for TickActor {
 }
 
 impl Handler<Tick> for TickActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Tick, _ctx: &mut Context<Self>) {
         self.count.fetch_add(1, Ordering::SeqCst);
     }
@@ -169,6 +406,8 @@ impl Actor for HeartbeatActor {
 }
 
 impl Handler<Heartbeat> for HeartbeatActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Heartbeat, _ctx: &mut Context<Self>) {
         self.count.fetch_add(1, Ordering::SeqCst);
     }
@@ -189,12 +428,458 @@ async fn run_interval_sends_periodic_messages() {
 
     let ticks = count.load(Ordering::SeqCst);
     assert!(
-        ticks >= 2 && ticks <= 4,
+        (2..=4).contains(&ticks),
         "Expected 2-4 ticks, got {}",
         ticks
     );
 }
 
+struct JitteredHeartbeat {
+    ticks: Arc<std::sync::Mutex<Vec<std::time::Instant>>>,
+}
+
+impl Actor for JitteredHeartbeat {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        let interval = Interval::new(Duration::from_millis(40))
+            .jitter(0.5)
+            .missed_tick(MissedTick::Skip);
+        ctx.run_interval(interval, Heartbeat);
+    }
+}
+
+impl Handler<Heartbeat> for JitteredHeartbeat {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Heartbeat, _ctx: &mut Context<Self>) {
+        self.ticks.lock().unwrap().push(std::time::Instant::now());
+    }
+}
+
+#[tokio::test]
+async fn run_interval_jitter_spreads_ticks() {
+    let sys = ActorSystem::new();
+    let mut logs = Vec::new();
+    let mut addrs = Vec::new();
+    for _ in 0..20 {
+        let ticks = Arc::new(std::sync::Mutex::new(Vec::new()));
+        addrs.push(sys.spawn(JitteredHeartbeat {
+            ticks: ticks.clone(),
+        }));
+        logs.push(ticks);
+    }
+
+    tokio::time::sleep(Duration::from_millis(210)).await;
+
+    let mut second_ticks = Vec::new();
+    for log in &logs {
+        let ticks = log.lock().unwrap();
+        // jitter moves ticks around but keeps the average rate
+        assert!((3..=7).contains(&ticks.len()), "got {} ticks", ticks.len());
+        second_ticks.push(ticks[1]);
+    }
+    let first = second_ticks.iter().min().unwrap();
+    let last = second_ticks.iter().max().unwrap();
+    assert!(
+        *last - *first >= Duration::from_millis(5),
+        "ticks fired in lockstep"
+    );
+}
+
+// not Clone: every tick needs its own buffer
+struct Batch(Vec<u32>);
+impl Message for Batch {
+    type Result = ();
+}
+
+struct BatchActor {
+    batches: Arc<std::sync::Mutex<Vec<Vec<u32>>>>,
+}
+
+impl Actor for BatchActor {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        let next = AtomicU32::new(0);
+        ctx.run_interval_with(Duration::from_millis(20), move || {
+            Batch(vec![next.fetch_add(1, Ordering::SeqCst)])
+        });
+    }
+}
+
+impl Handler<Batch> for BatchActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Batch, _ctx: &mut Context<Self>) {
+        self.batches.lock().unwrap().push(msg.0);
+    }
+}
+
+#[tokio::test]
+async fn run_interval_with_builds_fresh_messages() {
+    let batches = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let _addr = sys.spawn(BatchActor {
+        batches: batches.clone(),
+    });
+
+    tokio::time::sleep(Duration::from_millis(70)).await;
+
+    let batches = batches.lock().unwrap();
+    assert!(batches.len() >= 3, "got {} batches", batches.len());
+    for (i, batch) in batches.iter().enumerate() {
+        assert_eq!(batch, &vec![i as u32]);
+    }
+}
+
+struct AsyncTimers {
+    delayed: Arc<AtomicU32>,
+    beats: Arc<AtomicU32>,
+}
+
+impl Actor for AsyncTimers {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        ctx.run_later_async(Duration::from_millis(20), Tick);
+        ctx.run_interval_async(Duration::from_millis(30), Heartbeat);
+    }
+}
+
+impl AsyncHandler<Tick> for AsyncTimers {
+    fn handle(&mut self, _msg: Tick, _ctx: &mut Context<Self>) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            self.delayed.fetch_add(1, Ordering::SeqCst);
+        })
+    }
+}
+
+impl AsyncHandler<Heartbeat> for AsyncTimers {
+    fn handle(&mut self, _msg: Heartbeat, _ctx: &mut Context<Self>) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            self.beats.fetch_add(1, Ordering::SeqCst);
+        })
+    }
+}
+
+#[tokio::test]
+async fn async_timers_reach_async_handlers() {
+    let delayed = Arc::new(AtomicU32::new(0));
+    let beats = Arc::new(AtomicU32::new(0));
+    let sys = ActorSystem::new();
+    let _addr = sys.spawn(AsyncTimers {
+        delayed: delayed.clone(),
+        beats: beats.clone(),
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(delayed.load(Ordering::SeqCst), 1);
+    let ticks = beats.load(Ordering::SeqCst);
+    assert!((2..=4).contains(&ticks), "Expected 2-4 ticks, got {}", ticks);
+}
+
+struct IdleConn {
+    conn: Option<String>,
+}
+
+struct IsOpen;
+impl Message for IsOpen {
+    type Result = bool;
+}
+
+impl Actor for IdleConn {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        // close the connection held in state once idle
+        ctx.run_later_with(Duration::from_millis(30), |actor: &mut Self, _ctx| {
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                actor.conn = None;
+            })
+        });
+    }
+}
+
+impl Handler<IsOpen> for IdleConn {
+    type Result = bool;
+
+    fn handle(&mut self, _msg: IsOpen, _ctx: &mut Context<Self>) -> bool {
+        self.conn.is_some()
+    }
+}
+
+#[tokio::test]
+async fn run_later_with_runs_closure_in_actor() {
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(IdleConn {
+        conn: Some("db".to_string()),
+    });
+
+    assert_eq!(addr.send(IsOpen).await, Ok(true));
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(addr.send(IsOpen).await, Ok(false));
+}
+
+// ======== Self Notification Tests ========
+
+struct Step(&'static str);
+impl Message for Step {
+    type Result = ();
+}
+
+struct Steps {
+    log: Arc<std::sync::Mutex<Vec<&'static str>>>,
+}
+
+impl Actor for Steps {}
+
+impl Handler<Step> for Steps {
+    type Result = ();
+
+    fn handle(&mut self, msg: Step, ctx: &mut Context<Self>) {
+        self.log.lock().unwrap().push(msg.0);
+        match msg.0 {
+            "start" => {
+                ctx.notify(Step("first"));
+                ctx.notify(Step("second"));
+            }
+            "later" => {
+                ctx.notify_later(Step("delayed"), Duration::from_millis(20));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[tokio::test]
+async fn notify_runs_before_queued_mail() {
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Steps { log: log.clone() });
+
+    // both queued before the actor handles "start"
+    addr.try_send(Step("start")).unwrap();
+    addr.try_send(Step("external")).unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["start", "first", "second", "external"]
+    );
+}
+
+#[tokio::test]
+async fn notify_later_sends_after_delay() {
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Steps { log: log.clone() });
+
+    addr.do_send(Step("later")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    assert_eq!(*log.lock().unwrap(), vec!["later"]);
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert_eq!(*log.lock().unwrap(), vec!["later", "delayed"]);
+}
+
+// ======== Context Wait Tests ========
+
+struct Session {
+    log: Arc<std::sync::Mutex<Vec<&'static str>>>,
+}
+
+impl Actor for Session {}
+
+impl Handler<Step> for Session {
+    type Result = ();
+
+    fn handle(&mut self, msg: Step, ctx: &mut Context<Self>) {
+        self.log.lock().unwrap().push(msg.0);
+        if msg.0 == "expired" {
+            let log = self.log.clone();
+            ctx.wait(async move {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                log.lock().unwrap().push("reauthenticated");
+            });
+        }
+    }
+}
+
+#[tokio::test]
+async fn wait_suspends_mailbox_until_future_resolves() {
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Session { log: log.clone() });
+
+    addr.do_send(Step("expired")).await.unwrap();
+    addr.do_send(Step("query")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(*log.lock().unwrap(), vec!["expired"]);
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["expired", "reauthenticated", "query"]
+    );
+}
+
+// ======== Selective Receive Tests ========
+
+struct Coordinator {
+    log: Vec<&'static str>,
+}
+
+impl Actor for Coordinator {}
+
+struct Prepare;
+
+impl Message for Prepare {
+    type Result = ();
+}
+
+struct CommitVote;
+
+impl Message for CommitVote {
+    type Result = ();
+}
+
+struct Abort;
+
+impl Message for Abort {
+    type Result = ();
+}
+
+struct Write(&'static str);
+
+impl Message for Write {
+    type Result = ();
+}
+
+struct History;
+
+impl Message for History {
+    type Result = Vec<&'static str>;
+}
+
+impl Handler<Prepare> for Coordinator {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Prepare, ctx: &mut Context<Self>) {
+        self.log.push("prepare");
+        ctx.receive_only::<(CommitVote, Abort)>();
+    }
+}
+
+impl Handler<CommitVote> for Coordinator {
+    type Result = ();
+
+    fn handle(&mut self, _msg: CommitVote, ctx: &mut Context<Self>) {
+        self.log.push("commit");
+        ctx.receive_all();
+    }
+}
+
+impl Handler<Abort> for Coordinator {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Abort, ctx: &mut Context<Self>) {
+        self.log.push("abort");
+        ctx.receive_all();
+    }
+}
+
+impl Handler<Write> for Coordinator {
+    type Result = ();
+
+    fn handle(&mut self, msg: Write, _ctx: &mut Context<Self>) {
+        self.log.push(msg.0);
+    }
+}
+
+impl Handler<History> for Coordinator {
+    type Result = Vec<&'static str>;
+
+    fn handle(&mut self, _msg: History, _ctx: &mut Context<Self>) -> Vec<&'static str> {
+        self.log.clone()
+    }
+}
+
+#[tokio::test]
+async fn receive_only_sets_other_mail_aside_in_order() {
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Coordinator { log: Vec::new() });
+
+    addr.do_send(Prepare).await.unwrap();
+    addr.do_send(Write("a")).await.unwrap();
+    addr.do_send(Write("b")).await.unwrap();
+    addr.do_send(CommitVote).await.unwrap();
+    addr.do_send(Write("c")).await.unwrap();
+    // the set-aside writes go before the one still in the mailbox
+    assert_eq!(
+        addr.send(History).await.unwrap(),
+        vec!["prepare", "commit", "a", "b", "c"]
+    );
+
+    addr.do_send(Prepare).await.unwrap();
+    addr.do_send(Write("d")).await.unwrap();
+    addr.do_send(Abort).await.unwrap();
+    assert_eq!(
+        addr.send(History).await.unwrap()[5..],
+        ["prepare", "abort", "d"]
+    );
+}
+
+// ======== Actor Future Tests ========
+
+struct Fetch;
+impl Message for Fetch {
+    type Result = ();
+}
+
+struct GetValue;
+impl Message for GetValue {
+    type Result = Option<u32>;
+}
+
+struct Fetcher {
+    value: Option<u32>,
+}
+
+impl Actor for Fetcher {}
+
+impl Handler<Fetch> for Fetcher {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Fetch, ctx: &mut Context<Self>) {
+        ctx.spawn_future(
+            async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                42
+            },
+            |value, actor: &mut Self, _ctx| actor.value = Some(value),
+        );
+    }
+}
+
+impl Handler<GetValue> for Fetcher {
+    type Result = Option<u32>;
+
+    fn handle(&mut self, _msg: GetValue, _ctx: &mut Context<Self>) -> Option<u32> {
+        self.value
+    }
+}
+
+#[tokio::test]
+async fn spawn_future_continues_on_actor() {
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Fetcher { value: None });
+
+    addr.do_send(Fetch).await.unwrap();
+    // the actor keeps handling mail while the future runs
+    assert_eq!(addr.send(GetValue).await, Ok(None));
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert_eq!(addr.send(GetValue).await, Ok(Some(42)));
+}
+
 // ======== Timer Cancellation Tests ========
 
 struct CancelTickActor {
@@ -210,6 +895,8 @@ impl Actor for CancelTickActor {
 }
 
 impl Handler<Tick> for CancelTickActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Tick, _ctx: &mut Context<Self>) {
         self.count.fetch_add(1, Ordering::SeqCst);
     }
@@ -256,12 +943,16 @@ impl Actor for CancellableActor {
 }
 
 impl Handler<Tick> for CancellableActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Tick, _ctx: &mut Context<Self>) {
         self.count.fetch_add(1, Ordering::SeqCst);
     }
 }
 
 impl Handler<CancelMsg> for CancellableActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: CancelMsg, _ctx: &mut Context<Self>) {
         if let Some(ref handle) = self.handle {
             handle.cancel();
@@ -309,12 +1000,16 @@ impl Actor for CancellableIntervalActor {
 }
 
 impl Handler<IntervalTick> for CancellableIntervalActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: IntervalTick, _ctx: &mut Context<Self>) {
         self.count.fetch_add(1, Ordering::SeqCst);
     }
 }
 
 impl Handler<CancelMsg> for CancellableIntervalActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: CancelMsg, _ctx: &mut Context<Self>) {
         if let Some(ref handle) = self.handle {
             handle.cancel();
@@ -379,6 +1074,47 @@ impl AsyncHandler<FetchData> for AsyncActor {
     }
 }
 
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct CountBytes(Vec<u8>);
+
+#[derive(Message)]
+#[rtype(u64)]
+struct Pause(u64);
+
+struct MacroActor {
+    paused: u64,
+}
+
+impl Actor for MacroActor {}
+
+#[cinema::async_handler]
+impl AsyncHandler<CountBytes> for MacroActor {
+    async fn handle(&mut self, msg: CountBytes, _ctx: &mut Context<Self>) -> usize {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        msg.0.len()
+    }
+}
+
+#[cinema::async_handler]
+impl AsyncHandler<Pause> for MacroActor {
+    async fn handle(&mut self, msg: Pause, _ctx: &mut Context<Self>) -> u64 {
+        tokio::time::sleep(Duration::from_millis(msg.0)).await;
+        self.paused += msg.0;
+        self.paused
+    }
+}
+
+#[tokio::test]
+async fn async_handler_macro_generates_boxed_futures() {
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(MacroActor { paused: 0 });
+
+    assert_eq!(addr.send_async(CountBytes(vec![1, 2, 3])).await.unwrap(), 3);
+    assert_eq!(addr.send_async(Pause(5)).await.unwrap(), 5);
+    assert_eq!(addr.send_async(Pause(5)).await.unwrap(), 10);
+}
+
 #[tokio::test]
 async fn async_handler_returns_response() {
     let sys = ActorSystem::new();
@@ -419,3 +1155,290 @@ async fn async_handler_fire_and_forget() {
 
     assert_eq!(count.load(Ordering::SeqCst), 1);
 }
+
+// ======== Envelope Extension Tests ========
+
+struct Tagged {
+    tag: &'static str,
+    log: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl Envelope<LogActor> for Tagged {
+    fn handle(self: Box<Self>, actor: &mut LogActor, _ctx: &mut Context<LogActor>) {
+        actor.entries += 1;
+        self.log.lock().unwrap().push(format!("custom:{}", self.tag));
+    }
+}
+
+struct LogActor {
+    entries: usize,
+    log: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl Actor for LogActor {}
+
+struct Entry;
+impl Message for Entry {
+    type Result = usize;
+}
+
+impl Handler<Entry> for LogActor {
+    type Result = usize;
+
+    fn handle(&mut self, _msg: Entry, _ctx: &mut Context<Self>) -> usize {
+        self.entries += 1;
+        self.log.lock().unwrap().push("handler".to_string());
+        self.entries
+    }
+}
+
+struct LogHook;
+
+impl DispatchHook<LogActor> for LogHook {
+    fn before(&mut self, actor: &mut LogActor, _ctx: &mut Context<LogActor>) {
+        actor.log.lock().unwrap().push("before".to_string());
+    }
+
+    fn after(&mut self, actor: &mut LogActor, _ctx: &mut Context<LogActor>) {
+        actor.log.lock().unwrap().push("after".to_string());
+    }
+}
+
+#[tokio::test]
+async fn custom_envelope_is_dispatched() {
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(LogActor {
+        entries: 0,
+        log: log.clone(),
+    });
+
+    addr.send_envelope(ActorMessage::Sync(Box::new(Tagged {
+        tag: "a",
+        log: log.clone(),
+    })))
+    .await
+    .unwrap();
+
+    // custom envelope shares the mailbox ordering with regular messages
+    assert_eq!(addr.send(Entry).await.unwrap(), 2);
+    assert_eq!(*log.lock().unwrap(), vec!["custom:a", "handler"]);
+}
+
+#[tokio::test]
+async fn dispatch_hook_runs_around_handler() {
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(LogActor {
+        entries: 0,
+        log: log.clone(),
+    });
+
+    let envelope = ActorMessage::Sync(Box::new(MessageEnvelope::new(Entry)));
+    assert!(envelope.message_type().ends_with("Entry"));
+
+    addr.try_send_envelope(envelope.with_hook(LogHook)).unwrap();
+    addr.send(Entry).await.unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["before", "handler", "after", "handler"]
+    );
+}
+
+// ======== Unhandled Message Tests ========
+
+struct Strict {
+    seen: Arc<std::sync::Mutex<Vec<UnhandledMessage>>>,
+}
+
+impl Actor for Strict {
+    fn unhandled(&mut self, msg: UnhandledMessage, _ctx: &mut Context<Self>) {
+        self.seen.lock().unwrap().push(msg);
+    }
+}
+
+impl Handler<Ping> for Strict {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) {}
+}
+
+#[tokio::test]
+async fn unhandled_hook_receives_reports() {
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Strict { seen: seen.clone() });
+
+    addr.notify_unhandled("app::Unknown", MessageOrigin::Local);
+    addr.send(Ping).await.unwrap();
+
+    assert_eq!(addr.unhandled_messages(), 1);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![UnhandledMessage {
+            message_type: "app::Unknown".to_string(),
+            origin: MessageOrigin::Local,
+        }]
+    );
+}
+
+// ======== Closure Actor Tests ========
+
+#[tokio::test]
+async fn spawn_fn_keeps_state_between_messages() {
+    let sys = ActorSystem::new();
+    let totals = sys.spawn_fn(0u32, |total, msg: Add, _ctx| {
+        *total += msg.0 + msg.1;
+        *total
+    });
+
+    assert_eq!(totals.send(Add(1, 2)).await.unwrap(), 3);
+    assert_eq!(totals.send(Add(10, 0)).await.unwrap(), 13);
+}
+
+#[tokio::test]
+async fn spawn_fn_can_use_context() {
+    let sys = ActorSystem::new();
+    let once = sys.spawn_fn((), |_, _msg: Ping, ctx| ctx.stop());
+
+    once.do_send(Ping).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(!once.is_alive());
+}
+
+// ======== Receiver Actor Tests ========
+
+struct Event(u32);
+impl Message for Event {
+    type Result = ();
+}
+
+#[tokio::test]
+async fn spawn_receiver_streams_messages() {
+    use futures::StreamExt;
+
+    let sys = ActorSystem::new();
+    let (addr, mut events) = sys.spawn_receiver::<Event>();
+
+    addr.do_send(Event(1)).await.unwrap();
+    addr.try_send(Event(2)).unwrap();
+
+    assert_eq!(events.next().await.unwrap().0, 1);
+    assert_eq!(events.next().await.unwrap().0, 2);
+}
+
+#[tokio::test]
+async fn spawn_receiver_stops_when_stream_dropped() {
+    let sys = ActorSystem::new();
+    let (addr, events) = sys.spawn_receiver::<Event>();
+    drop(events);
+
+    addr.do_send(Event(1)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(!addr.is_alive());
+}
+
+// ======== Weak Address Tests ========
+
+#[tokio::test]
+async fn weak_addr_upgrades_while_actor_alive() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(PingActor {
+        count: count.clone(),
+    });
+
+    let weak = addr.downgrade();
+    assert_eq!(weak.id(), addr.id());
+
+    weak.upgrade().unwrap().do_send(Ping).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+    assert!(addr.is_alive());
+}
+
+#[tokio::test]
+async fn dropping_last_addr_stops_actor() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(PingActor {
+        count: count.clone(),
+    });
+    let weak = addr.downgrade();
+
+    // queued messages are still handled before the actor stops
+    addr.do_send(Ping).await.unwrap();
+    let clone = addr.clone();
+    drop(addr);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(weak.is_alive());
+
+    drop(clone);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert!(!weak.is_alive());
+    assert!(weak.upgrade().is_none());
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+}
+
+// ======== Throttled Address Tests ========
+
+#[tokio::test]
+async fn throttled_addr_limits_send_rate() {
+    let sys = ActorSystem::new();
+    let calculator = sys.spawn(Calculator).throttled(20, 2);
+    let fetcher = sys.spawn(AsyncActor).throttled(20, 1);
+
+    // the burst goes through right away, then the bucket is empty
+    calculator.try_send(Add(1, 1)).unwrap();
+    assert_eq!(calculator.send(Add(1, 2)).await.unwrap(), 3);
+    assert_eq!(calculator.try_send(Add(1, 3)), Err(MailboxError::Throttled));
+
+    // awaiting sends wait for a token instead, one every 50ms
+    let start = std::time::Instant::now();
+    assert_eq!(calculator.send(Add(2, 2)).await.unwrap(), 4);
+    calculator.do_send(Add(2, 3)).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(90));
+
+    // async handlers share the same rules
+    assert_eq!(fetcher.send_async(FetchData).await.unwrap(), "data fetched");
+    assert_eq!(
+        fetcher.try_send_async(FetchData),
+        Err(MailboxError::Throttled)
+    );
+}
+
+// ======== Circuit Breaker Tests ========
+
+#[tokio::test]
+async fn circuit_breaker_fails_fast_and_half_opens() {
+    use cinema::{BreakerState, CircuitBreaker};
+
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Calculator);
+    let breaker = CircuitBreaker::new(addr, 2, Duration::from_millis(50));
+    assert_eq!(breaker.send(Add(1, 1)).await, Ok(2));
+
+    sys.shutdown();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(breaker.send(Add(1, 1)).await, Err(MailboxError::MailboxClosed));
+    assert_eq!(breaker.state(), BreakerState::Closed);
+    assert_eq!(breaker.do_send(Add(1, 1)).await, Err(MailboxError::MailboxClosed));
+    assert_eq!(breaker.state(), BreakerState::Open);
+    assert_eq!(breaker.send(Add(1, 1)).await, Err(MailboxError::CircuitOpen));
+
+    // the trial call after the cooldown fails, so the breaker opens again
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(breaker.state(), BreakerState::HalfOpen);
+    assert_eq!(breaker.send(Add(1, 1)).await, Err(MailboxError::MailboxClosed));
+    assert_eq!(breaker.send(Add(1, 1)).await, Err(MailboxError::CircuitOpen));
+
+    // a successful trial closes it
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(breaker.call(async { Ok::<_, MailboxError>(7) }).await, Ok(7));
+    assert_eq!(breaker.state(), BreakerState::Closed);
+}
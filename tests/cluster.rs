@@ -68,13 +68,13 @@ async fn seven_nodes_gossip_converge() {
     tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 
     // Chain: 1->2->3->4->5->6->7
-    for i in 0..6 {
+    for (i, node) in nodes.iter().enumerate().take(6) {
         let peer = Node {
             id: format!("node-{}", i + 2),
             addr: format!("127.0.0.1:{}", 9101 + (i + 1)),
             status: NodeStatus::Up,
         };
-        nodes[i].send_gossip_to(&peer).await.unwrap();
+        node.send_gossip_to(&peer).await.unwrap();
         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
     }
 
@@ -370,7 +370,7 @@ async fn cluster_remote_communication() {
     }
     impl cinema::remote::RemoteMessage for Ping {}
 
-    #[derive(Clone, ProstMessage)]
+    #[derive(Clone, ProstMessage, cinema::MessageResponse)]
     struct Pong {
         #[prost(string, tag = "1")]
         reply: String,
@@ -384,6 +384,8 @@ async fn cluster_remote_communication() {
     struct PingPong;
     impl Actor for PingPong {}
     impl Handler<Ping> for PingPong {
+        type Result = Pong;
+
         fn handle(&mut self, msg: Ping, _ctx: &mut Context<Self>) -> Pong {
             println!("pingpong received: {}", msg.msg);
             Pong {
@@ -477,3 +479,1169 @@ async fn cluster_remote_communication() {
     assert_eq!(pong.reply, "pong: hello typed");
     println!("high-level call successful: {}", pong.reply);
 }
+
+#[test]
+fn phi_accrual_detector_follows_heartbeat_rhythm() {
+    use cinema::remote::{FailureDetection, FailureDetector, PhiAccrualConfig, Verdict};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    let ms = Duration::from_millis;
+    let config = PhiAccrualConfig::default()
+        .min_std_deviation(ms(10))
+        .down_after(ms(500));
+    let mut detector = FailureDetector::new(FailureDetection::PhiAccrual(config));
+
+    // a heartbeat every 100ms
+    let start = Instant::now();
+    for i in 0..20 {
+        detector.heartbeat("node-2", start + ms(100 * i));
+    }
+    let last = start + ms(1900);
+
+    // on time is unremarkable, a few missed heartbeats are not
+    assert!(detector.phi("node-2", last + ms(100)) < 1.0);
+    assert!(detector.phi("node-2", last + ms(200)) > 8.0);
+    assert!(detector.phi("node-2", last + ms(400)) > detector.phi("node-2", last + ms(200)));
+
+    assert_eq!(detector.check("node-2", last + ms(100)), Some(Verdict::Healthy));
+    assert_eq!(detector.check("node-2", last + ms(300)), Some(Verdict::Suspect));
+    assert_eq!(detector.check("node-2", last + ms(900)), Some(Verdict::Dead));
+    assert_eq!(detector.check("node-3", last), None);
+
+    // a member heard from irregularly gets more leeway
+    let mut detector = FailureDetector::new(FailureDetection::PhiAccrual(config));
+    for (i, at) in [0, 150, 200, 450, 500, 800, 850, 1100].into_iter().enumerate() {
+        detector.heartbeat("node-2", start + ms(at));
+        assert!(i < 1 || detector.phi("node-2", start + ms(at)) < 1.0);
+    }
+    assert!(detector.phi("node-2", start + ms(1400)) < 8.0);
+}
+
+#[tokio::test]
+async fn phi_accrual_marks_silent_member_suspect_then_down() {
+    use cinema::remote::{FailureDetection, PhiAccrualConfig};
+    use std::{sync::Arc, time::Duration};
+
+    let detection = FailureDetection::PhiAccrual(
+        PhiAccrualConfig::default()
+            .threshold(3.0)
+            .min_std_deviation(Duration::from_millis(20))
+            .first_heartbeat_estimate(Duration::from_millis(50))
+            .down_after(Duration::from_millis(300)),
+    );
+    let node1 = Arc::new(
+        ClusterNode::new("node-1".to_string(), "127.0.0.1:9601".to_string())
+            .failure_detection(detection),
+    );
+    let node2 = Arc::new(ClusterNode::new(
+        "node-2".to_string(),
+        "127.0.0.1:9602".to_string(),
+    ));
+    node2
+        .add_member(Node {
+            id: "node-1".to_string(),
+            addr: "127.0.0.1:9601".to_string(),
+            status: NodeStatus::Up,
+        })
+        .await;
+
+    tokio::spawn(node1.clone().start_gossip_server(9601));
+    let server2 = tokio::spawn(node2.clone().start_gossip_server(9602));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // node-2 gossips to node-1 every 50ms, node-1 only checks
+    let gossip2 = node2
+        .clone()
+        .start_periodic_gossip(Duration::from_millis(50), Duration::from_secs(10));
+    let gossip1 = node1
+        .clone()
+        .start_periodic_gossip(Duration::from_millis(50), Duration::from_secs(10));
+
+    let status = |node: Arc<ClusterNode>| async move {
+        node.get_members()
+            .await
+            .into_iter()
+            .find(|n| n.id == "node-2")
+            .map(|n| n.status)
+    };
+
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert_eq!(status(node1.clone()).await, Some(NodeStatus::Up));
+
+    // node-2 goes silent
+    gossip2.abort();
+    server2.abort();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(status(node1.clone()).await, Some(NodeStatus::Suspect));
+    assert!(node1.phi("node-2").await >= 3.0);
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(status(node1.clone()).await, Some(NodeStatus::Down));
+    gossip1.abort();
+}
+
+#[tokio::test]
+async fn swim_spreads_membership_from_a_single_seed() {
+    use cinema::remote::SwimConfig;
+    use std::{sync::Arc, time::Duration};
+
+    let swim = SwimConfig::default()
+        .protocol_period(Duration::from_millis(50))
+        .ping_timeout(Duration::from_millis(20));
+    let nodes: Vec<Arc<ClusterNode>> = (1..=5)
+        .map(|i| {
+            Arc::new(
+                ClusterNode::new(format!("node-{}", i), format!("127.0.0.1:{}", 9800 + i))
+                    .swim(swim),
+            )
+        })
+        .collect();
+
+    for (i, node) in nodes.iter().enumerate() {
+        tokio::spawn(node.clone().start_gossip_server(9801 + i as u16));
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // everyone only knows node-1
+    for node in &nodes[1..] {
+        node.add_member(Node {
+            id: "node-1".to_string(),
+            addr: "127.0.0.1:9801".to_string(),
+            status: NodeStatus::Up,
+        })
+        .await;
+    }
+    let handles: Vec<_> = nodes.iter().map(|n| n.clone().start_swim()).collect();
+
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+
+    for node in &nodes {
+        let members = node.get_members().await;
+        assert_eq!(members.len(), 5, "{} knows {}", node.local_node.id, members.len());
+        assert!(members.iter().all(|n| n.status == NodeStatus::Up));
+    }
+    handles.iter().for_each(|h| h.abort());
+}
+
+#[tokio::test]
+async fn swim_marks_unreachable_member_suspect_then_down_everywhere() {
+    use cinema::remote::SwimConfig;
+    use std::{sync::Arc, time::Duration};
+
+    let swim = SwimConfig::default()
+        .protocol_period(Duration::from_millis(50))
+        .ping_timeout(Duration::from_millis(20))
+        .suspect_timeout(Duration::from_millis(300));
+    let nodes: Vec<Arc<ClusterNode>> = (1..=3)
+        .map(|i| {
+            Arc::new(
+                ClusterNode::new(format!("node-{}", i), format!("127.0.0.1:{}", 9810 + i))
+                    .swim(swim),
+            )
+        })
+        .collect();
+    nodes[1]
+        .test_insert_actor("cache".to_string(), "node-3".to_string(), "Cache".to_string())
+        .await;
+
+    let servers: Vec<_> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| tokio::spawn(node.clone().start_gossip_server(9811 + i as u16)))
+        .collect();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    for node in &nodes[1..] {
+        node.add_member(Node {
+            id: "node-1".to_string(),
+            addr: "127.0.0.1:9811".to_string(),
+            status: NodeStatus::Up,
+        })
+        .await;
+    }
+    let handles: Vec<_> = nodes.iter().map(|n| n.clone().start_swim()).collect();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(nodes[0].get_members().await.len(), 3);
+
+    // node-3 disappears
+    handles[2].abort();
+    servers[2].abort();
+
+    let status = |node: Arc<ClusterNode>| async move {
+        node.get_members()
+            .await
+            .into_iter()
+            .find(|n| n.id == "node-3")
+            .map(|n| n.status)
+    };
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    for node in &nodes[..2] {
+        assert_eq!(status(node.clone()).await, Some(NodeStatus::Suspect));
+    }
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    for node in &nodes[..2] {
+        assert_eq!(status(node.clone()).await, Some(NodeStatus::Down));
+    }
+    assert!(nodes[1].lookup_actor("cache").await.is_none());
+    assert_eq!(status(nodes[0].clone()).await, Some(NodeStatus::Down));
+    handles.iter().for_each(|h| h.abort());
+}
+
+#[tokio::test]
+async fn suspected_node_refutes_with_higher_incarnation() {
+    use cinema::remote::proto::{self, GossipMessage, NodeInfo};
+
+    let node1 = ClusterNode::new("node-1".to_string(), "127.0.0.1:8001".to_string());
+    let node2 = ClusterNode::new("node-2".to_string(), "127.0.0.1:8002".to_string());
+    node1
+        .merge_gossip(node2.create_gossip_message().await, "node-2")
+        .await;
+
+    // node-1 hears that node-2 is suspect
+    let suspicion = GossipMessage {
+        members: vec![NodeInfo {
+            id: "node-2".to_string(),
+            addr: "127.0.0.1:8002".to_string(),
+            status: proto::NodeStatus::Suspect as i32,
+            incarnation: 0,
+            generation: 0,
+        }],
+        actors: vec![],
+        topics: vec![],
+    };
+    node1.merge_gossip(suspicion, "node-3").await;
+    let status = |members: Vec<Node>| {
+        members
+            .into_iter()
+            .find(|n| n.id == "node-2")
+            .unwrap()
+            .status
+    };
+    assert_eq!(status(node1.get_members().await), NodeStatus::Suspect);
+
+    // an Up at the same incarnation doesn't clear the suspicion
+    let stale = node2.create_gossip_message().await;
+    node1.merge_gossip(stale, "node-2").await;
+    assert_eq!(status(node1.get_members().await), NodeStatus::Suspect);
+
+    // node-2 learns it's suspected and refutes
+    node2
+        .merge_gossip(node1.create_gossip_message().await, "node-1")
+        .await;
+    let refutation = node2.create_gossip_message().await;
+    let own = refutation.members.iter().find(|n| n.id == "node-2").unwrap();
+    assert_eq!(
+        (own.status, own.incarnation),
+        (proto::NodeStatus::Up as i32, 1)
+    );
+    assert_eq!(status(node2.get_members().await), NodeStatus::Up);
+
+    // the refutation beats the suspicion
+    node1.merge_gossip(refutation, "node-2").await;
+    assert_eq!(status(node1.get_members().await), NodeStatus::Up);
+}
+
+#[tokio::test]
+async fn join_pulls_membership_from_seed_and_announces_itself() {
+    use std::{sync::Arc, time::Duration};
+
+    let nodes: Vec<Arc<ClusterNode>> = (1..=3)
+        .map(|i| {
+            Arc::new(ClusterNode::new(
+                format!("node-{}", i),
+                format!("127.0.0.1:{}", 9820 + i),
+            ))
+        })
+        .collect();
+    for (i, node) in nodes.iter().enumerate() {
+        tokio::spawn(node.clone().start_gossip_server(9821 + i as u16));
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // node-1 starts the cluster, node-2 joins it
+    nodes[0].join(&["127.0.0.1:9821"]).await.unwrap();
+    nodes[1]
+        .register_actor("cache".to_string(), "Cache".to_string())
+        .await;
+    nodes[1].join(&["127.0.0.1:9821"]).await.unwrap();
+
+    // a dead seed doesn't keep node-3 out
+    nodes[2]
+        .join(&["127.0.0.1:9829", "127.0.0.1:9821"])
+        .await
+        .unwrap();
+
+    let mut known: Vec<String> = nodes[2]
+        .get_members()
+        .await
+        .into_iter()
+        .map(|n| n.id)
+        .collect();
+    known.sort();
+    assert_eq!(known, ["node-1", "node-2", "node-3"]);
+    assert_eq!(
+        nodes[2].lookup_actor("cache").await,
+        Some(("node-2".to_string(), "Cache".to_string()))
+    );
+    assert_eq!(nodes[0].get_members().await.len(), 3);
+}
+
+#[tokio::test]
+async fn join_retries_until_a_seed_is_up_and_checks_the_handshake() {
+    use cinema::remote::{
+        proto::Envelope, Connection, HandshakeConfig, ReconnectPolicy, TcpTransport, Transport,
+        TransportError, HANDSHAKE,
+    };
+    use std::{sync::Arc, time::Duration};
+
+    let retry = ReconnectPolicy::new(Duration::from_millis(20), Duration::from_millis(50));
+    let seed = Arc::new(
+        ClusterNode::new("seed".to_string(), "127.0.0.1:9831".to_string())
+            .handshake(HandshakeConfig::new("seed", "prod").token("secret")),
+    );
+    let joiner = Arc::new(
+        ClusterNode::new("joiner".to_string(), "127.0.0.1:9832".to_string())
+            .handshake(HandshakeConfig::new("joiner", "prod").token("secret"))
+            .join_retry(retry),
+    );
+
+    // nobody there yet
+    let joining = tokio::spawn({
+        let joiner = joiner.clone();
+        async move { joiner.join(&["127.0.0.1:9831"]).await }
+    });
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(!joining.is_finished());
+
+    tokio::spawn(seed.clone().start_gossip_server(9831));
+    tokio::time::timeout(Duration::from_secs(2), joining)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(seed.get_members().await.len(), 2);
+
+    // another cluster is turned away, without retrying
+    let intruder = ClusterNode::new("intruder".to_string(), "127.0.0.1:9833".to_string())
+        .handshake(HandshakeConfig::new("intruder", "staging"));
+    let result = intruder.join(&["127.0.0.1:9831"]).await;
+    assert!(
+        matches!(result, Err(TransportError::Handshake(_))),
+        "{:?}",
+        result.err()
+    );
+    assert_eq!(seed.get_members().await.len(), 2);
+
+    // connections that skip the handshake are turned away, members' gossip isn't
+    let mut raw = TcpTransport.connect("127.0.0.1:9831").await.unwrap();
+    let gossip = Envelope {
+        message_type: "cluster".to_string(),
+        ..Default::default()
+    };
+    raw.send(gossip).await.unwrap();
+    assert_eq!(raw.recv().await.unwrap().message_type, HANDSHAKE);
+    assert!(raw.recv().await.is_err());
+    let seed_node = Node {
+        id: "seed".to_string(),
+        addr: "127.0.0.1:9831".to_string(),
+        status: NodeStatus::Up,
+    };
+    joiner.send_gossip_to(&seed_node).await.unwrap();
+
+    // bounded retries give up when no seed ever answers
+    let loner = ClusterNode::new("loner".to_string(), "127.0.0.1:9834".to_string())
+        .join_retry(retry.max_attempts(3));
+    assert!(loner.join(&["127.0.0.1:9839"]).await.is_err());
+}
+
+#[tokio::test]
+async fn membership_changes_reach_subscribed_actors() {
+    use cinema::remote::cluster::{MemberDown, MemberSuspect, MemberUp};
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    struct Topology {
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+    impl Actor for Topology {}
+    impl Handler<MemberUp> for Topology {
+        type Result = ();
+        fn handle(&mut self, msg: MemberUp, _ctx: &mut Context<Self>) {
+            self.seen.lock().unwrap().push(format!("up {}", msg.0.id));
+        }
+    }
+    impl Handler<MemberSuspect> for Topology {
+        type Result = ();
+        fn handle(&mut self, msg: MemberSuspect, _ctx: &mut Context<Self>) {
+            self.seen.lock().unwrap().push(format!("suspect {}", msg.0.id));
+        }
+    }
+    impl Handler<MemberDown> for Topology {
+        type Result = ();
+        fn handle(&mut self, msg: MemberDown, _ctx: &mut Context<Self>) {
+            self.seen.lock().unwrap().push(format!("down {}", msg.0.id));
+        }
+    }
+
+    let system = ActorSystem::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let topology = system.spawn(Topology { seen: seen.clone() });
+
+    let node1 = Arc::new(ClusterNode::new(
+        "node-1".to_string(),
+        "127.0.0.1:9841".to_string(),
+    ));
+    node1.events().subscribe::<MemberUp, _>(&topology);
+    node1.events().subscribe::<MemberSuspect, _>(&topology);
+    node1.events().subscribe::<MemberDown, _>(&topology);
+
+    // node-2 is never started
+    node1
+        .add_member(Node {
+            id: "node-2".to_string(),
+            addr: "127.0.0.1:9842".to_string(),
+            status: NodeStatus::Up,
+        })
+        .await;
+    // learning it again changes nothing
+    node1
+        .add_member(Node {
+            id: "node-2".to_string(),
+            addr: "127.0.0.1:9842".to_string(),
+            status: NodeStatus::Up,
+        })
+        .await;
+
+    let _handle = node1
+        .clone()
+        .start_periodic_gossip(Duration::from_millis(50), Duration::from_millis(100));
+    tokio::time::sleep(Duration::from_millis(400)).await;
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        ["up node-2", "suspect node-2", "down node-2"]
+    );
+}
+
+#[tokio::test]
+async fn distributed_pubsub_reaches_subscribers_on_every_node() {
+    use cinema::remote::{
+        proto::{self, GossipMessage, NodeInfo},
+        DistributedPubSub,
+    };
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    #[derive(Clone, ProstMessage)]
+    struct Announcement {
+        #[prost(string, tag = "1")]
+        text: String,
+    }
+    impl Message for Announcement {
+        type Result = ();
+    }
+    impl cinema::remote::RemoteMessage for Announcement {}
+
+    struct Reader {
+        name: &'static str,
+        read: Arc<Mutex<Vec<String>>>,
+    }
+    impl Actor for Reader {}
+    impl Handler<Announcement> for Reader {
+        type Result = ();
+        fn handle(&mut self, msg: Announcement, _ctx: &mut Context<Self>) {
+            self.read
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", self.name, msg.text));
+        }
+    }
+
+    let node1 = Arc::new(ClusterNode::new(
+        "node-1".to_string(),
+        "127.0.0.1:9851".to_string(),
+    ));
+    let node2 = Arc::new(ClusterNode::new(
+        "node-2".to_string(),
+        "127.0.0.1:9852".to_string(),
+    ));
+    tokio::spawn(node1.clone().start_gossip_server(9851));
+    tokio::spawn(node2.clone().start_gossip_server(9852));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let system = ActorSystem::new();
+    let read = Arc::new(Mutex::new(Vec::new()));
+    let reader = |name| {
+        system.spawn(Reader {
+            name,
+            read: read.clone(),
+        })
+    };
+    let (a, b, c) = (reader("a"), reader("b"), reader("c"));
+
+    let pubsub1 = DistributedPubSub::new(node1.clone());
+    let pubsub2 = DistributedPubSub::new(node2.clone());
+    pubsub1.subscribe::<Announcement, _>("news", &a);
+    pubsub2.subscribe::<Announcement, _>("news", &b);
+    pubsub2.subscribe::<Announcement, _>("alerts", &c);
+
+    // subscriptions travel with the membership exchange
+    node2.join(&["127.0.0.1:9851"]).await.unwrap();
+    let ids = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.id).collect::<Vec<_>>();
+    assert_eq!(ids(node1.subscribed_nodes("news").await), ["node-2"]);
+    assert_eq!(ids(node2.subscribed_nodes("news").await), ["node-1"]);
+    assert_eq!(ids(node1.subscribed_nodes("alerts").await), ["node-2"]);
+    assert!(node2.subscribed_nodes("alerts").await.is_empty());
+
+    let news = Announcement {
+        text: "release".to_string(),
+    };
+    assert_eq!(pubsub1.publish("news", news.clone()).await, 2);
+    let alert = Announcement {
+        text: "disk full".to_string(),
+    };
+    assert_eq!(pubsub2.publish("alerts", alert).await, 1);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let mut got = read.lock().unwrap().clone();
+    got.sort();
+    assert_eq!(got, ["a: release", "b: release", "c: disk full"]);
+
+    // once node-2 is down its subscriptions are forgotten
+    let down = GossipMessage {
+        members: vec![NodeInfo {
+            id: "node-2".to_string(),
+            addr: "127.0.0.1:9852".to_string(),
+            status: proto::NodeStatus::Down as i32,
+            incarnation: 0,
+            generation: 0,
+        }],
+        actors: vec![],
+        topics: vec![],
+    };
+    node1.merge_gossip(down, "node-3").await;
+    assert!(node1.subscribed_nodes("news").await.is_empty());
+    assert_eq!(pubsub1.publish("news", news).await, 1);
+}
+
+#[tokio::test]
+async fn cluster_router_spreads_over_hosting_members_and_skips_unreachable() {
+    use cinema::remote::{ClusterRouter, Routing};
+    use std::{sync::Arc, time::Duration};
+
+    #[derive(Clone, ProstMessage)]
+    struct Work {
+        #[prost(uint32, tag = "1")]
+        n: u32,
+    }
+    impl Message for Work {
+        type Result = Done;
+    }
+    impl cinema::remote::RemoteMessage for Work {}
+
+    #[derive(Clone, ProstMessage, cinema::MessageResponse)]
+    struct Done {
+        #[prost(string, tag = "1")]
+        by: String,
+    }
+    impl Message for Done {
+        type Result = ();
+    }
+    impl cinema::remote::RemoteMessage for Done {}
+
+    struct Worker {
+        node: &'static str,
+    }
+    impl Actor for Worker {}
+    impl Handler<Work> for Worker {
+        type Result = Done;
+        fn handle(&mut self, _msg: Work, _ctx: &mut Context<Self>) -> Done {
+            Done {
+                by: self.node.to_string(),
+            }
+        }
+    }
+
+    // every node runs its own "worker" and registers it
+    let system = ActorSystem::new();
+    let mut nodes = Vec::new();
+    for (i, id) in ["node-1", "node-2", "node-3"].into_iter().enumerate() {
+        let port = 9861 + i as u16;
+        let node = Arc::new(ClusterNode::new(id.to_string(), format!("127.0.0.1:{}", port)));
+        let worker = system.spawn(Worker { node: id });
+        let handler = MessageRouter::new()
+            .route::<Work>(LocalNode::new(id).handler::<Worker, Work>(worker))
+            .build();
+        tokio::spawn(node.clone().start_server(port, Some(handler)));
+        ClusterRouter::<Worker>::register(&node, "worker").await;
+        nodes.push(node);
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let node1 = nodes[0].clone();
+    for node in &nodes[1..] {
+        node1
+            .merge_gossip(node.create_gossip_message().await, &node.local_node.id)
+            .await;
+    }
+    // registered, but nothing listens there
+    let node4 = ClusterNode::new("node-4".to_string(), "127.0.0.1:9864".to_string());
+    ClusterRouter::<Worker>::register(&node4, "worker").await;
+    node1.merge_gossip(node4.create_gossip_message().await, "node-4").await;
+    // registered, but down
+    let node5 = ClusterNode::new("node-5".to_string(), "127.0.0.1:9865".to_string());
+    ClusterRouter::<Worker>::register(&node5, "worker").await;
+    node1.merge_gossip(node5.create_gossip_message().await, "node-5").await;
+    node1
+        .add_member(Node {
+            id: "node-5".to_string(),
+            addr: "127.0.0.1:9865".to_string(),
+            status: NodeStatus::Down,
+        })
+        .await;
+    // up, but hosts no worker
+    node1
+        .add_member(Node {
+            id: "node-6".to_string(),
+            addr: "127.0.0.1:9866".to_string(),
+            status: NodeStatus::Up,
+        })
+        .await;
+
+    // the local worker is not a routee
+    let router: ClusterRouter<Worker> = ClusterRouter::round_robin(node1.clone(), "worker");
+    let routees: Vec<String> = router.routees().await.into_iter().map(|n| n.id).collect();
+    assert_eq!(routees, ["node-2", "node-3", "node-4"]);
+
+    let mut by = Vec::new();
+    for n in 0..6 {
+        by.push(router.call(Work { n }).await.unwrap().by);
+    }
+    // node-4 can't be dialed, its turns go on to the next routee
+    assert_eq!(by, ["node-2", "node-3", "node-2", "node-2", "node-3", "node-2"]);
+
+    // least recently used: a failed dial counts as a use too, so
+    // node-4 goes to the back of the line like the routees that answered
+    let router: ClusterRouter<Worker> =
+        ClusterRouter::least_recently_used(node1.clone(), "worker");
+    assert_eq!(router.routing(), Routing::LeastRecentlyUsed);
+    let mut by = Vec::new();
+    for n in 0..4 {
+        by.push(router.call(Work { n }).await.unwrap().by);
+    }
+    assert_eq!(by, ["node-2", "node-3", "node-2", "node-3"]);
+}
+
+#[tokio::test]
+async fn peers_learn_the_advertised_address_not_the_bind_address() {
+    use std::{sync::Arc, time::Duration};
+
+    let node = ClusterNode::new("node-3".to_string(), "node-3.cinema.svc:9000".to_string());
+    assert_eq!(node.bind_addr(), "0.0.0.0:9000");
+    let node = node.bind("0.0.0.0:9001");
+    assert_eq!(node.bind_addr(), "0.0.0.0:9001");
+    assert_eq!(node.advertised_addr(), "node-3.cinema.svc:9000");
+
+    // listens on the loopback address, tells peers to use the host name
+    let node1 = Arc::new(
+        ClusterNode::new("node-1".to_string(), "localhost:9871".to_string()).bind("127.0.0.1:9871"),
+    );
+    let node2 = Arc::new(ClusterNode::new(
+        "node-2".to_string(),
+        "127.0.0.1:9872".to_string(),
+    ));
+    tokio::spawn(node1.clone().serve(None));
+    tokio::spawn(node2.clone().serve(None));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    node2.join(&["127.0.0.1:9871"]).await.unwrap();
+    let node1_seen = node2
+        .get_members()
+        .await
+        .into_iter()
+        .find(|n| n.id == "node-1")
+        .unwrap();
+    assert_eq!(node1_seen.addr, "localhost:9871");
+
+    // and the advertised address is one peers can reach it at
+    let node3 = ClusterNode::new("node-3".to_string(), "127.0.0.1:9873".to_string());
+    node3.join(&[&node1_seen.addr]).await.unwrap();
+    assert_eq!(node3.get_members().await.len(), 3);
+}
+
+#[tokio::test]
+async fn restarted_node_replaces_its_previous_generation() {
+    use cinema::remote::{HandshakeConfig, NodeId};
+    use std::{sync::Arc, time::Duration};
+
+    let id = NodeId::random();
+    assert_eq!(id.0.len(), 36);
+    assert_eq!(&id.0[14..15], "4");
+    assert_ne!(id, NodeId::random());
+
+    let cluster = |id: &str, port: u16| {
+        Arc::new(
+            ClusterNode::new(id.to_string(), format!("127.0.0.1:{}", port))
+                .handshake(HandshakeConfig::new(id, "shop")),
+        )
+    };
+    let node1 = cluster("node-1", 9881);
+    tokio::spawn(node1.clone().serve(None));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let node2 = cluster("node-2", 9882);
+    node2
+        .register_actor("cache".to_string(), "Cache".to_string())
+        .await;
+    node2.join(&["127.0.0.1:9881"]).await.unwrap();
+    assert!(node1.lookup_actor("cache").await.is_some());
+
+    // the same process joining again is just a reconnect
+    node2.join(&["127.0.0.1:9881"]).await.unwrap();
+    assert!(node1.lookup_actor("cache").await.is_some());
+
+    // a new process under the same id is a restart: its old actors are gone
+    let restarted = cluster("node-2", 9882);
+    assert!(restarted.generation() > node2.generation());
+    restarted.join(&["127.0.0.1:9881"]).await.unwrap();
+    assert_eq!(node1.lookup_actor("cache").await, None);
+    let members = node1.get_members().await;
+    let node2_seen = members.iter().find(|n| n.id == "node-2").unwrap();
+    assert_eq!(node2_seen.status, NodeStatus::Up);
+
+    // and news of the old generation no longer counts
+    node1
+        .merge_gossip(node2.create_gossip_message().await, "node-2")
+        .await;
+    assert_eq!(node1.lookup_actor("cache").await, None);
+}
+
+#[tokio::test]
+async fn receptionist_resolves_service_keys_on_any_node() {
+    use cinema::remote::Receptionist;
+    use std::{sync::Arc, time::Duration};
+
+    #[derive(Clone, ProstMessage)]
+    struct Quote {
+        #[prost(string, tag = "1")]
+        symbol: String,
+    }
+    impl Message for Quote {
+        type Result = Price;
+    }
+    impl cinema::remote::RemoteMessage for Quote {}
+
+    #[derive(Clone, ProstMessage, cinema::MessageResponse)]
+    struct Price {
+        #[prost(string, tag = "1")]
+        by: String,
+    }
+    impl Message for Price {
+        type Result = ();
+    }
+    impl cinema::remote::RemoteMessage for Price {}
+
+    struct Pricing {
+        node: &'static str,
+    }
+    impl Actor for Pricing {}
+    impl Handler<Quote> for Pricing {
+        type Result = Price;
+        fn handle(&mut self, msg: Quote, _ctx: &mut Context<Self>) -> Price {
+            Price {
+                by: format!("{} {}", self.node, msg.symbol),
+            }
+        }
+    }
+
+    struct Other;
+    impl Actor for Other {}
+
+    // each node serves whatever its receptionist has registered
+    let system = ActorSystem::new();
+    let mut nodes = Vec::new();
+    let mut receptionists = Vec::new();
+    let mut pricing = Vec::new();
+    for (i, id) in ["node-1", "node-2"].into_iter().enumerate() {
+        let port = 9891 + i as u16;
+        let node = Arc::new(ClusterNode::new(id.to_string(), format!("127.0.0.1:{}", port)));
+        let receptionist = Receptionist::new(node.clone());
+        tokio::spawn(node.clone().serve(Some(receptionist.handler())));
+        nodes.push(node);
+        receptionists.push(receptionist);
+        pricing.push(system.spawn(Pricing { node: id }));
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let (receptionist1, receptionist2) = (&receptionists[0], &receptionists[1]);
+
+    receptionist1.register::<Quote, _>("pricing", &pricing[0]).await;
+    nodes[1].join(&["127.0.0.1:9891"]).await.unwrap();
+
+    assert_eq!(receptionist2.host("pricing").await.as_deref(), Some("node-1"));
+    assert!(receptionist2.lookup_remote::<Other>("pricing").await.is_none());
+    assert!(receptionist2.lookup_remote::<Pricing>("billing").await.is_none());
+    let remote = receptionist2
+        .lookup_remote::<Pricing>("pricing")
+        .await
+        .unwrap();
+    let quote = |symbol: &str| Quote {
+        symbol: symbol.to_string(),
+    };
+    assert_eq!(remote.call(quote("ACME")).await.unwrap().by, "node-1 ACME");
+
+    // the service moves to node-2, the address follows it
+    receptionist2.register::<Quote, _>("pricing", &pricing[1]).await;
+    assert_eq!(remote.call(quote("ACME")).await.unwrap().by, "node-2 ACME");
+
+    // and node-1 hears of the move through gossip
+    nodes[1]
+        .send_gossip_to(&nodes[0].local_node)
+        .await
+        .unwrap();
+    assert_eq!(receptionist1.host("pricing").await.as_deref(), Some("node-2"));
+    let remote = receptionist1
+        .lookup_remote::<Pricing>("pricing")
+        .await
+        .unwrap();
+    assert_eq!(remote.call(quote("XYZ")).await.unwrap().by, "node-2 XYZ");
+}
+
+#[tokio::test]
+async fn swim_and_gossip_run_over_udp() {
+    use cinema::remote::{
+        codec,
+        proto::{cluster_message, ClusterMessage, Envelope, NodeInfo, Ping},
+        SwimConfig,
+    };
+    use std::{sync::Arc, time::Duration};
+    use tokio::net::UdpSocket;
+
+    let swim = SwimConfig::default()
+        .protocol_period(Duration::from_millis(50))
+        .ping_timeout(Duration::from_millis(20))
+        .suspect_timeout(Duration::from_millis(300));
+    let nodes: Vec<Arc<ClusterNode>> = (1..=3)
+        .map(|i| {
+            let addr = format!("127.0.0.1:{}", 9900 + i);
+            Arc::new(
+                ClusterNode::new(format!("node-{}", i), addr.clone())
+                    .bind(addr)
+                    .swim(swim)
+                    .gossip_over_udp(),
+            )
+        })
+        .collect();
+    let servers: Vec<_> = nodes
+        .iter()
+        .map(|node| tokio::spawn(node.clone().serve(None)))
+        .collect();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // a ping datagram is acked with a datagram
+    let probe = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let ping = ClusterMessage {
+        payload: Some(cluster_message::Payload::Ping(Ping {
+            source: Some(NodeInfo {
+                id: "probe".to_string(),
+                addr: probe.local_addr().unwrap().to_string(),
+                status: 0,
+                incarnation: 0,
+                generation: 1,
+            }),
+            updates: Vec::new(),
+        })),
+    };
+    let request = Envelope {
+        message_type: "cluster".to_string(),
+        payload: ping.encode_to_vec(),
+        correlation_id: 77,
+        sender_node: "probe".to_string(),
+        target_actor: "".to_string(),
+        is_response: false,
+        codec: codec::PROST,
+        ..Default::default()
+    };
+    probe
+        .send_to(&request.to_bytes(), "127.0.0.1:9901")
+        .await
+        .unwrap();
+    let mut buf = vec![0u8; 2048];
+    let (len, _) = tokio::time::timeout(Duration::from_secs(1), probe.recv_from(&mut buf))
+        .await
+        .unwrap()
+        .unwrap();
+    let ack = Envelope::from_bytes(&buf[..len]).unwrap();
+    assert!(ack.is_response);
+    assert_eq!(ack.correlation_id, 77);
+    match ClusterMessage::decode(ack.payload.as_slice()).unwrap().payload {
+        Some(cluster_message::Payload::PingAck(ack)) => assert!(ack.alive),
+        other => panic!("expected a ping ack, got {:?}", other),
+    }
+    assert!(nodes[0].get_members().await.iter().any(|n| n.id == "probe"));
+
+    // joins still go over TCP, the probes after them over UDP
+    for node in &nodes[1..] {
+        node.join(&["127.0.0.1:9901"]).await.unwrap();
+    }
+    let handles: Vec<_> = nodes.iter().map(|n| n.clone().start_swim()).collect();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let status = |node: Arc<ClusterNode>, id: &'static str| async move {
+        node.get_members()
+            .await
+            .into_iter()
+            .find(|n| n.id == id)
+            .map(|n| n.status)
+    };
+    assert_eq!(status(nodes[1].clone(), "node-3").await, Some(NodeStatus::Up));
+
+    // node-3 stops answering datagrams
+    handles[2].abort();
+    servers[2].abort();
+    tokio::time::sleep(Duration::from_millis(800)).await;
+    for node in &nodes[..2] {
+        assert_eq!(status(node.clone(), "node-3").await, Some(NodeStatus::Down));
+    }
+    handles.iter().for_each(|h| h.abort());
+}
+
+#[tokio::test]
+async fn udp_gossip_is_refused_alongside_a_handshake() {
+    use cinema::remote::HandshakeConfig;
+    use std::sync::Arc;
+
+    let node = Arc::new(
+        ClusterNode::new("guarded".to_string(), "127.0.0.1:9911".to_string())
+            .bind("127.0.0.1:9911")
+            .handshake(HandshakeConfig::new("guarded", "prod").token("secret"))
+            .gossip_over_udp(),
+    );
+    let error = node.serve(None).await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[derive(Clone, ProstMessage)]
+struct Increment {}
+impl Message for Increment {
+    type Result = Count;
+}
+impl cinema::remote::RemoteMessage for Increment {}
+
+#[derive(Clone, PartialEq, ProstMessage, cinema::MessageResponse)]
+struct Count {
+    #[prost(uint64, tag = "1")]
+    value: u64,
+}
+impl Message for Count {
+    type Result = ();
+}
+impl cinema::remote::RemoteMessage for Count {}
+
+struct Counter {
+    value: u64,
+}
+impl Actor for Counter {}
+impl Handler<Increment> for Counter {
+    type Result = Count;
+    fn handle(&mut self, _msg: Increment, _ctx: &mut Context<Self>) -> Count {
+        self.value += 1;
+        Count { value: self.value }
+    }
+}
+impl cinema::remote::Migratable for Counter {
+    fn hand_off(&self) -> cinema::persistence::Snapshot {
+        cinema::persistence::Snapshot::new(&Count { value: self.value })
+    }
+
+    fn take_over(snapshot: cinema::persistence::Snapshot) -> std::io::Result<Self> {
+        Ok(Counter {
+            value: snapshot.state::<Count>()?.value,
+        })
+    }
+
+    fn actor_type() -> &'static str {
+        "counter"
+    }
+}
+
+///the counter of a node built from other sources, known by the same tag
+struct RenamedCounter {
+    value: u64,
+}
+impl Actor for RenamedCounter {}
+impl Handler<Increment> for RenamedCounter {
+    type Result = Count;
+    fn handle(&mut self, _msg: Increment, _ctx: &mut Context<Self>) -> Count {
+        self.value += 1;
+        Count { value: self.value }
+    }
+}
+impl cinema::remote::Migratable for RenamedCounter {
+    fn hand_off(&self) -> cinema::persistence::Snapshot {
+        cinema::persistence::Snapshot::new(&Count { value: self.value })
+    }
+
+    fn take_over(snapshot: cinema::persistence::Snapshot) -> std::io::Result<Self> {
+        Ok(RenamedCounter {
+            value: snapshot.state::<Count>()?.value,
+        })
+    }
+
+    fn actor_type() -> &'static str {
+        "counter"
+    }
+}
+
+///serve a counter under `key` on node `id`
+fn counter_routes(
+    id: &str,
+    key: &str,
+    addr: cinema::Addr<Counter>,
+) -> cinema::remote::EnvelopeHandler {
+    MessageRouter::new()
+        .route_actor::<Increment>(key, LocalNode::new(id).handler::<Counter, Increment>(addr))
+        .build()
+}
+
+///a node hosting migratable counters, accepting them too if `accept` is set
+async fn migration_node(
+    system: &std::sync::Arc<ActorSystem>,
+    id: &'static str,
+    port: u16,
+    accept: bool,
+) -> (std::sync::Arc<ClusterNode>, cinema::remote::Migrations) {
+    use std::sync::Arc;
+
+    let node = Arc::new(ClusterNode::new(id.to_string(), format!("127.0.0.1:{}", port)));
+    let migrations = cinema::remote::Migrations::new(node.clone());
+    if accept {
+        let system = system.clone();
+        migrations.accept(move |key: &str, counter: Counter| {
+            let addr = system.spawn(counter);
+            (addr.clone(), counter_routes(id, key, addr))
+        });
+    }
+    tokio::spawn(node.clone().serve(Some(migrations.handler(None))));
+    (node, migrations)
+}
+
+#[tokio::test]
+async fn migrated_actor_keeps_its_state_and_mail() {
+    use std::{sync::Arc, time::Duration};
+
+    let system = Arc::new(ActorSystem::new());
+    let (node1, migrations1) = migration_node(&system, "node-1", 9951, true).await;
+    let (node2, migrations2) = migration_node(&system, "node-2", 9952, true).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    node2.join(&["127.0.0.1:9951"]).await.unwrap();
+
+    let counter = system.spawn(Counter { value: 0 });
+    migrations1
+        .host("counter-7", counter.clone(), counter_routes("node-1", "counter-7", counter.clone()))
+        .await;
+
+    // a sender that never hears of the move
+    let stale = Arc::new(ClusterNode::new("client".to_string(), "127.0.0.1:9953".to_string()));
+    for node in [&node1, &node2] {
+        stale.add_member(node.local_node.clone()).await;
+    }
+    stale
+        .test_insert_actor("counter-7".to_string(), "node-1".to_string(), "Counter".to_string())
+        .await;
+    let remote = ClusterClient::new(stale).remote_addr::<Counter>("counter-7");
+    for expected in 1..=3 {
+        assert_eq!(remote.call(Increment {}).await.unwrap().value, expected);
+    }
+
+    // requests keep coming while the actor moves, none is lost or handled twice
+    let sender = tokio::spawn(async move {
+        let mut counts = Vec::new();
+        for _ in 0..20 {
+            counts.push(remote.call(Increment {}).await.unwrap().value);
+        }
+        (remote, counts)
+    });
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    migrations1.migrate("counter-7", "node-2").await.unwrap();
+    let (remote, counts) = sender.await.unwrap();
+    assert_eq!(counts, (4..=23).collect::<Vec<_>>());
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!counter.is_alive());
+    assert_eq!(migrations1.location("counter-7").as_deref(), Some("node-2"));
+    assert_eq!(migrations2.location("counter-7").as_deref(), Some("node-2"));
+    assert_eq!(node1.lookup_actor("counter-7").await.unwrap().0, "node-2");
+    assert_eq!(node2.lookup_actor("counter-7").await.unwrap().0, "node-2");
+
+    // through the registry, straight to the new host
+    let fresh = ClusterClient::new(node1.clone()).remote_addr::<Counter>("counter-7");
+    assert_eq!(fresh.call(Increment {}).await.unwrap().value, 24);
+    // and still forwarded for the stale sender
+    assert_eq!(remote.call(Increment {}).await.unwrap().value, 25);
+}
+
+#[tokio::test]
+async fn refused_migration_leaves_the_actor_in_place() {
+    use std::{sync::Arc, time::Duration};
+
+    let system = Arc::new(ActorSystem::new());
+    let (node1, migrations1) = migration_node(&system, "node-1", 9954, true).await;
+    let (node2, _migrations2) = migration_node(&system, "node-2", 9955, false).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    node2.join(&["127.0.0.1:9954"]).await.unwrap();
+
+    let counter = system.spawn(Counter { value: 0 });
+    migrations1
+        .host("counter-8", counter.clone(), counter_routes("node-1", "counter-8", counter))
+        .await;
+    let remote = ClusterClient::new(node1.clone()).remote_addr::<Counter>("counter-8");
+    assert_eq!(remote.call(Increment {}).await.unwrap().value, 1);
+
+    // node-2 doesn't take counters
+    assert!(migrations1.migrate("counter-8", "node-2").await.is_err());
+    assert!(migrations1.migrate("counter-8", "node-9").await.is_err());
+    assert_eq!(migrations1.location("counter-8").as_deref(), Some("node-1"));
+    assert_eq!(node1.lookup_actor("counter-8").await.unwrap().0, "node-1");
+    assert_eq!(remote.call(Increment {}).await.unwrap().value, 2);
+}
+
+#[tokio::test]
+async fn migration_matches_types_by_their_tag() {
+    use std::{sync::Arc, time::Duration};
+
+    let system = Arc::new(ActorSystem::new());
+    let (node1, migrations1) = migration_node(&system, "node-1", 9956, false).await;
+    let node2 = Arc::new(ClusterNode::new("node-2".to_string(), "127.0.0.1:9957".to_string()));
+    let migrations2 = cinema::remote::Migrations::new(node2.clone());
+    let spawner = system.clone();
+    migrations2.accept(move |key: &str, counter: RenamedCounter| {
+        let addr = spawner.spawn(counter);
+        let routes = MessageRouter::new()
+            .route_actor::<Increment>(
+                key,
+                LocalNode::new("node-2").handler::<RenamedCounter, Increment>(addr.clone()),
+            )
+            .build();
+        (addr, routes)
+    });
+    tokio::spawn(node2.clone().serve(Some(migrations2.handler(None))));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    node2.join(&["127.0.0.1:9956"]).await.unwrap();
+
+    let counter = system.spawn(Counter { value: 0 });
+    migrations1
+        .host("counter-9", counter.clone(), counter_routes("node-1", "counter-9", counter))
+        .await;
+    let remote = ClusterClient::new(node1.clone()).remote_addr::<Counter>("counter-9");
+    assert_eq!(remote.call(Increment {}).await.unwrap().value, 1);
+
+    // a different Rust type on node-2, taken in under the same tag
+    migrations1.migrate("counter-9", "node-2").await.unwrap();
+    assert_eq!(migrations2.location("counter-9").as_deref(), Some("node-2"));
+    let moved = ClusterClient::new(node1.clone()).remote_addr::<Counter>("counter-9");
+    assert_eq!(moved.call(Increment {}).await.unwrap().value, 2);
+}
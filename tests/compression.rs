@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use cinema::remote::{
+    handshake, proto::Envelope, CompressedConnection, Compression, CompressionConfig, Connection,
+    EnvelopeHandler, HandshakeConfig, HandshakeTransport, RemoteClient, RemoteServer,
+    TcpConnection, TcpTransport, Transport,
+};
+
+fn echo() -> EnvelopeHandler {
+    Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            Some(Envelope {
+                is_response: true,
+                ..envelope
+            })
+        })
+    })
+}
+
+async fn start(config: HandshakeConfig) -> String {
+    let server = RemoteServer::bind("127.0.0.1:0", echo())
+        .await
+        .unwrap()
+        .handshake(config);
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+    addr
+}
+
+fn request(correlation_id: u64, payload: Vec<u8>) -> Envelope {
+    Envelope {
+        message_type: "test::Blob".to_string(),
+        payload,
+        correlation_id,
+        sender_node: "client".to_string(),
+        target_actor: "echo".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn large_payloads_are_compressed_on_the_wire() {
+    let zstd = CompressionConfig::new(Compression::Zstd).threshold(1024);
+    let addr = start(HandshakeConfig::new("server", "prod").compression(zstd)).await;
+
+    //speak the handshake by hand to look at what the server puts on the wire
+    let mut conn = TcpTransport.connect(&addr).await.unwrap();
+    let ack = handshake(
+        &mut conn,
+        &HandshakeConfig::new("raw", "prod").compression(zstd),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ack.compression, Compression::Zstd.id());
+
+    let state = b"replicated state ".repeat(4096);
+    conn.send(request(1, state.clone())).await.unwrap();
+    let response = conn.recv().await.unwrap();
+    assert_eq!(response.compression, Compression::Zstd.id());
+    assert!(response.payload.len() < state.len() / 10);
+
+    //below the threshold payloads travel as they are
+    conn.send(request(2, b"small".to_vec())).await.unwrap();
+    let response = conn.recv().await.unwrap();
+    assert_eq!(response.compression, 0);
+    assert_eq!(response.payload, b"small");
+
+    //through HandshakeTransport it's transparent both ways
+    let transport = HandshakeTransport::new(
+        TcpTransport,
+        HandshakeConfig::new("client", "prod").compression(zstd),
+    );
+    let client = RemoteClient::new(transport.connect(&addr).await.unwrap());
+    let response = client.send(request(0, state.clone())).await.unwrap();
+    assert_eq!(response.compression, 0);
+    assert_eq!(response.payload, state);
+}
+
+#[tokio::test]
+async fn compression_is_negotiated_in_handshake() {
+    let addr = start(
+        HandshakeConfig::new("server", "prod")
+            .compression(CompressionConfig::new(Compression::Zstd)),
+    )
+    .await;
+    let negotiate = |config: HandshakeConfig| {
+        let addr = addr.clone();
+        async move {
+            let mut conn: TcpConnection = TcpTransport.connect(&addr).await.unwrap();
+            handshake(&mut conn, &config).await.unwrap().compression
+        }
+    };
+
+    //the server takes an offered algorithm it supports even if it prefers another
+    let lz4 =
+        HandshakeConfig::new("a", "prod").compression(CompressionConfig::new(Compression::Lz4));
+    assert_eq!(negotiate(lz4).await, Compression::Lz4.id());
+
+    //peers that don't offer compression don't get any
+    assert_eq!(negotiate(HandshakeConfig::new("b", "prod")).await, 0);
+
+    //nor when the server has none configured
+    let plain = start(HandshakeConfig::new("server", "prod")).await;
+    let mut conn = TcpTransport.connect(&plain).await.unwrap();
+    let config =
+        HandshakeConfig::new("c", "prod").compression(CompressionConfig::new(Compression::Lz4));
+    assert_eq!(handshake(&mut conn, &config).await.unwrap().compression, 0);
+}
+
+#[tokio::test]
+async fn oversized_or_unagreed_payloads_are_refused() {
+    use tokio::net::TcpListener;
+
+    async fn pair(
+        receiving: CompressionConfig,
+        limit: usize,
+    ) -> (
+        CompressedConnection<TcpConnection>,
+        CompressedConnection<TcpConnection>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let sender = TcpTransport.connect(&addr).await.unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+        let sending = CompressionConfig::new(Compression::Zstd).threshold(0);
+        (
+            CompressedConnection::new(sender, sending),
+            CompressedConnection::new(TcpConnection::new(stream), receiving).max_size(limit),
+        )
+    }
+    let zstd = CompressionConfig::new(Compression::Zstd);
+
+    //a small frame inflating past the limit
+    let (mut sender, mut receiver) = pair(zstd, 64 * 1024).await;
+    sender.send(request(1, vec![0; 1024 * 1024])).await.unwrap();
+    assert!(receiver.recv().await.is_err());
+
+    //within the limit it goes through
+    let (mut sender, mut receiver) = pair(zstd, 64 * 1024).await;
+    sender.send(request(2, vec![0; 1024])).await.unwrap();
+    assert_eq!(receiver.recv().await.unwrap().payload, vec![0; 1024]);
+
+    //compressed payloads with nothing agreed, or another algorithm
+    for agreed in [
+        CompressionConfig::default(),
+        CompressionConfig::new(Compression::Lz4),
+    ] {
+        let (mut sender, mut receiver) = pair(agreed, 64 * 1024).await;
+        sender.send(request(3, vec![0; 1024])).await.unwrap();
+        assert!(receiver.recv().await.is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use std::{sync::Arc, time::Duration};
+
+use cinema::{
+    remote::{
+        proto::Envelope, register_message, EnvelopeHandler, GrpcTransport, HandshakeConfig,
+        HandshakeTransport, RemoteAddr, RemoteClient, RemoteMessage, RemoteServer, Transport,
+        TransportError,
+    },
+    Message,
+};
+use prost::Message as ProstMessage;
+use tokio::time::Instant;
+
+#[derive(Clone, ProstMessage)]
+struct Ping {
+    #[prost(string, tag = "1")]
+    message: String,
+}
+
+impl Message for Ping {
+    type Result = ();
+}
+
+impl RemoteMessage for Ping {}
+
+// echoes the ping back, holding "slow" ones for a while first
+fn echo_handler() -> EnvelopeHandler {
+    Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            let ping = Ping::decode(envelope.payload.as_slice()).ok()?;
+            if ping.message == "slow" {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+            }
+            Some(Envelope {
+                message_type: envelope.message_type,
+                payload: envelope.payload,
+                correlation_id: envelope.correlation_id,
+                sender_node: "server".to_string(),
+                target_actor: envelope.sender_node,
+                is_response: true,
+                codec: envelope.codec,
+                compression: envelope.compression,
+                delivery_id: envelope.delivery_id,
+                trace: envelope.trace,
+                ..Default::default()
+            })
+        })
+    })
+}
+
+async fn start(server: RemoteServer) -> String {
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+    addr.to_string()
+}
+
+async fn grpc_server() -> String {
+    start(
+        RemoteServer::bind_grpc("127.0.0.1:0", echo_handler())
+            .await
+            .unwrap(),
+    )
+    .await
+}
+
+async fn remote<T: Transport>(transport: &T, addr: &str) -> RemoteAddr<()>
+where
+    T::Conn: 'static,
+{
+    register_message::<Ping>();
+    RemoteClient::new(transport.connect(addr).await.unwrap()).remote_addr("server-node", "echo")
+}
+
+fn ping(message: &str) -> Ping {
+    Ping {
+        message: message.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn grpc_round_trip() {
+    let addr = grpc_server().await;
+    let transport = GrpcTransport::new().header("authorization", "Bearer secret");
+    let remote = remote(&transport, &addr).await;
+
+    for message in ["over grpc", "and again"] {
+        let response = remote.send(ping(message)).await.unwrap();
+        assert_eq!(
+            Ping::decode(response.payload.as_slice()).unwrap().message,
+            message
+        );
+    }
+}
+
+#[tokio::test]
+async fn slow_request_does_not_block_others() {
+    let addr = grpc_server().await;
+    let transport = GrpcTransport::new();
+    let client = {
+        register_message::<Ping>();
+        RemoteClient::new(transport.connect(&addr).await.unwrap())
+    };
+    let remote: RemoteAddr<()> = client.remote_addr("server-node", "echo");
+
+    let slow = {
+        let remote: RemoteAddr<()> = client.remote_addr("server-node", "echo");
+        tokio::spawn(async move { remote.send(ping("slow")).await })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // the stream is served like a TCP connection, handlers run side by side
+    let start = Instant::now();
+    remote.send(ping("fast")).await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(200));
+    assert!(!slow.is_finished());
+
+    slow.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn grpc_enforces_message_size() {
+    let addr = grpc_server().await;
+    let transport = GrpcTransport::new().max_message_size(1024);
+    let remote = remote(&transport, &addr).await;
+
+    assert!(remote.send(ping("small")).await.is_ok());
+    let big = "x".repeat(4096);
+    assert!(remote
+        .send_timeout(ping(&big), Duration::from_millis(500))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn grpc_with_handshake() {
+    let server = RemoteServer::bind_grpc("127.0.0.1:0", echo_handler())
+        .await
+        .unwrap()
+        .handshake(HandshakeConfig::new("server", "prod"));
+    let addr = start(server).await;
+
+    let transport =
+        HandshakeTransport::new(GrpcTransport::new(), HandshakeConfig::new("client", "prod"));
+    let remote = remote(&transport, &addr).await;
+    assert!(remote.send(ping("introduced")).await.is_ok());
+
+    let transport = HandshakeTransport::new(
+        GrpcTransport::new(),
+        HandshakeConfig::new("client", "staging"),
+    );
+    assert!(matches!(
+        transport.connect(&addr).await,
+        Err(TransportError::Handshake(_))
+    ));
+}
+
+#[tokio::test]
+async fn tcp_client_is_not_served_by_grpc() {
+    let addr = grpc_server().await;
+
+    let transport = cinema::remote::TcpTransport;
+    let remote = remote(&transport, &addr).await;
+    assert!(remote
+        .send_timeout(ping("plain tcp"), Duration::from_millis(500))
+        .await
+        .is_err());
+}
@@ -0,0 +1,114 @@
+//! Model checked with `cargo test --features loom --test loom --release`
+#![cfg(feature = "loom")]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use cinema::{
+    actor::ActorId,
+    message::{Terminated, TerminationReason},
+    watcher::{Watcher, WatcherList},
+};
+
+struct CountingWatcher(Arc<AtomicUsize>);
+
+impl Watcher for CountingWatcher {
+    fn notify(&self, _msg: Terminated) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn terminated() -> Terminated {
+    Terminated {
+        id: ActorId::new(),
+        reason: TerminationReason::Normal,
+        name: None,
+        path: "/user/test".into(),
+        backtrace: None,
+    }
+}
+
+// ======== Watcher Race Tests ========
+
+#[test]
+fn watch_racing_stop_is_notified_once() {
+    loom::model(|| {
+        let list = Arc::new(WatcherList::new());
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let watching = {
+            let list = list.clone();
+            let count = count.clone();
+            loom::thread::spawn(move || list.add(Arc::new(CountingWatcher(count))))
+        };
+        let stopping = {
+            let list = list.clone();
+            loom::thread::spawn(move || list.notify_all(terminated()))
+        };
+
+        watching.join().unwrap();
+        stopping.join().unwrap();
+
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    });
+}
+
+#[test]
+fn concurrent_watchers_all_notified() {
+    loom::model(|| {
+        let list = Arc::new(WatcherList::new());
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let list = list.clone();
+                let count = count.clone();
+                loom::thread::spawn(move || list.add(Arc::new(CountingWatcher(count))))
+            })
+            .collect();
+
+        list.notify_all(terminated());
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    });
+}
+
+/// watches the list it is notified from, as a handler of a dying actor might
+struct Rewatcher {
+    list: Arc<WatcherList>,
+    count: Arc<AtomicUsize>,
+}
+
+impl Watcher for Rewatcher {
+    fn notify(&self, _msg: Terminated) {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        self.list.add(Arc::new(CountingWatcher(self.count.clone())));
+    }
+}
+
+#[test]
+fn watching_from_a_notification_does_not_block() {
+    loom::model(|| {
+        let list = Arc::new(WatcherList::new());
+        let count = Arc::new(AtomicUsize::new(0));
+        list.add(Arc::new(Rewatcher {
+            list: list.clone(),
+            count: count.clone(),
+        }));
+
+        let stopping = {
+            let list = list.clone();
+            loom::thread::spawn(move || list.notify_all(terminated()))
+        };
+        list.add(Arc::new(CountingWatcher(count.clone())));
+        stopping.join().unwrap();
+
+        // the rewatcher, its late watcher and the racing one
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    });
+}
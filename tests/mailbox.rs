@@ -0,0 +1,732 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use cinema::{
+    actor::{AsyncHandler, BoxFuture},
+    envelope::ActorMessage,
+    mailbox::{OverflowMailbox, OverflowStrategy, Priority, PriorityMailbox},
+    message::Terminated,
+    Actor, ActorSystem, Addr, Context, Handler, Mailbox, MailboxError, Message, RetryPolicy,
+    SpawnOptions,
+};
+use tokio::sync::Notify;
+
+// ======== Custom Mailbox Tests ========
+
+struct Record(u32);
+impl Message for Record {
+    type Result = ();
+}
+
+struct Recorder {
+    seen: Arc<Mutex<Vec<u32>>>,
+}
+
+impl Actor for Recorder {}
+
+impl Handler<Record> for Recorder {
+    type Result = ();
+
+    fn handle(&mut self, msg: Record, _ctx: &mut Context<Self>) {
+        self.seen.lock().unwrap().push(msg.0);
+    }
+}
+
+/// Unbounded mailbox that counts every enqueue
+struct CountingMailbox<A: Actor> {
+    queue: Mutex<VecDeque<ActorMessage<A>>>,
+    notify: Notify,
+    closed: std::sync::atomic::AtomicBool,
+    enqueued: Arc<AtomicUsize>,
+}
+
+impl<A: Actor> CountingMailbox<A> {
+    fn new(enqueued: Arc<AtomicUsize>) -> Self {
+        Self {
+            queue: Mutex::new(VecDeque::new()),
+            notify: Notify::new(),
+            closed: std::sync::atomic::AtomicBool::new(false),
+            enqueued,
+        }
+    }
+}
+
+impl<A: Actor> Mailbox<A> for CountingMailbox<A> {
+    fn enqueue(&self, msg: ActorMessage<A>) -> BoxFuture<'_, Result<(), MailboxError>> {
+        let result = self.try_enqueue(msg);
+        Box::pin(async move { result })
+    }
+
+    fn try_enqueue(&self, msg: ActorMessage<A>) -> Result<(), MailboxError> {
+        if self.is_closed() {
+            return Err(MailboxError::MailboxClosed);
+        }
+        self.queue.lock().unwrap().push_back(msg);
+        self.enqueued.fetch_add(1, Ordering::SeqCst);
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    fn dequeue(&self) -> BoxFuture<'_, Option<ActorMessage<A>>> {
+        Box::pin(async move {
+            loop {
+                if let Some(msg) = self.queue.lock().unwrap().pop_front() {
+                    return Some(msg);
+                }
+                if self.is_closed() {
+                    return None;
+                }
+                self.notify.notified().await;
+            }
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.notify.notify_one();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+#[tokio::test]
+async fn custom_mailbox_delivers_messages() {
+    let enqueued = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_mailbox(
+        Recorder { seen: seen.clone() },
+        CountingMailbox::new(enqueued.clone()),
+    );
+
+    for i in 0..5 {
+        addr.do_send(Record(i)).await.unwrap();
+    }
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(enqueued.load(Ordering::SeqCst), 5);
+    assert_eq!(*seen.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn custom_mailbox_is_closed_after_stop() {
+    let enqueued = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_mailbox(
+        Recorder { seen: seen.clone() },
+        CountingMailbox::new(enqueued.clone()),
+    );
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    sys.shutdown();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(!addr.is_alive());
+    assert_eq!(
+        addr.try_send(Record(1)),
+        Err(MailboxError::MailboxClosed)
+    );
+}
+
+#[tokio::test]
+async fn child_with_custom_mailbox() {
+    let enqueued = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    struct Parent {
+        enqueued: Arc<AtomicUsize>,
+        seen: Arc<Mutex<Vec<u32>>>,
+    }
+
+    impl Actor for Parent {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            let seen = self.seen.clone();
+            let child = ctx.spawn_child_with_mailbox(
+                move || Recorder { seen: seen.clone() },
+                cinema::SupervisorStrategy::Stop,
+                CountingMailbox::new(self.enqueued.clone()),
+            );
+            child.try_send(Record(7)).unwrap();
+        }
+    }
+
+    impl Handler<Terminated> for Parent {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    let sys = ActorSystem::new();
+    let _parent = sys.spawn(Parent {
+        enqueued: enqueued.clone(),
+        seen: seen.clone(),
+    });
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(enqueued.load(Ordering::SeqCst), 1);
+    assert_eq!(*seen.lock().unwrap(), vec![7]);
+}
+
+// ======== Mailbox Pressure Tests ========
+
+struct Stall(Arc<Notify>);
+impl Message for Stall {
+    type Result = ();
+}
+
+impl AsyncHandler<Stall> for Recorder {
+    fn handle(&mut self, msg: Stall, _ctx: &mut Context<Self>) -> BoxFuture<'_, ()> {
+        Box::pin(async move { msg.0.notified().await })
+    }
+}
+
+#[tokio::test]
+async fn addr_exposes_mailbox_pressure() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_capacity(Recorder { seen: seen.clone() }, 4);
+
+    assert_eq!(addr.capacity(), Some(4));
+    assert!(addr.is_empty());
+
+    // park the actor inside a handler so mail piles up
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    addr.try_send(Record(1)).unwrap();
+    addr.try_send(Record(2)).unwrap();
+    assert_eq!(addr.len(), 2);
+    assert!(!addr.is_full());
+
+    addr.try_send(Record(3)).unwrap();
+    addr.try_send(Record(4)).unwrap();
+    assert!(addr.is_full());
+    assert_eq!(addr.try_send(Record(5)), Err(MailboxError::MailboxFull));
+
+    release.notify_one();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(addr.is_empty());
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3, 4]);
+}
+
+// sends its last record from a Drop impl, where nothing can be awaited
+struct Farewell(cinema::Addr<Recorder>);
+
+impl Drop for Farewell {
+    fn drop(&mut self) {
+        self.0.do_send_now(Record(99)).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn do_send_now_enqueues_without_awaiting() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_capacity(Recorder { seen: seen.clone() }, 1);
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    addr.do_send_now(Record(1)).unwrap();
+    assert!(addr.is_full());
+    // no room left: delivered once the actor catches up
+    addr.do_send_now(Record(2)).unwrap();
+    drop(Farewell(addr.clone()));
+
+    release.notify_one();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(seen, vec![1, 2, 99]);
+}
+
+#[tokio::test]
+async fn do_send_now_defers_a_bounded_number_of_messages() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_capacity(Recorder { seen: seen.clone() }, 1);
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    // one fits, 1024 more wait for room, the next one is refused
+    for n in 0..=1024 {
+        addr.do_send_now(Record(n)).unwrap();
+    }
+    assert_eq!(
+        addr.do_send_now(Record(2000)),
+        Err(MailboxError::MailboxFull)
+    );
+
+    release.notify_one();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(seen.lock().unwrap().len(), 1025);
+    addr.do_send_now(Record(3000)).unwrap();
+}
+
+#[tokio::test]
+async fn reserved_room_is_held_for_the_permit() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_capacity(Recorder { seen: seen.clone() }, 4);
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    assert_eq!(
+        addr.reserve_many(5).await.err(),
+        Some(MailboxError::MailboxFull)
+    );
+    let mut batch = addr.reserve_many(3).await.unwrap();
+    // only the room nobody holds is left for other senders
+    addr.try_send(Record(1)).unwrap();
+    assert_eq!(addr.try_send(Record(5)), Err(MailboxError::MailboxFull));
+
+    for n in 2..=4 {
+        batch.send(Record(n)).unwrap();
+    }
+    assert_eq!(batch.remaining(), 0);
+    assert_eq!(batch.send(Record(5)), Err(MailboxError::MailboxFull));
+    drop(batch);
+
+    // a full mailbox holds the reservation back, not the send
+    let waiting = tokio::time::timeout(Duration::from_millis(20), addr.reserve()).await;
+    assert!(waiting.is_err());
+    release.notify_one();
+    addr.reserve().await.unwrap().send(Record(5)).unwrap();
+
+    // unused room is given back
+    let unused = addr.reserve_many(4).await.unwrap();
+    drop(unused);
+    addr.try_send(Record(6)).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[tokio::test]
+async fn send_retry_backs_off_while_the_mailbox_is_full() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_capacity(Recorder { seen: seen.clone() }, 1);
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    addr.try_send(Record(1)).unwrap();
+
+    // waits of 5ms then 10ms before the last attempt
+    let policy = RetryPolicy::new(3, Duration::from_millis(5)).jitter(0.0);
+    let err = addr.send_retry(Record(2), policy).await.unwrap_err();
+    assert_eq!(err.attempts, vec![MailboxError::MailboxFull; 3]);
+    assert!(err.elapsed >= Duration::from_millis(15));
+    assert_eq!(
+        addr.try_send_timeout(Record(2), Duration::from_millis(10)).await,
+        Err(MailboxError::MailboxFull)
+    );
+
+    // room frees up while it retries
+    let retrying = tokio::spawn({
+        let addr = addr.clone();
+        async move {
+            addr.send_retry(Record(3), RetryPolicy::new(10, Duration::from_millis(5)))
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    release.notify_one();
+    assert_eq!(retrying.await.unwrap(), Ok(()));
+
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    addr.try_send(Record(4)).unwrap();
+    let waiting = tokio::spawn({
+        let addr = addr.clone();
+        async move { addr.try_send_timeout(Record(5), Duration::from_secs(1)).await }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    release.notify_one();
+    assert_eq!(waiting.await.unwrap(), Ok(()));
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1, 3, 4, 5]);
+}
+
+#[tokio::test]
+async fn unbounded_mailbox_has_no_capacity() {
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_mailbox(
+        Recorder {
+            seen: Arc::new(Mutex::new(Vec::new())),
+        },
+        CountingMailbox::new(Arc::new(AtomicUsize::new(0))),
+    );
+
+    assert_eq!(addr.capacity(), None);
+    assert!(!addr.is_full());
+}
+
+// ======== Overflow Strategy Tests ========
+
+///spawn a Recorder parked in a handler, then overfill its 2-slot mailbox
+async fn overfill(
+    overflow: OverflowStrategy,
+) -> (Addr<Recorder>, Arc<Notify>, Arc<Mutex<Vec<u32>>>) {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with(
+        Recorder { seen: seen.clone() },
+        SpawnOptions::new().mailbox_capacity(2).overflow(overflow),
+    );
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    for i in 1..=3 {
+        let _ = addr.try_send(Record(i));
+    }
+    (addr, release, seen)
+}
+
+#[tokio::test]
+async fn drop_oldest_keeps_latest_messages() {
+    let (addr, release, seen) = overfill(OverflowStrategy::DropOldest).await;
+    addr.do_send(Record(4)).await.unwrap();
+    assert_eq!(addr.len(), 2);
+
+    release.notify_one();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![3, 4]);
+}
+
+#[tokio::test]
+async fn drop_newest_keeps_earliest_messages() {
+    let (addr, release, seen) = overfill(OverflowStrategy::DropNewest).await;
+    assert_eq!(addr.try_send(Record(4)), Ok(()));
+
+    release.notify_one();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2]);
+}
+
+#[tokio::test]
+async fn block_waits_for_space() {
+    let (addr, release, seen) = overfill(OverflowStrategy::Block).await;
+    assert_eq!(addr.try_send(Record(4)), Err(MailboxError::MailboxFull));
+
+    let sender = {
+        let addr = addr.clone();
+        tokio::spawn(async move { addr.do_send(Record(5)).await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!sender.is_finished());
+
+    release.notify_one();
+    sender.await.unwrap().unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 5]);
+}
+
+#[tokio::test]
+async fn overflow_mailbox_blocks_like_channel() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_mailbox(
+        Recorder { seen: seen.clone() },
+        OverflowMailbox::new(1, OverflowStrategy::Block),
+    );
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    addr.try_send(Record(1)).unwrap();
+    assert_eq!(addr.try_send(Record(2)), Err(MailboxError::MailboxFull));
+
+    release.notify_one();
+    addr.do_send(Record(3)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1, 3]);
+}
+
+// ======== Priority Mailbox Tests ========
+
+#[tokio::test]
+async fn high_priority_jumps_the_backlog() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_mailbox(Recorder { seen: seen.clone() }, PriorityMailbox::new(3));
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    addr.try_send(Record(1)).unwrap();
+    addr.try_send_priority(Record(2), Priority::Low).unwrap();
+    addr.do_send(Record(3)).await.unwrap();
+    assert_eq!(addr.try_send(Record(4)), Err(MailboxError::MailboxFull));
+
+    // control traffic is accepted even with the bounded lanes full
+    addr.try_send_priority(Record(5), Priority::High).unwrap();
+    addr.do_send_priority(Record(6), Priority::High).await.unwrap();
+
+    release.notify_one();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![5, 6, 1, 3, 2]);
+}
+
+#[tokio::test]
+async fn send_priority_on_fifo_mailbox_keeps_order() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Recorder { seen: seen.clone() });
+
+    addr.do_send(Record(1)).await.unwrap();
+    addr.send_priority(Record(2), Priority::High).await.unwrap();
+
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2]);
+}
+
+// ======== Request Cancellation Tests ========
+
+struct Ask(u32);
+impl Message for Ask {
+    type Result = u32;
+}
+
+impl Handler<Ask> for Recorder {
+    type Result = u32;
+
+    fn handle(&mut self, msg: Ask, _ctx: &mut Context<Self>) -> u32 {
+        self.seen.lock().unwrap().push(msg.0);
+        msg.0
+    }
+}
+
+#[tokio::test]
+async fn dropped_request_is_skipped() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn(Recorder { seen: seen.clone() });
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    // gives up while the ask is still queued
+    let res = addr.send_timeout(Ask(1), Duration::from_millis(10)).await;
+    assert_eq!(res, Err(MailboxError::Timeout));
+
+    // dropping the future before completion cancels too
+    let pending = tokio::time::timeout(Duration::from_millis(10), addr.send(Ask(2))).await;
+    assert!(pending.is_err());
+
+    release.notify_one();
+    assert_eq!(addr.send(Ask(3)).await, Ok(3));
+    assert_eq!(*seen.lock().unwrap(), vec![3]);
+}
+
+// ======== Durable Mailbox Tests ========
+
+#[derive(Clone, prost::Message)]
+struct Job {
+    #[prost(uint32, tag = "1")]
+    n: u32,
+}
+impl Message for Job {
+    type Result = ();
+}
+impl cinema::remote::RemoteMessage for Job {}
+
+impl Handler<Job> for Recorder {
+    type Result = ();
+
+    fn handle(&mut self, msg: Job, _ctx: &mut Context<Self>) {
+        self.seen.lock().unwrap().push(msg.n);
+    }
+}
+
+fn journal_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("cinema-{}-{}.log", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn durable_mailbox_redelivers_after_crash() {
+    use cinema::mailbox::DurableMailbox;
+
+    let path = journal_path("redeliver");
+
+    // first run: the actor is stuck, so the jobs are accepted but never handled
+    {
+        let sys = ActorSystem::new();
+        let mailbox = DurableMailbox::open(&path).unwrap().durable::<Job>();
+        let addr = sys.spawn_with_mailbox(
+            Recorder {
+                seen: Arc::new(Mutex::new(Vec::new())),
+            },
+            mailbox,
+        );
+        addr.do_send_async(Stall(Arc::new(Notify::new()))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        addr.do_send(Job { n: 1 }).await.unwrap();
+        addr.do_send(Job { n: 2 }).await.unwrap();
+    }
+
+    // second run picks the jobs up from disk
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let mailbox = DurableMailbox::open(&path).unwrap().durable::<Job>();
+    let addr = sys.spawn_with_mailbox(Recorder { seen: seen.clone() }, mailbox);
+    addr.do_send(Job { n: 3 }).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3]);
+
+    // everything was acknowledged, nothing left to replay
+    let replayed = DurableMailbox::<Recorder>::open(&path)
+        .unwrap()
+        .durable::<Job>();
+    assert!(replayed.is_empty());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn durable_mailbox_tags_records_with_the_message_type_id() {
+    use cinema::mailbox::DurableMailbox;
+
+    #[derive(Clone, prost::Message)]
+    struct Renamed {
+        #[prost(uint32, tag = "1")]
+        n: u32,
+    }
+    impl Message for Renamed {
+        type Result = ();
+    }
+    impl cinema::remote::RemoteMessage for Renamed {
+        fn type_id() -> &'static str {
+            "orders.Job"
+        }
+    }
+    impl Handler<Renamed> for Recorder {
+        type Result = ();
+
+        fn handle(&mut self, msg: Renamed, _ctx: &mut Context<Self>) {
+            self.seen.lock().unwrap().push(msg.n);
+        }
+    }
+
+    let path = journal_path("tagged");
+    {
+        let sys = ActorSystem::new();
+        let mailbox = DurableMailbox::open(&path).unwrap().durable::<Renamed>();
+        let addr = sys.spawn_with_mailbox(
+            Recorder {
+                seen: Arc::new(Mutex::new(Vec::new())),
+            },
+            mailbox,
+        );
+        addr.do_send_async(Stall(Arc::new(Notify::new()))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        addr.do_send(Renamed { n: 4 }).await.unwrap();
+    }
+    let log = std::fs::read(&path).unwrap();
+    assert!(log.windows(10).any(|w| w == b"orders.Job"));
+
+    // reopening rewrites the log in place, without leaving the copy behind
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let mailbox = DurableMailbox::open(&path).unwrap().durable::<Renamed>();
+    let mut rewritten = path.clone().into_os_string();
+    rewritten.push(".tmp");
+    assert!(!std::path::Path::new(&rewritten).exists());
+    sys.spawn_with_mailbox(Recorder { seen: seen.clone() }, mailbox);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![4]);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn durable_mailbox_keeps_other_messages_in_memory() {
+    use cinema::mailbox::DurableMailbox;
+
+    let path = journal_path("memory");
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_mailbox(
+        Recorder { seen: seen.clone() },
+        DurableMailbox::open(&path).unwrap().durable::<Job>(),
+    );
+
+    addr.do_send(Record(7)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![7]);
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+// ======== Dedup Mailbox Tests ========
+
+impl cinema::mailbox::DedupKey for Record {
+    type Key = u32;
+
+    fn dedup_key(&self) -> u32 {
+        self.0
+    }
+}
+
+#[tokio::test]
+async fn dedup_mailbox_drops_queued_duplicates() {
+    use cinema::mailbox::DedupMailbox;
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_mailbox(
+        Recorder { seen: seen.clone() },
+        DedupMailbox::new(8).dedup::<Record>(),
+    );
+
+    let release = Arc::new(Notify::new());
+    addr.do_send_async(Stall(release.clone())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    for n in [1, 2, 1, 1, 2, 3] {
+        addr.do_send(Record(n)).await.unwrap();
+    }
+    assert_eq!(addr.len(), 3);
+
+    release.notify_one();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3]);
+
+    // the key is free again once its message was handled
+    addr.do_send(Record(1)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3, 1]);
+}
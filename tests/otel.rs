@@ -0,0 +1,156 @@
+use std::sync::Once;
+
+use cinema::{
+    remote::{
+        proto::Envelope, LocalNode, RemoteAddr, RemoteClient, RemoteMessage, RemoteServer,
+        TcpTransport, Transport,
+    },
+    Actor, ActorSystem, Context, Handler, Message,
+};
+use opentelemetry::{baggage::BaggageExt, trace::TraceContextExt, trace::TracerProvider, KeyValue};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use prost::Message as ProstMessage;
+use tracing::{info_span, Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+static INIT: Once = Once::new();
+
+// spans are sampled but exported nowhere, the tests only need their ids
+fn init_tracing() {
+    INIT.call_once(|| {
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("cinema")));
+        tracing::subscriber::set_global_default(subscriber).unwrap();
+    });
+}
+
+fn trace_id(span: &Span) -> String {
+    span.context().span().span_context().trace_id().to_string()
+}
+
+#[derive(Clone, ProstMessage)]
+struct WhoAmI {
+    #[prost(string, tag = "1")]
+    baggage_key: String,
+}
+
+#[derive(Clone, ProstMessage, cinema::MessageResponse)]
+struct Seen {
+    #[prost(string, tag = "1")]
+    trace_id: String,
+    #[prost(string, tag = "2")]
+    baggage: String,
+}
+
+impl Message for WhoAmI {
+    type Result = Seen;
+}
+
+impl Message for Seen {
+    type Result = ();
+}
+
+impl RemoteMessage for WhoAmI {}
+impl RemoteMessage for Seen {}
+
+// reports the trace it handles messages in
+struct Witness;
+
+impl Actor for Witness {}
+
+impl Handler<WhoAmI> for Witness {
+    type Result = Seen;
+
+    fn handle(&mut self, msg: WhoAmI, _ctx: &mut Context<Self>) -> Seen {
+        let baggage = opentelemetry::Context::current()
+            .baggage()
+            .get(msg.baggage_key)
+            .map(|value| value.to_string())
+            .unwrap_or_default();
+        Seen {
+            trace_id: trace_id(&Span::current()),
+            baggage,
+        }
+    }
+}
+
+fn who_am_i() -> WhoAmI {
+    WhoAmI {
+        baggage_key: "tenant".to_string(),
+    }
+}
+
+#[test]
+fn envelope_carries_trace_of_current_span() {
+    init_tracing();
+
+    let outside = Envelope::from_message(&who_am_i(), 1, "node", "actor");
+    assert!(outside.trace.is_none());
+
+    let span = info_span!("request");
+    let inside = span.in_scope(|| Envelope::from_message(&who_am_i(), 2, "node", "actor"));
+    let trace = inside.trace.clone().unwrap();
+    assert!(trace.traceparent.contains(&trace_id(&span)));
+    assert!(trace.baggage.is_empty());
+
+    // round trips through the wire format
+    let decoded = Envelope::from_bytes(&inside.to_bytes()).unwrap();
+    assert_eq!(decoded.trace.unwrap().traceparent, trace.traceparent);
+}
+
+#[tokio::test]
+async fn local_handler_runs_in_senders_trace() {
+    init_tracing();
+    let system = ActorSystem::new();
+    let witness = system.spawn(Witness);
+
+    let span = info_span!("local request");
+    let seen = witness
+        .send(who_am_i())
+        .instrument(span.clone())
+        .await
+        .unwrap();
+    assert_eq!(seen.trace_id, trace_id(&span));
+}
+
+#[tokio::test]
+async fn trace_and_baggage_cross_nodes() {
+    init_tracing();
+    let system = ActorSystem::new();
+    let witness = system.spawn(Witness);
+
+    let node = LocalNode::new("server-node");
+    let server = RemoteServer::bind("127.0.0.1:0", node.handler::<Witness, WhoAmI>(witness))
+        .await
+        .unwrap();
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    let client = RemoteClient::new(TcpTransport.connect(&addr).await.unwrap());
+    let remote: RemoteAddr<Witness> =
+        LocalNode::new("client").remote_addr("server-node", "witness", client);
+
+    // baggage set before the span starts becomes part of its context
+    let span = {
+        let _baggage =
+            opentelemetry::Context::current_with_baggage([KeyValue::new("tenant", "acme")])
+                .attach();
+        info_span!("remote request")
+    };
+    let response = remote
+        .send(who_am_i())
+        .instrument(span.clone())
+        .await
+        .unwrap();
+
+    let seen = Seen::decode(response.payload.as_slice()).unwrap();
+    assert_eq!(seen.trace_id, trace_id(&span));
+    assert_eq!(seen.baggage, "acme");
+
+    // the response continues the same trace back
+    let trace = response.trace.unwrap();
+    assert!(trace.traceparent.contains(&trace_id(&span)));
+    assert!(trace.baggage.contains("tenant=acme"));
+}
@@ -0,0 +1,417 @@
+use std::{sync::Arc, time::Duration};
+
+use cinema::{
+    persistence::{
+        InMemoryJournal, InMemorySnapshotStore, Journal, PersistedSnapshot, PersistenceOptions,
+        Snapshot, SnapshotStore,
+    },
+    Actor, ActorSystem, Context, Handler, Message, PersistentActor, SpawnOptions,
+    SupervisorStrategy,
+};
+use prost::Message as ProstMessage;
+
+#[derive(Clone, PartialEq, ProstMessage)]
+struct Deposited {
+    #[prost(uint64, tag = "1")]
+    amount: u64,
+}
+
+#[derive(Clone, PartialEq, ProstMessage)]
+struct AccountState {
+    #[prost(uint64, tag = "1")]
+    balance: u64,
+}
+
+struct Account {
+    id: String,
+    balance: u64,
+    ///events applied during recovery, to tell them from fresh ones
+    recovered: u64,
+    ///balance `started` saw
+    at_start: Option<u64>,
+}
+
+impl Account {
+    fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            balance: 0,
+            recovered: 0,
+            at_start: None,
+        }
+    }
+}
+
+impl Actor for Account {
+    fn started(&mut self, _ctx: &mut Context<Self>) {
+        self.at_start = Some(self.balance);
+    }
+}
+
+impl PersistentActor for Account {
+    type Event = Deposited;
+
+    fn persistence_id(&self) -> String {
+        format!("account-{}", self.id)
+    }
+
+    fn recover(&mut self, event: Deposited, _ctx: &mut Context<Self>) {
+        self.balance += event.amount;
+        self.recovered += 1;
+    }
+
+    fn snapshot(&self) -> Option<Snapshot> {
+        Some(Snapshot::new(&AccountState {
+            balance: self.balance,
+        }))
+    }
+
+    fn recover_snapshot(
+        &mut self,
+        snapshot: Snapshot,
+        _ctx: &mut Context<Self>,
+    ) -> std::io::Result<()> {
+        self.balance = snapshot.state::<AccountState>()?.balance;
+        Ok(())
+    }
+}
+
+struct Deposit(u64);
+
+impl Message for Deposit {
+    type Result = bool;
+}
+
+impl Handler<Deposit> for Account {
+    type Result = bool;
+
+    fn handle(&mut self, msg: Deposit, ctx: &mut Context<Self>) -> bool {
+        ctx.persist(Deposited { amount: msg.0 }, |account, event| {
+            account.balance += event.amount
+        })
+        .is_ok()
+    }
+}
+
+struct Balance;
+
+impl Message for Balance {
+    type Result = (u64, u64);
+}
+
+impl Handler<Balance> for Account {
+    type Result = (u64, u64);
+
+    fn handle(&mut self, _msg: Balance, _ctx: &mut Context<Self>) -> (u64, u64) {
+        (self.balance, self.recovered)
+    }
+}
+
+struct BalanceAtStart;
+
+impl Message for BalanceAtStart {
+    type Result = Option<u64>;
+}
+
+impl Handler<BalanceAtStart> for Account {
+    type Result = Option<u64>;
+
+    fn handle(&mut self, _msg: BalanceAtStart, _ctx: &mut Context<Self>) -> Option<u64> {
+        self.at_start
+    }
+}
+
+struct Checkpoint;
+
+impl Message for Checkpoint {
+    type Result = bool;
+}
+
+impl Handler<Checkpoint> for Account {
+    type Result = bool;
+
+    fn handle(&mut self, _msg: Checkpoint, ctx: &mut Context<Self>) -> bool {
+        let state = AccountState {
+            balance: self.balance,
+        };
+        ctx.save_snapshot(&state).is_ok()
+    }
+}
+
+struct Crash;
+
+impl Message for Crash {
+    type Result = ();
+}
+
+impl Handler<Crash> for Account {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+        panic!("crash");
+    }
+}
+
+#[tokio::test]
+async fn persisted_events_outlive_the_actor() {
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(InMemoryJournal::new());
+
+    let account = system.spawn_persistent(Account::new("alice"), journal.clone());
+    assert!(account.send(Deposit(10)).await.unwrap());
+    assert!(account.send(Deposit(5)).await.unwrap());
+    // the event was applied before the next message was handled
+    assert_eq!(account.send(Balance).await.unwrap(), (15, 0));
+    drop(account);
+
+    assert_eq!(journal.highest_sequence("account-alice").unwrap(), 2);
+    let events = journal.replay("account-alice", 2).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        Deposited::decode(events[0].payload.as_slice()).unwrap(),
+        Deposited { amount: 5 }
+    );
+
+    // a new incarnation rebuilds its balance from the journal
+    let account = system.spawn_persistent(Account::new("alice"), journal.clone());
+    assert_eq!(account.send(Balance).await.unwrap(), (15, 2));
+    assert!(account.send(Deposit(1)).await.unwrap());
+    assert_eq!(account.send(Balance).await.unwrap(), (16, 2));
+    assert_eq!(journal.highest_sequence("account-alice").unwrap(), 3);
+
+    // other ids keep their own history
+    let other = system.spawn_persistent(Account::new("bob"), journal.clone());
+    assert_eq!(other.send(Balance).await.unwrap(), (0, 0));
+}
+
+#[tokio::test]
+async fn restarted_actor_replays_its_journal() {
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(InMemoryJournal::new());
+
+    let account = system.spawn_persistent_with_strategy(
+        || Account::new("carol"),
+        SupervisorStrategy::restart(3, Duration::from_secs(10)),
+        journal,
+    );
+    account.send(Deposit(7)).await.unwrap();
+    account.send(Deposit(3)).await.unwrap();
+
+    account.do_send(Crash).await.unwrap();
+    assert_eq!(account.send(Balance).await.unwrap(), (10, 2));
+}
+
+#[tokio::test]
+async fn recovery_runs_before_started() {
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(InMemoryJournal::new());
+
+    let account = system.spawn_persistent(Account::new("ivan"), journal.clone());
+    account.send(Deposit(8)).await.unwrap();
+    assert_eq!(account.send(BalanceAtStart).await.unwrap(), Some(0));
+    drop(account);
+
+    let account = system.spawn_persistent_with(
+        Account::new("ivan"),
+        SpawnOptions::new().name("ivan"),
+        journal,
+    );
+    assert_eq!(account.name(), Some("ivan"));
+    assert_eq!(account.send(BalanceAtStart).await.unwrap(), Some(8));
+    assert_eq!(account.send(Balance).await.unwrap(), (8, 1));
+}
+
+#[tokio::test]
+async fn rejected_event_stops_the_actor() {
+    use cinema::persistence::PersistedEvent;
+
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(InMemoryJournal::new());
+
+    let account = system.spawn_persistent(Account::new("judy"), journal.clone());
+    assert_eq!(account.send(Balance).await.unwrap(), (0, 0));
+    // another incarnation writes 1 first
+    journal
+        .append(PersistedEvent {
+            persistence_id: "account-judy".to_string(),
+            sequence: 1,
+            codec: 0,
+            payload: Vec::new(),
+        })
+        .unwrap();
+
+    assert!(account.send(Deposit(3)).await.unwrap());
+    // the deposit is never applied, the actor stops instead
+    if let Ok(balance) = account.send(Balance).await {
+        assert_eq!(balance, (0, 0));
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!account.is_alive());
+}
+
+///panics on its first append, then behaves
+struct PanickyJournal {
+    inner: InMemoryJournal,
+    panicked: std::sync::atomic::AtomicBool,
+}
+
+impl Journal for PanickyJournal {
+    fn append(&self, event: cinema::persistence::PersistedEvent) -> std::io::Result<()> {
+        if !self.panicked.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            panic!("disk on fire");
+        }
+        self.inner.append(event)
+    }
+
+    fn replay(
+        &self,
+        persistence_id: &str,
+        from: u64,
+    ) -> std::io::Result<Vec<cinema::persistence::PersistedEvent>> {
+        self.inner.replay(persistence_id, from)
+    }
+
+    fn highest_sequence(&self, persistence_id: &str) -> std::io::Result<u64> {
+        self.inner.highest_sequence(persistence_id)
+    }
+}
+
+#[tokio::test]
+async fn panicking_journal_fails_only_its_write() {
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(PanickyJournal {
+        inner: InMemoryJournal::new(),
+        panicked: std::sync::atomic::AtomicBool::new(false),
+    });
+    let options = PersistenceOptions::new(journal.clone());
+
+    let account = system.spawn_persistent(Account::new("kim"), options.clone());
+    assert!(account.send(Deposit(3)).await.unwrap());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!account.is_alive());
+
+    // the writer is free again, so the next incarnation recovers and persists
+    let account = tokio::time::timeout(Duration::from_secs(1), async {
+        let account = system.spawn_persistent(Account::new("kim"), options);
+        account.send(Deposit(4)).await.unwrap();
+        account.send(Balance).await.unwrap()
+    })
+    .await
+    .expect("recovery waits on a writer that is gone");
+    assert_eq!(account, (4, 0));
+    assert_eq!(journal.highest_sequence("account-kim").unwrap(), 1);
+}
+
+#[tokio::test]
+async fn persist_needs_a_journal() {
+    let system = ActorSystem::new();
+    let account = system.spawn(Account::new("dave"));
+
+    assert!(!account.send(Deposit(10)).await.unwrap());
+    assert_eq!(account.send(Balance).await.unwrap(), (0, 0));
+}
+
+#[tokio::test]
+async fn recovery_starts_from_the_latest_snapshot() {
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(InMemoryJournal::new());
+    let store = Arc::new(InMemorySnapshotStore::new());
+    let options = PersistenceOptions::new(journal).snapshots(store.clone());
+
+    let account = system.spawn_persistent(Account::new("erin"), options.clone());
+    account.send(Deposit(10)).await.unwrap();
+    assert!(account.send(Checkpoint).await.unwrap());
+    account.send(Deposit(5)).await.unwrap();
+    drop(account);
+
+    let snapshot = store.load("account-erin").unwrap().unwrap();
+    assert_eq!(snapshot.sequence, 1);
+
+    // only the event after the snapshot is replayed
+    let account = system.spawn_persistent(Account::new("erin"), options);
+    assert_eq!(account.send(Balance).await.unwrap(), (15, 1));
+}
+
+#[tokio::test]
+async fn snapshots_are_taken_every_n_events() {
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(InMemoryJournal::new());
+    let store = Arc::new(InMemorySnapshotStore::new());
+    let options = PersistenceOptions::new(journal)
+        .snapshots(store.clone())
+        .snapshot_every(3);
+
+    let account = system.spawn_persistent(Account::new("frank"), options.clone());
+    for amount in 1..=7 {
+        account.send(Deposit(amount)).await.unwrap();
+    }
+    assert_eq!(account.send(Balance).await.unwrap(), (28, 0));
+    drop(account);
+
+    let snapshot = store.load("account-frank").unwrap().unwrap();
+    assert_eq!(snapshot.sequence, 6);
+    assert_eq!(
+        snapshot.snapshot.state::<AccountState>().unwrap(),
+        AccountState { balance: 21 }
+    );
+
+    let account = system.spawn_persistent(Account::new("frank"), options);
+    assert_eq!(account.send(Balance).await.unwrap(), (28, 1));
+}
+
+#[tokio::test]
+async fn unreadable_snapshot_falls_back_to_the_journal() {
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(InMemoryJournal::new());
+    let store = Arc::new(InMemorySnapshotStore::new());
+    let options = PersistenceOptions::new(journal).snapshots(store.clone());
+
+    let account = system.spawn_persistent(Account::new("grace"), options.clone());
+    account.send(Deposit(4)).await.unwrap();
+    account.send(Deposit(6)).await.unwrap();
+    drop(account);
+
+    store
+        .save(PersistedSnapshot {
+            persistence_id: "account-grace".to_string(),
+            sequence: 2,
+            snapshot: Snapshot {
+                codec: 0,
+                payload: vec![0xff; 3],
+            },
+        })
+        .unwrap();
+
+    let account = system.spawn_persistent(Account::new("grace"), options);
+    assert_eq!(account.send(Balance).await.unwrap(), (10, 2));
+}
+
+#[tokio::test]
+async fn save_snapshot_needs_a_store() {
+    let system = ActorSystem::new();
+    let journal: Arc<dyn Journal> = Arc::new(InMemoryJournal::new());
+
+    let account = system.spawn_persistent(Account::new("heidi"), journal);
+    assert!(!account.send(Checkpoint).await.unwrap());
+}
+
+#[test]
+fn journal_rejects_out_of_order_events() {
+    use cinema::persistence::PersistedEvent;
+
+    let journal = InMemoryJournal::new();
+    let event = |sequence| PersistedEvent {
+        persistence_id: "id".to_string(),
+        sequence,
+        codec: 0,
+        payload: Vec::new(),
+    };
+
+    journal.append(event(1)).unwrap();
+    // another incarnation already wrote 1
+    let err = journal.append(event(1)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert!(journal.append(event(3)).is_err());
+    journal.append(event(2)).unwrap();
+    assert_eq!(journal.replay("id", 1).unwrap().len(), 2);
+}
@@ -0,0 +1,155 @@
+use std::{sync::Arc, time::Duration};
+
+use cinema::{
+    remote::{
+        proto::Envelope, register_message, EnvelopeHandler, QuicTransport, RemoteAddr,
+        RemoteClient, RemoteMessage, RemoteServer, TlsServerConfig, TlsTransport, Transport,
+    },
+    Message,
+};
+use prost::Message as ProstMessage;
+use tokio::time::Instant;
+
+const CA: &[u8] = include_bytes!("certs/ca.pem");
+const SERVER_CERT: &[u8] = include_bytes!("certs/server.pem");
+const SERVER_KEY: &[u8] = include_bytes!("certs/server.key");
+const CLIENT_CERT: &[u8] = include_bytes!("certs/client.pem");
+const CLIENT_KEY: &[u8] = include_bytes!("certs/client.key");
+
+#[derive(Clone, ProstMessage)]
+struct Ping {
+    #[prost(string, tag = "1")]
+    message: String,
+}
+
+impl Message for Ping {
+    type Result = ();
+}
+
+impl RemoteMessage for Ping {}
+
+// echoes the ping back, holding "slow" ones for a while first
+fn echo_handler() -> EnvelopeHandler {
+    Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            let ping = Ping::decode(envelope.payload.as_slice()).ok()?;
+            if ping.message == "slow" {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+            }
+            Some(Envelope {
+                message_type: envelope.message_type,
+                payload: envelope.payload,
+                correlation_id: envelope.correlation_id,
+                sender_node: "server".to_string(),
+                target_actor: envelope.sender_node,
+                is_response: true,
+                codec: envelope.codec,
+                compression: envelope.compression,
+                delivery_id: envelope.delivery_id,
+                trace: envelope.trace,
+                ..Default::default()
+            })
+        })
+    })
+}
+
+async fn start(tls: TlsServerConfig) -> String {
+    let server = RemoteServer::bind_quic("127.0.0.1:0", echo_handler(), tls)
+        .await
+        .unwrap();
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+    addr.to_string()
+}
+
+async fn client(transport: &QuicTransport, addr: &str) -> RemoteClient {
+    register_message::<Ping>();
+    RemoteClient::new(transport.connect(addr).await.unwrap())
+}
+
+async fn remote(transport: &QuicTransport, addr: &str) -> RemoteAddr<()> {
+    client(transport, addr).await.remote_addr("server-node", "echo")
+}
+
+fn ping(message: &str) -> Ping {
+    Ping {
+        message: message.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn quic_round_trip() {
+    let addr = start(TlsServerConfig::new(SERVER_CERT, SERVER_KEY).unwrap()).await;
+    let transport = QuicTransport::new(TlsTransport::new(CA).unwrap()).unwrap();
+    let remote = remote(&transport, &addr).await;
+
+    let response = remote.send(ping("over quic")).await.unwrap();
+    assert_eq!(
+        Ping::decode(response.payload.as_slice()).unwrap().message,
+        "over quic"
+    );
+}
+
+#[tokio::test]
+async fn quic_connections_share_one_client_endpoint() {
+    let addr = start(TlsServerConfig::new(SERVER_CERT, SERVER_KEY).unwrap()).await;
+    let transport = QuicTransport::new(TlsTransport::new(CA).unwrap()).unwrap();
+
+    let first = transport.connect(&addr).await.unwrap();
+    let second = transport.clone().connect(&addr).await.unwrap();
+    assert_eq!(first.local_addr(), second.local_addr());
+}
+
+#[tokio::test]
+async fn quic_carries_large_payloads() {
+    let addr = start(TlsServerConfig::new(SERVER_CERT, SERVER_KEY).unwrap()).await;
+    let transport = QuicTransport::new(TlsTransport::new(CA).unwrap()).unwrap();
+    let remote = remote(&transport, &addr).await;
+
+    let big = "x".repeat(4 * 1024 * 1024);
+    let response = remote.send(ping(&big)).await.unwrap();
+    assert_eq!(
+        Ping::decode(response.payload.as_slice()).unwrap().message.len(),
+        big.len()
+    );
+}
+
+#[tokio::test]
+async fn slow_request_does_not_block_others() {
+    let addr = start(TlsServerConfig::new(SERVER_CERT, SERVER_KEY).unwrap()).await;
+    let transport = QuicTransport::new(TlsTransport::new(CA).unwrap()).unwrap();
+    let client = client(&transport, &addr).await;
+    let remote: RemoteAddr<()> = client.remote_addr("server-node", "echo");
+
+    let slow = {
+        let remote: RemoteAddr<()> = client.remote_addr("server-node", "echo");
+        tokio::spawn(async move { remote.send(ping("slow")).await })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // answered on its own stream while the slow one is still being handled
+    let start = Instant::now();
+    remote.send(ping("fast")).await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(200));
+    assert!(!slow.is_finished());
+
+    slow.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn quic_with_mutual_tls() {
+    let tls = TlsServerConfig::new(SERVER_CERT, SERVER_KEY)
+        .unwrap()
+        .require_client_auth(CA)
+        .unwrap();
+    let addr = start(tls).await;
+
+    let tls = TlsTransport::new(CA)
+        .unwrap()
+        .server_name("node.cinema.test")
+        .unwrap()
+        .with_identity(CLIENT_CERT, CLIENT_KEY)
+        .unwrap();
+    let remote = remote(&QuicTransport::new(tls).unwrap(), &addr).await;
+    assert!(remote.send(ping("authenticated")).await.is_ok());
+}
@@ -56,6 +56,23 @@ fn registry_deserialize() {
     assert_eq!(downcasted.message, "Hello, Registry!");
 }
 
+#[derive(Clone, ProstMessage, cinema::Message, RemoteMessage)]
+struct Pong {
+    #[prost(uint64, tag = "1")]
+    seq: u64,
+}
+
+#[test]
+fn derived_remote_message_is_registered() {
+    let envelope = Envelope::from_message(&Pong { seq: 7 }, 1, "node", "actor");
+
+    //no register_message::<Pong>() call, the derive took care of it
+    let deserialized = deserialize_payload(&envelope.message_type, &envelope.payload).unwrap();
+    let downcasted = deserialized.downcast_ref::<Pong>().unwrap();
+
+    assert_eq!(downcasted.seq, 7);
+}
+
 #[tokio::test]
 async fn tcp_send_recv_envelope() {
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -129,6 +146,7 @@ async fn remote_client_send_recv() {
             sender_node: "node-server".to_string(),
             target_actor: request.sender_node.clone(),
             is_response: true, //mark as response
+            ..Default::default()
         };
 
         conn.send(resp).await.unwrap();
@@ -146,6 +164,7 @@ async fn remote_client_send_recv() {
         sender_node: "client".to_string(),
         target_actor: "some_actor".to_string(),
         is_response: false,
+        ..Default::default()
     };
 
     let response = client.send(request).await.unwrap();
@@ -158,6 +177,54 @@ async fn remote_client_send_recv() {
     server.await.unwrap();
 }
 
+#[tokio::test]
+async fn remote_client_coalesces_envelopes() {
+    use cinema::remote::CoalesceConfig;
+    use std::time::{Duration, Instant};
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut conn = TcpConnection::new(stream);
+
+        let mut received = Vec::new();
+        for _ in 0..5 {
+            let envelope = conn.recv().await.unwrap();
+            received.push((envelope.correlation_id, Instant::now()));
+        }
+        received
+    });
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let client = RemoteClient::with_coalescing(
+        TcpConnection::new(stream),
+        CoalesceConfig {
+            max_batch: 16,
+            max_delay: Duration::from_millis(50),
+        },
+    );
+
+    for i in 0..5 {
+        let envelope = Envelope {
+            message_type: "test::Ping".to_string(),
+            correlation_id: i,
+            ..Default::default()
+        };
+        client.do_send(envelope).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+    let last_sent = Instant::now();
+
+    let received = server.await.unwrap();
+    let ids: Vec<u64> = received.iter().map(|(id, _)| *id).collect();
+    assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+
+    // the first envelope was held back and flushed together with the rest
+    assert!(received[0].1 >= last_sent);
+}
+
 #[tokio::test]
 async fn remote_addr_to_server() {
     let handler: EnvelopeHandler = Arc::new(|envelope: Envelope| {
@@ -171,6 +238,7 @@ async fn remote_addr_to_server() {
                 sender_node: "server".to_string(),
                 target_actor: envelope.sender_node.clone(),
                 is_response: true,
+                ..Default::default()
             })
         })
     });
@@ -202,6 +270,58 @@ async fn remote_addr_to_server() {
     println!("Got response: {:?}", response.message_type);
 }
 
+#[tokio::test]
+async fn remote_addr_send_timeout_cleans_up_pending() {
+    use std::time::Duration;
+
+    // answers only pings saying "answer", the rest are left hanging
+    let handler: EnvelopeHandler = Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            let ping = Ping::decode(envelope.payload.as_slice()).ok()?;
+            (ping.message == "answer").then(|| Envelope {
+                message_type: "test::Pong".to_string(),
+                payload: Vec::new(),
+                correlation_id: envelope.correlation_id,
+                sender_node: "server".to_string(),
+                target_actor: envelope.sender_node.clone(),
+                is_response: true,
+                ..Default::default()
+            })
+        })
+    });
+
+    let server = RemoteServer::bind("127.0.0.1:0", handler).await.unwrap();
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+
+    let conn = TcpTransport.connect(&addr.to_string()).await.unwrap();
+    let client = RemoteClient::new(conn);
+    let remote: RemoteAddr<()> = RemoteAddr::new("client", "server-node", "echo", client.clone());
+
+    let ignored = Ping {
+        message: "ignore".to_string(),
+    };
+    let res = remote
+        .send_timeout(ignored, Duration::from_millis(50))
+        .await;
+    assert!(matches!(res, Err(cinema::remote::TransportError::Timeout)));
+    assert_eq!(client.pending_requests().await, 1);
+
+    // the next sweep drops the abandoned entry
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(client.pending_requests().await, 0);
+
+    let answered = Ping {
+        message: "answer".to_string(),
+    };
+    let response = remote
+        .send_timeout(answered, Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert!(response.is_response);
+    assert_eq!(client.pending_requests().await, 0);
+}
+
 #[tokio::test]
 async fn remote_addr_to_actor() {
     // Define a simple Counter actor
@@ -221,6 +341,8 @@ async fn remote_addr_to_actor() {
     }
     impl RemoteMessage for Increment {}
     impl Handler<Increment> for Counter {
+        type Result = i32;
+
         fn handle(&mut self, msg: Increment, _ctx: &mut Context<Self>) -> i32 {
             self.count += msg.amount;
             self.count
@@ -251,6 +373,7 @@ async fn remote_addr_to_actor() {
                     sender_node: "server".to_string(),
                     target_actor: envelope.sender_node.clone(),
                     is_response: true,
+                    ..Default::default()
                 })
             })
         })
@@ -299,7 +422,7 @@ async fn make_handler_simplifies_setup() {
     impl RemoteMessage for Add {}
 
     // Response type (protobuf wrapper for the result)
-    #[derive(Clone, prost::Message)]
+    #[derive(Clone, prost::Message, cinema::MessageResponse)]
     struct AddResult {
         #[prost(int32, tag = "1")]
         value: i32,
@@ -310,6 +433,8 @@ async fn make_handler_simplifies_setup() {
     impl RemoteMessage for AddResult {}
 
     impl Handler<Add> for Calculator {
+        type Result = AddResult;
+
         fn handle(&mut self, msg: Add, _ctx: &mut Context<Self>) -> AddResult {
             self.value += msg.n;
             AddResult { value: self.value }
@@ -346,6 +471,37 @@ async fn make_handler_simplifies_setup() {
     println!("Calculator returned: {}", result.value);
 }
 
+/// A tell reaches the actor, with nothing sent back
+#[tokio::test]
+async fn tell_handler_delivers_without_a_response() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Inbox(Arc<AtomicUsize>);
+    impl Actor for Inbox {}
+    impl Handler<Ping> for Inbox {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let system = ActorSystem::new();
+    let seen = Arc::new(AtomicUsize::new(0));
+    let inbox = system.spawn(Inbox(seen.clone()));
+    let handler = LocalNode::new("inbox-node").tell_handler::<Inbox, Ping>(inbox.clone());
+
+    let ping = Ping {
+        message: "hi".to_string(),
+    };
+    let envelope = Envelope::from_message(&ping, 0, "client", "inbox");
+    assert!(handler(envelope).await.is_none());
+
+    // queued behind the tell, so both were handled once this returns
+    inbox.send(ping).await.unwrap();
+    assert_eq!(seen.load(Ordering::SeqCst), 2);
+}
+
 /// Test auto-derived client identity from TCP socket address
 #[tokio::test]
 async fn auto_derived_client_identity() {
@@ -365,7 +521,7 @@ async fn auto_derived_client_identity() {
     }
     impl RemoteMessage for Add {}
 
-    #[derive(Clone, prost::Message)]
+    #[derive(Clone, prost::Message, cinema::MessageResponse)]
     struct AddResult {
         #[prost(int32, tag = "1")]
         value: i32,
@@ -376,6 +532,8 @@ async fn auto_derived_client_identity() {
     impl RemoteMessage for AddResult {}
 
     impl Handler<Add> for Calculator {
+        type Result = AddResult;
+
         fn handle(&mut self, msg: Add, _ctx: &mut Context<Self>) -> AddResult {
             self.value += msg.n;
             AddResult { value: self.value }
@@ -428,12 +586,14 @@ async fn two_servers_same_name() {
     impl Message for Add { type Result = AddResult; }
     impl RemoteMessage for Add {}
 
-    #[derive(Clone, prost::Message)]
+    #[derive(Clone, prost::Message, cinema::MessageResponse)]
     struct AddResult { #[prost(int32, tag = "1")] value: i32 }
     impl Message for AddResult { type Result = (); }
     impl RemoteMessage for AddResult {}
 
     impl Handler<Add> for Calculator {
+        type Result = AddResult;
+
         fn handle(&mut self, msg: Add, _ctx: &mut Context<Self>) -> AddResult {
             CALC_CALLS.fetch_add(1, Ordering::SeqCst);
             println!("Calculator handling Add!");
@@ -447,6 +607,8 @@ async fn two_servers_same_name() {
     impl Actor for HelloPrinter {}
 
     impl Handler<Add> for HelloPrinter {
+        type Result = AddResult;
+
         fn handle(&mut self, _msg: Add, _ctx: &mut Context<Self>) -> AddResult {
             HELLO_CALLS.fetch_add(1, Ordering::SeqCst);
             println!("Hello from calc! (not calculating)");
@@ -499,3 +661,1120 @@ async fn two_servers_same_name() {
     println!("Node name doesn't matter for routing!");
     println!("TCP connection determines which server handles the message.");
 }
+
+/// Two actors handling the same message type on one node, routed by target_actor
+#[tokio::test]
+async fn router_dispatches_by_actor_name() {
+    use cinema::remote::MessageRouter;
+
+    struct Accumulator {
+        value: i32,
+    }
+    impl Actor for Accumulator {}
+
+    #[derive(Clone, prost::Message)]
+    struct Add {
+        #[prost(int32, tag = "1")]
+        n: i32,
+    }
+    impl Message for Add {
+        type Result = AddResult;
+    }
+    impl RemoteMessage for Add {}
+
+    #[derive(Clone, prost::Message, cinema::MessageResponse)]
+    struct AddResult {
+        #[prost(int32, tag = "1")]
+        value: i32,
+    }
+    impl Message for AddResult {
+        type Result = ();
+    }
+    impl RemoteMessage for AddResult {}
+
+    impl Handler<Add> for Accumulator {
+        type Result = AddResult;
+
+        fn handle(&mut self, msg: Add, _ctx: &mut Context<Self>) -> AddResult {
+            self.value += msg.n;
+            AddResult { value: self.value }
+        }
+    }
+
+    let system = ActorSystem::new();
+    let left = system.spawn(Accumulator { value: 0 });
+    let right = system.spawn(Accumulator { value: 1000 });
+    let fallback = system.spawn(Accumulator { value: -1000 });
+
+    let node = LocalNode::new("acc-node");
+    let router = MessageRouter::new()
+        .route_actor::<Add>("left", node.handler::<Accumulator, Add>(left))
+        .route_actor::<Add>("right", node.handler::<Accumulator, Add>(right))
+        .route::<Add>(node.handler::<Accumulator, Add>(fallback))
+        .build();
+
+    let server = RemoteServer::bind("127.0.0.1:0", router).await.unwrap();
+    let server_addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    let transport = TcpTransport;
+    let conn = transport.connect(&server_addr.to_string()).await.unwrap();
+    let client = RemoteClient::new(conn);
+
+    let left: RemoteAddr<Accumulator> = client.remote_addr("acc-node", "left");
+    let right: RemoteAddr<Accumulator> = client.remote_addr("acc-node", "right");
+    let unknown: RemoteAddr<Accumulator> = client.remote_addr("acc-node", "unknown");
+
+    let decode = |env: Envelope| AddResult::decode(env.payload.as_slice()).unwrap().value;
+
+    assert_eq!(decode(left.send(Add { n: 1 }).await.unwrap()), 1);
+    assert_eq!(decode(right.send(Add { n: 1 }).await.unwrap()), 1001);
+    assert_eq!(decode(left.send(Add { n: 2 }).await.unwrap()), 3);
+    // unknown actor name falls back to type-only routing
+    assert_eq!(decode(unknown.send(Add { n: 1 }).await.unwrap()), -999);
+}
+
+/// Envelopes for a named actor with no matching route reach its unhandled hook
+#[tokio::test]
+async fn router_reports_unhandled_to_actor() {
+    use cinema::{
+        message::{MessageOrigin, UnhandledMessage},
+        remote::{unhandled_remote_messages, MessageRouter},
+    };
+    use std::sync::Mutex;
+
+    struct Strict {
+        seen: Arc<Mutex<Vec<UnhandledMessage>>>,
+    }
+    impl Actor for Strict {
+        fn unhandled(&mut self, msg: UnhandledMessage, _ctx: &mut Context<Self>) {
+            self.seen.lock().unwrap().push(msg);
+        }
+    }
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let system = ActorSystem::new();
+    let strict = system.spawn(Strict { seen: seen.clone() });
+
+    let router = MessageRouter::new()
+        .unhandled_to("strict", strict.clone())
+        .build();
+
+    let server = RemoteServer::bind("127.0.0.1:0", router).await.unwrap();
+    let server_addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    let transport = TcpTransport;
+    let conn = transport.connect(&server_addr.to_string()).await.unwrap();
+    let client = RemoteClient::new(conn);
+    let remote: RemoteAddr<()> = client.remote_addr("server", "strict");
+
+    let before = unhandled_remote_messages();
+    remote
+        .do_send(Ping {
+            message: "nobody home".to_string(),
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    assert!(unhandled_remote_messages() > before);
+    assert_eq!(strict.unhandled_messages(), 1);
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 1);
+    assert!(seen[0].message_type.contains("Ping"));
+    assert_eq!(seen[0].origin, MessageOrigin::Remote);
+}
+
+/// Test: a local actor reference travels inside a message and is used for the reply
+#[tokio::test]
+async fn remote_ref_reply_to() {
+    use cinema::remote::proto::RemoteRef;
+    use std::sync::Mutex;
+
+    #[derive(Clone, prost::Message)]
+    struct Job {
+        #[prost(string, tag = "1")]
+        text: String,
+        #[prost(message, optional, tag = "2")]
+        reply_to: Option<RemoteRef>,
+    }
+    impl Message for Job {
+        type Result = ();
+    }
+    impl RemoteMessage for Job {}
+
+    #[derive(Clone, prost::Message)]
+    struct Done {
+        #[prost(string, tag = "1")]
+        text: String,
+    }
+    impl Message for Done {
+        type Result = ();
+    }
+    impl RemoteMessage for Done {}
+
+    struct Worker;
+    impl Actor for Worker {}
+    impl Handler<Job> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, msg: Job, _ctx: &mut Context<Self>) {
+            let reply_to = msg.reply_to.expect("missing reply_to");
+            tokio::spawn(async move {
+                let local = LocalNode::new("worker-node");
+                let caller = reply_to.connect::<Collector>(&local).await.unwrap();
+                caller
+                    .do_send(Done {
+                        text: msg.text.to_uppercase(),
+                    })
+                    .await
+                    .unwrap();
+            });
+        }
+    }
+
+    struct Collector {
+        got: Arc<Mutex<Vec<String>>>,
+    }
+    impl Actor for Collector {}
+    impl Handler<Done> for Collector {
+        type Result = ();
+
+        fn handle(&mut self, msg: Done, _ctx: &mut Context<Self>) {
+            self.got.lock().unwrap().push(msg.text);
+        }
+    }
+
+    let system = ActorSystem::new();
+
+    // worker node
+    let worker = system.spawn(Worker);
+    let worker_server = RemoteServer::bind(
+        "127.0.0.1:0",
+        LocalNode::new("worker-node").tell_handler::<Worker, Job>(worker),
+    )
+    .await
+    .unwrap();
+    let worker_addr = worker_server.local_addr().unwrap().to_string();
+    tokio::spawn(worker_server.run());
+
+    // caller node, with a named collector exposed on its own server
+    let got = Arc::new(Mutex::new(Vec::new()));
+    let collector = system.spawn(Collector { got: got.clone() });
+    system.register("collector", collector.clone());
+    let caller_server = RemoteServer::bind(
+        "127.0.0.1:0",
+        LocalNode::new("caller-node").tell_handler::<Collector, Done>(collector),
+    )
+    .await
+    .unwrap();
+    let caller = LocalNode::with_addr(
+        "caller-node",
+        &caller_server.local_addr().unwrap().to_string(),
+    );
+    tokio::spawn(caller_server.run());
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    assert!(caller.export::<Worker>(&system, "collector").is_none());
+    let reply_to = caller.export::<Collector>(&system, "collector").unwrap();
+    assert_eq!(reply_to.actor_name, "collector");
+
+    let conn = TcpTransport.connect(&worker_addr).await.unwrap();
+    let worker: RemoteAddr<Worker> = caller.remote_addr("worker-node", "worker", RemoteClient::new(conn));
+    worker
+        .do_send(Job {
+            text: "hello".to_string(),
+            reply_to: Some(reply_to),
+        })
+        .await
+        .unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(*got.lock().unwrap(), vec!["HELLO".to_string()]);
+}
+
+// answers one request per connection, then hangs up like a restarting node
+async fn one_request_per_connection(listener: TcpListener, answer_first: bool) {
+    let mut answer = answer_first;
+    loop {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut conn = TcpConnection::new(stream);
+        if let Ok(envelope) = conn.recv().await {
+            if answer {
+                let response = Envelope {
+                    is_response: true,
+                    ..envelope
+                };
+                conn.send(response).await.unwrap();
+            }
+        }
+        answer = true;
+    }
+}
+
+#[tokio::test]
+async fn reconnecting_client_survives_peer_restart() {
+    use cinema::remote::ReconnectPolicy;
+    use std::time::Duration;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(one_request_per_connection(listener, true));
+
+    let policy = ReconnectPolicy::new(Duration::from_millis(10), Duration::from_millis(100));
+    let client = RemoteClient::connect_with_reconnect(TcpTransport, &addr, policy)
+        .await
+        .unwrap();
+
+    for i in 0..3 {
+        let envelope = Envelope::from_message(&Ping { message: "hi".to_string() }, i, "client", "echo");
+        let response = client.send(envelope).await.unwrap();
+        assert_eq!(response.correlation_id, i);
+        // the server hangs up after answering
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[tokio::test]
+async fn reconnecting_client_fails_requests_lost_with_connection() {
+    use cinema::remote::{ReconnectPolicy, TransportError};
+    use std::time::Duration;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(one_request_per_connection(listener, false));
+
+    // nothing is held while reconnecting
+    let policy = ReconnectPolicy::new(Duration::from_millis(200), Duration::from_millis(200)).buffer(0);
+    let client = RemoteClient::connect_with_reconnect(TcpTransport, &addr, policy)
+        .await
+        .unwrap();
+    let ping = || Envelope::from_message(&Ping { message: "hi".to_string() }, 1, "client", "echo");
+
+    // dropped unanswered by the first connection
+    let res = client.send(ping()).await;
+    assert!(matches!(res, Err(TransportError::Reconnecting)));
+
+    // still backing off, with no room to hold the request
+    let res = client.send(ping()).await;
+    assert!(matches!(res, Err(TransportError::Reconnecting)));
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert!(client.send(ping()).await.is_ok());
+}
+
+#[tokio::test]
+async fn reconnecting_client_holds_sends_until_reconnected() {
+    use cinema::remote::ReconnectPolicy;
+    use std::time::Duration;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(one_request_per_connection(listener, false));
+
+    let policy = ReconnectPolicy::new(Duration::from_millis(100), Duration::from_millis(100));
+    let client = RemoteClient::connect_with_reconnect(TcpTransport, &addr, policy)
+        .await
+        .unwrap();
+    let ping = || Envelope::from_message(&Ping { message: "hi".to_string() }, 1, "client", "echo");
+    assert!(client.send(ping()).await.is_err());
+
+    // sent while backing off, written once the new connection is up
+    assert!(client.send(ping()).await.is_ok());
+}
+
+#[tokio::test]
+async fn connection_manager_multiplexes_asks_per_peer() {
+    use cinema::remote::ConnectionManager;
+    use std::time::{Duration, Instant};
+
+    // holds "slow" pings for a while before answering
+    let handler: EnvelopeHandler = Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            let ping = Ping::decode(envelope.payload.as_slice()).ok()?;
+            if ping.message == "slow" {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+            }
+            Some(Envelope {
+                is_response: true,
+                ..envelope
+            })
+        })
+    });
+    let server = RemoteServer::bind("127.0.0.1:0", handler).await.unwrap();
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    let manager = ConnectionManager::default();
+    let ping = |message: &str| Ping {
+        message: message.to_string(),
+    };
+
+    let slow = {
+        let remote: RemoteAddr<()> = manager.remote_addr(&addr, "echo").await.unwrap();
+        tokio::spawn(async move { remote.send(ping("slow")).await })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // same connection, answered while the slow ask is still being handled
+    let remote: RemoteAddr<()> = manager.remote_addr(&addr, "echo").await.unwrap();
+    let start = Instant::now();
+    remote.send(ping("fast")).await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(200));
+    assert!(!slow.is_finished());
+    assert_eq!(manager.connections(&addr).await, 1);
+    slow.await.unwrap().unwrap();
+
+    // a bigger pool opens connections up to its size, then takes turns
+    let pooled = ConnectionManager::new(TcpTransport).connections_per_peer(2);
+    for _ in 0..4 {
+        let remote: RemoteAddr<()> = pooled.remote_addr(&addr, "echo").await.unwrap();
+        remote.send(ping("fast")).await.unwrap();
+    }
+    assert_eq!(pooled.connections(&addr).await, 2);
+
+    pooled.remove(&addr).await;
+    assert_eq!(pooled.connections(&addr).await, 0);
+}
+
+#[tokio::test]
+async fn connection_manager_dials_peers_independently() {
+    use cinema::remote::{ConnectionManager, HandshakeConfig, HandshakeTransport};
+    use std::time::{Duration, Instant};
+
+    let echo: EnvelopeHandler = Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            Some(Envelope {
+                is_response: true,
+                ..envelope
+            })
+        })
+    });
+    let server = RemoteServer::bind("127.0.0.1:0", echo).await.unwrap();
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    // accepts connections but never answers the handshake
+    let stuck = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let stuck_addr = stuck.local_addr().unwrap().to_string();
+
+    let config = HandshakeConfig::new("client", "").timeout(Duration::from_secs(2));
+    let manager = ConnectionManager::new(HandshakeTransport::new(TcpTransport, config));
+    let dialing = {
+        let manager = manager.clone();
+        tokio::spawn(async move { manager.client(&stuck_addr).await })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // the healthy peer is reached while the other dial hangs
+    let start = Instant::now();
+    let remote: RemoteAddr<()> = manager.remote_addr(&addr, "echo").await.unwrap();
+    remote.send(Ping { message: "hi".to_string() }).await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(500));
+    assert!(!dialing.is_finished());
+    drop(stuck);
+}
+
+#[tokio::test]
+async fn heartbeat_detects_half_open_connection() {
+    use cinema::remote::{HeartbeatConfig, HeartbeatConnection, TransportError};
+    use std::time::Duration;
+
+    // accepts, then never reads or writes, like a peer that vanished
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let silent = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        drop(stream);
+    });
+
+    let config = HeartbeatConfig {
+        interval: Duration::from_millis(20),
+        timeout: Duration::from_millis(100),
+    };
+    let conn = TcpTransport.connect(&addr.to_string()).await.unwrap();
+    let client = RemoteClient::new(HeartbeatConnection::new(conn, config));
+
+    let envelope = Envelope::from_message(&Ping { message: "hi".to_string() }, 1, "client", "echo");
+    let res = tokio::time::timeout(Duration::from_secs(1), client.send(envelope)).await;
+    assert!(matches!(res, Ok(Err(TransportError::Disconnected))));
+    silent.abort();
+}
+
+#[tokio::test]
+async fn server_heartbeat_drops_idle_connections() {
+    use cinema::remote::{HeartbeatConfig, HeartbeatTransport};
+    use std::time::Duration;
+
+    let handler: EnvelopeHandler = Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            Some(Envelope {
+                is_response: true,
+                ..envelope
+            })
+        })
+    });
+    let config = HeartbeatConfig {
+        interval: Duration::from_millis(20),
+        timeout: Duration::from_millis(100),
+    };
+    let server = RemoteServer::bind("127.0.0.1:0", handler)
+        .await
+        .unwrap()
+        .heartbeat(config);
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    let ping = || Envelope::from_message(&Ping { message: "hi".to_string() }, 1, "client", "echo");
+    let plain = RemoteClient::new(TcpTransport.connect(&addr).await.unwrap());
+    let beating = RemoteClient::new(
+        HeartbeatTransport::new(TcpTransport, config)
+            .connect(&addr)
+            .await
+            .unwrap(),
+    );
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // the silent client was cut off, the heartbeating one kept its connection
+    assert!(plain.send(ping()).await.is_err());
+    assert!(beating.send(ping()).await.is_ok());
+}
+
+#[tokio::test]
+async fn heartbeat_recv_queues_what_it_owes_until_flushed() {
+    use cinema::remote::{HeartbeatConfig, HeartbeatConnection, HEARTBEAT};
+    use std::time::Duration;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let config = HeartbeatConfig {
+        interval: Duration::from_millis(20),
+        timeout: Duration::from_secs(5),
+    };
+    let mut conn = HeartbeatConnection::new(TcpTransport.connect(&addr).await.unwrap(), config);
+    let mut peer = TcpConnection::new(listener.accept().await.unwrap().0);
+
+    // the probe falls due inside recv, which hands back control instead of writing
+    let woken = conn.recv().await.unwrap();
+    assert_eq!(woken.message_type, HEARTBEAT);
+    assert!(woken.is_response);
+    let nothing = tokio::time::timeout(Duration::from_millis(50), peer.recv()).await;
+    assert!(nothing.is_err());
+
+    conn.flush().await.unwrap();
+    let probe = peer.recv().await.unwrap();
+    assert_eq!(probe.message_type, HEARTBEAT);
+    assert!(!probe.is_response);
+}
+
+#[tokio::test]
+async fn handshake_rejects_foreign_cluster_and_bad_token() {
+    use cinema::remote::{HandshakeConfig, HandshakeTransport, TransportError};
+
+    let echo = || -> EnvelopeHandler {
+        Arc::new(|envelope: Envelope| {
+            Box::pin(async move {
+                Some(Envelope {
+                    is_response: true,
+                    ..envelope
+                })
+            })
+        })
+    };
+    let server = RemoteServer::bind("127.0.0.1:0", echo())
+        .await
+        .unwrap()
+        .handshake(HandshakeConfig::new("server", "prod").token("s3cret"));
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    let ping = || Envelope::from_message(&Ping { message: "hi".to_string() }, 1, "client", "echo");
+    let connect = |config: HandshakeConfig| {
+        let addr = addr.clone();
+        async move { HandshakeTransport::new(TcpTransport, config).connect(&addr).await }
+    };
+
+    let conn = connect(HandshakeConfig::new("a", "prod").token("s3cret")).await.unwrap();
+    assert!(RemoteClient::new(conn).send(ping()).await.is_ok());
+
+    let res = connect(HandshakeConfig::new("b", "staging").token("s3cret")).await;
+    assert!(matches!(res, Err(TransportError::Handshake(reason)) if reason.contains("cluster 'staging'")));
+
+    let res = connect(HandshakeConfig::new("c", "prod")).await;
+    assert!(matches!(res, Err(TransportError::Handshake(reason)) if reason.contains("invalid token")));
+
+    // skipping the handshake gets the connection dropped
+    let plain = RemoteClient::new(TcpTransport.connect(&addr).await.unwrap());
+    assert!(plain.send(ping()).await.is_err());
+
+    // servers that don't require a handshake still answer one
+    let open = RemoteServer::bind("127.0.0.1:0", echo()).await.unwrap();
+    let open_addr = open.local_addr().unwrap().to_string();
+    tokio::spawn(open.run());
+    let conn = HandshakeTransport::new(TcpTransport, HandshakeConfig::new("d", "prod"))
+        .connect(&open_addr)
+        .await
+        .unwrap();
+    assert!(RemoteClient::new(conn).send(ping()).await.is_ok());
+}
+
+#[tokio::test]
+async fn codec_rejects_frames_over_max_size() {
+    let blob = |size: usize| Envelope {
+        message_type: "test::Blob".to_string(),
+        payload: vec![7; size],
+        ..Default::default()
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut conn = TcpConnection::new(stream).max_frame_size(1024);
+        conn.recv().await
+    });
+
+    // the receiver refuses them before buffering the announced length
+    let mut conn = TcpConnection::new(TcpStream::connect(addr).await.unwrap());
+    conn.send(blob(4096)).await.unwrap();
+    assert!(server.await.unwrap().is_err());
+
+    // and a limited sender doesn't write them in the first place
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+    let mut conn = TcpConnection::new(stream).max_frame_size(1024);
+    assert!(conn.send(blob(4096)).await.is_err());
+    assert!(conn.send(blob(512)).await.is_ok());
+}
+
+#[tokio::test]
+async fn chunked_payloads_pass_frame_limit() {
+    use cinema::remote::{ChunkConfig, ChunkedTransport};
+
+    let echo: EnvelopeHandler = Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            Some(Envelope {
+                is_response: true,
+                ..envelope
+            })
+        })
+    });
+    let chunks = ChunkConfig::default().chunk_size(16 * 1024);
+    let server = RemoteServer::bind("127.0.0.1:0", echo)
+        .await
+        .unwrap()
+        .max_frame_size(64 * 1024)
+        .chunking(chunks);
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    let state: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let request = || Envelope {
+        message_type: "test::State".to_string(),
+        payload: state.clone(),
+        ..Default::default()
+    };
+
+    // split into 16K fragments on the way there and back
+    let conn = ChunkedTransport::new(TcpTransport, chunks).connect(&addr).await.unwrap();
+    let response = RemoteClient::new(conn).send(request()).await.unwrap();
+    assert_eq!(response.payload, state);
+    assert!(response.chunk.is_none());
+
+    // in one piece it's over the server's limit and the connection is dropped
+    let plain = RemoteClient::new(TcpTransport.connect(&addr).await.unwrap());
+    assert!(plain.send(request()).await.is_err());
+}
+
+#[tokio::test]
+async fn reliable_tells_survive_lost_connection_and_slow_acks() {
+    use cinema::remote::{DeliveryConfig, ReconnectPolicy};
+    use std::{sync::Mutex, time::Duration};
+
+    struct Sink;
+
+    // records each ping, taking a while over the first one
+    let got = Arc::new(Mutex::new(Vec::new()));
+    let handler: EnvelopeHandler = {
+        let got = got.clone();
+        Arc::new(move |envelope: Envelope| {
+            let got = got.clone();
+            Box::pin(async move {
+                let ping = Ping::decode(envelope.payload.as_slice()).ok()?;
+                if got.lock().unwrap().is_empty() {
+                    tokio::time::sleep(Duration::from_millis(300)).await;
+                }
+                got.lock().unwrap().push(ping.message);
+                None
+            })
+        })
+    };
+
+    // the first connection swallows what it gets and hangs up, then the node comes back
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let server_addr = addr.clone();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let _ = TcpConnection::new(stream).recv().await;
+        drop(listener);
+        let server = RemoteServer::bind(&server_addr, handler).await.unwrap();
+        server.run().await;
+    });
+
+    let policy = ReconnectPolicy::new(Duration::from_millis(20), Duration::from_millis(100));
+    let client = RemoteClient::connect_with_reconnect(TcpTransport, &addr, policy)
+        .await
+        .unwrap();
+    let sink = client
+        .remote_addr::<Sink>("node", "sink")
+        .reliable(DeliveryConfig::default().ack_timeout(Duration::from_millis(100)).retry(policy));
+
+    for message in ["a", "b", "c"] {
+        sink.do_send(Ping { message: message.to_string() }).await.unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(5), sink.flush()).await.unwrap();
+
+    // each exactly once and in order, despite the drop and the resent first ping
+    assert_eq!(sink.unacked(), 0);
+    assert_eq!(*got.lock().unwrap(), vec!["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn reliable_tells_are_resent_until_the_handler_succeeds() {
+    use cinema::remote::{DeliveryConfig, ReconnectPolicy, HANDLER_FAILED};
+    use std::{sync::Mutex, time::Duration};
+
+    struct Sink;
+
+    // the first attempt fails, as if the actor had stopped
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+    let handler: EnvelopeHandler = {
+        let attempts = attempts.clone();
+        Arc::new(move |envelope: Envelope| {
+            let attempts = attempts.clone();
+            Box::pin(async move {
+                let ping = Ping::decode(envelope.payload.as_slice()).ok()?;
+                let mut attempts = attempts.lock().unwrap();
+                attempts.push(ping.message);
+                (attempts.len() == 1).then(|| Envelope {
+                    message_type: HANDLER_FAILED.to_string(),
+                    correlation_id: envelope.correlation_id,
+                    is_response: true,
+                    ..Default::default()
+                })
+            })
+        })
+    };
+    let server = RemoteServer::bind("127.0.0.1:0", handler).await.unwrap();
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    let policy = ReconnectPolicy::new(Duration::from_millis(20), Duration::from_millis(100));
+    let client = RemoteClient::new(TcpTransport.connect(&addr).await.unwrap());
+    let sink = client
+        .remote_addr::<Sink>("node", "sink")
+        .reliable(DeliveryConfig::default().retry(policy));
+
+    sink.do_send(Ping { message: "a".to_string() }).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(5), sink.flush()).await.unwrap();
+
+    // not acked until the handler went through
+    assert_eq!(*attempts.lock().unwrap(), vec!["a", "a"]);
+}
+
+#[tokio::test]
+async fn actor_ref_sends_the_same_way_locally_and_remotely() {
+    use cinema::remote::{ActorRef, SendError};
+
+    struct Counter {
+        count: i32,
+    }
+    impl Actor for Counter {}
+
+    #[derive(Clone, prost::Message)]
+    struct Add {
+        #[prost(int32, tag = "1")]
+        n: i32,
+    }
+    impl Message for Add {
+        type Result = Total;
+    }
+    impl RemoteMessage for Add {}
+
+    #[derive(Clone, prost::Message, cinema::MessageResponse)]
+    struct Total {
+        #[prost(int32, tag = "1")]
+        count: i32,
+    }
+    impl Message for Total {
+        type Result = ();
+    }
+    impl RemoteMessage for Total {}
+
+    impl Handler<Add> for Counter {
+        type Result = Total;
+
+        fn handle(&mut self, msg: Add, _ctx: &mut Context<Self>) -> Total {
+            self.count += msg.n;
+            Total { count: self.count }
+        }
+    }
+
+    // one code path for either kind of target
+    async fn add_twice(counter: &ActorRef<Counter>) -> Result<i32, SendError> {
+        counter.do_send(Add { n: 1 }).await?;
+        Ok(counter.send(Add { n: 2 }).await?.count)
+    }
+
+    let system = ActorSystem::new();
+    let local = system.spawn(Counter { count: 0 });
+    let served = system.spawn(Counter { count: 100 });
+
+    let handler = LocalNode::new("counter-node").handler::<Counter, Add>(served.clone());
+    let server = RemoteServer::bind("127.0.0.1:0", handler).await.unwrap();
+    let server_addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+
+    let conn = TcpTransport.connect(&server_addr.to_string()).await.unwrap();
+    let remote: RemoteAddr<Counter> =
+        LocalNode::new("client").remote_addr("counter-node", "counter", RemoteClient::new(conn));
+
+    let local = ActorRef::from(local);
+    let remote = ActorRef::from(remote);
+    assert!(local.is_local() && !remote.is_local());
+    assert_eq!(add_twice(&local).await.unwrap(), 3);
+    assert_eq!(add_twice(&remote).await.unwrap(), 103);
+
+    // a stopped local actor fails like an unreachable remote one would
+    cinema::address::ErasedAddr::from(local.local().unwrap().clone()).stop();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(matches!(
+        add_twice(&local).await,
+        Err(SendError::Mailbox(_))
+    ));
+}
+
+#[derive(Clone, Default)]
+struct RemoteRecorder(Arc<std::sync::Mutex<Vec<(String, cinema::RemoteEvent)>>>);
+
+impl RemoteRecorder {
+    fn events(&self, peer: &str) -> Vec<cinema::RemoteEvent> {
+        let seen = self.0.lock().unwrap();
+        seen.iter()
+            .filter(|(p, _)| p == peer)
+            .map(|(_, event)| *event)
+            .collect()
+    }
+}
+
+impl cinema::MetricsSink for RemoteRecorder {
+    fn record(&self, _metrics: &cinema::MessageMetrics<'_>) {}
+
+    fn record_remote(&self, metrics: &cinema::RemoteMetrics<'_>) {
+        self.0
+            .lock()
+            .unwrap()
+            .push((metrics.peer.to_string(), metrics.event));
+    }
+}
+
+#[tokio::test]
+async fn metered_connections_report_traffic_per_peer() {
+    use cinema::{remote::MeteredTransport, RemoteEvent};
+
+    let handler: EnvelopeHandler = Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            Some(Envelope {
+                correlation_id: envelope.correlation_id,
+                is_response: true,
+                ..envelope
+            })
+        })
+    });
+    let system = ActorSystem::new();
+    let recorder = RemoteRecorder::default();
+    system.set_metrics_sink(recorder.clone());
+
+    let server = RemoteServer::bind("127.0.0.1:0", handler)
+        .await
+        .unwrap()
+        .metrics(&system);
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    register_message::<Ping>();
+    let transport = MeteredTransport::new(TcpTransport, &system);
+    let conn = transport.connect(&addr).await.unwrap();
+    let local = conn.local_addr().to_string();
+    let remote: RemoteAddr<()> =
+        RemoteAddr::new("client", "server-node", "echo", RemoteClient::new(conn));
+    for _ in 0..2 {
+        remote
+            .send(Ping {
+                message: "metered".to_string(),
+            })
+            .await
+            .unwrap();
+    }
+
+    let client_side = recorder.events(&addr);
+    let sent = |events: &[RemoteEvent]| {
+        events
+            .iter()
+            .filter(|e| matches!(e, RemoteEvent::Sent { bytes } if *bytes > 0))
+            .count()
+    };
+    let received = |events: &[RemoteEvent]| {
+        events
+            .iter()
+            .filter(|e| matches!(e, RemoteEvent::Received { .. }))
+            .count()
+    };
+    assert_eq!(sent(&client_side), 2);
+    assert_eq!(received(&client_side), 2);
+    let round_trips = client_side
+        .iter()
+        .filter(|e| matches!(e, RemoteEvent::RoundTrip(_)))
+        .count();
+    assert_eq!(round_trips, 2);
+
+    // the server sees the same traffic the other way, from the client's address
+    // (its last send may be recorded just after the answer arrived)
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let server_side = recorder.events(&local);
+    assert_eq!(received(&server_side), 2);
+    assert_eq!(sent(&server_side), 2);
+
+    // connecting to the same address again counts as a reconnect
+    assert!(!client_side.contains(&RemoteEvent::Reconnected));
+    let _again = transport.connect(&addr).await.unwrap();
+    assert!(recorder.events(&addr).contains(&RemoteEvent::Reconnected));
+}
+
+#[tokio::test]
+async fn requests_past_their_deadline_are_answered_with_deadline_exceeded() {
+    use cinema::{remote::TransportError, ResponseFuture};
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    };
+
+    struct Worker {
+        started: Arc<AtomicUsize>,
+    }
+    impl Actor for Worker {}
+
+    #[derive(Clone, prost::Message)]
+    struct Work {
+        #[prost(uint64, tag = "1")]
+        millis: u64,
+    }
+    impl Message for Work {
+        type Result = Done;
+    }
+    impl RemoteMessage for Work {}
+
+    #[derive(Clone, prost::Message, cinema::MessageResponse)]
+    struct Done {}
+    impl Message for Done {
+        type Result = ();
+    }
+    impl RemoteMessage for Done {}
+
+    impl Handler<Work> for Worker {
+        type Result = ResponseFuture<Done>;
+
+        fn handle(&mut self, msg: Work, _ctx: &mut Context<Self>) -> Self::Result {
+            self.started.fetch_add(1, Ordering::SeqCst);
+            ResponseFuture::new(async move {
+                tokio::time::sleep(Duration::from_millis(msg.millis)).await;
+                Done {}
+            })
+        }
+    }
+
+    let system = ActorSystem::new();
+    let started = Arc::new(AtomicUsize::new(0));
+    let worker = system.spawn(Worker {
+        started: started.clone(),
+    });
+    let handler = LocalNode::new("worker-node").handler::<Worker, Work>(worker);
+    let server = RemoteServer::bind("127.0.0.1:0", handler).await.unwrap();
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+
+    let client = RemoteClient::new(TcpTransport.connect(&addr.to_string()).await.unwrap());
+
+    // already expired on arrival, the actor never sees it
+    let mut expired = Envelope::from_message(&Work { millis: 0 }, 1, "client", "worker");
+    expired.deadline_ms = 1;
+    assert!(matches!(
+        client.send(expired).await,
+        Err(TransportError::DeadlineExceeded)
+    ));
+    assert_eq!(started.load(Ordering::SeqCst), 0);
+
+    // runs out while the actor works, the node answers when time is up
+    let mut slow = Envelope::from_message(&Work { millis: 2000 }, 2, "client", "worker");
+    slow.set_timeout(Duration::from_millis(100));
+    let start = Instant::now();
+    assert!(matches!(
+        client.send(slow).await,
+        Err(TransportError::DeadlineExceeded)
+    ));
+    assert!(start.elapsed() < Duration::from_millis(1000));
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+
+    // send_timeout hands its deadline to the node, met ones are answered as usual
+    let remote: RemoteAddr<Worker> = client.remote_addr("worker-node", "worker");
+    assert!(remote
+        .send_timeout(Work { millis: 0 }, Duration::from_secs(2))
+        .await
+        .is_ok());
+}
+
+/// Test: expose routes an actor by name, remote_listener serves it until shutdown
+#[tokio::test]
+async fn system_exposes_actor_and_serves_it() {
+    use std::time::Duration;
+
+    struct Calculator {
+        value: i32,
+    }
+    impl Actor for Calculator {}
+
+    #[derive(Clone, prost::Message)]
+    struct Add {
+        #[prost(int32, tag = "1")]
+        n: i32,
+    }
+    impl Message for Add {
+        type Result = AddResult;
+    }
+    impl RemoteMessage for Add {}
+
+    #[derive(Clone, prost::Message, cinema::MessageResponse)]
+    struct AddResult {
+        #[prost(int32, tag = "1")]
+        value: i32,
+    }
+    impl Message for AddResult {
+        type Result = ();
+    }
+    impl RemoteMessage for AddResult {}
+
+    impl Handler<Add> for Calculator {
+        type Result = AddResult;
+
+        fn handle(&mut self, msg: Add, _ctx: &mut Context<Self>) -> AddResult {
+            self.value += msg.n;
+            AddResult { value: self.value }
+        }
+    }
+
+    let system = ActorSystem::new();
+    let calc = system.spawn(Calculator { value: 10 });
+    let server_addr = system.remote_listener("127.0.0.1:0").await.unwrap();
+    // routes added after the listener started are served too
+    system.expose::<Add, _>("calculator", calc.clone());
+
+    let conn = TcpTransport.connect(&server_addr.to_string()).await.unwrap();
+    let client = RemoteClient::new(conn);
+    let remote: RemoteAddr<Calculator> = client.remote_addr("cinema", "calculator");
+    let response = remote.send(Add { n: 5 }).await.unwrap();
+    assert_eq!(AddResult::decode(response.payload.as_slice()).unwrap().value, 15);
+
+    // a type the actor was not exposed for reaches its unhandled hook
+    let untyped: RemoteAddr<()> = client.remote_addr("cinema", "calculator");
+    untyped
+        .do_send(Ping {
+            message: "not exposed".to_string(),
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(calc.unhandled_messages(), 1);
+
+    system.shutdown();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(TcpTransport
+        .connect(&server_addr.to_string())
+        .await
+        .is_err());
+}
+
+/// Test: failures on the remote side come back as distinct errors instead of timeouts
+#[tokio::test]
+async fn remote_failures_are_told_apart() {
+    use cinema::remote::TransportError;
+    use std::error::Error as _;
+
+    struct Echo;
+    impl Actor for Echo {}
+
+    #[derive(Clone, PartialEq, prost::Message, cinema::MessageResponse)]
+    struct Say {
+        #[prost(string, tag = "1")]
+        text: String,
+    }
+    impl Message for Say {
+        type Result = Say;
+    }
+    impl RemoteMessage for Say {}
+    impl Handler<Say> for Echo {
+        type Result = Say;
+
+        fn handle(&mut self, msg: Say, _ctx: &mut Context<Self>) -> Say {
+            msg
+        }
+    }
+
+    let system = ActorSystem::new();
+    let echo = system.spawn(Echo);
+    system.expose::<Say, _>("echo", echo.clone());
+    let server_addr = system.remote_listener("127.0.0.1:0").await.unwrap();
+    let client = RemoteClient::new(TcpTransport.connect(&server_addr.to_string()).await.unwrap());
+    let remote: RemoteAddr<Echo> = client.remote_addr("cinema", "echo");
+    let say = || Say {
+        text: "hi".to_string(),
+    };
+
+    // no route for the type
+    let err = remote
+        .send(Ping {
+            message: "unrouted".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(&err, TransportError::UnknownMessageType(reason) if reason.contains("Ping")));
+    let err = cinema::Error::from(err);
+    assert!(matches!(err, cinema::Error::UnknownMessageType(_)));
+    assert!(!err.is_retryable());
+
+    // a payload the node can't decode
+    let mut garbled = Envelope::from_message(&say(), 1, "client", "echo");
+    garbled.payload = vec![0xff; 4];
+    let err = cinema::Error::from(client.send(garbled).await.unwrap_err());
+    assert!(matches!(err, cinema::Error::Serialization(_)));
+    assert!(err.source().is_some());
+
+    // the actor has stopped
+    assert_eq!(remote.send(say()).await.unwrap().message::<Say>().unwrap(), say());
+    echo.stop_graceful().await;
+    let err = remote.send(say()).await.unwrap_err();
+    assert!(matches!(&err, TransportError::Remote(reason) if reason.contains("closed")));
+    assert!(matches!(cinema::Error::from(err), cinema::Error::RemoteHandler(_)));
+
+    // local mailbox and connection errors keep their cause
+    let err = cinema::Error::from(cinema::MailboxError::MailboxFull);
+    assert!(err.is_retryable());
+    assert_eq!(err.source().unwrap().to_string(), "Actor's mailbox is full");
+    let err = cinema::Error::from(TransportError::Disconnected);
+    assert!(matches!(err, cinema::Error::Transport(_)) && err.is_retryable());
+}
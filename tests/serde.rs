@@ -0,0 +1,139 @@
+use cinema::{
+    actor::ActorId,
+    remote::{
+        codec, deserialize_envelope, make_handler, proto::Envelope, register_message, Bincode,
+        Json, RemoteClient, RemoteMessage, RemoteServer, Serializer, TcpTransport, Transport,
+        WireFormat,
+    },
+    Actor, ActorSystem, Context, Handler, Message,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Deposit {
+    account: String,
+    amount: u64,
+}
+
+impl Message for Deposit {
+    type Result = Balance;
+}
+
+impl WireFormat for Deposit {
+    type Serializer = Bincode;
+}
+
+impl RemoteMessage for Deposit {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, cinema::MessageResponse)]
+struct Balance {
+    total: u64,
+}
+
+impl Message for Balance {
+    type Result = ();
+}
+
+impl WireFormat for Balance {
+    type Serializer = Json;
+}
+
+impl RemoteMessage for Balance {}
+
+struct Account {
+    total: u64,
+}
+
+impl Actor for Account {}
+
+impl Handler<Deposit> for Account {
+    type Result = Balance;
+
+    fn handle(&mut self, msg: Deposit, _ctx: &mut Context<Self>) -> Balance {
+        self.total += msg.amount;
+        Balance { total: self.total }
+    }
+}
+
+#[test]
+fn envelope_carries_serializer_codec() {
+    let deposit = Deposit {
+        account: "alice".to_string(),
+        amount: 5,
+    };
+    let envelope = Envelope::from_message(&deposit, 1, "node", "account");
+    assert_eq!(envelope.codec, codec::BINCODE);
+
+    let decoded = Envelope::from_bytes(&envelope.to_bytes()).unwrap();
+    assert_eq!(decoded.message::<Deposit>().unwrap(), deposit);
+
+    let balance = Envelope::from_message(&Balance { total: 7 }, 2, "node", "account");
+    assert_eq!(balance.codec, codec::JSON);
+    assert_eq!(balance.payload, br#"{"total":7}"#);
+}
+
+#[test]
+fn actor_id_round_trips_through_serde() {
+    let id = ActorId::parse("node-a@/3/7#worker").unwrap();
+    let bytes = <Json as Serializer<ActorId>>::encode(&id);
+    let decoded = <Json as Serializer<ActorId>>::decode(&bytes).unwrap();
+
+    assert_eq!(decoded, id);
+    assert_eq!(decoded.to_string(), "node-a@/3/7#worker");
+    assert_eq!(decoded.created_at(), id.created_at());
+}
+
+#[test]
+fn decodes_payload_written_by_other_serde_codec() {
+    //a node still on JSON talking to one that switched to bincode
+    let envelope = Envelope {
+        message_type: Deposit::type_id().to_string(),
+        payload: serde_json::to_vec(&Deposit {
+            account: "bob".to_string(),
+            amount: 3,
+        })
+        .unwrap(),
+        codec: codec::JSON,
+        ..Default::default()
+    };
+    assert_eq!(envelope.message::<Deposit>().unwrap().amount, 3);
+
+    register_message::<Deposit>();
+    let msg = deserialize_envelope(&envelope).unwrap();
+    assert_eq!(msg.downcast_ref::<Deposit>().unwrap().account, "bob");
+
+    let protobuf = Envelope {
+        codec: codec::PROST,
+        ..envelope
+    };
+    assert!(protobuf.message::<Deposit>().is_err());
+}
+
+#[tokio::test]
+async fn serde_messages_round_trip_through_server() {
+    let system = ActorSystem::new();
+    let account = system.spawn(Account { total: 10 });
+
+    let handler = make_handler::<Account, Deposit>(account, "bank");
+    let server = RemoteServer::bind("127.0.0.1:0", handler).await.unwrap();
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    let conn = TcpTransport.connect(&addr).await.unwrap();
+    let client = RemoteClient::new(conn);
+    let remote = client.remote_addr::<Account>("bank", "account");
+
+    let response = remote
+        .send(Deposit {
+            account: "alice".to_string(),
+            amount: 5,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.codec, codec::JSON);
+    assert_eq!(
+        response.message::<Balance>().unwrap(),
+        Balance { total: 15 }
+    );
+}
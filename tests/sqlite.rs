@@ -0,0 +1,184 @@
+use std::{path::PathBuf, sync::Arc};
+
+use cinema::{
+    persistence::{
+        Journal, PersistedEvent, PersistedSnapshot, PersistenceOptions, Snapshot, SnapshotStore,
+        SqliteJournal,
+    },
+    Actor, ActorSystem, Context, Handler, Message, PersistentActor,
+};
+use prost::Message as ProstMessage;
+
+///a database file of its own per test, removed when dropped
+struct TempDb(PathBuf);
+
+impl TempDb {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("cinema-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Self(path)
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[derive(Clone, PartialEq, ProstMessage)]
+struct Added {
+    #[prost(uint64, tag = "1")]
+    n: u64,
+}
+
+#[derive(Clone, PartialEq, ProstMessage)]
+struct Total {
+    #[prost(uint64, tag = "1")]
+    total: u64,
+}
+
+struct Counter {
+    total: u64,
+    replayed: u64,
+}
+
+impl Actor for Counter {}
+
+impl PersistentActor for Counter {
+    type Event = Added;
+
+    fn persistence_id(&self) -> String {
+        "counter".to_string()
+    }
+
+    fn recover(&mut self, event: Added, _ctx: &mut Context<Self>) {
+        self.total += event.n;
+        self.replayed += 1;
+    }
+
+    fn snapshot(&self) -> Option<Snapshot> {
+        Some(Snapshot::new(&Total { total: self.total }))
+    }
+
+    fn recover_snapshot(
+        &mut self,
+        snapshot: Snapshot,
+        _ctx: &mut Context<Self>,
+    ) -> std::io::Result<()> {
+        self.total = snapshot.state::<Total>()?.total;
+        Ok(())
+    }
+}
+
+struct Add(u64);
+
+impl Message for Add {
+    type Result = ();
+}
+
+impl Handler<Add> for Counter {
+    type Result = ();
+
+    fn handle(&mut self, msg: Add, ctx: &mut Context<Self>) {
+        ctx.persist(Added { n: msg.0 }, |counter, event| {
+            counter.total += event.n
+        })
+        .unwrap();
+    }
+}
+
+struct Get;
+
+impl Message for Get {
+    type Result = (u64, u64);
+}
+
+impl Handler<Get> for Counter {
+    type Result = (u64, u64);
+
+    fn handle(&mut self, _msg: Get, _ctx: &mut Context<Self>) -> (u64, u64) {
+        (self.total, self.replayed)
+    }
+}
+
+fn counter() -> Counter {
+    Counter {
+        total: 0,
+        replayed: 0,
+    }
+}
+
+#[tokio::test]
+async fn state_survives_reopening_the_database() {
+    let db = TempDb::new("reopen");
+    let system = ActorSystem::new();
+
+    {
+        let store = Arc::new(SqliteJournal::open(&db.0).unwrap());
+        let options = PersistenceOptions::new(store.clone())
+            .snapshots(store)
+            .snapshot_every(2);
+        let counter = system.spawn_persistent(counter(), options);
+        for n in 1..=5 {
+            counter.send(Add(n)).await.unwrap();
+        }
+        assert_eq!(counter.send(Get).await.unwrap(), (15, 0));
+    }
+
+    let store = Arc::new(SqliteJournal::open(&db.0).unwrap());
+    assert_eq!(store.highest_sequence("counter").unwrap(), 5);
+    assert_eq!(store.load("counter").unwrap().unwrap().sequence, 4);
+
+    let options = PersistenceOptions::new(store.clone()).snapshots(store);
+    let counter = system.spawn_persistent(counter(), options);
+    // the snapshot at 4, then event 5
+    assert_eq!(counter.send(Get).await.unwrap(), (15, 1));
+}
+
+#[test]
+fn sqlite_journal_keeps_histories_in_order() {
+    let journal = SqliteJournal::in_memory().unwrap();
+    let event = |id: &str, sequence| PersistedEvent {
+        persistence_id: id.to_string(),
+        sequence,
+        codec: 0,
+        payload: vec![sequence as u8],
+    };
+
+    journal.append(event("a", 1)).unwrap();
+    journal.append(event("b", 1)).unwrap();
+    journal.append(event("a", 2)).unwrap();
+    let err = journal.append(event("a", 2)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert!(journal.append(event("b", 3)).is_err());
+
+    assert_eq!(
+        journal.replay("a", 1).unwrap(),
+        vec![event("a", 1), event("a", 2)]
+    );
+    assert_eq!(journal.replay("a", 2).unwrap(), vec![event("a", 2)]);
+    assert!(journal.replay("c", 1).unwrap().is_empty());
+    assert_eq!(journal.highest_sequence("b").unwrap(), 1);
+    assert_eq!(journal.highest_sequence("c").unwrap(), 0);
+}
+
+#[test]
+fn sqlite_snapshots_keep_the_latest() {
+    let store = SqliteJournal::in_memory().unwrap();
+    let snapshot = |sequence| PersistedSnapshot {
+        persistence_id: "a".to_string(),
+        sequence,
+        snapshot: Snapshot {
+            codec: 0,
+            payload: vec![sequence as u8],
+        },
+    };
+
+    assert!(store.load("a").unwrap().is_none());
+    store.save(snapshot(3)).unwrap();
+    store.save(snapshot(5)).unwrap();
+    // a late save of an older state is ignored
+    store.save(snapshot(4)).unwrap();
+    assert_eq!(store.load("a").unwrap(), Some(snapshot(5)));
+}
@@ -7,8 +7,13 @@ use std::{
 };
 
 use cinema::{
-    address::ChildHandle, message::Terminated, Actor, ActorSystem, Addr, Context, Handler, Message,
-    SupervisorStrategy,
+    address::ChildHandle,
+    mailbox::{OverflowStrategy, DEFAULT_CAPACITY},
+    message::Terminated,
+    supervisor::{BackoffPolicy, GuardianPolicy},
+    actor::ActorId,
+    Actor, ActorSystem, ActorSystemConfig, Addr, ChildSpec, Context, FailureInfo, Handler, Message,
+    PanicPolicy, SpawnOptions, SupervisionMode, Supervisor, SupervisorDirective, SupervisorStrategy,
 };
 
 // ======== Panic Handling Tests ========
@@ -35,11 +40,15 @@ impl Actor for CrashActor {
 }
 
 impl Handler<Crash> for CrashActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
         panic!("Intentional crash!");
     }
 }
 impl Handler<Ping> for CrashActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) {
         // Do nothing
     }
@@ -99,6 +108,8 @@ struct Worker;
 impl Actor for Worker {}
 
 impl Handler<Die> for Worker {
+    type Result = ();
+
     fn handle(&mut self, _msg: Die, ctx: &mut Context<Self>) {
         ctx.stop();
     }
@@ -123,6 +134,8 @@ impl Actor for Monitor {
 }
 
 impl Handler<SetWorker> for Monitor {
+    type Result = ();
+
     fn handle(&mut self, msg: SetWorker, ctx: &mut Context<Self>) {
         self.worker_addr = Some(msg.0);
         ctx.watch(self.worker_addr.as_ref().unwrap());
@@ -130,6 +143,8 @@ impl Handler<SetWorker> for Monitor {
 }
 
 impl Handler<Terminated> for Monitor {
+    type Result = ();
+
     fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {
         self.worker_died.store(true, Ordering::SeqCst);
     }
@@ -157,6 +172,69 @@ async fn watch_notifies_on_death() {
     assert!(worker_died.load(Ordering::SeqCst));
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Primary,
+    Backup,
+}
+
+struct WorkerDown {
+    which: Side,
+}
+impl Message for WorkerDown {
+    type Result = ();
+}
+
+struct PairMonitor {
+    primary: Addr<Worker>,
+    backup: Addr<Worker>,
+    downs: Arc<std::sync::Mutex<Vec<Side>>>,
+}
+
+impl Actor for PairMonitor {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        ctx.watch_with(&self.primary, |_| WorkerDown {
+            which: Side::Primary,
+        });
+        ctx.watch_with(&self.backup, |_| WorkerDown {
+            which: Side::Backup,
+        });
+    }
+}
+
+impl Handler<WorkerDown> for PairMonitor {
+    type Result = ();
+
+    fn handle(&mut self, msg: WorkerDown, _ctx: &mut Context<Self>) {
+        self.downs.lock().unwrap().push(msg.which);
+    }
+}
+
+#[tokio::test]
+async fn watch_with_maps_termination() {
+    let downs = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let sys = ActorSystem::new();
+    let primary = sys.spawn(Worker);
+    let backup = sys.spawn(Worker);
+
+    let _monitor = sys.spawn(PairMonitor {
+        primary: primary.clone(),
+        backup: backup.clone(),
+        downs: downs.clone(),
+    });
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    backup.do_send(Die).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(*downs.lock().unwrap(), vec![Side::Backup]);
+
+    primary.do_send(Die).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(*downs.lock().unwrap(), vec![Side::Backup, Side::Primary]);
+}
+
 // ======== Child Actor Tests ========
 
 ///parent stopping kills child actors
@@ -176,6 +254,8 @@ async fn parent_stopping_stops_children() {
     }
 
     impl Handler<Terminated> for Parent {
+        type Result = ();
+
         fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
     }
 
@@ -211,6 +291,8 @@ async fn child_stopping_notifies_parent() {
     }
 
     impl Handler<DieMsg> for Child {
+        type Result = ();
+
         fn handle(&mut self, _msg: DieMsg, ctx: &mut Context<Self>) {
             ctx.stop();
         }
@@ -228,6 +310,8 @@ async fn child_stopping_notifies_parent() {
     }
 
     impl Handler<Terminated> for Parent {
+        type Result = ();
+
         fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {
             PARENT_NOTIFIED.store(true, Ordering::SeqCst);
         }
@@ -239,6 +323,8 @@ async fn child_stopping_notifies_parent() {
         type Result = ();
     }
     impl Handler<StopChild> for Parent {
+        type Result = ();
+
         fn handle(&mut self, _msg: StopChild, _ctx: &mut Context<Self>) {
             if let Some(child) = &self.child_addr {
                 let _ = child.try_send(DieMsg);
@@ -281,6 +367,8 @@ async fn actor_restarts_on_panic() {
     }
 
     impl Handler<CrashMsg> for RestartableWorker {
+        type Result = ();
+
         fn handle(&mut self, _msg: CrashMsg, _ctx: &mut Context<Self>) {
             panic!("Intentional crash for restart test");
         }
@@ -290,6 +378,8 @@ async fn actor_restarts_on_panic() {
     impl Actor for Supervisor {}
 
     impl Handler<Terminated> for Supervisor {
+        type Result = ();
+
         fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
     }
 
@@ -299,6 +389,8 @@ async fn actor_restarts_on_panic() {
     }
 
     impl Handler<SpawnWorker> for Supervisor {
+        type Result = Addr<RestartableWorker>;
+
         fn handle(
             &mut self,
             _msg: SpawnWorker,
@@ -345,6 +437,8 @@ async fn actor_stops_after_max_restarts() {
     }
 
     impl Handler<CrashMsg> for FragileWorker {
+        type Result = ();
+
         fn handle(&mut self, _msg: CrashMsg, _ctx: &mut Context<Self>) {
             panic!("Intentional crash for restart limit test");
         }
@@ -353,6 +447,8 @@ async fn actor_stops_after_max_restarts() {
     struct Supervisor;
     impl Actor for Supervisor {}
     impl Handler<Terminated> for Supervisor {
+        type Result = ();
+
         fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {
             TERMINATED_RECEIVED.store(true, Ordering::SeqCst);
         }
@@ -364,6 +460,8 @@ async fn actor_stops_after_max_restarts() {
     }
 
     impl Handler<SpawnFragileWorker> for Supervisor {
+        type Result = Addr<FragileWorker>;
+
         fn handle(
             &mut self,
             _msg: SpawnFragileWorker,
@@ -402,121 +500,1308 @@ async fn actor_stops_after_max_restarts() {
     assert!(!worker.is_alive(), "Worker should be dead");
 }
 
-// ======== Escalate Strategy Tests ========
-
-/// child panic causes parent to "panic",
-/// which triggers grandparent's restart strategy
+///messages queued behind the crash are handled by the rebuilt child
 #[tokio::test]
-async fn escalate_triggers_parent_restart() {
-    // Track how many times Parent gets started (should be 2: initial + 1 restart)
-    static PARENT_START_COUNT: AtomicU32 = AtomicU32::new(0);
-    // Track how many times Child gets started
-    static CHILD_START_COUNT: AtomicU32 = AtomicU32::new(0);
+async fn restarted_child_handles_queued_messages() {
+    static HANDLED: AtomicU32 = AtomicU32::new(0);
+    static BUILT: AtomicU32 = AtomicU32::new(0);
 
-    // Grandchild that panics
-    struct Grandchild;
-    impl Actor for Grandchild {
-        fn started(&mut self, _ctx: &mut Context<Self>) {
-            CHILD_START_COUNT.fetch_add(1, Ordering::SeqCst);
+    struct Counter;
+    impl Actor for Counter {}
+
+    impl Handler<Crash> for Counter {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+            panic!("Intentional crash with messages queued");
         }
     }
 
-    struct CrashMsg;
-    impl Message for CrashMsg {
+    impl Handler<Ping> for Counter {
         type Result = ();
-    }
 
-    impl Handler<CrashMsg> for Grandchild {
-        fn handle(&mut self, _msg: CrashMsg, _ctx: &mut Context<Self>) {
-            panic!("Grandchild crash to test escalate!");
+        fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) {
+            HANDLED.fetch_add(1, Ordering::SeqCst);
         }
     }
 
-    // Parent spawns Grandchild with Escalate strategy
-    struct Parent {
-        grandchild: Option<Addr<Grandchild>>,
+    struct Owner {
+        child: Option<Addr<Counter>>,
     }
 
-    impl Actor for Parent {
+    impl Actor for Owner {
         fn started(&mut self, ctx: &mut Context<Self>) {
-            PARENT_START_COUNT.fetch_add(1, Ordering::SeqCst);
-            // Parent spawns grandchild with Escalate strategy
-            self.grandchild =
-                Some(ctx.spawn_child_with_strategy(|| Grandchild, SupervisorStrategy::Escalate));
+            let child = ctx.spawn_child_with_strategy(
+                || {
+                    BUILT.fetch_add(1, Ordering::SeqCst);
+                    Counter
+                },
+                SupervisorStrategy::restart(3, Duration::from_secs(10)),
+            );
+            // crash first, the pings wait in the mailbox
+            child.try_send(Crash).unwrap();
+            child.try_send(Ping).unwrap();
+            child.try_send(Ping).unwrap();
+            self.child = Some(child);
         }
     }
 
-    impl Handler<Terminated> for Parent {
+    impl Handler<Terminated> for Owner {
+        type Result = ();
+
         fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
     }
 
-    // Message to crash the grandchild via parent
-    struct CrashGrandchild;
-    impl Message for CrashGrandchild {
+    let sys = ActorSystem::new();
+    let _owner = sys.spawn(Owner { child: None });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(BUILT.load(Ordering::SeqCst), 2);
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 2);
+}
+
+///restarts keep the Addr, registered name and watchers attached to the new instance
+#[tokio::test]
+async fn restart_keeps_address_stable() {
+    static STARTS: AtomicU32 = AtomicU32::new(0);
+    static TERMINATIONS: AtomicU32 = AtomicU32::new(0);
+
+    struct Stable {
+        generation: u32,
+    }
+
+    impl Actor for Stable {}
+
+    impl Handler<Crash> for Stable {
         type Result = ();
+
+        fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+            panic!("Intentional crash for stable address test");
+        }
     }
 
-    impl Handler<CrashGrandchild> for Parent {
-        fn handle(&mut self, _msg: CrashGrandchild, _ctx: &mut Context<Self>) {
-            if let Some(ref gc) = self.grandchild {
-                let _ = gc.try_send(CrashMsg);
-            }
+    impl Handler<Die> for Stable {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Die, ctx: &mut Context<Self>) {
+            ctx.stop();
         }
     }
 
-    // Grandparent spawns Parent with Restart strategy
-    struct Grandparent;
-    impl Actor for Grandparent {}
+    struct Generation;
+    impl Message for Generation {
+        type Result = u32;
+    }
 
-    impl Handler<Terminated> for Grandparent {
-        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    impl Handler<Generation> for Stable {
+        type Result = u32;
+
+        fn handle(&mut self, _msg: Generation, _ctx: &mut Context<Self>) -> u32 {
+            self.generation
+        }
     }
 
-    struct SpawnParent;
-    impl Message for SpawnParent {
-        type Result = Addr<Parent>;
+    struct Watchdog(Addr<Stable>);
+
+    impl Actor for Watchdog {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            ctx.watch(&self.0);
+        }
     }
 
-    impl Handler<SpawnParent> for Grandparent {
-        fn handle(&mut self, _msg: SpawnParent, ctx: &mut Context<Self>) -> Addr<Parent> {
-            ctx.spawn_child_with_strategy(
-                || Parent { grandchild: None },
-                SupervisorStrategy::restart(3, Duration::from_secs(10)),
-            )
+    impl Handler<Terminated> for Watchdog {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {
+            TERMINATIONS.fetch_add(1, Ordering::SeqCst);
         }
     }
 
     let sys = ActorSystem::new();
-    let grandparent = sys.spawn(Grandparent);
+    let addr = sys.spawn_with_strategy(
+        || Stable {
+            generation: STARTS.fetch_add(1, Ordering::SeqCst) + 1,
+        },
+        SupervisorStrategy::restart(3, Duration::from_secs(10)),
+    );
+    sys.register("stable", addr.clone());
+    let _watchdog = sys.spawn(Watchdog(addr.clone()));
+    tokio::time::sleep(Duration::from_millis(10)).await;
 
-    let parent = grandparent.send(SpawnParent).await.unwrap();
+    assert_eq!(addr.send(Generation).await, Ok(1));
 
+    addr.do_send(Crash).await.unwrap();
     tokio::time::sleep(Duration::from_millis(50)).await;
 
-    assert_eq!(
-        PARENT_START_COUNT.load(Ordering::SeqCst),
-        1,
-        "Parent should have started once"
+    // same handle, new instance behind it
+    assert!(addr.is_alive());
+    assert_eq!(addr.send(Generation).await, Ok(2));
+    let looked_up = sys.lookup::<Stable>("stable").unwrap();
+    assert_eq!(looked_up.id(), addr.id());
+    assert_eq!(looked_up.send(Generation).await, Ok(2));
+
+    // watchers don't see a restart as a death
+    assert_eq!(TERMINATIONS.load(Ordering::SeqCst), 0);
+
+    addr.do_send(Die).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(TERMINATIONS.load(Ordering::SeqCst), 1);
+    assert!(sys.lookup::<Stable>("stable").is_none());
+}
+
+// ======== Backoff Strategy Tests ========
+
+#[test]
+fn backoff_delay_grows_and_caps() {
+    let policy = BackoffPolicy::new(Duration::from_millis(10), Duration::from_millis(100));
+
+    assert_eq!(policy.delay(1), Duration::from_millis(10));
+    assert_eq!(policy.delay(2), Duration::from_millis(20));
+    assert_eq!(policy.delay(3), Duration::from_millis(40));
+    assert_eq!(policy.delay(5), Duration::from_millis(100));
+    assert_eq!(policy.multiplier(3.0).delay(2), Duration::from_millis(30));
+}
+
+struct Looping;
+impl Actor for Looping {}
+
+impl Handler<Crash> for Looping {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+        panic!("Intentional crash loop");
+    }
+}
+
+#[tokio::test]
+async fn backoff_spaces_out_restarts() {
+    static BUILT: AtomicU32 = AtomicU32::new(0);
+
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_strategy(
+        || {
+            BUILT.fetch_add(1, Ordering::SeqCst);
+            Looping
+        },
+        SupervisorStrategy::backoff(BackoffPolicy::new(
+            Duration::from_millis(50),
+            Duration::from_secs(1),
+        )),
+    );
+
+    addr.do_send(Crash).await.unwrap();
+    addr.do_send(Crash).await.unwrap();
+
+    // first restart waits 50ms, the second 100ms
+    tokio::time::sleep(Duration::from_millis(25)).await;
+    assert_eq!(BUILT.load(Ordering::SeqCst), 1);
+    tokio::time::sleep(Duration::from_millis(75)).await;
+    assert_eq!(BUILT.load(Ordering::SeqCst), 2);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(BUILT.load(Ordering::SeqCst), 3);
+}
+
+///mail sent while the actor waits out its backoff is queued for the next instance
+#[tokio::test]
+async fn messages_sent_mid_restart_reach_the_new_instance() {
+    static BUILT: AtomicU32 = AtomicU32::new(0);
+    static HANDLED: AtomicU32 = AtomicU32::new(0);
+
+    struct Patient;
+    impl Actor for Patient {}
+
+    impl Handler<Crash> for Patient {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+            panic!("Intentional crash before a backoff");
+        }
+    }
+
+    impl Handler<Ping> for Patient {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) {
+            HANDLED.fetch_add(BUILT.load(Ordering::SeqCst), Ordering::SeqCst);
+        }
+    }
+
+    let sys = ActorSystem::new();
+    let addr = sys.spawn_with_strategy(
+        || {
+            BUILT.fetch_add(1, Ordering::SeqCst);
+            Patient
+        },
+        SupervisorStrategy::backoff(BackoffPolicy::new(
+            Duration::from_millis(50),
+            Duration::from_secs(1),
+        )),
     );
+    let id = addr.id();
+    addr.do_send(Crash).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    // between instances: the address still resolves and accepts mail
+    assert_eq!(BUILT.load(Ordering::SeqCst), 1);
+    assert!(addr.is_alive());
+    assert!(sys.resolve(id).is_some());
+    addr.do_send(Ping).await.unwrap();
+    addr.try_send(Ping).unwrap();
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 0);
+
+    // both pings are handled by the second instance, under the same id
+    addr.send(Ping).await.unwrap();
+    assert_eq!(BUILT.load(Ordering::SeqCst), 2);
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 6);
+    assert_eq!(addr.id(), id);
+}
+
+#[tokio::test]
+async fn backoff_gives_up_with_distinct_reason() {
+    use cinema::message::TerminationReason;
+    use std::sync::Mutex;
+
+    struct Overseer {
+        reason: Arc<Mutex<Option<TerminationReason>>>,
+        child: Option<Addr<Looping>>,
+    }
+
+    impl Actor for Overseer {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            let child = ctx.spawn_child_with_strategy(
+                || Looping,
+                SupervisorStrategy::backoff(
+                    BackoffPolicy::new(Duration::from_millis(5), Duration::from_millis(20))
+                        .max_restarts(1, Duration::from_secs(10)),
+                ),
+            );
+            child.try_send(Crash).unwrap();
+            child.try_send(Crash).unwrap();
+            self.child = Some(child);
+        }
+    }
+
+    impl Handler<Terminated> for Overseer {
+        type Result = ();
+
+        fn handle(&mut self, msg: Terminated, _ctx: &mut Context<Self>) {
+            *self.reason.lock().unwrap() = Some(msg.reason);
+        }
+    }
+
+    let reason = Arc::new(Mutex::new(None));
+    let sys = ActorSystem::new();
+    let _overseer = sys.spawn(Overseer {
+        reason: reason.clone(),
+        child: None,
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
     assert_eq!(
-        CHILD_START_COUNT.load(Ordering::SeqCst),
-        1,
-        "Grandchild should have started once"
+        *reason.lock().unwrap(),
+        Some(TerminationReason::RestartLimitExceeded)
     );
+}
 
-    parent.do_send(CrashGrandchild).await.unwrap();
+#[tokio::test]
+async fn termination_reason_tells_stops_from_crashes() {
+    use cinema::{actor::ActorId, message::TerminationReason};
+    use std::{collections::HashMap, sync::Mutex};
+
+    struct Quit;
+    impl Message for Quit {
+        type Result = ();
+    }
+
+    struct Victim;
+    impl Actor for Victim {}
+
+    impl Handler<Crash> for Victim {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+            panic!("victim crashed");
+        }
+    }
+
+    impl Handler<Quit> for Victim {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Quit, ctx: &mut Context<Self>) {
+            ctx.stop();
+        }
+    }
+
+    struct Coroner {
+        victims: Vec<Addr<Victim>>,
+        reasons: Arc<Mutex<HashMap<ActorId, TerminationReason>>>,
+    }
+
+    impl Actor for Coroner {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            //watch, then let go so the dropped victim can stop
+            for victim in self.victims.drain(..) {
+                ctx.watch(&victim);
+            }
+        }
+    }
+
+    impl Handler<Terminated> for Coroner {
+        type Result = ();
+
+        fn handle(&mut self, msg: Terminated, _ctx: &mut Context<Self>) {
+            self.reasons.lock().unwrap().insert(msg.id, msg.reason);
+        }
+    }
 
+    let sys = ActorSystem::new();
+    let doomed = ActorSystem::new();
+    let crashed = sys.spawn_with_strategy(|| Victim, SupervisorStrategy::Stop);
+    let quitter = sys.spawn(Victim);
+    let dropped = sys.spawn(Victim);
+    let killed = doomed.spawn(Victim);
+    let ids = [crashed.id(), quitter.id(), dropped.id(), killed.id()];
+
+    let reasons = Arc::new(Mutex::new(HashMap::new()));
+    let _coroner = sys.spawn(Coroner {
+        victims: vec![crashed.clone(), quitter.clone(), dropped, killed.clone()],
+        reasons: reasons.clone(),
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    crashed.do_send(Crash).await.unwrap();
+    quitter.do_send(Quit).await.unwrap();
+    doomed.shutdown();
     tokio::time::sleep(Duration::from_millis(100)).await;
 
+    let reasons = reasons.lock().unwrap();
     assert_eq!(
-        PARENT_START_COUNT.load(Ordering::SeqCst),
-        2,
-        "Parent should have been restarted by grandparent"
+        reasons.get(&ids[0]),
+        Some(&TerminationReason::Panicked("victim crashed".to_string()))
     );
-
+    assert_eq!(reasons.get(&ids[1]), Some(&TerminationReason::Normal));
     assert_eq!(
-        CHILD_START_COUNT.load(Ordering::SeqCst),
-        2,
-        "Grandchild should have been recreated with new parent"
+        reasons.get(&ids[2]),
+        Some(&TerminationReason::MailboxDropped)
     );
+    assert_eq!(reasons.get(&ids[3]), Some(&TerminationReason::Killed));
+}
+
+#[tokio::test]
+async fn panic_hook_sees_every_panic() {
+    use cinema::panic::PanicInfo;
+    use std::sync::Mutex;
+
+    let seen: Arc<Mutex<Vec<PanicInfo>>> = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let hook_seen = seen.clone();
+    sys.on_actor_panic(move |info| hook_seen.lock().unwrap().push(info.clone()));
+
+    let addr = sys.spawn_with_strategy(
+        || Looping,
+        SupervisorStrategy::restart(5, Duration::from_secs(10)),
+    );
+    addr.do_send(Crash).await.unwrap();
+    addr.do_send(Crash).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    //another system's hooks stay quiet
+    let other = ActorSystem::new();
+    let unseen = other.spawn_with_strategy(|| Looping, SupervisorStrategy::Stop);
+    unseen.do_send(Crash).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert!(seen.iter().all(|info| info.id == addr.id()));
+    assert_eq!(seen[0].message, "Intentional crash loop");
+}
+
+#[tokio::test]
+async fn event_stream_reports_lifecycle() {
+    use cinema::{message::TerminationReason, SystemEvent};
+    use futures::StreamExt;
+
+    let sys = ActorSystem::new();
+    let mut events = sys.events();
+
+    let addr = sys.spawn_with_strategy(
+        || Looping,
+        SupervisorStrategy::restart(1, Duration::from_secs(10)),
+    );
+    addr.do_send(Crash).await.unwrap();
+    addr.do_send(Crash).await.unwrap();
+
+    let mut seen = Vec::new();
+    while seen.len() < 5 {
+        let event = tokio::time::timeout(Duration::from_secs(1), events.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.id(), addr.id());
+        seen.push(event);
+    }
+
+    assert!(matches!(
+        &seen[0],
+        SystemEvent::ActorSpawned { actor_type, .. } if actor_type.ends_with("Looping")
+    ));
+    assert!(matches!(
+        &seen[1],
+        SystemEvent::ActorPanicked { message, .. } if message == "Intentional crash loop"
+    ));
+    assert!(matches!(&seen[2], SystemEvent::ActorRestarted { .. }));
+    assert!(matches!(&seen[3], SystemEvent::ActorPanicked { .. }));
+    assert!(matches!(
+        &seen[4],
+        SystemEvent::ActorStopped {
+            reason: TerminationReason::RestartLimitExceeded,
+            ..
+        }
+    ));
+}
+
+// ======== Child Spec Tests ========
+
+#[tokio::test]
+async fn child_spec_limits_restarts_per_child() {
+    use std::sync::Mutex;
+
+    static BUILT: AtomicU32 = AtomicU32::new(0);
+
+    struct Parent {
+        child: Arc<Mutex<Option<Addr<Looping>>>>,
+    }
+
+    impl Actor for Parent {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            let child = ctx.spawn_child_spec(
+                ChildSpec::new(|| {
+                    BUILT.fetch_add(1, Ordering::SeqCst);
+                    Looping
+                })
+                .max_restarts(2, Duration::from_secs(10)),
+            );
+            *self.child.lock().unwrap() = Some(child);
+        }
+    }
+
+    impl Handler<Terminated> for Parent {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    let slot = Arc::new(Mutex::new(None));
+    let sys = ActorSystem::new();
+    let parent = sys.spawn(Parent {
+        child: slot.clone(),
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let child = slot.lock().unwrap().clone().unwrap();
+    for _ in 0..3 {
+        let _ = child.do_send(Crash).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    //initial instance plus two restarts, then the budget is spent
+    assert_eq!(BUILT.load(Ordering::SeqCst), 3);
+    assert!(!child.is_alive());
+    assert!(
+        parent.is_alive(),
+        "an ordinary child does not take its parent down"
+    );
+}
+
+#[tokio::test]
+async fn significant_child_failure_stops_parent() {
+    use std::sync::Mutex;
+
+    struct Parent {
+        stopped: Arc<AtomicBool>,
+        child: Arc<Mutex<Option<Addr<Looping>>>>,
+    }
+
+    impl Actor for Parent {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            let child = ctx.spawn_child_spec(ChildSpec::new(|| Looping).significant(true));
+            *self.child.lock().unwrap() = Some(child);
+        }
+
+        fn stopped(&mut self, _ctx: &mut Context<Self>) {
+            self.stopped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    impl Handler<Terminated> for Parent {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let slot = Arc::new(Mutex::new(None));
+    let sys = ActorSystem::new();
+    let parent = sys.spawn(Parent {
+        stopped: stopped.clone(),
+        child: slot.clone(),
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let child = slot.lock().unwrap().clone().unwrap();
+    child.do_send(Crash).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(stopped.load(Ordering::SeqCst));
+    assert!(!parent.is_alive());
+}
+
+#[tokio::test]
+async fn child_spec_bounds_the_child_mailbox() {
+    use std::sync::Mutex;
+
+    struct Parent {
+        children: Arc<Mutex<Vec<Addr<Looping>>>>,
+    }
+
+    impl Actor for Parent {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            let mut children = self.children.lock().unwrap();
+            children.push(ctx.spawn_child(Looping));
+            children.push(ctx.spawn_child_spec(
+                ChildSpec::new(|| Looping)
+                    .mailbox_capacity(2)
+                    .overflow(OverflowStrategy::DropNewest),
+            ));
+        }
+    }
+
+    impl Handler<Terminated> for Parent {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    let slot = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let _parent = sys.spawn(Parent {
+        children: slot.clone(),
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let children = slot.lock().unwrap().clone();
+    assert_eq!(children[0].capacity(), Some(DEFAULT_CAPACITY));
+    assert_eq!(children[1].capacity(), Some(2));
+}
+
+#[tokio::test]
+async fn handler_timeout_fails_hung_handler_and_mailbox_moves_on() {
+    use cinema::actor::{AsyncHandler, BoxFuture};
+    use std::sync::Mutex;
+
+    static STARTS: AtomicU32 = AtomicU32::new(0);
+
+    struct Hang;
+    impl Message for Hang {
+        type Result = ();
+    }
+
+    struct Ping;
+    impl Message for Ping {
+        type Result = u32;
+    }
+
+    struct Worker;
+    impl Actor for Worker {
+        fn started(&mut self, _ctx: &mut Context<Self>) {
+            STARTS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl AsyncHandler<Hang> for Worker {
+        fn handle(&mut self, _msg: Hang, _ctx: &mut Context<Self>) -> BoxFuture<'_, ()> {
+            Box::pin(tokio::time::sleep(Duration::from_secs(3600)))
+        }
+    }
+
+    impl AsyncHandler<Ping> for Worker {
+        fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) -> BoxFuture<'_, u32> {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                STARTS.load(Ordering::SeqCst)
+            })
+        }
+    }
+
+    struct Parent {
+        child: Arc<Mutex<Option<Addr<Worker>>>>,
+    }
+
+    impl Actor for Parent {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            let child = ctx.spawn_child_spec(
+                ChildSpec::new(|| Worker)
+                    .strategy(SupervisorStrategy::restart(5, Duration::from_secs(10)))
+                    .options(SpawnOptions::new().handler_timeout::<Hang>(Duration::from_millis(50))),
+            );
+            *self.child.lock().unwrap() = Some(child);
+        }
+    }
+
+    impl Handler<Terminated> for Parent {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    let slot = Arc::new(Mutex::new(None));
+    let sys = ActorSystem::new();
+    let _parent = sys.spawn(Parent {
+        child: slot.clone(),
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let child = slot.lock().unwrap().clone().unwrap();
+
+    //only Hang is limited, other handlers run as long as they need
+    assert_eq!(child.send_async(Ping).await.unwrap(), 1);
+
+    let queued = {
+        let child = child.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            child.send_async(Ping).await
+        })
+    };
+    let started = std::time::Instant::now();
+    assert!(child.send_async(Hang).await.is_err());
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    //the restarted instance handles the mail queued behind the hung handler
+    assert_eq!(queued.await.unwrap().unwrap(), 2);
+    assert!(child.is_alive());
+}
+
+// ======== Supervision Mode Tests ========
+
+struct Stage {
+    built: Arc<AtomicU32>,
+}
+
+impl Actor for Stage {
+    fn started(&mut self, _ctx: &mut Context<Self>) {
+        self.built.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl Handler<Crash> for Stage {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+        panic!("stage crashed");
+    }
+}
+
+struct Pipeline {
+    mode: SupervisionMode,
+    built: [Arc<AtomicU32>; 2],
+    first: Arc<std::sync::Mutex<Option<Addr<Stage>>>>,
+}
+
+impl Actor for Pipeline {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        ctx.set_supervision_mode(self.mode);
+        for (n, built) in self.built.iter().enumerate() {
+            let built = built.clone();
+            let stage = ctx.spawn_child_with_strategy(
+                move || Stage {
+                    built: built.clone(),
+                },
+                SupervisorStrategy::restart(3, Duration::from_secs(10)),
+            );
+            if n == 0 {
+                *self.first.lock().unwrap() = Some(stage);
+            }
+        }
+    }
+}
+
+impl Handler<Terminated> for Pipeline {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+}
+
+///crash the first stage and report how often each stage was built
+async fn crash_first_stage(mode: SupervisionMode) -> (u32, u32) {
+    let built = [Arc::new(AtomicU32::new(0)), Arc::new(AtomicU32::new(0))];
+    let first = Arc::new(std::sync::Mutex::new(None));
+    let sys = ActorSystem::new();
+    let _pipeline = sys.spawn(Pipeline {
+        mode,
+        built: built.clone(),
+        first: first.clone(),
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let stage = first.lock().unwrap().clone().unwrap();
+    stage.do_send(Crash).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    (
+        built[0].load(Ordering::SeqCst),
+        built[1].load(Ordering::SeqCst),
+    )
+}
+
+#[tokio::test]
+async fn one_for_one_restarts_only_the_failed_child() {
+    assert_eq!(crash_first_stage(SupervisionMode::OneForOne).await, (2, 1));
+}
+
+#[tokio::test]
+async fn all_for_one_restarts_siblings_too() {
+    assert_eq!(crash_first_stage(SupervisionMode::AllForOne).await, (2, 2));
+}
+
+// ======== Escalate Strategy Tests ========
+
+/// child panic causes parent to "panic",
+/// which triggers grandparent's restart strategy
+#[tokio::test]
+async fn escalate_triggers_parent_restart() {
+    // Track how many times Parent gets started (should be 2: initial + 1 restart)
+    static PARENT_START_COUNT: AtomicU32 = AtomicU32::new(0);
+    // Track how many times Child gets started
+    static CHILD_START_COUNT: AtomicU32 = AtomicU32::new(0);
+
+    // Grandchild that panics
+    struct Grandchild;
+    impl Actor for Grandchild {
+        fn started(&mut self, _ctx: &mut Context<Self>) {
+            CHILD_START_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    struct CrashMsg;
+    impl Message for CrashMsg {
+        type Result = ();
+    }
+
+    impl Handler<CrashMsg> for Grandchild {
+        type Result = ();
+
+        fn handle(&mut self, _msg: CrashMsg, _ctx: &mut Context<Self>) {
+            panic!("Grandchild crash to test escalate!");
+        }
+    }
+
+    // Parent spawns Grandchild with Escalate strategy
+    struct Parent {
+        grandchild: Option<Addr<Grandchild>>,
+    }
+
+    impl Actor for Parent {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            PARENT_START_COUNT.fetch_add(1, Ordering::SeqCst);
+            // Parent spawns grandchild with Escalate strategy
+            self.grandchild =
+                Some(ctx.spawn_child_with_strategy(|| Grandchild, SupervisorStrategy::Escalate));
+        }
+    }
+
+    impl Handler<Terminated> for Parent {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    // Message to crash the grandchild via parent
+    struct CrashGrandchild;
+    impl Message for CrashGrandchild {
+        type Result = ();
+    }
+
+    impl Handler<CrashGrandchild> for Parent {
+        type Result = ();
+
+        fn handle(&mut self, _msg: CrashGrandchild, _ctx: &mut Context<Self>) {
+            if let Some(ref gc) = self.grandchild {
+                let _ = gc.try_send(CrashMsg);
+            }
+        }
+    }
+
+    // Grandparent spawns Parent with Restart strategy
+    struct Grandparent;
+    impl Actor for Grandparent {}
+
+    impl Handler<Terminated> for Grandparent {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    struct SpawnParent;
+    impl Message for SpawnParent {
+        type Result = Addr<Parent>;
+    }
+
+    impl Handler<SpawnParent> for Grandparent {
+        type Result = Addr<Parent>;
+
+        fn handle(&mut self, _msg: SpawnParent, ctx: &mut Context<Self>) -> Addr<Parent> {
+            ctx.spawn_child_with_strategy(
+                || Parent { grandchild: None },
+                SupervisorStrategy::restart(3, Duration::from_secs(10)),
+            )
+        }
+    }
+
+    let sys = ActorSystem::new();
+    let grandparent = sys.spawn(Grandparent);
+
+    let parent = grandparent.send(SpawnParent).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(
+        PARENT_START_COUNT.load(Ordering::SeqCst),
+        1,
+        "Parent should have started once"
+    );
+    assert_eq!(
+        CHILD_START_COUNT.load(Ordering::SeqCst),
+        1,
+        "Grandchild should have started once"
+    );
+
+    parent.do_send(CrashGrandchild).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(
+        PARENT_START_COUNT.load(Ordering::SeqCst),
+        2,
+        "Parent should have been restarted by grandparent"
+    );
+
+    assert_eq!(
+        CHILD_START_COUNT.load(Ordering::SeqCst),
+        2,
+        "Grandchild should have been recreated with new parent"
+    );
+}
+
+#[tokio::test]
+async fn parent_handles_child_failed() {
+    use cinema::message::ChildFailed;
+    use std::sync::Mutex;
+
+    static PARENT_STARTS: AtomicU32 = AtomicU32::new(0);
+
+    struct Lenient {
+        failures: Arc<Mutex<Vec<ChildFailed>>>,
+        child: Option<Addr<Flaky>>,
+    }
+
+    impl Actor for Lenient {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            PARENT_STARTS.fetch_add(1, Ordering::SeqCst);
+            let child = ctx.spawn_child_with_strategy(|| Flaky, SupervisorStrategy::Escalate);
+            child.try_send(Crash).unwrap();
+            self.child = Some(child);
+        }
+
+        fn child_failed(&mut self, failure: &ChildFailed, _ctx: &mut Context<Self>) -> bool {
+            self.failures.lock().unwrap().push(failure.clone());
+            true
+        }
+    }
+
+    impl Handler<Terminated> for Lenient {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    let failures = Arc::new(Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let parent = sys.spawn_with_strategy(
+        {
+            let failures = failures.clone();
+            move || Lenient {
+                failures: failures.clone(),
+                child: None,
+            }
+        },
+        SupervisorStrategy::restart(3, Duration::from_secs(10)),
+    );
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let failures = failures.lock().unwrap();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].error.contains("guardian test"));
+    // handled, so the parent was neither restarted nor stopped
+    assert_eq!(PARENT_STARTS.load(Ordering::SeqCst), 1);
+    assert!(parent.is_alive());
+}
+
+#[tokio::test]
+async fn unhandled_child_failure_reaches_guardian() {
+    struct Careless {
+        stopped: Arc<AtomicBool>,
+    }
+
+    impl Actor for Careless {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            let child = ctx.spawn_child_with_strategy(|| Flaky, SupervisorStrategy::Escalate);
+            child.try_send(Crash).unwrap();
+        }
+
+        fn stopped(&mut self, _ctx: &mut Context<Self>) {
+            self.stopped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    impl Handler<Terminated> for Careless {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let sys = ActorSystem::new();
+    let parent = sys.spawn(Careless {
+        stopped: stopped.clone(),
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(stopped.load(Ordering::SeqCst));
+    assert!(!parent.is_alive());
+}
+
+// ======== Parent Directive Tests ========
+
+#[tokio::test]
+async fn supervisor_parent_decides_per_failure() {
+    static WORKER_STARTS: AtomicU32 = AtomicU32::new(0);
+
+    struct Worker;
+    impl Actor for Worker {
+        fn started(&mut self, _ctx: &mut Context<Self>) {
+            WORKER_STARTS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    struct Fail(&'static str);
+    impl Message for Fail {
+        type Result = ();
+    }
+
+    impl Handler<Fail> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, msg: Fail, _ctx: &mut Context<Self>) {
+            panic!("{}", msg.0);
+        }
+    }
+
+    struct Owner {
+        worker: Option<Addr<Worker>>,
+        failures: Arc<std::sync::Mutex<Vec<FailureInfo>>>,
+    }
+
+    impl Actor for Owner {
+        fn started(&mut self, ctx: &mut Context<Self>) {
+            self.worker = Some(ctx.spawn_child_directed(|| Worker));
+        }
+    }
+
+    impl Handler<Terminated> for Owner {
+        type Result = ();
+
+        fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+    }
+
+    // io errors are worth a retry, a bad config never heals
+    impl Supervisor for Owner {
+        fn on_child_failure(&mut self, child: ActorId, err: &FailureInfo) -> SupervisorDirective {
+            assert_eq!(Some(child), self.worker.as_ref().map(|w| w.id()));
+            self.failures.lock().unwrap().push(err.clone());
+            if err.message.starts_with("io") {
+                SupervisorDirective::Restart
+            } else {
+                SupervisorDirective::Stop
+            }
+        }
+    }
+
+    struct GetWorker;
+    impl Message for GetWorker {
+        type Result = Addr<Worker>;
+    }
+
+    impl Handler<GetWorker> for Owner {
+        type Result = Addr<Worker>;
+
+        fn handle(&mut self, _msg: GetWorker, _ctx: &mut Context<Self>) -> Addr<Worker> {
+            self.worker.clone().unwrap()
+        }
+    }
+
+    let failures = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = ActorSystem::new();
+    let owner = sys.spawn(Owner {
+        worker: None,
+        failures: failures.clone(),
+    });
+    let worker = owner.send(GetWorker).await.unwrap();
+
+    worker.do_send(Fail("io timeout")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(worker.is_alive());
+    assert_eq!(WORKER_STARTS.load(Ordering::SeqCst), 2);
+
+    worker.do_send(Fail("config missing")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!worker.is_alive());
+    assert_eq!(WORKER_STARTS.load(Ordering::SeqCst), 2);
+
+    let failures = failures.lock().unwrap();
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0].message, "io timeout");
+    assert_eq!((failures[0].restarts, failures[1].restarts), (0, 1));
+    assert_eq!(&*failures[1].path, worker.path());
+}
+
+// ======== Guardian Policy Tests ========
+
+struct Flaky;
+impl Actor for Flaky {}
+
+impl Handler<Crash> for Flaky {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+        panic!("Intentional crash for guardian test");
+    }
+}
+
+struct ChildStopped(Arc<AtomicBool>);
+impl Actor for ChildStopped {
+    fn stopped(&mut self, _ctx: &mut Context<Self>) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+struct FlakyParent {
+    child_stopped: Arc<AtomicBool>,
+}
+
+impl Actor for FlakyParent {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        ctx.spawn_child(ChildStopped(self.child_stopped.clone()));
+    }
+}
+
+impl Handler<Terminated> for FlakyParent {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Terminated, _ctx: &mut Context<Self>) {}
+}
+
+impl Handler<Crash> for FlakyParent {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Crash, _ctx: &mut Context<Self>) {
+        panic!("Intentional crash for guardian test");
+    }
+}
+
+///top-level actor exceeding its restart limit terminates the system
+#[tokio::test]
+async fn guardian_terminates_system() {
+    let sys = ActorSystem::with_guardian(GuardianPolicy::TerminateSystem { exit_code: 3 });
+    let flaky = sys.spawn_with_strategy(
+        || Flaky,
+        SupervisorStrategy::restart(2, Duration::from_secs(10)),
+    );
+
+    // two crashes are absorbed by restarts
+    for _ in 0..2 {
+        flaky.do_send(Crash).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(flaky.is_alive());
+    assert_eq!(sys.exit_code(), None);
+
+    // third crash within the window reaches the guardian
+    flaky.do_send(Crash).await.unwrap();
+    let code = tokio::time::timeout(Duration::from_secs(1), sys.terminated())
+        .await
+        .unwrap();
+
+    assert_eq!(code, 3);
+    assert_eq!(sys.exit_code(), Some(3));
+}
+
+///default guardian stops the failed actor together with its children
+#[tokio::test]
+async fn guardian_stops_subtree_by_default() {
+    let child_stopped = Arc::new(AtomicBool::new(false));
+    let sys = ActorSystem::new();
+    let parent = sys.spawn(FlakyParent {
+        child_stopped: child_stopped.clone(),
+    });
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    parent.do_send(Crash).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(!parent.is_alive());
+    assert!(child_stopped.load(Ordering::SeqCst));
+    assert_eq!(sys.exit_code(), None);
+}
+
+///StopActor leaves the failed actor's children running
+#[tokio::test]
+async fn guardian_stop_actor_keeps_children() {
+    let child_stopped = Arc::new(AtomicBool::new(false));
+    let sys = ActorSystem::with_guardian(GuardianPolicy::StopActor);
+    let parent = sys.spawn(FlakyParent {
+        child_stopped: child_stopped.clone(),
+    });
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    parent.do_send(Crash).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(!parent.is_alive());
+    assert!(!child_stopped.load(Ordering::SeqCst));
+
+    sys.shutdown();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(child_stopped.load(Ordering::SeqCst));
+}
+
+// ======== Panic Policy Tests ========
+
+#[tokio::test]
+async fn panic_policy_restart_overrides_stop_strategy() {
+    let built = Arc::new(AtomicU32::new(0));
+    let sys = ActorSystem::with_config(
+        ActorSystemConfig::new().panic_policy(PanicPolicy::RestartActor),
+    );
+    let counter = built.clone();
+    let flaky = sys.spawn_with_strategy(
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Flaky
+        },
+        SupervisorStrategy::Stop,
+    );
+
+    for _ in 0..3 {
+        flaky.do_send(Crash).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(flaky.is_alive());
+    assert_eq!(built.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn panic_policy_restart_keeps_the_restart_budget() {
+    let built = Arc::new(AtomicU32::new(0));
+    let sys = ActorSystem::with_config(
+        ActorSystemConfig::new().panic_policy(PanicPolicy::RestartActor),
+    );
+    let counter = built.clone();
+    let flaky = sys.spawn_with_strategy(
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Flaky
+        },
+        SupervisorStrategy::restart(2, Duration::from_secs(10)),
+    );
+
+    for _ in 0..3 {
+        flaky.do_send(Crash).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    // the third crash within the window ends it
+    assert!(!flaky.is_alive());
+    assert_eq!(built.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn panic_policy_restart_limits_a_crash_loop() {
+    let built = Arc::new(AtomicU32::new(0));
+    let sys = ActorSystem::with_config(
+        ActorSystemConfig::new().panic_policy(PanicPolicy::RestartActor),
+    );
+    let counter = built.clone();
+    let flaky = sys.spawn_with_strategy(
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Flaky
+        },
+        SupervisorStrategy::Stop,
+    );
+
+    // a strategy without a budget gets 10 restarts per minute
+    for _ in 0..11 {
+        let _ = flaky.do_send(Crash).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(!flaky.is_alive());
+    assert_eq!(built.load(Ordering::SeqCst), 11);
+}
+
+#[tokio::test]
+async fn panic_policy_stop_overrides_restart_strategy() {
+    let panics = Arc::new(AtomicU32::new(0));
+    let seen = panics.clone();
+    let sys = ActorSystem::with_config(
+        ActorSystemConfig::new()
+            .panic_policy(PanicPolicy::StopActor)
+            .on_panic(move |_info| {
+                seen.fetch_add(1, Ordering::SeqCst);
+            }),
+    );
+    let flaky = sys.spawn_with_strategy(
+        || Flaky,
+        SupervisorStrategy::restart(5, Duration::from_secs(10)),
+    );
+
+    flaky.do_send(Crash).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!flaky.is_alive());
+    assert_eq!(panics.load(Ordering::SeqCst), 1);
+    assert_eq!(sys.exit_code(), None);
+}
+
+#[tokio::test]
+async fn panic_policy_escalation_terminates_system() {
+    let child_stopped = Arc::new(AtomicBool::new(false));
+    let sys = ActorSystem::with_config(
+        ActorSystemConfig::new().panic_policy(PanicPolicy::EscalateToSystem),
+    );
+    let bystander = sys.spawn(ChildStopped(child_stopped.clone()));
+    let flaky = sys.spawn_with_strategy(
+        || Flaky,
+        SupervisorStrategy::restart(5, Duration::from_secs(10)),
+    );
+
+    flaky.do_send(Crash).await.unwrap();
+    let code = tokio::time::timeout(Duration::from_secs(1), sys.terminated())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert_eq!(code, 1);
+    assert!(!flaky.is_alive());
+    assert!(!bystander.is_alive());
+    assert!(child_stopped.load(Ordering::SeqCst));
 }
@@ -7,6 +7,7 @@ use cinema::{Actor, Handler, Message};
 
 // ======== Actor Lifecycle Tests ========
 
+#[derive(Clone)]
 struct StopMe;
 
 impl Message for StopMe {
@@ -24,6 +25,8 @@ impl Actor for TestActor {
 }
 
 impl Handler<StopMe> for TestActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: StopMe, ctx: &mut cinema::Context<Self>) {
         ctx.stop();
     }
@@ -68,6 +71,231 @@ async fn system_shutdown_stops_actor() {
     assert!(stopped.load(Ordering::SeqCst));
 }
 
+struct AsyncLifecycle {
+    connected: bool,
+    flushed: Arc<AtomicBool>,
+}
+
+impl Actor for AsyncLifecycle {
+    fn started_async<'a>(
+        &'a mut self,
+        _ctx: &'a mut cinema::Context<Self>,
+    ) -> cinema::actor::BoxFuture<'a, ()> {
+        Box::pin(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.connected = true;
+        })
+    }
+
+    fn stopped_async<'a>(
+        &'a mut self,
+        _ctx: &'a mut cinema::Context<Self>,
+    ) -> cinema::actor::BoxFuture<'a, ()> {
+        Box::pin(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            self.flushed.store(true, Ordering::SeqCst);
+        })
+    }
+}
+
+struct IsConnected;
+impl Message for IsConnected {
+    type Result = bool;
+}
+
+impl Handler<IsConnected> for AsyncLifecycle {
+    type Result = bool;
+
+    fn handle(&mut self, _msg: IsConnected, _ctx: &mut cinema::Context<Self>) -> bool {
+        self.connected
+    }
+}
+
+impl Handler<StopMe> for AsyncLifecycle {
+    type Result = ();
+
+    fn handle(&mut self, _msg: StopMe, ctx: &mut cinema::Context<Self>) {
+        ctx.stop();
+    }
+}
+
+#[tokio::test]
+async fn async_lifecycle_hooks_are_awaited() {
+    let flushed = Arc::new(AtomicBool::new(false));
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(AsyncLifecycle {
+        connected: false,
+        flushed: flushed.clone(),
+    });
+
+    // queued while started_async runs, handled once it completes
+    assert_eq!(addr.send(IsConnected).await, Ok(true));
+
+    addr.do_send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(flushed.load(Ordering::SeqCst));
+    assert!(!addr.is_alive());
+}
+
+// ======== Handler Cancellation Tests ========
+
+struct LongJob;
+impl Message for LongJob {
+    type Result = ();
+}
+
+struct Cooperative {
+    wound_down: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Actor for Cooperative {
+    fn stopped(&mut self, _ctx: &mut cinema::Context<Self>) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl cinema::actor::AsyncHandler<LongJob> for Cooperative {
+    fn handle(
+        &mut self,
+        _msg: LongJob,
+        ctx: &mut cinema::Context<Self>,
+    ) -> cinema::actor::BoxFuture<'_, ()> {
+        let token = ctx.cancellation_token();
+        Box::pin(async move {
+            token.cancelled().await;
+            self.wound_down.store(true, Ordering::SeqCst);
+        })
+    }
+}
+
+#[tokio::test]
+async fn stop_cancels_running_async_handler() {
+    let wound_down = Arc::new(AtomicBool::new(false));
+    let stopped = Arc::new(AtomicBool::new(false));
+
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Cooperative {
+        wound_down: wound_down.clone(),
+        stopped: stopped.clone(),
+    });
+
+    addr.do_send_async(LongJob).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    sys.shutdown();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    assert!(wound_down.load(Ordering::SeqCst));
+    assert!(stopped.load(Ordering::SeqCst));
+}
+
+struct Stubborn {
+    stopped: Arc<AtomicBool>,
+}
+
+impl Actor for Stubborn {
+    fn started(&mut self, ctx: &mut cinema::Context<Self>) {
+        ctx.set_stop_grace(std::time::Duration::from_millis(100));
+    }
+
+    fn stopped(&mut self, _ctx: &mut cinema::Context<Self>) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl cinema::actor::AsyncHandler<LongJob> for Stubborn {
+    fn handle(
+        &mut self,
+        _msg: LongJob,
+        _ctx: &mut cinema::Context<Self>,
+    ) -> cinema::actor::BoxFuture<'_, ()> {
+        // ignores the cancellation token
+        Box::pin(tokio::time::sleep(std::time::Duration::from_secs(10)))
+    }
+}
+
+#[tokio::test]
+async fn stop_drops_handler_after_grace_period() {
+    let stopped = Arc::new(AtomicBool::new(false));
+
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Stubborn {
+        stopped: stopped.clone(),
+    });
+
+    addr.do_send_async(LongJob).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    // well inside the 100ms grace, then well past it
+    sys.shutdown();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert!(!stopped.load(Ordering::SeqCst));
+
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(stopped.load(Ordering::SeqCst));
+    assert!(!addr.is_alive());
+}
+
+#[tokio::test]
+async fn kill_drops_a_stuck_handler_and_the_queue() {
+    use futures::StreamExt;
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let sys = cinema::ActorSystem::new();
+    let mut letters = sys.dead_letters();
+    let addr = sys.spawn(Stubborn {
+        stopped: stopped.clone(),
+    });
+
+    addr.do_send_async(LongJob).await.unwrap();
+    addr.do_send_async(LongJob).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    addr.kill();
+    assert!(!addr.is_alive());
+    // no stop grace, no `stopped` hook
+    let reason = tokio::time::timeout(std::time::Duration::from_millis(10), addr.stop_graceful())
+        .await
+        .unwrap();
+    assert_eq!(reason, cinema::message::TerminationReason::Killed);
+    assert!(!stopped.load(Ordering::SeqCst));
+
+    // the queued job was never handled; other tests in this process publish too
+    let letter = loop {
+        let letter = letters.next().await.unwrap();
+        if letter.recipient == addr.id() {
+            break letter;
+        }
+    };
+    assert!(letter.message_type.contains("LongJob"));
+}
+
+#[tokio::test]
+async fn dead_letters_reach_only_their_own_system() {
+    use futures::StreamExt;
+
+    let sys = cinema::ActorSystem::new();
+    let other = cinema::ActorSystem::new();
+    let mut letters = sys.dead_letters();
+    let mut others = other.dead_letters();
+
+    let addr = sys.spawn(RegistryActor);
+    addr.do_send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let _ = addr.do_send(StopMe).await;
+
+    let letter = letters.next().await.unwrap();
+    assert_eq!(letter.recipient, addr.id());
+    let nothing =
+        tokio::time::timeout(std::time::Duration::from_millis(50), others.next()).await;
+    assert!(nothing.is_err());
+
+    // a dropped system's subscriptions end
+    drop(other);
+    assert!(others.next().await.is_none());
+}
+
 // ======== Actor Registry Tests ========
 
 struct RegistryActor;
@@ -75,6 +303,8 @@ struct RegistryActor;
 impl Actor for RegistryActor {}
 
 impl Handler<StopMe> for RegistryActor {
+    type Result = ();
+
     fn handle(&mut self, _msg: StopMe, ctx: &mut cinema::Context<Self>) {
         ctx.stop();
     }
@@ -149,3 +379,1309 @@ async fn registry_manual_does_not_auto_unregister() {
     let found: Option<cinema::Addr<RegistryActor>> = sys.lookup("manual_actor");
     assert!(found.is_none());
 }
+
+// ======== Actor Id Tests ========
+
+struct Parent {
+    child: Option<cinema::Addr<RegistryActor>>,
+}
+
+impl Actor for Parent {
+    fn started(&mut self, ctx: &mut cinema::Context<Self>) {
+        self.child = Some(ctx.spawn_child(RegistryActor));
+    }
+}
+
+impl Handler<cinema::message::Terminated> for Parent {
+    type Result = ();
+
+    fn handle(&mut self, _msg: cinema::message::Terminated, _ctx: &mut cinema::Context<Self>) {}
+}
+
+struct GetChild;
+impl Message for GetChild {
+    type Result = Option<cinema::Addr<RegistryActor>>;
+}
+
+impl Handler<GetChild> for Parent {
+    type Result = Option<cinema::Addr<RegistryActor>>;
+
+    fn handle(
+        &mut self,
+        _msg: GetChild,
+        _ctx: &mut cinema::Context<Self>,
+    ) -> Option<cinema::Addr<RegistryActor>> {
+        self.child.clone()
+    }
+}
+
+#[tokio::test]
+async fn actor_id_tracks_parent_and_path() {
+    let sys = cinema::ActorSystem::new();
+    let parent = sys.spawn(Parent { child: None });
+    let child = parent.send(GetChild).await.unwrap().unwrap();
+
+    let parent_id = parent.id();
+    let child_id = child.id();
+
+    assert!(parent_id < child_id);
+    assert!(parent_id.created_at() <= child_id.created_at());
+    assert_eq!(parent_id.parent(), None);
+    assert_eq!(child_id.parent(), Some(parent_id));
+    assert_eq!(
+        child_id.to_string(),
+        format!("/{}/{}", parent_id.sequence(), child_id.sequence())
+    );
+}
+
+#[tokio::test]
+async fn actor_id_round_trips_through_text_and_protobuf() {
+    use cinema::{actor::ActorId, remote::proto, SpawnOptions};
+    use prost::Message as ProstMessage;
+
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn_with(RegistryActor, SpawnOptions::new().name("ingest"));
+    let id = addr.id();
+    assert_eq!(id.name(), Some("ingest"));
+    assert_eq!(id.node(), None);
+    assert_eq!(id.to_string(), format!("/{}#ingest", id.sequence()));
+    assert_eq!(ActorId::parse(&id.to_string()), Ok(id));
+
+    // the same sequence number on another node is another actor
+    let remote = id.with_node("node-a");
+    assert_ne!(remote, id);
+    assert!(id < remote);
+    assert_eq!(remote.to_string(), format!("node-a@/{}#ingest", id.sequence()));
+    let parsed = ActorId::parse("node-a@/3/7#worker").unwrap();
+    assert_eq!(
+        (parsed.node(), parsed.sequence(), parsed.name()),
+        (Some("node-a"), 7, Some("worker"))
+    );
+    assert_eq!(parsed.to_string(), "node-a@/3/7#worker");
+    assert!(ActorId::parse("3/7").is_err());
+    assert!(ActorId::parse("/3/x").is_err());
+
+    let bytes = proto::ActorId::from(remote).encode_to_vec();
+    let decoded = ActorId::from(proto::ActorId::decode(bytes.as_slice()).unwrap());
+    assert_eq!(decoded, remote);
+    assert_eq!(decoded.name(), Some("ingest"));
+}
+
+#[tokio::test]
+async fn resolve_returns_erased_addr() {
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(RegistryActor);
+
+    let resolved = sys.resolve(addr.id()).unwrap();
+    assert_eq!(resolved.id(), addr.id());
+    assert!(resolved.actor_type().contains("RegistryActor"));
+    assert!(resolved.downcast::<TestActor>().is_none());
+
+    // typed handle works like the original
+    let typed = resolved.downcast::<RegistryActor>().unwrap();
+    typed.do_send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    assert!(!resolved.is_alive());
+    assert!(sys.resolve(addr.id()).is_none());
+}
+
+#[tokio::test]
+async fn resolve_finds_only_actors_of_its_own_system() {
+    let sys = cinema::ActorSystem::new();
+    let other = cinema::ActorSystem::new();
+    let addr = sys.spawn(RegistryActor);
+
+    assert!(sys.resolve(addr.id()).is_some());
+    assert!(other.resolve(addr.id()).is_none());
+}
+
+struct Ingest {
+    worker: Option<cinema::Addr<RegistryActor>>,
+}
+
+impl Actor for Ingest {
+    fn started(&mut self, ctx: &mut cinema::Context<Self>) {
+        let options = cinema::SpawnOptions::new().name("worker-3");
+        self.worker = Some(ctx.spawn_child_with(RegistryActor, options));
+    }
+}
+
+impl Handler<cinema::message::Terminated> for Ingest {
+    type Result = ();
+
+    fn handle(&mut self, _msg: cinema::message::Terminated, _ctx: &mut cinema::Context<Self>) {}
+}
+
+impl Handler<GetChild> for Ingest {
+    type Result = Option<cinema::Addr<RegistryActor>>;
+
+    fn handle(
+        &mut self,
+        _msg: GetChild,
+        _ctx: &mut cinema::Context<Self>,
+    ) -> Option<cinema::Addr<RegistryActor>> {
+        self.worker.clone()
+    }
+}
+
+struct GetPath;
+impl Message for GetPath {
+    type Result = String;
+}
+
+impl Handler<GetPath> for RegistryActor {
+    type Result = String;
+
+    fn handle(&mut self, _msg: GetPath, ctx: &mut cinema::Context<Self>) -> String {
+        ctx.path().to_string()
+    }
+}
+
+#[tokio::test]
+async fn paths_follow_names_down_the_tree() {
+    use futures::StreamExt;
+
+    let sys = cinema::ActorSystem::new();
+    let mut letters = sys.dead_letters();
+    let ingest = sys.spawn_with(
+        Ingest { worker: None },
+        cinema::SpawnOptions::new().name("ingest"),
+    );
+    let worker = ingest.send(GetChild).await.unwrap().unwrap();
+    let anonymous = sys.spawn(RegistryActor);
+
+    assert_eq!(ingest.path(), "/user/ingest");
+    assert_eq!(
+        worker.send(GetPath).await.unwrap(),
+        "/user/ingest/worker-3"
+    );
+    assert_eq!(
+        anonymous.path(),
+        format!("/user/{}", anonymous.id().sequence())
+    );
+    assert_eq!(
+        sys.resolve(worker.id()).unwrap().path(),
+        "/user/ingest/worker-3"
+    );
+
+    worker.do_send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let _ = worker.do_send(StopMe).await;
+    let letter = loop {
+        let letter = letters.next().await.unwrap();
+        if letter.recipient == worker.id() {
+            break letter;
+        }
+    };
+    assert_eq!(&*letter.recipient_path, "/user/ingest/worker-3");
+}
+
+#[test]
+#[should_panic(expected = "actor path separator")]
+fn names_cannot_contain_the_path_separator() {
+    let _ = cinema::SpawnOptions::new().name("ingest/worker-3");
+}
+
+#[tokio::test]
+async fn select_matches_subtree_by_pattern() {
+    let sys = cinema::ActorSystem::new();
+    let ingest = sys.spawn_with(
+        Ingest { worker: None },
+        cinema::SpawnOptions::new().name("ingest"),
+    );
+    let worker = ingest.send(GetChild).await.unwrap().unwrap();
+    let _other = sys.spawn_with(RegistryActor, cinema::SpawnOptions::new().name("other"));
+
+    let selection = sys.select("/user/ingest/*");
+    let paths: Vec<String> = selection
+        .actors()
+        .iter()
+        .map(|addr| addr.path().to_string())
+        .collect();
+    assert_eq!(paths, ["/user/ingest/worker-3"]);
+
+    let recipients = selection.recipients::<RegistryActor, GetPath>();
+    assert_eq!(recipients.len(), 1);
+    assert_eq!(recipients[0].id(), worker.id());
+    assert_eq!(
+        recipients[0].send(GetPath).await.unwrap(),
+        "/user/ingest/worker-3"
+    );
+
+    assert_eq!(sys.select("/user/**").actors().len(), 3);
+    assert_eq!(sys.select("/user/work*").actors().len(), 0);
+    assert_eq!(sys.select("/user/*/worker-*").actors().len(), 1);
+
+    assert_eq!(selection.do_send::<RegistryActor, StopMe>(StopMe).await, 1);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!worker.is_alive());
+    assert!(selection.actors().is_empty());
+}
+
+#[tokio::test]
+async fn snapshot_lists_running_actors_with_activity() {
+    let sys = cinema::ActorSystem::new();
+    let ingest = sys.spawn_with(
+        Ingest { worker: None },
+        cinema::SpawnOptions::new().name("ingest"),
+    );
+    let worker = ingest.send(GetChild).await.unwrap().unwrap();
+    worker.send(GetPath).await.unwrap();
+    worker.send(GetPath).await.unwrap();
+
+    let snapshot = sys.snapshot();
+    let paths: Vec<&str> = snapshot.iter().map(|actor| &*actor.path).collect();
+    assert_eq!(paths, ["/user/ingest", "/user/ingest/worker-3"]);
+
+    let ingest_view = &snapshot[0];
+    assert_eq!(ingest_view.id, ingest.id());
+    assert_eq!(ingest_view.name.as_deref(), Some("ingest"));
+    assert!(ingest_view.actor_type.ends_with("Ingest"));
+    assert_eq!(ingest_view.messages_processed, 1);
+
+    let worker_view = &snapshot[1];
+    assert_eq!(worker_view.messages_processed, 2);
+    assert_eq!(worker_view.mailbox_len, 0);
+    assert!(worker_view.last_active.unwrap() <= std::time::SystemTime::now());
+
+    let idle = sys.spawn(RegistryActor);
+    let idle_view = sys
+        .snapshot()
+        .into_iter()
+        .find(|actor| actor.id == idle.id())
+        .unwrap();
+    assert_eq!(idle_view.messages_processed, 0);
+    assert!(idle_view.last_active.is_none());
+}
+
+///path, message type, queue depth, whether queue latency was measured
+type Measured = (String, &'static str, usize, bool);
+
+struct Recorder(Arc<std::sync::Mutex<Vec<Measured>>>);
+
+impl cinema::MetricsSink for Recorder {
+    fn record(&self, metrics: &cinema::MessageMetrics<'_>) {
+        self.0.lock().unwrap().push((
+            metrics.path.to_string(),
+            metrics.message_type,
+            metrics.queue_depth,
+            metrics.queue_latency.is_some(),
+        ));
+    }
+}
+
+#[tokio::test]
+async fn metrics_sink_sees_every_handled_message() {
+    let sys = cinema::ActorSystem::new();
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let addr = sys.spawn_with(RegistryActor, cinema::SpawnOptions::new().name("measured"));
+    addr.send(GetPath).await.unwrap();
+
+    sys.set_metrics_sink(Recorder(seen.clone()));
+    addr.send(GetPath).await.unwrap();
+    addr.send(GetPath).await.unwrap();
+
+    sys.clear_metrics_sink();
+    addr.send(GetPath).await.unwrap();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    let (path, message_type, queue_depth, timed) = &seen[0];
+    assert_eq!(path, "/user/measured");
+    assert!(message_type.ends_with("GetPath"));
+    assert_eq!(*queue_depth, 0);
+    assert!(timed);
+}
+
+// ======== Spawn Options Tests ========
+
+struct NameWatcher {
+    target: cinema::Addr<RegistryActor>,
+    seen: Arc<std::sync::Mutex<Option<String>>>,
+}
+
+impl Actor for NameWatcher {
+    fn started(&mut self, ctx: &mut cinema::Context<Self>) {
+        ctx.watch(&self.target);
+    }
+}
+
+impl Handler<cinema::message::Terminated> for NameWatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: cinema::message::Terminated, _ctx: &mut cinema::Context<Self>) {
+        *self.seen.lock().unwrap() = msg.name.as_deref().map(str::to_string);
+    }
+}
+
+#[tokio::test]
+async fn spawn_with_sets_capacity_and_name() {
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn_with(
+        RegistryActor,
+        cinema::SpawnOptions::new().mailbox_capacity(8).name("ingest"),
+    );
+
+    assert_eq!(addr.capacity(), Some(8));
+    assert_eq!(addr.name(), Some("ingest"));
+    assert_eq!(sys.resolve(addr.id()).unwrap().name(), Some("ingest"));
+    assert_eq!(sys.spawn(RegistryActor).name(), None);
+}
+
+#[tokio::test]
+async fn terminated_carries_actor_name() {
+    let sys = cinema::ActorSystem::new();
+    let seen = Arc::new(std::sync::Mutex::new(None));
+    let addr = sys.spawn_with(RegistryActor, cinema::SpawnOptions::new().name("ingest"));
+    let _watcher = sys.spawn(NameWatcher {
+        target: addr.clone(),
+        seen: seen.clone(),
+    });
+
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    addr.do_send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    assert_eq!(seen.lock().unwrap().as_deref(), Some("ingest"));
+}
+
+// ======== System Builder Tests ========
+
+struct ThreadName;
+
+impl Message for ThreadName {
+    type Result = Option<String>;
+}
+
+///counts its incarnations, panics on StopMe
+struct Fragile(Arc<std::sync::atomic::AtomicUsize>);
+
+impl Actor for Fragile {
+    fn started(&mut self, _ctx: &mut cinema::Context<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl Handler<StopMe> for Fragile {
+    type Result = ();
+
+    fn handle(&mut self, _msg: StopMe, _ctx: &mut cinema::Context<Self>) {
+        panic!("fragile");
+    }
+}
+
+impl Handler<ThreadName> for Fragile {
+    type Result = Option<String>;
+
+    fn handle(&mut self, _msg: ThreadName, _ctx: &mut cinema::Context<Self>) -> Option<String> {
+        std::thread::current().name().map(str::to_string)
+    }
+}
+
+#[tokio::test]
+async fn builder_sets_system_wide_defaults() {
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = cinema::ActorSystem::builder()
+        .name("ingest")
+        .mailbox_capacity(16)
+        .metrics_sink(Recorder(seen.clone()))
+        .build();
+
+    assert_eq!(sys.name(), "ingest");
+    assert_eq!(cinema::ActorSystem::new().name(), "cinema");
+
+    let addr = sys.spawn(RegistryActor);
+    assert_eq!(addr.capacity(), Some(16));
+    let named = sys.spawn_with(RegistryActor, cinema::SpawnOptions::new().name("named"));
+    assert_eq!(named.capacity(), Some(16));
+    let bounded = sys.spawn_with(RegistryActor, cinema::SpawnOptions::new().mailbox_capacity(4));
+    assert_eq!(bounded.capacity(), Some(4));
+
+    // the sink is in place before the first message
+    named.send(GetPath).await.unwrap();
+    assert_eq!(seen.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn builder_supervision_restarts_supervised_actors() {
+    let sys = cinema::ActorSystem::builder()
+        .supervision(cinema::SupervisorStrategy::restart(
+            3,
+            std::time::Duration::from_secs(1),
+        ))
+        .build();
+    let started = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let counter = started.clone();
+    let addr = sys.spawn_supervised(move || Fragile(counter.clone()));
+    addr.do_send(StopMe).await.unwrap();
+    // the restarted actor still answers
+    addr.send(ThreadName).await.unwrap();
+    assert_eq!(started.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn builder_runtime_runs_top_level_actors() {
+    let arbiter = cinema::Arbiter::with_name("builder-runtime");
+    let sys = cinema::ActorSystem::builder()
+        .runtime(arbiter.handle().clone())
+        .build();
+    let started = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let addr = sys.spawn(Fragile(started.clone()));
+    let thread = addr.send(ThreadName).await.unwrap();
+    assert_eq!(thread.as_deref(), Some("builder-runtime"));
+
+    // an arbiter in the spawn options still wins
+    let other = cinema::Arbiter::with_name("builder-other");
+    let addr = sys.spawn_with(
+        Fragile(started),
+        cinema::SpawnOptions::new().arbiter(&other),
+    );
+    let thread = addr.send(ThreadName).await.unwrap();
+    assert_eq!(thread.as_deref(), Some("builder-other"));
+}
+
+// ======== Dead Letter Tests ========
+
+struct DeadLetterLog {
+    seen: Arc<std::sync::Mutex<Vec<cinema::dead_letter::DeadLetter>>>,
+}
+
+impl Actor for DeadLetterLog {}
+
+impl Handler<cinema::dead_letter::DeadLetter> for DeadLetterLog {
+    type Result = ();
+
+    fn handle(&mut self, msg: cinema::dead_letter::DeadLetter, _ctx: &mut cinema::Context<Self>) {
+        self.seen.lock().unwrap().push(msg);
+    }
+}
+
+///sends StopMe to the target from inside a handler
+struct Relay(cinema::Addr<RegistryActor>);
+
+impl Actor for Relay {}
+
+impl Handler<StopMe> for Relay {
+    type Result = ();
+
+    fn handle(&mut self, _msg: StopMe, _ctx: &mut cinema::Context<Self>) {
+        let _ = self.0.try_send(StopMe);
+    }
+}
+
+#[tokio::test]
+async fn send_to_stopped_actor_is_a_dead_letter() {
+    use futures::StreamExt;
+
+    let sys = cinema::ActorSystem::new();
+    let mut letters = sys.dead_letters();
+    let addr = sys.spawn(RegistryActor);
+
+    addr.do_send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(addr.do_send(StopMe).await.is_err());
+
+    // other tests in this process publish too
+    let letter = loop {
+        let letter = letters.next().await.unwrap();
+        if letter.recipient == addr.id() {
+            break letter;
+        }
+    };
+    assert!(letter.message_type.contains("StopMe"));
+    assert_eq!(letter.sender, None);
+    assert!(letter.timestamp <= std::time::SystemTime::now());
+}
+
+#[tokio::test]
+async fn dead_letters_reach_subscribed_actor_with_sender() {
+    let sys = cinema::ActorSystem::new();
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = sys.spawn(DeadLetterLog { seen: seen.clone() });
+    sys.subscribe_dead_letters(&log);
+
+    let target = sys.spawn(RegistryActor);
+    let relay = sys.spawn(Relay(target.clone()));
+    target.do_send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    relay.do_send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let seen = seen.lock().unwrap();
+    let letter = seen
+        .iter()
+        .find(|letter| letter.recipient == target.id())
+        .unwrap();
+    assert_eq!(letter.sender, Some(relay.id()));
+}
+
+// ======== Graceful Shutdown Tests ========
+
+struct Work;
+impl Message for Work {
+    type Result = ();
+}
+
+struct Worker {
+    done: Arc<std::sync::atomic::AtomicUsize>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Actor for Worker {
+    fn stopped(&mut self, _ctx: &mut cinema::Context<Self>) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Handler<Work> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Work, _ctx: &mut cinema::Context<Self>) {
+        self.done.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn shutdown_graceful_drains_mailboxes() {
+    let done = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Worker {
+        done: done.clone(),
+        stopped: stopped.clone(),
+    });
+    let idle = sys.spawn(RegistryActor);
+
+    for _ in 0..5 {
+        addr.try_send(Work).unwrap();
+    }
+    let report = sys
+        .shutdown_graceful(std::time::Duration::from_secs(1))
+        .await;
+
+    assert!(report.is_clean());
+    assert!(report.stopped.contains(&addr.id()));
+    assert!(report.stopped.contains(&idle.id()));
+    assert_eq!(done.load(Ordering::SeqCst), 5);
+    assert!(stopped.load(Ordering::SeqCst));
+    assert_eq!(
+        addr.try_send(Work),
+        Err(cinema::MailboxError::MailboxClosed)
+    );
+}
+
+#[tokio::test]
+async fn shutdown_graceful_reports_stuck_actors() {
+    let wound_down = Arc::new(AtomicBool::new(false));
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Cooperative {
+        wound_down: wound_down.clone(),
+        stopped: Arc::new(AtomicBool::new(false)),
+    });
+    addr.do_send_async(LongJob).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    let report = sys
+        .shutdown_graceful(std::time::Duration::from_millis(50))
+        .await;
+
+    assert_eq!(report.timed_out, vec![addr.id()]);
+    // laggards are stopped forcefully, cancelling the running handler
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert!(wound_down.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn stop_graceful_drains_the_mailbox_first() {
+    let done = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Worker {
+        done: done.clone(),
+        stopped: stopped.clone(),
+    });
+
+    for _ in 0..5 {
+        addr.try_send(Work).unwrap();
+    }
+    let reason = addr.stop_graceful().await;
+
+    assert_eq!(reason, cinema::message::TerminationReason::Normal);
+    assert_eq!(done.load(Ordering::SeqCst), 5);
+    assert!(stopped.load(Ordering::SeqCst));
+    assert_eq!(
+        addr.try_send(Work),
+        Err(cinema::MailboxError::MailboxClosed)
+    );
+    // already stopped, nothing to wait for
+    assert_eq!(addr.stop_graceful().await, cinema::message::TerminationReason::Normal);
+}
+
+#[tokio::test]
+async fn stop_graceful_timeout_stops_a_stuck_actor() {
+    let wound_down = Arc::new(AtomicBool::new(false));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Cooperative {
+        wound_down: wound_down.clone(),
+        stopped: stopped.clone(),
+    });
+    addr.do_send_async(LongJob).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    let started = std::time::Instant::now();
+    let reason = addr
+        .stop_graceful_timeout(std::time::Duration::from_millis(50))
+        .await;
+
+    assert!(started.elapsed() >= std::time::Duration::from_millis(50));
+    // killed past the deadline: no wind-down and no `stopped`
+    assert_eq!(reason, cinema::message::TerminationReason::Killed);
+    assert!(!wound_down.load(Ordering::SeqCst));
+    assert!(!stopped.load(Ordering::SeqCst));
+    assert!(!addr.is_alive());
+}
+
+///sleeps through a stop, without a stop grace
+struct Deaf;
+
+impl Actor for Deaf {}
+
+impl cinema::actor::AsyncHandler<LongJob> for Deaf {
+    fn handle(
+        &mut self,
+        _msg: LongJob,
+        _ctx: &mut cinema::Context<Self>,
+    ) -> cinema::actor::BoxFuture<'_, ()> {
+        Box::pin(tokio::time::sleep(std::time::Duration::from_secs(10)))
+    }
+}
+
+#[tokio::test]
+async fn stop_graceful_timeout_kills_a_handler_ignoring_the_token() {
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Deaf);
+    addr.do_send_async(LongJob).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    let reason = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        addr.stop_graceful_timeout(std::time::Duration::from_millis(50)),
+    )
+    .await
+    .expect("the deadline kills the actor");
+
+    assert_eq!(reason, cinema::message::TerminationReason::Killed);
+    assert!(!addr.is_alive());
+}
+
+#[tokio::test]
+async fn wait_idle_waits_for_mail_and_running_handlers() {
+    let done = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Worker {
+        done: done.clone(),
+        stopped: Arc::new(AtomicBool::new(false)),
+    });
+
+    for _ in 0..50 {
+        addr.try_send(Work).unwrap();
+    }
+    assert!(sys.wait_idle(std::time::Duration::from_secs(1)).await);
+    assert_eq!(done.load(Ordering::SeqCst), 50);
+
+    // a handler that never returns keeps the system busy
+    let stuck = sys.spawn(Cooperative {
+        wound_down: Arc::new(AtomicBool::new(false)),
+        stopped: Arc::new(AtomicBool::new(false)),
+    });
+    stuck.do_send_async(LongJob).await.unwrap();
+    assert!(!sys.wait_idle(std::time::Duration::from_millis(50)).await);
+
+    sys.resolve(stuck.id()).unwrap().stop();
+    assert!(sys.wait_idle(std::time::Duration::from_secs(1)).await);
+}
+
+#[tokio::test]
+async fn shutdown_hooks_run_in_order_before_actors_stop() {
+    let done = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Worker {
+        done: done.clone(),
+        stopped: stopped.clone(),
+    });
+
+    // the intake hands over its last request while the actors still run
+    let (intake_log, intake_addr) = (log.clone(), addr.clone());
+    sys.on_shutdown(|| async move {
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        intake_addr.send(Work).await.unwrap();
+        intake_log.lock().unwrap().push("intake");
+    });
+    let (consumer_log, consumer_stopped) = (log.clone(), stopped.clone());
+    sys.on_shutdown(|| async move {
+        assert!(!consumer_stopped.load(Ordering::SeqCst));
+        consumer_log.lock().unwrap().push("consumer");
+    });
+
+    sys.shutdown();
+    assert!(addr.is_alive());
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    assert_eq!(*log.lock().unwrap(), vec!["intake", "consumer"]);
+    assert_eq!(done.load(Ordering::SeqCst), 1);
+    assert!(stopped.load(Ordering::SeqCst));
+    assert!(!addr.is_alive());
+}
+
+// ======== Scheduler Tests ========
+
+#[test]
+fn cron_next_after() {
+    use cinema::Schedule;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let at = |secs: u64| UNIX_EPOCH + Duration::from_secs(secs);
+
+    // 00:07 -> 00:15
+    let quarter = Schedule::cron("*/15 * * * *").unwrap();
+    assert_eq!(quarter.next_after(at(7 * 60)), Some(at(15 * 60)));
+
+    // Saturday 2024-01-06 00:00 -> Monday 2024-01-08 09:00
+    let weekdays = Schedule::cron("0 9 * * 1-5").unwrap();
+    assert_eq!(
+        weekdays.next_after(at(1_704_499_200)),
+        Some(at(1_704_499_200 + 2 * 86_400 + 9 * 3_600))
+    );
+
+    // strictly after: 2024-01-01 00:00 -> 2025-01-01 00:00
+    let yearly = Schedule::cron("@yearly").unwrap();
+    assert_eq!(yearly.next_after(at(1_704_067_200)), Some(at(1_735_689_600)));
+    // 2024-01-01 -> leap day 2024-02-29 12:30
+    let feb_29 = Schedule::cron("30 12 29 2 *").unwrap();
+    assert_eq!(
+        feb_29.next_after(at(1_704_067_200)),
+        Some(at(1_709_164_800 + 12 * 3_600 + 30 * 60))
+    );
+
+    assert!(Schedule::cron("0 0 30 2 *").unwrap().next_after(at(0)).is_none());
+    assert!(Schedule::cron("* * *").is_err());
+    assert!(Schedule::cron("60 * * * *").is_err());
+    assert!(Schedule::cron("*/0 * * * *").is_err());
+}
+
+#[tokio::test]
+async fn scheduler_delivers_until_cancelled() {
+    use cinema::Schedule;
+    use std::time::{Duration, SystemTime};
+
+    let done = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let sys = cinema::ActorSystem::new();
+    let addr = sys.spawn(Worker {
+        done: done.clone(),
+        stopped: Arc::new(AtomicBool::new(false)),
+    });
+
+    let scheduler = sys.scheduler();
+    let every = scheduler.schedule(Schedule::every(Duration::from_millis(20)), &addr, || Work);
+    let once = scheduler.schedule(
+        Schedule::at(SystemTime::now() + Duration::from_millis(10)),
+        &addr,
+        || Work,
+    );
+    let jobs = scheduler.jobs();
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0].id, every);
+    assert_eq!(jobs[0].target, addr.id());
+    assert!(jobs[0].next_run.is_some());
+
+    tokio::time::sleep(Duration::from_millis(70)).await;
+    // the one-shot job is gone once it has fired
+    assert_eq!(scheduler.jobs().len(), 1);
+    assert!(!scheduler.cancel(once));
+
+    assert!(scheduler.cancel(every));
+    assert!(scheduler.jobs().is_empty());
+    let delivered = done.load(Ordering::SeqCst);
+    assert!(delivered >= 3, "delivered {}", delivered);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(done.load(Ordering::SeqCst), delivered);
+}
+
+// ======== Event Bus Tests ========
+
+#[derive(Clone)]
+struct PriceChanged(u32);
+impl Message for PriceChanged {
+    type Result = ();
+}
+
+struct PriceWatcher {
+    seen: Arc<std::sync::Mutex<Vec<u32>>>,
+}
+
+impl Actor for PriceWatcher {}
+
+impl Handler<PriceChanged> for PriceWatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: PriceChanged, _ctx: &mut cinema::Context<Self>) {
+        self.seen.lock().unwrap().push(msg.0);
+    }
+}
+
+impl Handler<StopMe> for PriceWatcher {
+    type Result = ();
+
+    fn handle(&mut self, _msg: StopMe, ctx: &mut cinema::Context<Self>) {
+        ctx.stop();
+    }
+}
+
+#[tokio::test]
+async fn event_bus_delivers_to_subscribers() {
+    let sys = cinema::ActorSystem::new();
+    let bus = cinema::EventBus::new();
+    let first = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let second = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let a = sys.spawn(PriceWatcher { seen: first.clone() });
+    let b = sys.spawn(PriceWatcher { seen: second.clone() });
+
+    bus.subscribe::<PriceChanged, _>(&a);
+    bus.subscribe::<PriceChanged, _>(&a);
+    bus.subscribe::<PriceChanged, _>(&b);
+    assert_eq!(bus.subscribers::<PriceChanged>(), 2);
+
+    assert_eq!(bus.publish(PriceChanged(1)), 2);
+    assert!(bus.unsubscribe::<PriceChanged, _>(&b));
+    assert_eq!(bus.publish(PriceChanged(2)), 1);
+    // subscriptions are per event type
+    assert_eq!(bus.subscribers::<Work>(), 0);
+
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert_eq!(*first.lock().unwrap(), vec![1, 2]);
+    assert_eq!(*second.lock().unwrap(), vec![1]);
+}
+
+#[tokio::test]
+async fn event_bus_drops_dead_subscribers() {
+    let sys = cinema::ActorSystem::new();
+    let bus = cinema::EventBus::new();
+    let addr = sys.spawn(PriceWatcher {
+        seen: Arc::new(std::sync::Mutex::new(Vec::new())),
+    });
+    bus.subscribe::<PriceChanged, _>(&addr);
+    assert_eq!(bus.subscribers::<PriceChanged>(), 1);
+
+    addr.send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    assert_eq!(bus.subscribers::<PriceChanged>(), 0);
+    assert_eq!(bus.publish(PriceChanged(3)), 0);
+}
+
+// ======== Middleware Tests ========
+
+///doubles prices, drops zero prices, and logs what went through
+struct PriceFilter {
+    log: Arc<std::sync::Mutex<Vec<String>>>,
+    tag: &'static str,
+}
+
+impl cinema::Middleware for PriceFilter {
+    fn before(&self, msg: &mut cinema::Intercepted<'_>) -> cinema::Flow {
+        self.log.lock().unwrap().push(format!("{} before", self.tag));
+        match msg.message_mut::<PriceChanged>() {
+            Some(PriceChanged(0)) => cinema::Flow::Reject,
+            Some(price) => {
+                price.0 *= 2;
+                cinema::Flow::Continue
+            }
+            None => cinema::Flow::Continue,
+        }
+    }
+
+    fn after(&self, msg: &cinema::Intercepted<'_>) {
+        assert!(msg.message_type.ends_with("PriceChanged"));
+        self.log.lock().unwrap().push(format!("{} after", self.tag));
+    }
+}
+
+#[tokio::test]
+async fn middleware_wraps_handlers_in_order() {
+    let sys = cinema::ActorSystem::new();
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    sys.add_middleware(PriceFilter {
+        log: log.clone(),
+        tag: "system",
+    });
+    let options = cinema::SpawnOptions::new().middleware(PriceFilter {
+        log: log.clone(),
+        tag: "actor",
+    });
+    let watcher = sys.spawn_with(PriceWatcher { seen: seen.clone() }, options);
+
+    watcher.send(PriceChanged(0)).await.unwrap_err();
+    log.lock().unwrap().clear();
+    watcher.send(PriceChanged(5)).await.unwrap();
+
+    // both layers doubled the price
+    assert_eq!(*seen.lock().unwrap(), [20]);
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["system before", "actor before", "actor after", "system after"]
+    );
+}
+
+// ======== Router Tests ========
+
+struct WhoAmI;
+impl Message for WhoAmI {
+    type Result = cinema::actor::ActorId;
+}
+
+struct Explode;
+impl Message for Explode {
+    type Result = ();
+}
+
+struct PoolWorker;
+
+impl Actor for PoolWorker {}
+
+impl Handler<WhoAmI> for PoolWorker {
+    type Result = cinema::actor::ActorId;
+
+    fn handle(&mut self, _msg: WhoAmI, ctx: &mut cinema::Context<Self>) -> cinema::actor::ActorId {
+        ctx.id()
+    }
+}
+
+impl Handler<Explode> for PoolWorker {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Explode, _ctx: &mut cinema::Context<Self>) {
+        panic!("worker exploded");
+    }
+}
+
+#[tokio::test]
+async fn router_round_robins_across_workers() {
+    let sys = cinema::ActorSystem::new();
+    let router = cinema::Router::round_robin(&sys, 3, || PoolWorker);
+    assert_eq!(router.len(), 3);
+
+    let mut seen = Vec::new();
+    for _ in 0..6 {
+        seen.push(router.send(WhoAmI).await.unwrap());
+    }
+    let workers: Vec<_> = router.workers().iter().map(|w| w.id()).collect();
+    assert_eq!(&seen[..3], &workers[..]);
+    assert_eq!(&seen[3..], &workers[..]);
+}
+
+#[tokio::test]
+async fn router_resizes_at_runtime() {
+    let sys = cinema::ActorSystem::new();
+    let router = cinema::Router::round_robin(&sys, 2, || PoolWorker);
+
+    router.resize(4);
+    assert_eq!(router.len(), 4);
+
+    let removed = router.workers().split_off(1);
+    router.resize(1);
+    assert_eq!(router.len(), 1);
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert!(removed.iter().all(|w| !w.is_alive()));
+    assert!(router.send(WhoAmI).await.is_ok());
+
+    router.resize(0);
+    assert!(router.is_empty());
+    assert_eq!(
+        router.try_send(WhoAmI),
+        Err(cinema::MailboxError::MailboxClosed)
+    );
+}
+
+#[tokio::test]
+async fn router_replaces_failed_workers() {
+    let sys = cinema::ActorSystem::new();
+    let router = cinema::Router::round_robin(&sys, 2, || PoolWorker);
+    let before: Vec<_> = router.workers().iter().map(|w| w.id()).collect();
+
+    router.do_send(Explode).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let after: Vec<_> = router.workers().iter().map(|w| w.id()).collect();
+    assert_eq!(after.len(), 2);
+    assert_ne!(after[0], before[0]);
+    assert_eq!(after[1], before[1]);
+    for _ in 0..2 {
+        assert!(router.send(WhoAmI).await.is_ok());
+    }
+}
+
+///keeps its worker busy for a while
+struct Crunch;
+impl Message for Crunch {
+    type Result = cinema::actor::ActorId;
+}
+
+impl cinema::actor::AsyncHandler<Crunch> for PoolWorker {
+    fn handle(
+        &mut self,
+        _msg: Crunch,
+        ctx: &mut cinema::Context<Self>,
+    ) -> cinema::actor::BoxFuture<'_, cinema::actor::ActorId> {
+        let id = ctx.id();
+        Box::pin(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            id
+        })
+    }
+}
+
+#[tokio::test]
+async fn work_stealing_pool_hands_mail_to_idle_workers() {
+    let sys = cinema::ActorSystem::new();
+    let router = cinema::Router::work_stealing(&sys, 2, || PoolWorker);
+    assert_eq!(router.len(), 2);
+
+    // any worker's address feeds the shared queue
+    let first = router.workers()[0].clone();
+    let busy = tokio::spawn(async move { first.send_async(Crunch).await.unwrap() });
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    // round-robin would queue every other message behind the crunch
+    let mut seen = Vec::new();
+    for _ in 0..4 {
+        seen.push(router.send(WhoAmI).await.unwrap());
+    }
+    assert!(!busy.is_finished());
+    let busy = busy.await.unwrap();
+    assert!(seen.iter().all(|id| *id != busy));
+
+    // a removed worker leaves the queued mail to the rest
+    router.resize(1);
+    for _ in 0..4 {
+        router.do_send(WhoAmI).await.unwrap();
+    }
+    assert!(router.send(WhoAmI).await.is_ok());
+}
+
+// ======== Sync Arbiter Tests ========
+
+struct BlockingJob;
+impl Message for BlockingJob {
+    type Result = String;
+}
+
+struct BlockingWorker;
+
+impl Actor for BlockingWorker {}
+
+impl Handler<BlockingJob> for BlockingWorker {
+    type Result = String;
+
+    fn handle(&mut self, _msg: BlockingJob, _ctx: &mut cinema::Context<Self>) -> String {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        std::thread::current().name().unwrap_or_default().to_string()
+    }
+}
+
+impl Handler<Explode> for BlockingWorker {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Explode, _ctx: &mut cinema::Context<Self>) {
+        panic!("blocking worker exploded");
+    }
+}
+
+impl Handler<StopMe> for BlockingWorker {
+    type Result = ();
+
+    fn handle(&mut self, _msg: StopMe, ctx: &mut cinema::Context<Self>) {
+        ctx.stop();
+    }
+}
+
+#[tokio::test]
+async fn sync_arbiter_runs_blocking_handlers_off_the_runtime() {
+    let sys = cinema::ActorSystem::new();
+    let addr = cinema::SyncArbiter::start(&sys, 2, || BlockingWorker);
+
+    let started = std::time::Instant::now();
+    let first = addr.send(BlockingJob);
+    let second = addr.send(BlockingJob);
+    // the single-threaded test runtime keeps ticking while both handlers block
+    let ticker = async {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        started.elapsed()
+    };
+    let (first, second, ticked) = tokio::join!(first, second, ticker);
+
+    assert!(
+        ticked < std::time::Duration::from_millis(40),
+        "runtime stalled for {:?}",
+        ticked
+    );
+    let (first, second) = (first.unwrap(), second.unwrap());
+    assert!(first.starts_with("cinema-sync-"));
+    assert_ne!(first, second);
+    assert!(started.elapsed() < std::time::Duration::from_millis(95));
+}
+
+#[tokio::test]
+async fn sync_arbiter_restarts_panicked_instances_and_stops() {
+    let sys = cinema::ActorSystem::new();
+    let addr = cinema::SyncArbiter::start(&sys, 1, || BlockingWorker);
+
+    addr.do_send(Explode).await.unwrap();
+    assert!(addr.send(BlockingJob).await.is_ok());
+
+    addr.send(StopMe).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert!(!addr.is_alive());
+    assert_eq!(
+        addr.send(BlockingJob).await,
+        Err(cinema::MailboxError::MailboxClosed)
+    );
+}
+
+#[tokio::test]
+async fn sync_arbiter_follows_the_panic_policy() {
+    let sys = cinema::ActorSystem::with_config(
+        cinema::ActorSystemConfig::new().panic_policy(cinema::PanicPolicy::StopActor),
+    );
+    let addr = cinema::SyncArbiter::start(&sys, 2, || BlockingWorker);
+
+    addr.do_send(Explode).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!addr.is_alive());
+    assert_eq!(
+        addr.send(BlockingJob).await,
+        Err(cinema::MailboxError::MailboxClosed)
+    );
+
+    let sys = cinema::ActorSystem::with_config(
+        cinema::ActorSystemConfig::new().panic_policy(cinema::PanicPolicy::EscalateToSystem),
+    );
+    let addr = cinema::SyncArbiter::start(&sys, 1, || BlockingWorker);
+    addr.do_send(Explode).await.unwrap();
+    let code = tokio::time::timeout(std::time::Duration::from_secs(1), sys.terminated())
+        .await
+        .unwrap();
+    assert_eq!(code, 1);
+    assert!(!addr.is_alive());
+}
+
+#[tokio::test]
+async fn sync_arbiter_drains_queued_mail_once_every_addr_is_dropped() {
+    use std::sync::atomic::AtomicUsize;
+
+    struct Tally(Arc<AtomicUsize>);
+    impl Actor for Tally {}
+    impl Handler<BlockingJob> for Tally {
+        type Result = String;
+
+        fn handle(&mut self, _msg: BlockingJob, _ctx: &mut cinema::Context<Self>) -> String {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            self.0.fetch_add(1, Ordering::SeqCst);
+            String::new()
+        }
+    }
+
+    let sys = cinema::ActorSystem::new();
+    let handled = Arc::new(AtomicUsize::new(0));
+    let tally = handled.clone();
+    let addr = cinema::SyncArbiter::start(&sys, 1, move || Tally(tally.clone()));
+    for _ in 0..5 {
+        addr.do_send(BlockingJob).await.unwrap();
+    }
+    drop(addr);
+
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert_eq!(handled.load(Ordering::SeqCst), 5);
+}
+
+// ======== Arbiter Tests ========
+
+struct WhereAmI;
+impl Message for WhereAmI {
+    type Result = String;
+}
+
+struct Pinned;
+
+impl Actor for Pinned {}
+
+impl Handler<WhereAmI> for Pinned {
+    type Result = String;
+
+    fn handle(&mut self, _msg: WhereAmI, _ctx: &mut cinema::Context<Self>) -> String {
+        std::thread::current().name().unwrap_or_default().to_string()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn arbiter_pins_actors_to_its_thread() {
+    let sys = cinema::ActorSystem::new();
+    let arbiter = cinema::Arbiter::with_name("ledger");
+    let pinned = sys.spawn_with(Pinned, cinema::SpawnOptions::new().arbiter(&arbiter));
+    let shared = sys.spawn(Pinned);
+
+    for _ in 0..5 {
+        assert_eq!(pinned.send(WhereAmI).await.unwrap(), "ledger");
+        assert_ne!(shared.send(WhereAmI).await.unwrap(), "ledger");
+    }
+}
+
+struct PinnedWatcher {
+    target: cinema::Addr<Pinned>,
+    seen: Arc<std::sync::Mutex<Option<cinema::message::TerminationReason>>>,
+}
+
+impl Actor for PinnedWatcher {
+    fn started(&mut self, ctx: &mut cinema::Context<Self>) {
+        ctx.watch(&self.target);
+    }
+}
+
+impl Handler<cinema::message::Terminated> for PinnedWatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: cinema::message::Terminated, _ctx: &mut cinema::Context<Self>) {
+        *self.seen.lock().unwrap() = Some(msg.reason);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dropping_an_arbiter_reports_its_actors_killed() {
+    let sys = cinema::ActorSystem::new();
+    let arbiter = cinema::Arbiter::with_name("doomed");
+    let pinned = sys.spawn_with(Pinned, cinema::SpawnOptions::new().arbiter(&arbiter));
+    let seen = Arc::new(std::sync::Mutex::new(None));
+    let _watcher = sys.spawn(PinnedWatcher {
+        target: pinned.clone(),
+        seen: seen.clone(),
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    drop(arbiter);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        Some(cinema::message::TerminationReason::Killed)
+    );
+    assert!(sys.resolve(pinned.id()).is_none());
+    assert!(!pinned.is_alive());
+}
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use cinema::{
+    remote::{
+        proto::Envelope, register_message, EnvelopeHandler, RemoteAddr, RemoteClient,
+        RemoteMessage, RemoteServer, TlsServerConfig, TlsTransport, Transport,
+    },
+    Message,
+};
+use prost::Message as ProstMessage;
+
+const CA: &[u8] = include_bytes!("certs/ca.pem");
+const SERVER_CERT: &[u8] = include_bytes!("certs/server.pem");
+const SERVER_KEY: &[u8] = include_bytes!("certs/server.key");
+const CLIENT_CERT: &[u8] = include_bytes!("certs/client.pem");
+const CLIENT_KEY: &[u8] = include_bytes!("certs/client.key");
+
+#[derive(Clone, ProstMessage)]
+struct Ping {
+    #[prost(string, tag = "1")]
+    message: String,
+}
+
+impl Message for Ping {
+    type Result = ();
+}
+
+impl RemoteMessage for Ping {}
+
+fn echo_handler() -> EnvelopeHandler {
+    Arc::new(|envelope: Envelope| {
+        Box::pin(async move {
+            Some(Envelope {
+                message_type: envelope.message_type,
+                payload: envelope.payload,
+                correlation_id: envelope.correlation_id,
+                sender_node: "server".to_string(),
+                target_actor: envelope.sender_node,
+                is_response: true,
+                codec: envelope.codec,
+                compression: envelope.compression,
+                delivery_id: envelope.delivery_id,
+                trace: envelope.trace,
+                ..Default::default()
+            })
+        })
+    })
+}
+
+async fn start(tls: TlsServerConfig) -> String {
+    let server = RemoteServer::bind_tls("127.0.0.1:0", echo_handler(), tls)
+        .await
+        .unwrap();
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+    addr.to_string()
+}
+
+async fn ping(transport: &TlsTransport, addr: &str) -> Ping {
+    register_message::<Ping>();
+    let conn = transport.connect(addr).await.unwrap();
+    let remote: RemoteAddr<()> =
+        RemoteAddr::new("client", "server-node", "echo", RemoteClient::new(conn));
+    let response = remote
+        .send(Ping {
+            message: "over tls".to_string(),
+        })
+        .await
+        .unwrap();
+    Ping::decode(response.payload.as_slice()).unwrap()
+}
+
+#[tokio::test]
+async fn tls_round_trip() {
+    let addr = start(TlsServerConfig::new(SERVER_CERT, SERVER_KEY).unwrap()).await;
+    let transport = TlsTransport::new(CA).unwrap();
+
+    // the certificate is checked against "127.0.0.1" taken from the address
+    assert_eq!(ping(&transport, &addr).await.message, "over tls");
+}
+
+#[tokio::test]
+async fn tls_server_name_overrides_address() {
+    let addr = start(TlsServerConfig::new(SERVER_CERT, SERVER_KEY).unwrap()).await;
+
+    let transport = TlsTransport::new(CA)
+        .unwrap()
+        .server_name("node.cinema.test")
+        .unwrap();
+    assert_eq!(ping(&transport, &addr).await.message, "over tls");
+
+    // a name the certificate doesn't cover fails verification
+    let wrong = TlsTransport::new(CA)
+        .unwrap()
+        .server_name("other.cinema.test")
+        .unwrap();
+    assert!(wrong.connect(&addr).await.is_err());
+}
+
+#[tokio::test]
+async fn tls_rejects_untrusted_server() {
+    let addr = start(TlsServerConfig::new(SERVER_CERT, SERVER_KEY).unwrap()).await;
+
+    // the client trusts only its own cert, not the CA that signed the server
+    let transport = TlsTransport::new(CLIENT_CERT).unwrap();
+    assert!(transport.connect(&addr).await.is_err());
+}
+
+#[tokio::test]
+async fn mutual_tls_accepts_client_identity() {
+    let tls = TlsServerConfig::new(SERVER_CERT, SERVER_KEY)
+        .unwrap()
+        .require_client_auth(CA)
+        .unwrap();
+    let addr = start(tls).await;
+
+    let transport = TlsTransport::new(CA)
+        .unwrap()
+        .with_identity(CLIENT_CERT, CLIENT_KEY)
+        .unwrap();
+    assert_eq!(ping(&transport, &addr).await.message, "over tls");
+}
+
+#[tokio::test]
+async fn mutual_tls_rejects_anonymous_client() {
+    let tls = TlsServerConfig::new(SERVER_CERT, SERVER_KEY)
+        .unwrap()
+        .require_client_auth(CA)
+        .unwrap();
+    let addr = start(tls).await;
+
+    // TLS 1.3 reports the rejected certificate after the client handshake
+    // finishes, so the failure surfaces on the first request
+    let transport = TlsTransport::new(CA).unwrap();
+    let rejected = match transport.connect(&addr).await {
+        Err(_) => true,
+        Ok(conn) => {
+            let remote: RemoteAddr<()> =
+                RemoteAddr::new("client", "server-node", "echo", RemoteClient::new(conn));
+            let ping = Ping {
+                message: "anonymous".to_string(),
+            };
+            remote
+                .send_timeout(ping, std::time::Duration::from_secs(1))
+                .await
+                .is_err()
+        }
+    };
+    assert!(rejected);
+}
+
+#[tokio::test]
+async fn identity_added_to_a_prepared_config_keeps_its_roots() {
+    use tokio_rustls::rustls::{
+        self, pki_types::pem::PemObject, pki_types::CertificateDer, ClientConfig, RootCertStore,
+    };
+
+    let tls = TlsServerConfig::new(SERVER_CERT, SERVER_KEY)
+        .unwrap()
+        .require_client_auth(CA)
+        .unwrap();
+    let addr = start(tls).await;
+
+    let mut roots = RootCertStore::empty();
+    for cert in CertificateDer::pem_slice_iter(CA) {
+        roots.add(cert.unwrap()).unwrap();
+    }
+    let config = ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+
+    let transport = TlsTransport::from_config(Arc::new(config))
+        .with_identity(CLIENT_CERT, CLIENT_KEY)
+        .unwrap();
+    assert_eq!(ping(&transport, &addr).await.message, "over tls");
+}
+
+#[tokio::test]
+async fn stalled_tls_handshakes_are_dropped() {
+    use std::time::Duration;
+    use tokio::io::AsyncReadExt;
+
+    let tls = TlsServerConfig::new(SERVER_CERT, SERVER_KEY)
+        .unwrap()
+        .handshake_timeout(Duration::from_millis(100));
+    let addr = start(tls).await;
+
+    // connects, then never says hello
+    let mut stream = tokio::net::TcpStream::connect(&addr).await.unwrap();
+    let mut buf = [0u8; 16];
+    let read = tokio::time::timeout(Duration::from_secs(2), stream.read(&mut buf)).await;
+    assert!(matches!(read, Ok(Ok(0)) | Ok(Err(_))));
+}
+
+#[test]
+fn invalid_pem_is_rejected() {
+    assert!(TlsTransport::new(b"not a certificate").is_err());
+    assert!(TlsServerConfig::new(SERVER_CERT, b"not a key").is_err());
+}
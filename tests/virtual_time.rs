@@ -0,0 +1,177 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use cinema::{
+    actor::{AsyncHandler, BoxFuture},
+    testing::TestSystem,
+    Actor, Context, Handler, MailboxError, Message, TimerHandle,
+};
+
+#[derive(Clone)]
+struct Tick;
+impl Message for Tick {
+    type Result = ();
+}
+
+struct Expire;
+impl Message for Expire {
+    type Result = ();
+}
+
+struct Hang;
+impl Message for Hang {
+    type Result = ();
+}
+
+struct Session {
+    ticks: Arc<AtomicU32>,
+    expired: Arc<AtomicU32>,
+}
+
+impl Actor for Session {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        ctx.run_interval(Duration::from_secs(1), Tick);
+        ctx.run_later(Duration::from_secs(30), Expire);
+    }
+}
+
+impl Handler<Tick> for Session {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Tick, _ctx: &mut Context<Self>) {
+        self.ticks.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl Handler<Expire> for Session {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Expire, _ctx: &mut Context<Self>) {
+        self.expired.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl AsyncHandler<Hang> for Session {
+    fn handle(&mut self, _msg: Hang, _ctx: &mut Context<Self>) -> BoxFuture<'_, ()> {
+        Box::pin(std::future::pending())
+    }
+}
+
+fn session(sys: &TestSystem) -> (cinema::Addr<Session>, Arc<AtomicU32>, Arc<AtomicU32>) {
+    let ticks = Arc::new(AtomicU32::new(0));
+    let expired = Arc::new(AtomicU32::new(0));
+    let addr = sys.spawn(Session {
+        ticks: ticks.clone(),
+        expired: expired.clone(),
+    });
+    (addr, ticks, expired)
+}
+
+#[tokio::test]
+async fn advance_fires_timers_in_virtual_time() {
+    let sys = TestSystem::new();
+    let (_addr, ticks, expired) = session(&sys);
+
+    // the first interval tick is immediate
+    sys.advance(Duration::from_millis(500)).await;
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+
+    sys.advance(Duration::from_millis(29_000)).await;
+    assert_eq!(ticks.load(Ordering::SeqCst), 30);
+    assert_eq!(expired.load(Ordering::SeqCst), 0);
+
+    sys.advance(Duration::from_secs(60)).await;
+    assert_eq!(ticks.load(Ordering::SeqCst), 90);
+    assert_eq!(expired.load(Ordering::SeqCst), 1);
+    assert_eq!(sys.elapsed(), Duration::from_millis(89_500));
+}
+
+#[tokio::test]
+async fn send_timeout_expires_on_virtual_clock() {
+    let sys = TestSystem::new();
+    let (addr, _ticks, _expired) = session(&sys);
+
+    // nothing else to do, so the paused clock skips ahead to the deadline
+    let wall = std::time::Instant::now();
+    addr.do_send_async(Hang).await.unwrap();
+    let res = addr.send_timeout(Tick, Duration::from_secs(3600)).await;
+    assert_eq!(res, Err(MailboxError::Timeout));
+    assert!(wall.elapsed() < Duration::from_secs(1));
+}
+
+struct Lease {
+    timer: Arc<Mutex<Option<TimerHandle>>>,
+    expired: Arc<AtomicU32>,
+}
+
+impl Actor for Lease {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        *self.timer.lock().unwrap() = Some(ctx.run_later(Duration::from_secs(10), Expire));
+    }
+}
+
+impl Handler<Expire> for Lease {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Expire, _ctx: &mut Context<Self>) {
+        self.expired.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+///a started Lease, with the handle of its expiry timer
+async fn lease(sys: &TestSystem) -> (TimerHandle, Arc<AtomicU32>) {
+    let timer = Arc::new(Mutex::new(None));
+    let expired = Arc::new(AtomicU32::new(0));
+    let _addr = sys.spawn(Lease {
+        timer: timer.clone(),
+        expired: expired.clone(),
+    });
+    sys.advance(Duration::ZERO).await;
+    let timer = timer.lock().unwrap().clone().unwrap();
+    (timer, expired)
+}
+
+#[tokio::test]
+async fn rescheduled_timer_fires_at_its_new_deadline() {
+    let sys = TestSystem::new();
+    let (timer, expired) = lease(&sys).await;
+    sys.advance(Duration::from_secs(4)).await;
+    // the clock runs a millisecond ahead, see TestSystem
+    let left = timer.remaining().unwrap();
+    assert!(left > Duration::from_secs(5) && left <= Duration::from_secs(6));
+
+    // renewed before it ran out
+    assert!(timer.reschedule(Duration::from_secs(10)));
+    sys.advance(Duration::from_secs(9)).await;
+    assert_eq!(expired.load(Ordering::SeqCst), 0);
+    assert!(timer.remaining().unwrap() <= Duration::from_secs(1));
+
+    sys.advance(Duration::from_secs(2)).await;
+    assert_eq!(expired.load(Ordering::SeqCst), 1);
+    assert_eq!(timer.remaining(), None);
+    assert!(!timer.reschedule(Duration::from_secs(10)));
+    assert!(!timer.fire_now());
+}
+
+#[tokio::test]
+async fn timer_fires_now_or_never() {
+    let sys = TestSystem::new();
+    let (timer, expired) = lease(&sys).await;
+    sys.advance(Duration::from_secs(1)).await;
+
+    assert!(timer.fire_now());
+    sys.advance(Duration::from_millis(1)).await;
+    assert_eq!(expired.load(Ordering::SeqCst), 1);
+
+    let (cancelled, expired) = lease(&sys).await;
+    cancelled.cancel();
+    assert_eq!(cancelled.remaining(), None);
+    assert!(!cancelled.fire_now());
+    sys.advance(Duration::from_secs(20)).await;
+    assert_eq!(expired.load(Ordering::SeqCst), 0);
+}